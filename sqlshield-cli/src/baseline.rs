@@ -0,0 +1,125 @@
+//! Baseline files: findings recorded with `--write-baseline` are accepted
+//! on later runs, so a legacy codebase can adopt sqlshield and only be
+//! held to new findings.
+//!
+//! A finding is identified by its file, the hash of its query's normalized
+//! text and its rule code — not its line, so edits elsewhere in the file
+//! don't invalidate the baseline. Identical fingerprints are matched one
+//! for one: a query with two baselined `unknown-column` errors fails once
+//! it has three.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sqlshield::validation::Diagnostic;
+
+use crate::report::report_path;
+
+pub const BASELINE_FILE_NAME: &str = ".sqlshield-baseline.json";
+
+const VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BaselineFile {
+    version: u32,
+    findings: Vec<Entry>,
+}
+
+/// One accepted finding. `description` is informational; matching uses
+/// the other three fields.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Entry {
+    pub file: String,
+    pub query_hash: String,
+    pub code: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+impl Entry {
+    fn from_diagnostic(d: &Diagnostic) -> Self {
+        Entry {
+            file: report_path(&d.path),
+            query_hash: format!("{:016x}", d.query_hash),
+            code: d.code.to_string(),
+            description: d.description.clone(),
+        }
+    }
+
+    fn key(&self) -> (&str, &str, &str) {
+        (&self.file, &self.query_hash, &self.code)
+    }
+}
+
+/// Record `diagnostics` as the baseline at `path`, replacing any previous
+/// one.
+pub fn write(path: &Path, diagnostics: &[Diagnostic]) -> Result<(), String> {
+    let mut findings: Vec<Entry> = diagnostics.iter().map(Entry::from_diagnostic).collect();
+    findings.sort_by(|a, b| a.key().cmp(&b.key()));
+    let file = BaselineFile {
+        version: VERSION,
+        findings,
+    };
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("failed to serialize baseline: {e}"))?;
+    std::fs::write(path, json + "\n")
+        .map_err(|e| format!("failed to write {}: {e}", path.display()))
+}
+
+pub fn load(path: &Path) -> Result<Vec<Entry>, String> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+    let file: BaselineFile = serde_json::from_str(&source)
+        .map_err(|e| format!("invalid baseline {}: {e}", path.display()))?;
+    if file.version != VERSION {
+        return Err(format!(
+            "invalid baseline {}: unsupported version {} (expected {VERSION})",
+            path.display(),
+            file.version
+        ));
+    }
+    Ok(file.findings)
+}
+
+/// Split `diagnostics` against the baseline: returns the findings it
+/// doesn't cover, and the entries that matched nothing (fixed findings,
+/// to be pruned). A run over part of the tree passes the files it checked
+/// as `scope`, and entries for other files are ignored so it doesn't call
+/// the rest stale; on a full run every unmatched entry is stale, those of
+/// deleted or renamed files included.
+pub fn apply(
+    diagnostics: Vec<Diagnostic>,
+    baseline: Vec<Entry>,
+    scope: Option<&[PathBuf]>,
+) -> (Vec<Diagnostic>, Vec<Entry>) {
+    let scope: Option<HashSet<String>> =
+        scope.map(|files| files.iter().map(|p| report_path(p)).collect());
+    let in_scope = |entry: &Entry| scope.as_ref().map_or(true, |s| s.contains(&entry.file));
+    let mut pending: HashMap<(String, String, String), Vec<Entry>> = HashMap::new();
+    for entry in baseline.into_iter().filter(in_scope) {
+        let key = (
+            entry.file.clone(),
+            entry.query_hash.clone(),
+            entry.code.clone(),
+        );
+        pending.entry(key).or_default().push(entry);
+    }
+
+    let new = diagnostics
+        .into_iter()
+        .filter(|d| {
+            let entry = Entry::from_diagnostic(d);
+            let key = (entry.file, entry.query_hash, entry.code);
+            !pending
+                .get_mut(&key)
+                .is_some_and(|entries| entries.pop().is_some())
+        })
+        .collect();
+
+    let mut stale: Vec<Entry> = pending.into_values().flatten().collect();
+    stale.sort_by(|a, b| a.key().cmp(&b.key()));
+    (new, stale)
+}
@@ -0,0 +1,192 @@
+//! `--changed-since <ref>`: check only what a branch touched. Files changed
+//! relative to the ref are checked directly; when the schema file or a
+//! migration changed, every file whose queries name an affected table or
+//! call an affected function is checked too, since the change can break
+//! queries the branch didn't edit. A table is affected when its columns
+//! change or the labels of an enum type one of them uses do.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use sqlshield::finder::SQL_FILE_EXTENSION;
+use sqlshield::schema::{self, Schema};
+use sqlshield::usage::TableUsageIndex;
+use sqlshield::walk::{Skipped, WalkOptions};
+use sqlshield::Dialect;
+
+use crate::report::report_path;
+
+/// What a `--changed-since` run checks.
+pub struct ChangedFiles {
+    /// Files to check, changed ones first.
+    pub files: Vec<PathBuf>,
+    /// Table keys whose definition changed.
+    pub affected_tables: BTreeSet<String>,
+    /// Function keys created, dropped or redefined.
+    pub affected_functions: BTreeSet<String>,
+    /// How many of `files` are only checked because they use an affected
+    /// table or function.
+    pub dependents: usize,
+    /// Changed files the `include` / `exclude` globs leave out.
+    pub skipped: Vec<Skipped>,
+}
+
+/// Work out what to check for a run over `directory` against `git_ref`.
+/// `schema` is the current schema, loaded from `schema_path`.
+pub fn collect(
+    git_ref: &str,
+    directory: &Path,
+    walk: &WalkOptions,
+    schema_path: &Path,
+    schema: &Schema,
+    dialect: Dialect,
+) -> Result<ChangedFiles, String> {
+    let schema_file = schema_path.canonicalize().ok();
+    // Git reports paths relative to the current directory while `directory`
+    // may be spelled either way, so compare canonical paths.
+    let within = directory
+        .canonicalize()
+        .map_err(|e| format!("{}: {e}", directory.display()))?;
+
+    let mut affected_tables = BTreeSet::new();
+    let mut affected_functions = BTreeSet::new();
+    let mut files = Vec::new();
+    let mut skipped = Vec::new();
+    for path in changed_paths(git_ref)? {
+        let canonical = path.canonicalize().ok();
+        if schema_file.is_some() && canonical == schema_file {
+            let old = previous_schema(git_ref, schema_path, dialect);
+            affected_tables.extend(old.changed_tables(schema));
+            affected_functions.extend(old.changed_functions(schema));
+            continue;
+        }
+        if !canonical.is_some_and(|c| c.starts_with(&within))
+            || !sqlshield::finder::is_supported_file(&path)
+        {
+            continue;
+        }
+        if let Some((tables, functions)) = migrated(&path, schema, dialect) {
+            affected_tables.extend(tables);
+            affected_functions.extend(functions);
+            continue;
+        }
+        match walk.skip_reason(&path) {
+            Some(reason) => skipped.push(Skipped { path, reason }),
+            None => files.push(path),
+        }
+    }
+
+    let mut dependents = 0;
+    if !affected_tables.is_empty() || !affected_functions.is_empty() {
+        let mut seen: BTreeSet<PathBuf> =
+            files.iter().filter_map(|f| f.canonicalize().ok()).collect();
+        let index = TableUsageIndex::build(&sqlshield::walk::walk(directory, walk).files, dialect);
+        let mut using = index.files_using(&affected_tables);
+        using.extend(index.files_calling(&affected_functions));
+        for file in using {
+            let file = file.strip_prefix(".").unwrap_or(file);
+            let Ok(canonical) = file.canonicalize() else {
+                continue;
+            };
+            if schema_file.as_ref() == Some(&canonical) {
+                continue;
+            }
+            if seen.insert(canonical) {
+                files.push(file.to_path_buf());
+                dependents += 1;
+            }
+        }
+    }
+
+    Ok(ChangedFiles {
+        files,
+        affected_tables,
+        affected_functions,
+        dependents,
+        skipped,
+    })
+}
+
+/// Existing files that differ from `git_ref` in the working tree, plus
+/// untracked ones, relative to the current directory.
+fn changed_paths(git_ref: &str) -> Result<Vec<PathBuf>, String> {
+    let mut paths = git(&["diff", "--name-only", "--relative", git_ref, "--"])?;
+    paths.extend(git(&["ls-files", "--others", "--exclude-standard"])?);
+    let mut seen = BTreeSet::new();
+    Ok(paths
+        .into_iter()
+        .filter(|p| seen.insert(p.clone()))
+        .map(PathBuf::from)
+        .filter(|p| p.is_file())
+        .collect())
+}
+
+fn git(args: &[&str]) -> Result<Vec<String>, String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| format!("--changed-since needs git: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// The schema file as of `git_ref`. A schema that didn't exist or doesn't
+/// load there counts as empty, so every table in it is affected.
+fn previous_schema(git_ref: &str, schema_path: &Path, dialect: Dialect) -> Schema {
+    let extension = schema_path
+        .extension()
+        .map(|e| e.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    // `ref:./path` is relative to the current directory, so an absolute
+    // schema path is made relative to it first.
+    let relative = std::env::current_dir()
+        .and_then(|cwd| cwd.canonicalize())
+        .ok()
+        .zip(schema_path.canonicalize().ok())
+        .and_then(|(cwd, path)| path.strip_prefix(cwd).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| schema_path.to_path_buf());
+    let spec = format!("{git_ref}:./{}", report_path(&relative));
+    Command::new("git")
+        .args(["show", &spec])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| schema::load_file_schema(&o.stdout, &extension, dialect, schema_path).ok())
+        .unwrap_or_default()
+}
+
+/// If `path` is a `.sql` file with DDL, it's a migration: returns the
+/// tables and functions it touches. Ones it creates count even when the
+/// schema file already has them, since the migration may have been folded
+/// in already.
+fn migrated(
+    path: &Path,
+    schema: &Schema,
+    dialect: Dialect,
+) -> Option<(BTreeSet<String>, BTreeSet<String>)> {
+    if path.extension().map_or(true, |e| e != SQL_FILE_EXTENSION) {
+        return None;
+    }
+    let ddl = std::fs::read(path).ok()?;
+    let mut migrated = schema.clone();
+    schema::apply_ddl(&mut migrated, &ddl, dialect).ok()?;
+    let mut created = Schema::default();
+    schema::apply_ddl(&mut created, &ddl, dialect).ok()?;
+
+    let mut tables = schema.changed_tables(&migrated);
+    tables.extend(created.changed_tables(&Schema::default()));
+    let mut functions = schema.changed_functions(&migrated);
+    functions.extend(created.changed_functions(&Schema::default()));
+    (!tables.is_empty() || !functions.is_empty()).then_some((tables, functions))
+}
@@ -0,0 +1,150 @@
+//! `sqlshield extract`: every query the finders pick up, with where it was
+//! found, the SQL as parsed and what it references. For auditing the
+//! extraction, and for tools that want the queries without the checks.
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use serde::Serialize;
+use sqlshield::config::Config;
+use sqlshield::finder::{self, UnparsedKind};
+use sqlshield::{usage, Dialect};
+
+use crate::report::report_path;
+use crate::target::{self, Target};
+use crate::Args;
+
+#[derive(clap::Args, Debug)]
+pub struct ExtractArgs {
+    /// Files to read instead of walking the target directories.
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    files: Vec<PathBuf>,
+
+    /// Output format: one JSON array, or one JSON object per line.
+    #[arg(long, value_enum, default_value = "json")]
+    format: ExtractFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ExtractFormat {
+    Json,
+    Ndjson,
+}
+
+#[derive(Serialize)]
+struct Query {
+    file: String,
+    line: usize,
+    column: usize,
+    end_line: usize,
+    end_column: usize,
+    language: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    function: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<String>,
+    /// The SQL as parsed, placeholders substituted.
+    sql: String,
+    parsed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    tables: Vec<String>,
+    columns: Vec<String>,
+}
+
+/// Extract the queries of every target's files and write them to stdout.
+/// Files that can't be read are reported on stderr and skipped.
+pub fn run(
+    args: &Args,
+    extract: &ExtractArgs,
+    targets: &[Target],
+    config: &Config,
+) -> Result<(), String> {
+    let mut queries = Vec::new();
+    for (i, target) in targets.iter().enumerate() {
+        let files = target::files_of(targets, i, &extract.files);
+        for group in target::split_by_config(target, files, config, args)? {
+            let dialect = group.nested.as_ref().map_or(target.dialect, |n| n.dialect);
+            for file in group.included() {
+                match extract_file(&file, dialect, target.name.as_deref()) {
+                    Ok(found) => queries.extend(found),
+                    Err(err) => eprintln!("sqlshield: {}: {err}", report_path(&file)),
+                }
+            }
+        }
+    }
+
+    write(&queries, extract.format, &mut io::stdout().lock())
+        .map_err(|err| format!("failed to write queries: {err}"))
+}
+
+/// The parsed queries of `file`, and the strings that read like SQL but
+/// don't parse, in source order.
+fn extract_file(file: &Path, dialect: Dialect, target: Option<&str>) -> Result<Vec<Query>, String> {
+    let parser_dialect = dialect.as_sqlparser();
+    let extracted =
+        finder::extract_from_file(file, parser_dialect.as_ref()).map_err(|e| e.to_string())?;
+    let language = file
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(finder::language)
+        .unwrap_or_default();
+    let query = |line, column, end_line, end_column, sql| Query {
+        file: report_path(file),
+        line,
+        column,
+        end_line,
+        end_column,
+        language,
+        function: None,
+        target: target.map(str::to_string),
+        sql,
+        parsed: true,
+        error: None,
+        tables: Vec::new(),
+        columns: Vec::new(),
+    };
+
+    let mut queries: Vec<Query> = extracted
+        .queries
+        .into_iter()
+        .map(|q| Query {
+            tables: usage::relation_names(&q.statements).into_iter().collect(),
+            columns: usage::column_references(&q.statements)
+                .into_iter()
+                .collect(),
+            function: q.function,
+            ..query(q.line, q.column, q.end_line, q.end_column, q.text)
+        })
+        .collect();
+    queries.extend(
+        extracted
+            .unparsed
+            .into_iter()
+            .filter(|u| u.kind == UnparsedKind::Sql)
+            .map(|u| Query {
+                parsed: false,
+                error: Some(u.message),
+                ..query(u.line, u.column, u.end_line, u.end_column, u.text)
+            }),
+    );
+    queries.sort_by_key(|q| (q.line, q.column));
+    Ok(queries)
+}
+
+fn write(queries: &[Query], format: ExtractFormat, out: &mut dyn Write) -> io::Result<()> {
+    match format {
+        ExtractFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, queries)?;
+            writeln!(out)
+        }
+        ExtractFormat::Ndjson => {
+            for query in queries {
+                serde_json::to_writer(&mut *out, query)?;
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! `sqlshield impact --migration FILE`: the queries a pending migration
+//! would break. Each target's queries are validated against its schema as
+//! it is and with the migrations applied; only what the migrations break
+//! is reported, and any break fails the run so CI can gate destructive
+//! migrations.
+
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::ValueEnum;
+use serde::Serialize;
+use sqlshield::config::Config;
+use sqlshield::impact::{self, Breakage, Renames};
+use sqlshield::lint::{self, Severity};
+use sqlshield::schema;
+
+use crate::report::report_path;
+use crate::target::{self, Target};
+use crate::{Args, EXIT_VALIDATION_ERRORS};
+
+#[derive(clap::Args, Debug)]
+pub struct ImpactArgs {
+    /// DDL to apply on top of the schema (repeatable, applied in order).
+    #[arg(long, value_name = "FILE", required = true, value_hint = clap::ValueHint::FilePath)]
+    migration: Vec<PathBuf>,
+
+    /// Files to read instead of walking the target directories.
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    files: Vec<PathBuf>,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value = "text")]
+    format: ImpactFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ImpactFormat {
+    Text,
+    Json,
+}
+
+#[derive(Serialize)]
+struct Broken {
+    file: String,
+    line: usize,
+    column: usize,
+    end_line: usize,
+    end_column: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    function: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<String>,
+    sql: String,
+    reasons: Vec<String>,
+    errors: Vec<Error>,
+}
+
+#[derive(Serialize)]
+struct Error {
+    code: &'static str,
+    description: String,
+}
+
+/// Report what the migrations break in every target and fail when they
+/// break anything.
+pub fn run(
+    args: &Args,
+    impact_args: &ImpactArgs,
+    targets: &[Target],
+    config: &Config,
+) -> Result<ExitCode, String> {
+    let mut migrations = Vec::new();
+    for path in &impact_args.migration {
+        let ddl = std::fs::read(path)
+            .map_err(|err| format!("failed to read migration {}: {err}", path.display()))?;
+        migrations.push((path, ddl));
+    }
+    let migration_files: Vec<PathBuf> = impact_args
+        .migration
+        .iter()
+        .filter_map(|m| m.canonicalize().ok())
+        .collect();
+
+    let mut broken = Vec::new();
+    for (i, target) in targets.iter().enumerate() {
+        let context = |err: String| match &target.name {
+            Some(name) => format!("target `{name}`: {err}"),
+            None => err,
+        };
+        let files: Vec<PathBuf> = target::files_of(targets, i, &impact_args.files)
+            .into_iter()
+            .filter(|f| {
+                f.canonicalize()
+                    .map_or(true, |f| !migration_files.contains(&f))
+            })
+            .collect();
+        for group in target::split_by_config(target, files, config, args).map_err(context)? {
+            let files = group.included();
+            let (db_url, schema_path, dialect, group_config) = match &group.nested {
+                Some(nested) => (
+                    nested.db_url.as_deref(),
+                    &nested.schema_path,
+                    nested.dialect,
+                    &nested.config,
+                ),
+                None => (
+                    target.db_url.as_deref(),
+                    &target.schema_path,
+                    target.dialect,
+                    config,
+                ),
+            };
+            let before = crate::resolve_schema(db_url, schema_path, group_config, dialect)
+                .map_err(context)?;
+            let mut after = before.clone();
+            let mut renames = Renames::default();
+            for (path, ddl) in &migrations {
+                schema::apply_ddl(&mut after, ddl, dialect)
+                    .and_then(|()| renames.record(ddl, dialect))
+                    .map_err(|err| context(format!("{}: {err}", path.display())))?;
+            }
+            for breakage in impact::breakages(&files, &before, &after, &renames, dialect) {
+                let rules = group_config.rules.for_path(&breakage.path);
+                broken.extend(report(breakage, &rules, target.name.as_deref()));
+            }
+        }
+    }
+
+    let mut out = io::stdout().lock();
+    match impact_args.format {
+        ImpactFormat::Json => serde_json::to_writer_pretty(&mut out, &broken)
+            .map_err(io::Error::from)
+            .and_then(|()| writeln!(out)),
+        ImpactFormat::Text => write_text(&broken, &mut out),
+    }
+    .map_err(|err| format!("failed to write impact report: {err}"))?;
+
+    let migration = match impact_args.migration.as_slice() {
+        [one] => report_path(one),
+        many => format!("{} migrations", many.len()),
+    };
+    if broken.is_empty() {
+        eprintln!("sqlshield: {migration} breaks no queries");
+        Ok(ExitCode::SUCCESS)
+    } else {
+        eprintln!("sqlshield: {migration} breaks {} query(ies)", broken.len());
+        Ok(ExitCode::from(EXIT_VALIDATION_ERRORS))
+    }
+}
+
+/// `breakage` as reported, without the errors of rules set to off; `None`
+/// when that leaves none.
+fn report(breakage: Breakage, rules: &lint::RuleConfig, target: Option<&str>) -> Option<Broken> {
+    let errors: Vec<Error> = breakage
+        .errors
+        .into_iter()
+        .map(|error| Error {
+            code: error.code,
+            description: error.message,
+        })
+        .filter(|e| rules.severity(e.code) != Severity::Off)
+        .collect();
+    (!errors.is_empty()).then(|| Broken {
+        file: report_path(&breakage.path),
+        line: breakage.line,
+        column: breakage.column,
+        end_line: breakage.end_line,
+        end_column: breakage.end_column,
+        function: breakage.function,
+        target: target.map(str::to_string),
+        sql: breakage.sql,
+        reasons: breakage.reasons,
+        errors,
+    })
+}
+
+/// `path:line in function: reasons`, then the query's new errors.
+fn write_text(broken: &[Broken], out: &mut dyn Write) -> io::Result<()> {
+    for b in broken {
+        write!(out, "{}:{}", b.file, b.line)?;
+        if let Some(function) = &b.function {
+            write!(out, " in {function}")?;
+        }
+        if b.reasons.is_empty() {
+            writeln!(out, ": no longer validates")?;
+        } else {
+            writeln!(out, ": {}", b.reasons.join("; "))?;
+        }
+        for error in &b.errors {
+            writeln!(out, "  {} [{}]", error.description, error.code)?;
+        }
+    }
+    Ok(())
+}
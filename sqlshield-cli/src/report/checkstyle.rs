@@ -0,0 +1,36 @@
+//! `--format checkstyle`: Checkstyle XML, read by Jenkins warnings-ng and
+//! most code-review bots.
+
+use std::io::{self, Write};
+
+use super::{escape_xml, report_path, target_suffix, Report, Reporter};
+
+pub struct Checkstyle;
+
+impl Reporter for Checkstyle {
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(out, r#"<checkstyle version="4.3">"#)?;
+        for (path, found) in report.by_file() {
+            let name = escape_xml(&report_path(path));
+            if found.is_empty() {
+                writeln!(out, r#"  <file name="{name}"/>"#)?;
+                continue;
+            }
+            writeln!(out, r#"  <file name="{name}">"#)?;
+            for d in found {
+                writeln!(
+                    out,
+                    r#"    <error line="{}" column="{}" severity="{}" message="{}" source="sqlshield.{}"/>"#,
+                    d.line,
+                    d.column,
+                    d.severity,
+                    escape_xml(&format!("{}{}", d.description, target_suffix(d))),
+                    d.code
+                )?;
+            }
+            writeln!(out, "  </file>")?;
+        }
+        writeln!(out, "</checkstyle>")
+    }
+}
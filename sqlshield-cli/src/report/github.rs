@@ -0,0 +1,48 @@
+//! `--format github`: GitHub Actions workflow commands, which the runner
+//! turns into annotations on the pull request diff.
+
+use std::io::{self, Write};
+
+use sqlshield::lint::Severity;
+
+use super::{report_path, Report, Reporter};
+
+pub struct Github;
+
+impl Reporter for Github {
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        for d in report.diagnostics {
+            let command = match d.severity {
+                Severity::Error => "error",
+                _ => "warning",
+            };
+            writeln!(
+                out,
+                "::{command} file={},line={},col={},endLine={},endColumn={},title={}::{}",
+                escape_property(&report_path(&d.path)),
+                d.line,
+                d.column,
+                d.end_line,
+                d.end_column,
+                escape_property(&match &d.target {
+                    Some(target) => format!("sqlshield {} ({target})", d.code),
+                    None => format!("sqlshield {}", d.code),
+                }),
+                escape_data(&d.description)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Escape a command's message, per the runner's `toolkit/core` rules.
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a command property value, which also can't hold `:` or `,`.
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
@@ -0,0 +1,38 @@
+//! `--format json`: an array of `{location, severity, code, description}`,
+//! plus `target` in a multi-target run.
+
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use super::{Report, Reporter};
+
+#[derive(Serialize)]
+struct JsonErrorReport<'a> {
+    location: &'a str,
+    severity: &'static str,
+    code: &'a str,
+    description: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<&'a str>,
+}
+
+pub struct Json;
+
+impl Reporter for Json {
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        let reports: Vec<JsonErrorReport<'_>> = report
+            .diagnostics
+            .iter()
+            .map(|d| JsonErrorReport {
+                location: &d.location,
+                severity: d.severity.as_str(),
+                code: d.code,
+                description: &d.description,
+                target: d.target.as_deref(),
+            })
+            .collect();
+        serde_json::to_writer_pretty(&mut *out, &reports)?;
+        writeln!(out)
+    }
+}
@@ -0,0 +1,55 @@
+//! `--format junit`: JUnit XML with one testcase per checked file and one
+//! failure per finding, so CI test dashboards list files that pass too.
+
+use std::io::{self, Write};
+
+use super::{escape_xml, report_path, target_suffix, Report, Reporter};
+
+pub struct Junit;
+
+impl Reporter for Junit {
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        let files = report.by_file();
+        let tests = files.len();
+        let failures = files.iter().filter(|(_, found)| !found.is_empty()).count();
+
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            out,
+            r#"<testsuites name="sqlshield" tests="{tests}" failures="{failures}" errors="0">"#
+        )?;
+        writeln!(
+            out,
+            r#"  <testsuite name="sqlshield" tests="{tests}" failures="{failures}" errors="0">"#
+        )?;
+        for (path, found) in files {
+            let name = escape_xml(&report_path(path));
+            if found.is_empty() {
+                writeln!(
+                    out,
+                    r#"    <testcase classname="sqlshield" name="{name}"/>"#
+                )?;
+                continue;
+            }
+            writeln!(out, r#"    <testcase classname="sqlshield" name="{name}">"#)?;
+            for d in found {
+                writeln!(
+                    out,
+                    r#"      <failure type="{}" message="{}">{}:{}:{}: {}: {} [{}]{}</failure>"#,
+                    d.code,
+                    escape_xml(&d.description),
+                    name,
+                    d.line,
+                    d.column,
+                    d.severity,
+                    escape_xml(&d.description),
+                    d.code,
+                    target_suffix(d)
+                )?;
+            }
+            writeln!(out, "    </testcase>")?;
+        }
+        writeln!(out, "  </testsuite>")?;
+        writeln!(out, "</testsuites>")
+    }
+}
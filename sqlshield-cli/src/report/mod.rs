@@ -0,0 +1,111 @@
+//! Output formats for a directory run. Each `--format` is a [`Reporter`];
+//! adding one means a module here and a variant in [`OutputFormat`].
+
+mod checkstyle;
+mod github;
+mod json;
+mod junit;
+mod sarif;
+mod text;
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use sqlshield::validation::Diagnostic;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    /// SARIF 2.1.0, for code-scanning dashboards.
+    Sarif,
+    /// GitHub Actions workflow commands (`::error file=…`).
+    Github,
+    /// JUnit XML: one testcase per file, one failure per finding.
+    Junit,
+    /// Checkstyle XML, e.g. for Jenkins warnings-ng.
+    Checkstyle,
+}
+
+impl OutputFormat {
+    pub fn reporter(self) -> Box<dyn Reporter> {
+        match self {
+            Self::Text => Box::new(text::Text),
+            Self::Json => Box::new(json::Json),
+            Self::Sarif => Box::new(sarif::Sarif),
+            Self::Github => Box::new(github::Github),
+            Self::Junit => Box::new(junit::Junit),
+            Self::Checkstyle => Box::new(checkstyle::Checkstyle),
+        }
+    }
+
+    /// Whether the format can report a `--stdin` buffer, which has no file
+    /// or location.
+    pub fn supports_stdin(self) -> bool {
+        matches!(self, Self::Text | Self::Json)
+    }
+}
+
+/// What a run produced: every file checked, and the findings to report.
+pub struct Report<'a> {
+    pub files: &'a [PathBuf],
+    pub diagnostics: &'a [Diagnostic],
+}
+
+impl<'a> Report<'a> {
+    /// Each checked file with its findings, in `files` order.
+    fn by_file(&self) -> Vec<(&'a Path, Vec<&'a Diagnostic>)> {
+        let mut grouped: HashMap<&Path, Vec<&Diagnostic>> = HashMap::new();
+        for d in self.diagnostics {
+            grouped.entry(d.path.as_path()).or_default().push(d);
+        }
+        self.files
+            .iter()
+            .map(|file| {
+                (
+                    file.as_path(),
+                    grouped.remove(file.as_path()).unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+}
+
+pub trait Reporter {
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()>;
+}
+
+/// `path` as reports show it: forward slashes, no leading `./`, so it reads
+/// the same whichever way the directory was spelled and on any platform.
+pub fn report_path(path: &Path) -> String {
+    let path = path.strip_prefix(".").unwrap_or(path);
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// ` (target: name)` for a finding from a named target, as the text
+/// format prints it; empty otherwise.
+fn target_suffix(d: &Diagnostic) -> String {
+    d.target
+        .as_deref()
+        .map(|target| format!(" (target: {target})"))
+        .unwrap_or_default()
+}
+
+/// Escape text for an XML attribute or element.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
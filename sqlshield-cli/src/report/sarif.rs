@@ -0,0 +1,222 @@
+//! SARIF 2.1.0 output (`--format sarif`), the interchange format GitHub
+//! code scanning, GitLab and Azure DevOps read natively.
+//!
+//! Every catalog rule is listed in the driver with its default level, so
+//! viewers can show rule help even for rules with no results. Results
+//! point at the SQL string literal by its path relative to the working
+//! directory. Their fingerprint is that path plus the baseline's query hash
+//! and rule code, so alerts follow a query when it moves within a file but
+//! the same query in two files stays two alerts.
+
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+
+use serde::Serialize;
+use sqlshield::lint::{self, Severity};
+use sqlshield::validation::Diagnostic;
+
+use super::{report_path, Report, Reporter};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const INFORMATION_URI: &str = "https://github.com/davidsmfreire/sqlshield";
+
+pub struct Sarif;
+
+impl Reporter for Sarif {
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut *out, &log(report.diagnostics))?;
+        writeln!(out)
+    }
+}
+
+#[derive(Serialize)]
+struct Log<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run<'a> {
+    tool: Tool,
+    column_kind: &'static str,
+    results: Vec<SarifResult<'a>>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<ReportingDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    id: &'static str,
+    short_description: Message<'static>,
+    default_configuration: Configuration,
+}
+
+#[derive(Serialize)]
+struct Configuration {
+    level: &'static str,
+}
+
+#[derive(Serialize)]
+struct Message<'a> {
+    text: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult<'a> {
+    rule_id: &'static str,
+    rule_index: usize,
+    level: &'static str,
+    message: Message<'a>,
+    locations: Vec<Location>,
+    partial_fingerprints: Fingerprints,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    properties: Option<Properties<'a>>,
+}
+
+/// The `[[targets]]` entry a finding came from, in a multi-target run.
+#[derive(Serialize)]
+struct Properties<'a> {
+    target: &'a str,
+}
+
+#[derive(Serialize)]
+struct Fingerprints {
+    #[serde(rename = "sqlshield/v1")]
+    sqlshield: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactLocation {
+    uri: String,
+    uri_base_id: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Off => "none",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+fn log(diagnostics: &[Diagnostic]) -> Log<'_> {
+    let rules = lint::RULES
+        .iter()
+        .map(|rule| ReportingDescriptor {
+            id: rule.code,
+            short_description: Message {
+                text: rule.description,
+            },
+            default_configuration: Configuration {
+                level: level(rule.default_severity),
+            },
+        })
+        .collect();
+
+    let cwd = std::env::current_dir().ok();
+    let results = diagnostics
+        .iter()
+        .map(|d| (d, relative_path(&d.path, cwd.as_deref())))
+        .map(|(d, uri)| SarifResult {
+            rule_id: d.code,
+            rule_index: lint::RULES
+                .iter()
+                .position(|r| r.code == d.code)
+                .unwrap_or_default(),
+            level: level(d.severity),
+            message: Message {
+                text: &d.description,
+            },
+            locations: vec![Location {
+                physical_location: PhysicalLocation {
+                    artifact_location: ArtifactLocation {
+                        uri: uri.clone(),
+                        uri_base_id: "%SRCROOT%",
+                    },
+                    region: Region {
+                        start_line: d.line,
+                        start_column: d.column,
+                        end_line: d.end_line,
+                        end_column: d.end_column,
+                    },
+                },
+            }],
+            partial_fingerprints: Fingerprints {
+                sqlshield: format!("{uri}:{:016x}:{}", d.query_hash, d.code),
+            },
+            properties: d.target.as_deref().map(|target| Properties { target }),
+        })
+        .collect();
+
+    Log {
+        schema: SCHEMA,
+        version: "2.1.0",
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: "sqlshield",
+                    version: env!("CARGO_PKG_VERSION"),
+                    information_uri: INFORMATION_URI,
+                    rules,
+                },
+            },
+            column_kind: "unicodeCodePoints",
+            results,
+        }],
+    }
+}
+
+/// `path` relative to `cwd` (the `%SRCROOT%` uris resolve against), with
+/// `.` components dropped, in report form. Paths outside `cwd` stay as
+/// they are.
+fn relative_path(path: &Path, cwd: Option<&Path>) -> String {
+    let path = match cwd {
+        Some(cwd) if path.is_absolute() => path.strip_prefix(cwd).unwrap_or(path),
+        _ => path,
+    };
+    let normalized: PathBuf = path
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect();
+    report_path(&normalized)
+}
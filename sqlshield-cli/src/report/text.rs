@@ -0,0 +1,16 @@
+//! The default human-readable format: `path:line: error: description [code]`.
+
+use std::io::{self, Write};
+
+use super::{Report, Reporter};
+
+pub struct Text;
+
+impl Reporter for Text {
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        for diagnostic in report.diagnostics {
+            writeln!(out, "{diagnostic}")?;
+        }
+        Ok(())
+    }
+}
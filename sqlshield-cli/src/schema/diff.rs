@@ -0,0 +1,128 @@
+//! `sqlshield schema diff`: the tables, columns, types and keys the schema
+//! file and the live database disagree on, so a schema file that lags
+//! behind production shows up before it causes false passes or failures.
+
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use clap::ValueEnum;
+use serde::Serialize;
+use sqlshield::config::{Config, CONFIG_FILE_NAME};
+use sqlshield::schema::diff::{self, Drift, Side};
+
+use crate::report::report_path;
+use crate::target;
+use crate::{Args, EXIT_VALIDATION_ERRORS};
+
+const SCHEMA_SIDE: &str = "schema file";
+const DATABASE_SIDE: &str = "database";
+
+#[derive(clap::Args, Debug)]
+pub struct DiffArgs {
+    /// Output format.
+    #[arg(long, value_enum, default_value = "text")]
+    format: DiffFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum DiffFormat {
+    Text,
+    Json,
+}
+
+#[derive(Serialize)]
+struct Difference {
+    /// `table`, `column`, `type`, `nullability`, `primary_key` or
+    /// `foreign_key`.
+    kind: &'static str,
+    table: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<String>,
+    /// `schema` or `database`, for what only one side has.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    only_in: Option<&'static str>,
+    message: String,
+}
+
+/// Compare `--schema` (or `schema`) with `--db-url` (or `db_url`), print
+/// the differences and fail when there are any.
+pub fn run(args: &Args, diff_args: &DiffArgs, config: &Config) -> Result<ExitCode, String> {
+    if args.target.is_some() {
+        return Err(
+            "schema diff compares one schema file with one database; pass --schema and --db-url instead of --target"
+                .into(),
+        );
+    }
+    let schema_path = args
+        .schema
+        .clone()
+        .or_else(|| config.schema.clone())
+        .unwrap_or_else(|| target::in_dir(config.dir(), "schema.sql"));
+    let Some(db_url) = args.db_url.as_deref().or(config.db_url.as_deref()) else {
+        return Err(format!(
+            "schema diff needs a database: pass --db-url or set db_url in {CONFIG_FILE_NAME}"
+        ));
+    };
+    let dialect = args.dialect.or(config.dialect).unwrap_or_default();
+
+    let file = sqlshield::schema::load_schema_from_file(&schema_path, dialect)
+        .map_err(|e| format!("{}: {e}", schema_path.display()))?;
+    let database = crate::introspect_schema(db_url)?;
+    let differences: Vec<Difference> = diff::diff(&file, &database)
+        .iter()
+        .map(difference)
+        .collect();
+
+    let mut out = io::stdout().lock();
+    match diff_args.format {
+        DiffFormat::Json => serde_json::to_writer_pretty(&mut out, &differences)
+            .map_err(io::Error::from)
+            .and_then(|()| writeln!(out)),
+        DiffFormat::Text => differences
+            .iter()
+            .try_for_each(|d| writeln!(out, "{}", d.message)),
+    }
+    .map_err(|err| format!("failed to write schema diff: {err}"))?;
+
+    let schema_name = report_path(&schema_path);
+    if differences.is_empty() {
+        eprintln!("sqlshield: {schema_name} matches the database");
+        Ok(ExitCode::SUCCESS)
+    } else {
+        eprintln!(
+            "sqlshield: {schema_name} and the database differ in {} place(s)",
+            differences.len()
+        );
+        Ok(ExitCode::from(EXIT_VALIDATION_ERRORS))
+    }
+}
+
+fn difference(drift: &Drift) -> Difference {
+    let only_in = |side: &Side| {
+        Some(match side {
+            Side::Left => "schema",
+            Side::Right => "database",
+        })
+    };
+    let (kind, table, column, only_in) = match drift {
+        Drift::Table { table, only_in: s } => ("table", table, None, only_in(s)),
+        Drift::Column {
+            table,
+            column,
+            only_in: s,
+        } => ("column", table, Some(column), only_in(s)),
+        Drift::Type { table, column, .. } => ("type", table, Some(column), None),
+        Drift::Nullability { table, column, .. } => ("nullability", table, Some(column), None),
+        Drift::PrimaryKey { table, .. } => ("primary_key", table, None, None),
+        Drift::ForeignKey {
+            table, only_in: s, ..
+        } => ("foreign_key", table, None, only_in(s)),
+    };
+    Difference {
+        kind,
+        table: table.clone(),
+        column: column.cloned(),
+        only_in,
+        message: drift.describe(SCHEMA_SIDE, DATABASE_SIDE),
+    }
+}
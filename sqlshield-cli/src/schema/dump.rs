@@ -0,0 +1,48 @@
+//! `sqlshield schema dump`: the loaded schema as canonical DDL or JSON, so
+//! a database can be introspected once and the snapshot committed for CI
+//! runners that can't reach it.
+
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use clap::ValueEnum;
+use sqlshield::config::Config;
+use sqlshield::schema;
+
+use crate::target;
+use crate::Args;
+
+#[derive(clap::Args, Debug)]
+pub struct DumpArgs {
+    /// Output format: DDL, or sqlshield's versioned JSON.
+    #[arg(long, value_enum, default_value = "sql")]
+    format: DumpFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum DumpFormat {
+    Sql,
+    Json,
+}
+
+/// Load the schema from `--db-url` or `--schema` (or the config, or the
+/// `--target`) and write it to stdout.
+pub fn run(args: &Args, dump_args: &DumpArgs, config: &Config) -> Result<ExitCode, String> {
+    let [target] = target::resolve(args, config)?
+        .try_into()
+        .map_err(|_| "schema dump reads one schema; pick a target with --target".to_string())?;
+    let loaded = match &target.db_url {
+        Some(url) => crate::introspect_schema(url)?,
+        None => schema::load_schema_from_file(&target.schema_path, target.dialect)
+            .map_err(|e| e.to_string())?,
+    };
+    let dumped = match dump_args.format {
+        DumpFormat::Sql => schema::dump_sql(&loaded),
+        DumpFormat::Json => schema::dump_json(&loaded),
+    };
+    io::stdout()
+        .lock()
+        .write_all(dumped.as_bytes())
+        .map_err(|err| format!("failed to write schema: {err}"))?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,33 @@
+//! `sqlshield schema …`: commands about the schema itself rather than the
+//! queries checked against it.
+
+mod diff;
+mod dump;
+
+use std::process::ExitCode;
+
+use clap::Subcommand;
+use sqlshield::config::Config;
+
+use crate::Args;
+
+#[derive(clap::Args, Debug)]
+pub struct SchemaArgs {
+    #[command(subcommand)]
+    command: SchemaCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum SchemaCommand {
+    /// Compare the schema file with the live database and fail on drift.
+    Diff(diff::DiffArgs),
+    /// Write the schema, e.g. from --db-url, as canonical DDL or JSON.
+    Dump(dump::DumpArgs),
+}
+
+pub fn run(args: &Args, schema_args: &SchemaArgs, config: &Config) -> Result<ExitCode, String> {
+    match &schema_args.command {
+        SchemaCommand::Diff(diff_args) => diff::run(args, diff_args, config),
+        SchemaCommand::Dump(dump_args) => dump::run(args, dump_args, config),
+    }
+}
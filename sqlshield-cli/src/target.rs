@@ -0,0 +1,268 @@
+//! What a run checks. A config with `[[targets]]` (the services of a
+//! monorepo, each with its own schema and dialect) gives one target per
+//! entry; otherwise the flags and top-level config make a single unnamed
+//! target. `--directory`, `--schema` and `--db-url` always mean a single
+//! target. Files under a `.sqlshield.toml` of their own are checked with
+//! that config layered on top, see [`split_by_config`].
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use sqlshield::config::{self, Config, CONFIG_FILE_NAME};
+use sqlshield::walk::WalkOptions;
+use sqlshield::Dialect;
+
+use crate::Args;
+
+pub struct Target {
+    /// `None` for the single target of a config without `[[targets]]`;
+    /// findings are only tagged when there are named targets.
+    pub name: Option<String>,
+    pub directory: PathBuf,
+    /// Unused (empty) for a target read from `db_url`.
+    pub schema_path: PathBuf,
+    pub db_url: Option<String>,
+    pub dialect: Dialect,
+    pub walk: WalkOptions,
+}
+
+/// The targets a run covers, after `--target` selection.
+pub fn resolve(args: &Args, config: &Config) -> Result<Vec<Target>, String> {
+    // Only `schema diff` takes both; clap can't tell the subcommands apart
+    // for global flags.
+    if args.schema.is_some() && args.db_url.is_some() {
+        return Err("--schema and --db-url are mutually exclusive".into());
+    }
+    let explicit = args.directory.is_some() || args.schema.is_some() || args.db_url.is_some();
+    if explicit || config.targets.is_empty() {
+        if let Some(name) = &args.target {
+            return Err(format!(
+                "--target {name}: {CONFIG_FILE_NAME} declares no [[targets]]"
+            ));
+        }
+        let mut walk = config.walk.clone();
+        walk.exclude(&args.exclude)?;
+        return Ok(vec![Target {
+            name: None,
+            directory: args
+                .directory
+                .clone()
+                .or_else(|| config.directory.clone())
+                .unwrap_or_else(|| config.dir().to_path_buf()),
+            schema_path: args
+                .schema
+                .clone()
+                .or_else(|| config.schema.clone())
+                .unwrap_or_else(|| in_dir(config.dir(), "schema.sql")),
+            db_url: args.db_url.clone().or_else(|| config.db_url.clone()),
+            dialect: args.dialect.or(config.dialect).unwrap_or_default(),
+            walk,
+        }]);
+    }
+
+    let mut targets = Vec::new();
+    for t in &config.targets {
+        if args.target.as_ref().is_some_and(|name| *name != t.name) {
+            continue;
+        }
+        let mut walk = t.walk.clone();
+        walk.exclude(&args.exclude)?;
+        targets.push(Target {
+            name: Some(t.name.clone()),
+            directory: t.directory.clone(),
+            schema_path: t.schema.clone().unwrap_or_default(),
+            db_url: t.db_url.clone(),
+            dialect: args
+                .dialect
+                .or(t.dialect)
+                .or(config.dialect)
+                .unwrap_or_default(),
+            walk,
+        });
+    }
+    if targets.is_empty() {
+        let names: Vec<&str> = config.targets.iter().map(|t| t.name.as_str()).collect();
+        return Err(format!(
+            "no target named `{}` (have: {})",
+            args.target.as_deref().unwrap_or_default(),
+            names.join(", ")
+        ));
+    }
+    Ok(targets)
+}
+
+/// `name` in `dir`, without a leading `./` for the current directory.
+pub fn in_dir(dir: &Path, name: &str) -> PathBuf {
+    if dir == Path::new(".") {
+        PathBuf::from(name)
+    } else {
+        dir.join(name)
+    }
+}
+
+impl Target {
+    /// Whether `path` lies under the target's directory.
+    pub fn contains(&self, path: &Path) -> bool {
+        config::absolute(path).starts_with(config::absolute(&self.directory))
+    }
+
+    fn depth(&self) -> usize {
+        config::absolute(&self.directory).components().count()
+    }
+}
+
+/// Index of the target owning `path`: the one with the deepest directory
+/// containing it.
+pub fn owner(targets: &[Target], path: &Path) -> Option<usize> {
+    targets
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.contains(path))
+        .max_by_key(|(_, t)| t.depth())
+        .map(|(i, _)| i)
+}
+
+/// Files of target `i` for a run that reports on files rather than
+/// checking them: the ones its walk finds and no nested target owns, or
+/// those of `named` it owns, minus its schema file, unsupported files and
+/// files its globs exclude.
+pub fn files_of(targets: &[Target], i: usize, named: &[PathBuf]) -> Vec<PathBuf> {
+    let target = &targets[i];
+    if named.is_empty() {
+        return sqlshield::walk::walk(&target.directory, &target.walk)
+            .files
+            .into_iter()
+            .filter(|f| owner(targets, f) == Some(i))
+            .collect();
+    }
+    let schema_file = target.schema_path.canonicalize().ok();
+    named
+        .iter()
+        .filter(|f| {
+            sqlshield::finder::is_supported_file(f)
+                && owner(targets, f) == Some(i)
+                && target.walk.skip_reason(f).is_none()
+                && (schema_file.is_none() || f.canonicalize().ok() != schema_file)
+        })
+        .cloned()
+        .collect()
+}
+
+/// How to check files that sit under a config file the run's config
+/// doesn't include: their own configs, layered, with the flags still
+/// winning.
+pub struct Nested {
+    pub schema_path: PathBuf,
+    pub db_url: Option<String>,
+    pub dialect: Dialect,
+    /// The nested configs' globs, for files the run's walk let through.
+    pub walk: WalkOptions,
+    pub config: Config,
+}
+
+/// Files of a target that share their config files.
+pub struct Group {
+    /// `None` for files under the same configs as the run: the target as
+    /// resolved covers them.
+    pub nested: Option<Nested>,
+    pub files: Vec<PathBuf>,
+}
+
+impl Group {
+    /// The files the nested configs' globs don't exclude.
+    pub fn included(&self) -> Vec<PathBuf> {
+        let Some(nested) = &self.nested else {
+            return self.files.clone();
+        };
+        self.files
+            .iter()
+            .filter(|f| nested.walk.skip_reason(f).is_none())
+            .cloned()
+            .collect()
+    }
+}
+
+/// Split `files` of `target` by the config files that apply to them, the
+/// run's own group first.
+pub fn split_by_config(
+    target: &Target,
+    files: Vec<PathBuf>,
+    run_config: &Config,
+    args: &Args,
+) -> Result<Vec<Group>, String> {
+    let run_chain: Vec<PathBuf> = run_config
+        .files
+        .iter()
+        .map(|f| config::absolute(f))
+        .collect();
+    let mut chains: HashMap<PathBuf, (Vec<PathBuf>, Vec<PathBuf>)> = HashMap::new();
+    let mut groups: Vec<(Vec<PathBuf>, Vec<PathBuf>, Vec<PathBuf>)> = Vec::new();
+    for file in files {
+        let dir = file.parent().unwrap_or(Path::new("")).to_path_buf();
+        let dir = if dir.as_os_str().is_empty() {
+            PathBuf::from(".")
+        } else {
+            dir
+        };
+        let (found, key) = chains.entry(dir.clone()).or_insert_with(|| {
+            let found = config::find(&dir);
+            let key = found.iter().map(|f| config::absolute(f)).collect();
+            (found, key)
+        });
+        let key = if *key == run_chain {
+            Vec::new()
+        } else {
+            key.clone()
+        };
+        match groups.iter_mut().find(|(k, _, _)| *k == key) {
+            Some((_, _, group)) => group.push(file),
+            None => groups.push((key, found.clone(), vec![file])),
+        }
+    }
+    groups.sort_by_key(|(key, _, _)| !key.is_empty());
+
+    let mut split = Vec::new();
+    for (key, found, files) in groups {
+        if key.is_empty() {
+            split.push(Group {
+                nested: None,
+                files,
+            });
+            continue;
+        }
+        let config = config::load_chain(&found)?;
+        let own = target
+            .name
+            .as_ref()
+            .and_then(|name| config.targets.iter().find(|t| t.name == *name));
+        let (schema_path, db_url) = if args.schema.is_some() || args.db_url.is_some() {
+            (target.schema_path.clone(), target.db_url.clone())
+        } else if let Some(t) = own {
+            (t.schema.clone().unwrap_or_default(), t.db_url.clone())
+        } else if config.schema.is_some() || config.db_url.is_some() {
+            (
+                config.schema.clone().unwrap_or_default(),
+                config.db_url.clone(),
+            )
+        } else {
+            (target.schema_path.clone(), target.db_url.clone())
+        };
+        let dialect = args
+            .dialect
+            .or(own.and_then(|t| t.dialect))
+            .or(config.dialect)
+            .unwrap_or(target.dialect);
+        let walk = own.map_or_else(|| config.walk.clone(), |t| t.walk.clone());
+        split.push(Group {
+            nested: Some(Nested {
+                schema_path,
+                db_url,
+                dialect,
+                walk,
+                config,
+            }),
+            files,
+        });
+    }
+    Ok(split)
+}
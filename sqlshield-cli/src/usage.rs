@@ -0,0 +1,267 @@
+//! `sqlshield usage`: where each schema table and column is read and
+//! written, and what no query uses. Reported per schema, since targets and
+//! nested configs can each have their own.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use serde::Serialize;
+use sqlshield::config::Config;
+use sqlshield::schema::Schema;
+use sqlshield::usage::{self, Access, Use};
+
+use crate::report::report_path;
+use crate::target::{self, Target};
+use crate::Args;
+
+#[derive(clap::Args, Debug)]
+pub struct UsageArgs {
+    /// Files to read instead of walking the target directories.
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    files: Vec<PathBuf>,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value = "text")]
+    format: UsageFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum UsageFormat {
+    Text,
+    Json,
+}
+
+/// The uses of one schema, by the files checked against it.
+struct SchemaUses {
+    target: Option<String>,
+    /// The schema file, or `None` for a live database.
+    schema_path: Option<PathBuf>,
+    schema: Schema,
+    uses: Vec<Use>,
+}
+
+#[derive(Serialize, PartialEq, Eq, PartialOrd, Ord)]
+struct Place {
+    file: String,
+    line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    function: Option<String>,
+}
+
+#[derive(Serialize, Default)]
+struct Accesses {
+    reads: Vec<Place>,
+    writes: Vec<Place>,
+}
+
+#[derive(Serialize)]
+struct ColumnReport {
+    column: String,
+    #[serde(flatten)]
+    accesses: Accesses,
+}
+
+#[derive(Serialize)]
+struct TableReport {
+    table: String,
+    #[serde(flatten)]
+    accesses: Accesses,
+    columns: Vec<ColumnReport>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<String>,
+    /// The schema file; absent for a live database.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schema: Option<String>,
+    tables: Vec<TableReport>,
+    unused_tables: Vec<String>,
+    /// `table.column`.
+    unused_columns: Vec<String>,
+}
+
+/// Collect the uses of every target's schema by its files and write the
+/// report to stdout.
+pub fn run(
+    args: &Args,
+    usage_args: &UsageArgs,
+    targets: &[Target],
+    config: &Config,
+) -> Result<(), String> {
+    let mut schemas: Vec<SchemaUses> = Vec::new();
+    for (i, target) in targets.iter().enumerate() {
+        let context = |err: String| match &target.name {
+            Some(name) => format!("target `{name}`: {err}"),
+            None => err,
+        };
+        let files = target::files_of(targets, i, &usage_args.files);
+        for group in target::split_by_config(target, files, config, args).map_err(context)? {
+            let files = group.included();
+            let (db_url, schema_path, dialect, group_config) = match &group.nested {
+                Some(nested) => (
+                    nested.db_url.as_deref(),
+                    &nested.schema_path,
+                    nested.dialect,
+                    &nested.config,
+                ),
+                None => (
+                    target.db_url.as_deref(),
+                    &target.schema_path,
+                    target.dialect,
+                    config,
+                ),
+            };
+            let schema_path = db_url.is_none().then(|| schema_path.clone());
+            let index = match schemas
+                .iter()
+                .position(|s| s.target == target.name && s.schema_path == schema_path)
+            {
+                Some(index) => index,
+                None => {
+                    let schema = crate::resolve_schema(
+                        db_url,
+                        schema_path.as_deref().unwrap_or(Path::new("")),
+                        group_config,
+                        dialect,
+                    )
+                    .map_err(context)?;
+                    schemas.push(SchemaUses {
+                        target: target.name.clone(),
+                        schema_path,
+                        schema,
+                        uses: Vec::new(),
+                    });
+                    schemas.len() - 1
+                }
+            };
+            let entry = &mut schemas[index];
+            entry
+                .uses
+                .extend(usage::uses(&files, &entry.schema, dialect));
+        }
+    }
+
+    let reports: Vec<Report> = schemas.iter().map(report).collect();
+    let mut out = io::stdout().lock();
+    match usage_args.format {
+        UsageFormat::Json => serde_json::to_writer_pretty(&mut out, &reports)
+            .map_err(io::Error::from)
+            .and_then(|()| writeln!(out)),
+        UsageFormat::Text => write_text(&reports, &mut out),
+    }
+    .map_err(|err| format!("failed to write usage report: {err}"))
+}
+
+fn report(schema_uses: &SchemaUses) -> Report {
+    let mut tables: BTreeMap<&str, (Accesses, BTreeMap<&str, Accesses>)> = BTreeMap::new();
+    for u in &schema_uses.uses {
+        let place = Place {
+            file: report_path(&u.path),
+            line: u.line,
+            function: u.function.clone(),
+        };
+        let (table, columns) = tables.entry(&u.reference.table).or_default();
+        let accesses = match &u.reference.column {
+            Some(column) => columns.entry(column).or_default(),
+            None => table,
+        };
+        match u.reference.access {
+            Access::Read => accesses.reads.push(place),
+            Access::Write => accesses.writes.push(place),
+        }
+    }
+
+    let unused = usage::unused(&schema_uses.schema, &schema_uses.uses);
+    Report {
+        target: schema_uses.target.clone(),
+        schema: schema_uses.schema_path.as_deref().map(report_path),
+        tables: tables
+            .into_iter()
+            .map(|(table, (accesses, columns))| TableReport {
+                table: table.to_string(),
+                accesses: sorted(accesses),
+                columns: columns
+                    .into_iter()
+                    .map(|(column, accesses)| ColumnReport {
+                        column: column.to_string(),
+                        accesses: sorted(accesses),
+                    })
+                    .collect(),
+            })
+            .collect(),
+        unused_tables: unused.tables,
+        unused_columns: unused
+            .columns
+            .into_iter()
+            .map(|(table, column)| format!("{table}.{column}"))
+            .collect(),
+    }
+}
+
+fn sorted(mut accesses: Accesses) -> Accesses {
+    for places in [&mut accesses.reads, &mut accesses.writes] {
+        places.sort();
+        places.dedup();
+    }
+    accesses
+}
+
+fn write_text(reports: &[Report], out: &mut dyn Write) -> io::Result<()> {
+    for (i, report) in reports.iter().enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        if reports.len() > 1 {
+            let mut heading = match &report.target {
+                Some(target) => format!("target {target}"),
+                None => "schema".to_string(),
+            };
+            match &report.schema {
+                Some(schema) => heading.push_str(&format!(" ({schema})")),
+                None => heading.push_str(" (live database)"),
+            }
+            writeln!(out, "== {heading} ==")?;
+        }
+        for table in &report.tables {
+            writeln!(out, "{}", table.table)?;
+            write_places(&table.accesses, out)?;
+            for column in &table.columns {
+                writeln!(out, "{}.{}", table.table, column.column)?;
+                write_places(&column.accesses, out)?;
+            }
+        }
+        if !report.unused_tables.is_empty() || !report.unused_columns.is_empty() {
+            writeln!(out, "Unused (dead-schema candidates):")?;
+            for table in &report.unused_tables {
+                writeln!(out, "  table   {table}")?;
+            }
+            for column in &report.unused_columns {
+                writeln!(out, "  column  {column}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_places(accesses: &Accesses, out: &mut dyn Write) -> io::Result<()> {
+    let places = (accesses.reads.iter().map(|p| (Access::Read, p)))
+        .chain(accesses.writes.iter().map(|p| (Access::Write, p)));
+    for (access, place) in places {
+        write!(
+            out,
+            "  {:<6} {}:{}",
+            access.as_str(),
+            place.file,
+            place.line
+        )?;
+        match &place.function {
+            Some(function) => writeln!(out, " in {function}")?,
+            None => writeln!(out)?,
+        }
+    }
+    Ok(())
+}
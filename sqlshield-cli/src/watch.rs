@@ -0,0 +1,349 @@
+//! `--watch`: keep the schema and every file's findings in memory and
+//! re-check only the files that changed. Changes arrive as file system
+//! events from `notify`, debounced so a save or a branch switch is handled
+//! as one batch. Files are checked under the config files that apply to
+//! them, as in a one-off run. A change to a schema input — the target's
+//! schema file, a nested config's schema file, any `.sql` file under the
+//! directory (migrations included), any `.sqlshield.toml` or the baseline —
+//! reloads and re-checks everything, re-reading a `db_url` schema from the
+//! database.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use notify_debouncer_mini::notify::RecursiveMode;
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, DebouncedEventKind};
+use sqlshield::config::{self, Config, CONFIG_FILE_NAME};
+use sqlshield::lint::Severity;
+use sqlshield::schema::Schema;
+use sqlshield::validation::Diagnostic;
+
+use crate::report::{OutputFormat, Report};
+use crate::target::Target;
+use crate::{baseline, check_target_files, Args, NestedSchemas};
+
+/// How long a file must be quiet before its events are handled.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// What a reload produces.
+pub struct Loaded {
+    pub target: Target,
+    pub config: Config,
+    pub schema: Schema,
+    pub baseline: Vec<baseline::Entry>,
+}
+
+/// A loaded target and what has been checked against it.
+struct State {
+    loaded: Loaded,
+    nested_schemas: NestedSchemas,
+    /// The walked files, in walk order.
+    files: Vec<PathBuf>,
+    /// Findings of every checked file. Files the nested configs' globs
+    /// exclude have none and aren't reported.
+    results: HashMap<PathBuf, Vec<Diagnostic>>,
+    excluded: HashSet<PathBuf>,
+    /// `.sql` files under the directory, as [`file_key`]s.
+    sql_files: HashSet<PathBuf>,
+}
+
+/// Watch `directory` until interrupted. `load` re-reads the config, the
+/// schema and the baseline at `baseline_path`.
+pub fn run(
+    args: &Args,
+    directory: &Path,
+    baseline_path: &Path,
+    load: impl Fn() -> Result<Loaded, String>,
+) -> io::Result<()> {
+    let (tx, rx) = mpsc::channel::<DebounceEventResult>();
+    let mut debouncer = new_debouncer(DEBOUNCE, tx).map_err(io::Error::other)?;
+    let mut watched = BTreeSet::new();
+    let mut watch = |path: &Path, mode: RecursiveMode| -> io::Result<()> {
+        let path = config::absolute(path);
+        if path.is_dir() && watched.insert(path.clone()) {
+            debouncer
+                .watcher()
+                .watch(&path, mode)
+                .map_err(io::Error::other)?;
+        }
+        Ok(())
+    };
+    let root = file_key(directory);
+    watch(directory, RecursiveMode::Recursive)?;
+
+    let mut state: Option<State> = None;
+    let mut reload = Some("checked everything");
+    loop {
+        if let Some(status) = reload.take() {
+            state = match load().and_then(|loaded| State::check_all(loaded, args)) {
+                Ok(state) => Some(state),
+                Err(err) => {
+                    wait_for_fix(&err)?;
+                    None
+                }
+            };
+            // Inputs outside the directory are watched through their own
+            // directories; a `.sqlshield.toml` may appear in the current one.
+            let mut inputs = vec![baseline_path.to_path_buf(), PathBuf::from(CONFIG_FILE_NAME)];
+            if let Some(state) = &state {
+                inputs.extend(state.schema_inputs());
+                inputs.extend(state.loaded.config.files.iter().cloned());
+            }
+            for input in &inputs {
+                if !file_key(input).starts_with(&root) {
+                    let parent = config::absolute(input);
+                    watch(
+                        parent.parent().unwrap_or(&parent),
+                        RecursiveMode::NonRecursive,
+                    )?;
+                }
+            }
+            if let Some(state) = &state {
+                state.render(status)?;
+            }
+        }
+
+        let events = match rx.recv() {
+            Ok(Ok(events)) => events,
+            Ok(Err(err)) => return Err(io::Error::other(err)),
+            Err(_) => return Ok(()),
+        };
+        // A path still being written to is reported again once it settles.
+        let changed: HashSet<PathBuf> = events
+            .iter()
+            .filter(|e| e.kind == DebouncedEventKind::Any)
+            .map(|e| file_key(&e.path))
+            .collect();
+        if changed.is_empty() {
+            continue;
+        }
+
+        // Until everything loads, any change may be the fix.
+        let reloads = match &mut state {
+            Some(state) => state.reloads_on(&changed, baseline_path),
+            None => true,
+        };
+        if reloads {
+            reload = Some("schema changed, re-checked everything");
+            continue;
+        }
+        let Some(current) = &mut state else {
+            continue;
+        };
+        match current.recheck(&changed, args) {
+            Ok(Some(status)) => current.render(&status)?,
+            Ok(None) => {}
+            Err(err) => {
+                wait_for_fix(&err)?;
+                state = None;
+            }
+        }
+    }
+}
+
+impl State {
+    fn check_all(loaded: Loaded, args: &Args) -> Result<Self, String> {
+        let mut state = Self {
+            loaded,
+            nested_schemas: NestedSchemas::new(),
+            files: Vec::new(),
+            results: HashMap::new(),
+            excluded: HashSet::new(),
+            sql_files: HashSet::new(),
+        };
+        state.files = state.walk();
+        state.sql_files = state.walk_sql();
+        let files = state.files.clone();
+        state.check(files, args)?;
+        Ok(state)
+    }
+
+    fn walk(&self) -> Vec<PathBuf> {
+        let target = &self.loaded.target;
+        sqlshield::walk::walk(&target.directory, &target.walk).files
+    }
+
+    fn walk_sql(&self) -> HashSet<PathBuf> {
+        sqlshield::walk::sql_files(&self.loaded.target.directory)
+            .iter()
+            .map(|f| file_key(f))
+            .collect()
+    }
+
+    /// Whether `changed` touches a schema input, so everything is reloaded.
+    fn reloads_on(&mut self, changed: &HashSet<PathBuf>, baseline_path: &Path) -> bool {
+        let inputs: HashSet<PathBuf> = self.schema_inputs().map(|p| file_key(&p)).collect();
+        let baseline = file_key(baseline_path);
+        let is_sql = |path: &&PathBuf| {
+            path.extension()
+                .is_some_and(|e| e == sqlshield::finder::SQL_FILE_EXTENSION)
+        };
+        if changed.iter().any(|path| {
+            inputs.contains(path)
+                || *path == baseline
+                || path.file_name().is_some_and(|n| n == CONFIG_FILE_NAME)
+                || self.sql_files.contains(path)
+        }) {
+            return true;
+        }
+        // A new `.sql` file counts unless the walk leaves it out.
+        if changed.iter().filter(is_sql).any(|path| path.is_file()) {
+            self.sql_files = self.walk_sql();
+            return changed.iter().any(|path| self.sql_files.contains(path));
+        }
+        false
+    }
+
+    /// Re-check the files among `changed` and pick up added and removed
+    /// ones. Returns the status line, or `None` if no file changed.
+    fn recheck(
+        &mut self,
+        changed: &HashSet<PathBuf>,
+        args: &Args,
+    ) -> Result<Option<String>, String> {
+        let known: HashMap<PathBuf, &PathBuf> =
+            self.files.iter().map(|f| (file_key(f), f)).collect();
+        let modified: HashSet<PathBuf> = changed
+            .iter()
+            .filter_map(|path| known.get(path).map(|f| (*f).clone()))
+            .collect();
+        // Edits to known files keep the file list; anything else (a new,
+        // deleted or renamed file) needs a fresh walk.
+        if changed
+            .iter()
+            .any(|path| !known.contains_key(path) || !path.is_file())
+        {
+            self.files = self.walk();
+        }
+
+        let current: HashSet<&PathBuf> = self.files.iter().collect();
+        let before = self.results.len() + self.excluded.len();
+        self.results.retain(|f, _| current.contains(f));
+        self.excluded.retain(|f| current.contains(f));
+        let removed = before - self.results.len() - self.excluded.len();
+        let stale: Vec<PathBuf> = self
+            .files
+            .iter()
+            .filter(|f| {
+                modified.contains(*f)
+                    || !(self.results.contains_key(*f) || self.excluded.contains(*f))
+            })
+            .cloned()
+            .collect();
+        if stale.is_empty() && removed == 0 {
+            return Ok(None);
+        }
+        let checked = stale.len();
+        self.check(stale, args)?;
+        Ok(Some(match (checked, removed) {
+            (n, 0) => format!("re-checked {n} file(s)"),
+            (0, r) => format!("{r} file(s) removed"),
+            (n, r) => format!("re-checked {n} file(s), {r} removed"),
+        }))
+    }
+
+    /// Check `files`, replacing their findings. Fails if a nested config's
+    /// schema doesn't load.
+    fn check(&mut self, files: Vec<PathBuf>, args: &Args) -> Result<(), String> {
+        let loaded = &self.loaded;
+        let (checked, found) = check_target_files(
+            &loaded.target,
+            files.clone(),
+            &loaded.config,
+            args,
+            &loaded.schema,
+            &mut self.nested_schemas,
+            &mut Vec::new(),
+        )?;
+        let mut by_file: HashMap<PathBuf, Vec<Diagnostic>> = HashMap::new();
+        for d in found {
+            by_file.entry(d.path.clone()).or_default().push(d);
+        }
+        let checked: HashSet<PathBuf> = checked.into_iter().collect();
+        for file in files {
+            if checked.contains(&file) {
+                self.excluded.remove(&file);
+                let diagnostics = by_file.remove(&file).unwrap_or_default();
+                self.results.insert(file, diagnostics);
+            } else {
+                self.results.remove(&file);
+                self.excluded.insert(file);
+            }
+        }
+        Ok(())
+    }
+
+    /// Schema files whose change reloads everything: the target's and the
+    /// ones nested configs point at.
+    fn schema_inputs(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        let target = &self.loaded.target;
+        let own = (target.db_url.is_none()).then(|| target.schema_path.clone());
+        own.into_iter().chain(
+            self.nested_schemas
+                .values()
+                .map(|(schema_path, _)| schema_path.clone())
+                .filter(|p| !p.as_os_str().is_empty()),
+        )
+    }
+
+    fn render(&self, status: &str) -> io::Result<()> {
+        let files: Vec<PathBuf> = self
+            .files
+            .iter()
+            .filter(|f| self.results.contains_key(*f))
+            .cloned()
+            .collect();
+        let diagnostics: Vec<Diagnostic> = files
+            .iter()
+            .flat_map(|f| self.results[f].iter().cloned())
+            .collect();
+        let (diagnostics, _stale) =
+            baseline::apply(diagnostics, self.loaded.baseline.clone(), None);
+
+        clear_screen()?;
+        let mut out = io::stdout().lock();
+        let report = Report {
+            files: &files,
+            diagnostics: &diagnostics,
+        };
+        OutputFormat::Text.reporter().write(&report, &mut out)?;
+        let errors = diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .count();
+        let warnings = diagnostics.len() - errors;
+        writeln!(
+            out,
+            "-- {errors} error(s), {warnings} warning(s) in {} file(s); {status}. Watching for changes (Ctrl-C to stop).",
+            files.len()
+        )?;
+        out.flush()
+    }
+}
+
+fn wait_for_fix(err: &str) -> io::Result<()> {
+    clear_screen()?;
+    println!("sqlshield: {err}");
+    println!("-- waiting for the schema or config to be fixed (Ctrl-C to stop)");
+    io::stdout().flush()
+}
+
+/// `path` as events name it: canonical while it exists, so symlinked
+/// temp directories and relative spellings compare equal.
+fn file_key(path: &Path) -> PathBuf {
+    path.canonicalize()
+        .unwrap_or_else(|_| config::absolute(path))
+}
+
+/// Clear the terminal before redrawing. Left out when stdout is piped so
+/// the output stays plain text.
+fn clear_screen() -> io::Result<()> {
+    let mut out = io::stdout();
+    if out.is_terminal() {
+        write!(out, "\x1b[2J\x1b[H")?;
+    }
+    Ok(())
+}
@@ -118,6 +118,43 @@ fn cli_flag_overrides_config_file() {
     );
 }
 
+#[test]
+fn config_functions_allow_extension_calls() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE places (id INT, geom TEXT);",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        b"q = \"SELECT st_distance(geom, geom) FROM places\"\n",
+    )
+    .unwrap();
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("`st_distance`"));
+
+    fs::write(
+        dir.path().join(".sqlshield.toml"),
+        br#"functions = ["ST_Distance"]"#,
+    )
+    .unwrap();
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "stdout: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
+
 #[test]
 fn malformed_config_file_returns_exit_two() {
     let dir = tempfile::tempdir().unwrap();
@@ -156,3 +193,1329 @@ fn json_format_produces_valid_json_on_directory_scan() {
         assert!(item.get("description").is_some());
     }
 }
+
+#[test]
+fn lint_warnings_pass_unless_escalated() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INT, name TEXT);",
+    )
+    .unwrap();
+    fs::write(dir.path().join("app.py"), b"q = \"SELECT * FROM users\"\n").unwrap();
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {stdout}");
+    assert!(stdout.contains("warning:") && stdout.contains("[select-star]"));
+
+    fs::write(
+        dir.path().join(".sqlshield.toml"),
+        b"[rules]\nselect-star = \"error\"\n",
+    )
+    .unwrap();
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+
+    fs::write(
+        dir.path().join(".sqlshield.toml"),
+        b"[rules]\nselect-star = false\n",
+    )
+    .unwrap();
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn unknown_rule_in_config_returns_exit_two() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join(".sqlshield.toml"),
+        b"[rules]\nselect-starr = \"off\"\n",
+    )
+    .unwrap();
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown rule `select-starr`"));
+}
+
+#[test]
+fn rules_and_path_overrides_tune_validation_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INT);",
+    )
+    .unwrap();
+    fs::create_dir(dir.path().join("scripts")).unwrap();
+    fs::write(
+        dir.path().join("scripts/backfill.py"),
+        b"q = \"SELECT nickname FROM users\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join(".sqlshield.toml"),
+        b"[[overrides]]\npaths = [\"scripts/**\"]\nrules = { unknown-column = \"warning\" }\n",
+    )
+    .unwrap();
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {stdout}");
+    assert!(stdout.contains("warning:") && stdout.contains("[unknown-column]"));
+
+    fs::write(
+        dir.path().join(".sqlshield.toml"),
+        b"[rules]\nunknown-column = \"off\"\n",
+    )
+    .unwrap();
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn baseline_hides_recorded_findings_and_reports_stale_ones() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INT);",
+    )
+    .unwrap();
+    let app = dir.path().join("app.py");
+    fs::write(&app, b"q = \"SELECT nickname FROM users\"\n").unwrap();
+    let run = || {
+        Command::new(cli_bin())
+            .current_dir(dir.path())
+            .output()
+            .unwrap()
+    };
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .arg("--write-baseline")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let written = fs::read_to_string(dir.path().join(".sqlshield-baseline.json")).unwrap();
+    assert!(
+        written.contains("\"code\": \"unknown-column\""),
+        "{written}"
+    );
+
+    // Line shifts and reformatting keep the fingerprint.
+    fs::write(
+        &app,
+        b"import db\n\n\nq = \"select   nickname from users\"\n",
+    )
+    .unwrap();
+    let output = run();
+    assert!(output.status.success(), "{output:?}");
+    assert!(output.stdout.is_empty());
+
+    // New findings still fail the run.
+    fs::write(
+        &app,
+        b"q = \"SELECT nickname FROM users\"\nr = \"SELECT email FROM users\"\n",
+    )
+    .unwrap();
+    let output = run();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("email") && !stdout.contains("nickname"),
+        "{stdout}"
+    );
+
+    // Fixed findings are reported for cleanup without failing.
+    fs::write(&app, b"q = \"SELECT id FROM users\"\n").unwrap();
+    let output = run();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("1 stale baseline entry(ies)") && stderr.contains("app.py"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn baseline_entries_of_deleted_files_are_stale_on_a_full_run() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INT);",
+    )
+    .unwrap();
+    let old = dir.path().join("old.py");
+    fs::write(&old, b"q = \"SELECT nickname FROM users\"\n").unwrap();
+    fs::write(dir.path().join("app.py"), b"q = \"SELECT id FROM users\"\n").unwrap();
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .arg("--write-baseline")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    fs::remove_file(&old).unwrap();
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("1 stale baseline entry(ies)") && stderr.contains("old.py"),
+        "{stderr}"
+    );
+
+    // A run over named files leaves other files' entries alone.
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .arg("app.py")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    assert!(output.stderr.is_empty(), "{output:?}");
+}
+
+#[test]
+fn sarif_output_has_rules_regions_and_fingerprints() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INT);",
+    )
+    .unwrap();
+    fs::create_dir(dir.path().join("src")).unwrap();
+    fs::write(
+        dir.path().join("src/app.py"),
+        b"import db\nq = \"SELECT nickname FROM users\"\n",
+    )
+    .unwrap();
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["--format", "sarif"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let log: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+    assert!(rules
+        .iter()
+        .any(|r| r["id"] == "select-star" && r["defaultConfiguration"]["level"] == "warning"));
+
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "unknown-column");
+    assert_eq!(result["level"], "error");
+    assert_eq!(
+        rules[result["ruleIndex"].as_u64().unwrap() as usize]["id"],
+        "unknown-column"
+    );
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "src/app.py");
+    let region = &location["region"];
+    assert_eq!(
+        (
+            &region["startLine"],
+            &region["startColumn"],
+            &region["endColumn"]
+        ),
+        (&2.into(), &5.into(), &33.into())
+    );
+    let fingerprint = result["partialFingerprints"]["sqlshield/v1"]
+        .as_str()
+        .unwrap()
+        .to_owned();
+    assert!(
+        fingerprint.starts_with("src/app.py:") && fingerprint.ends_with(":unknown-column"),
+        "{fingerprint}"
+    );
+
+    // Spelling the directory as an absolute path reports the same location
+    // and fingerprint.
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["--format", "sarif", "-d"])
+        .arg(dir.path().canonicalize().unwrap())
+        .output()
+        .unwrap();
+    let log: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    let result = &log["runs"][0]["results"][0];
+    assert_eq!(
+        result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "src/app.py"
+    );
+    assert_eq!(result["partialFingerprints"]["sqlshield/v1"], fingerprint);
+
+    // The same query in another file is a separate alert.
+    fs::write(
+        dir.path().join("src/other.py"),
+        b"import db\nq = \"SELECT nickname FROM users\"\n",
+    )
+    .unwrap();
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["--format", "sarif"])
+        .output()
+        .unwrap();
+    let log: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    let fingerprints: std::collections::HashSet<&str> = log["runs"][0]["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["partialFingerprints"]["sqlshield/v1"].as_str().unwrap())
+        .collect();
+    assert_eq!(fingerprints.len(), 2, "{fingerprints:?}");
+    fs::remove_file(dir.path().join("src/other.py")).unwrap();
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["--stdin", "--format", "sarif"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+}
+
+fn ci_format_fixture() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INT);",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("clean.py"),
+        b"q = \"SELECT id FROM users\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("bad.py"),
+        b"q = \"SELECT nickname FROM users\"\nr = \"SELECT * FROM users\"\n",
+    )
+    .unwrap();
+    dir
+}
+
+fn run_format(dir: &tempfile::TempDir, format: &str) -> String {
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["--format", format])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn github_format_emits_workflow_commands() {
+    let dir = ci_format_fixture();
+    let stdout = run_format(&dir, "github");
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "::error file=bad.py,line=1,col=5,endLine=1,endColumn=33,title=sqlshield unknown-column::Column `nickname` not found in table `users`",
+            "::warning file=bad.py,line=2,col=5,endLine=2,endColumn=26,title=sqlshield select-star::`SELECT *` depends on the table's column order and width; list the columns explicitly",
+        ]
+    );
+}
+
+#[test]
+fn junit_format_has_a_testcase_per_file() {
+    let dir = ci_format_fixture();
+    let stdout = run_format(&dir, "junit");
+    assert!(stdout.starts_with("<?xml"));
+    assert!(stdout.contains(r#"<testsuite name="sqlshield" tests="2" failures="1" errors="0">"#));
+    assert!(stdout.contains(r#"<testcase classname="sqlshield" name="clean.py"/>"#));
+    assert!(stdout.contains(
+        r#"<failure type="unknown-column" message="Column `nickname` not found in table `users`">"#
+    ));
+    assert_eq!(stdout.matches("<failure ").count(), 2);
+}
+
+#[test]
+fn checkstyle_format_lists_errors_per_file() {
+    let dir = ci_format_fixture();
+    let stdout = run_format(&dir, "checkstyle");
+    assert!(stdout.contains(r#"<checkstyle version="4.3">"#));
+    assert!(stdout.contains(r#"<file name="clean.py"/>"#));
+    assert!(stdout.contains(
+        r#"<error line="2" column="5" severity="warning" message="`SELECT *` depends on the table&apos;s column order and width; list the columns explicitly" source="sqlshield.select-star"/>"#
+    ));
+}
+
+#[test]
+fn positional_files_are_checked_alone() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INT);\nINSERT INTO users (id) VALUES (1);\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("bad.py"),
+        b"q = \"SELECT nickname FROM users\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("other.py"),
+        b"q = \"SELECT email FROM users\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("report.sql"),
+        b"SELECT id FROM users;\nSELECT total FROM users;\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("notes.txt"), b"SELECT ghost FROM nowhere").unwrap();
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["bad.py", "report.sql", "notes.txt", "schema.sql"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2, "{stdout}");
+    assert!(lines[0].starts_with("bad.py:1:") && lines[0].contains("nickname"));
+    assert!(lines[1].starts_with("report.sql:2:") && lines[1].contains("total"));
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["notes.txt"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+}
+
+fn git(dir: &std::path::Path, args: &[&str]) {
+    let status = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .env("GIT_AUTHOR_NAME", "test")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?}");
+}
+
+#[test]
+fn changed_since_checks_changed_files_and_schema_dependents() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INT, email TEXT);\nCREATE TABLE orders (id INT);\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("users.py"),
+        b"q = \"SELECT email FROM users\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("orders.py"),
+        b"q = \"SELECT id FROM orders\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("legacy.py"),
+        b"q = \"SELECT ghost FROM orders\"\n",
+    )
+    .unwrap();
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "init"]);
+
+    // Only the changed file is checked; legacy.py's error is left alone.
+    fs::write(
+        dir.path().join("orders.py"),
+        b"q = \"SELECT total FROM orders\"\n",
+    )
+    .unwrap();
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["--changed-since", "HEAD"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().count(), 1, "{stdout}");
+    assert!(stdout.starts_with("orders.py:1:") && stdout.contains("total"));
+
+    // Dropping `email` from the schema re-checks users.py, which no one
+    // touched.
+    git(dir.path(), &["checkout", "-q", "--", "orders.py"]);
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INT);\nCREATE TABLE orders (id INT);\n",
+    )
+    .unwrap();
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["--changed-since", "HEAD"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().count(), 1, "{stdout}");
+    assert!(stdout.starts_with("users.py:1:") && stdout.contains("email"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("schema changed for users"), "{stderr}");
+
+    // A new migration counts as a schema change too.
+    git(dir.path(), &["checkout", "-q", "--", "schema.sql"]);
+    fs::create_dir(dir.path().join("migrations")).unwrap();
+    fs::write(
+        dir.path().join("migrations/002.sql"),
+        b"ALTER TABLE orders ADD COLUMN total INT;\n",
+    )
+    .unwrap();
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["--changed-since", "HEAD"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("legacy.py:1:"), "{stdout}");
+    assert!(!stdout.contains("002.sql"), "{stdout}");
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["--changed-since", "no-such-ref"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn changed_since_follows_enum_and_function_changes_with_absolute_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TYPE mood AS ENUM ('happy', 'sad');\n\
+          CREATE TABLE people (id INT, feeling mood);\n\
+          CREATE TABLE orders (id INT);\n\
+          CREATE FUNCTION slugify(t TEXT) RETURNS TEXT AS 'select t' LANGUAGE sql;\n",
+    )
+    .unwrap();
+    fs::create_dir(dir.path().join("app")).unwrap();
+    fs::write(
+        dir.path().join("app/people.py"),
+        b"q = \"SELECT ghost FROM people\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("app/slugs.py"),
+        b"q = \"SELECT slugify(phantom) FROM orders\"\n",
+    )
+    .unwrap();
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "init"]);
+    let app = dir.path().join("app").canonicalize().unwrap();
+    let run = || {
+        Command::new(cli_bin())
+            .current_dir(dir.path())
+            .args(["--changed-since", "HEAD", "--directory"])
+            .arg(&app)
+            .output()
+            .unwrap()
+    };
+
+    // A changed file under an absolute directory is still found.
+    fs::write(
+        dir.path().join("app/slugs.py"),
+        b"q = \"SELECT slugify(spectre) FROM orders\"\n",
+    )
+    .unwrap();
+    let stdout = String::from_utf8_lossy(&run().stdout).into_owned();
+    assert!(
+        stdout.contains("spectre") && !stdout.contains("ghost"),
+        "{stdout}"
+    );
+    git(dir.path(), &["checkout", "-q", "--", "app/slugs.py"]);
+
+    // A migration adding an enum label re-checks the tables using the type.
+    fs::write(
+        dir.path().join("app/002.sql"),
+        b"ALTER TYPE mood ADD VALUE 'meh';\n",
+    )
+    .unwrap();
+    let output = run();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("ghost") && !stdout.contains("phantom"),
+        "{stdout}"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("schema changed for people"), "{stderr}");
+    fs::remove_file(dir.path().join("app/002.sql")).unwrap();
+
+    // Dropping a function re-checks the files calling it.
+    fs::write(
+        dir.path().join("app/003.sql"),
+        b"DROP FUNCTION slugify(TEXT);\n",
+    )
+    .unwrap();
+    let output = run();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("phantom") && !stdout.contains("ghost"),
+        "{stdout}"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("slugify()"), "{stderr}");
+}
+
+/// A `--watch` process in `dir`, killed on drop so a failed assertion
+/// doesn't leave it running.
+struct Watch {
+    child: std::process::Child,
+    lines: std::sync::mpsc::Receiver<String>,
+}
+
+impl Watch {
+    fn spawn(dir: &std::path::Path) -> Self {
+        use std::io::BufRead;
+
+        let mut child = Command::new(cli_bin())
+            .current_dir(dir)
+            .arg("--watch")
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let (tx, lines) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for line in std::io::BufReader::new(stdout).lines() {
+                if tx.send(line.unwrap()).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { child, lines }
+    }
+
+    /// Lines up to and including the next summary.
+    fn next_screen(&self) -> Vec<String> {
+        let mut screen = Vec::new();
+        loop {
+            let line = self
+                .lines
+                .recv_timeout(std::time::Duration::from_secs(20))
+                .unwrap();
+            let done = line.starts_with("-- ");
+            screen.push(line);
+            if done {
+                return screen;
+            }
+        }
+    }
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn watch_rechecks_changed_files_and_reloads_on_schema_change() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INT);\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("a.py"), b"q = \"SELECT id FROM users\"\n").unwrap();
+    fs::write(
+        dir.path().join("b.py"),
+        b"q = \"SELECT email FROM users\"\n",
+    )
+    .unwrap();
+
+    let watch = Watch::spawn(dir.path());
+    let screen = watch.next_screen();
+    assert_eq!(screen.len(), 2, "{screen:?}");
+    assert!(
+        screen[0].contains("b.py:1:") && screen[0].contains("email"),
+        "{screen:?}"
+    );
+    assert!(screen[1].contains("1 error(s)") && screen[1].contains("checked everything"));
+
+    fs::write(dir.path().join("a.py"), b"q = \"SELECT name FROM users\"\n").unwrap();
+    let screen = watch.next_screen();
+    assert_eq!(screen.len(), 3, "{screen:?}");
+    assert!(screen[2].contains("2 error(s)") && screen[2].contains("re-checked 1 file(s)"));
+
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INT, name TEXT, email TEXT);\n",
+    )
+    .unwrap();
+    let screen = watch.next_screen();
+    assert_eq!(screen.len(), 1, "{screen:?}");
+    assert!(screen[0].contains("0 error(s)") && screen[0].contains("schema changed"));
+}
+
+#[test]
+fn watch_uses_nested_configs_and_reloads_on_schema_inputs() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INT);\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("app.py"), b"q = \"SELECT id FROM users\"\n").unwrap();
+    fs::create_dir(dir.path().join("legacy")).unwrap();
+    fs::write(
+        dir.path().join("legacy/.sqlshield.toml"),
+        b"schema = \"old_schema.sql\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("legacy/old_schema.sql"),
+        b"CREATE TABLE accounts (id INT);\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("legacy/q.py"),
+        b"q = \"SELECT login FROM accounts\"\n",
+    )
+    .unwrap();
+
+    // legacy/q.py is checked against legacy's own schema.
+    let watch = Watch::spawn(dir.path());
+    let screen = watch.next_screen();
+    assert_eq!(screen.len(), 2, "{screen:?}");
+    assert!(
+        screen[0].contains("legacy/q.py:1:") && screen[0].contains("login"),
+        "{screen:?}"
+    );
+
+    // A new migration reloads everything.
+    fs::write(
+        dir.path().join("001_migration.sql"),
+        b"ALTER TABLE users ADD COLUMN nick TEXT;\n",
+    )
+    .unwrap();
+    let screen = watch.next_screen();
+    assert_eq!(screen.len(), 2, "{screen:?}");
+    assert!(screen[1].contains("schema changed"), "{screen:?}");
+
+    // A code file is only re-checked.
+    fs::write(
+        dir.path().join("app.py"),
+        b"q = \"SELECT nick FROM users\"\n",
+    )
+    .unwrap();
+    let screen = watch.next_screen();
+    assert_eq!(screen.len(), 3, "{screen:?}");
+    assert!(
+        screen
+            .iter()
+            .any(|l| l.contains("app.py:1:") && l.contains("nick"))
+            && screen[2].contains("re-checked 1 file(s)"),
+        "{screen:?}"
+    );
+
+    // So does the nested config's schema file.
+    fs::write(
+        dir.path().join("legacy/old_schema.sql"),
+        b"CREATE TABLE accounts (id INT, login TEXT);\n",
+    )
+    .unwrap();
+    let screen = watch.next_screen();
+    assert_eq!(screen.len(), 2, "{screen:?}");
+    assert!(
+        screen[0].contains("app.py:1:") && screen[1].contains("schema changed"),
+        "{screen:?}"
+    );
+}
+
+#[test]
+fn exclude_globs_and_gitignore_skip_files_and_are_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INT);\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join(".sqlshield.toml"),
+        b"exclude = [\"tests/fixtures/\"]\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join(".gitignore"), b"gen/\n").unwrap();
+    for sub in ["app", "gen", "third_party", "tests/fixtures"] {
+        fs::create_dir_all(dir.path().join(sub)).unwrap();
+        fs::write(
+            dir.path().join(sub).join("q.py"),
+            b"q = \"SELECT email FROM users\"\n",
+        )
+        .unwrap();
+    }
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["--exclude", "third_party/**", "--show-skipped"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().count(), 1, "{stdout}");
+    assert!(stdout.contains("app/q.py:1:"), "{stdout}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        stderr.lines().collect::<Vec<_>>(),
+        [
+            "sqlshield: skipped by .gitignore / .ignore (1):",
+            "  gen",
+            "sqlshield: skipped by exclude `tests/fixtures/` (1):",
+            "  tests/fixtures/q.py",
+            "sqlshield: skipped by exclude `third_party/**` (1):",
+            "  third_party/q.py",
+        ],
+        "{stderr}"
+    );
+
+    // Excludes apply to files named on the command line too.
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["tests/fixtures/q.py"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+}
+
+#[test]
+fn configs_are_found_upward_and_nested_ones_override_their_subtree() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("app")).unwrap();
+    fs::create_dir_all(dir.path().join("legacy")).unwrap();
+    fs::write(
+        dir.path().join(".sqlshield.toml"),
+        b"schema = \"db/schema.sql\"\n",
+    )
+    .unwrap();
+    fs::create_dir_all(dir.path().join("db")).unwrap();
+    fs::write(
+        dir.path().join("db/schema.sql"),
+        b"CREATE TABLE users (id INT, email TEXT);\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("app/db.py"),
+        b"q = \"SELECT email FROM users\"\nr = \"SELECT * FROM users\"\n",
+    )
+    .unwrap();
+    // The legacy code has its own schema and doesn't care about `*`.
+    fs::write(
+        dir.path().join("legacy/.sqlshield.toml"),
+        b"schema = \"old.sql\"\n\n[rules]\nselect-star = \"off\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("legacy/old.sql"),
+        b"CREATE TABLE old_users (id INT);\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("legacy/db.py"),
+        b"q = \"SELECT id FROM old_users\"\nr = \"SELECT * FROM old_users\"\n",
+    )
+    .unwrap();
+
+    for cwd in [dir.path().to_path_buf(), dir.path().join("app")] {
+        let output = Command::new(cli_bin())
+            .current_dir(&cwd)
+            .args(["--format", "json"])
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "from {}: {stderr}", cwd.display());
+        let findings: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let findings = findings.as_array().unwrap();
+        assert_eq!(findings.len(), 1, "{findings:?}");
+        assert_eq!(findings[0]["code"], "select-star");
+        assert!(
+            findings[0]["location"]
+                .as_str()
+                .unwrap()
+                .ends_with("app/db.py:2"),
+            "{findings:?}"
+        );
+    }
+}
+
+#[test]
+fn targets_check_each_service_against_its_own_schema() {
+    let dir = tempfile::tempdir().unwrap();
+    for (service, schema) in [
+        ("api", "CREATE TABLE users (id INT, email TEXT);\n"),
+        ("cli", "CREATE TABLE notes (id INT, body TEXT);\n"),
+    ] {
+        fs::create_dir_all(dir.path().join(service)).unwrap();
+        fs::write(dir.path().join(service).join("schema.sql"), schema).unwrap();
+    }
+    fs::write(
+        dir.path().join("api/db.py"),
+        b"q = \"SELECT email FROM users\"\nr = \"SELECT body FROM notes\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("cli/db.py"),
+        b"q = \"SELECT body FROM notes\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join(".sqlshield.toml"),
+        br#"
+[[targets]]
+name = "api"
+directory = "api"
+schema = "api/schema.sql"
+dialect = "postgres"
+
+[[targets]]
+directory = "cli"
+schema = "cli/schema.sql"
+dialect = "sqlite"
+"#,
+    )
+    .unwrap();
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let findings: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let findings = findings.as_array().unwrap();
+    assert_eq!(findings.len(), 1, "{findings:?}");
+    assert_eq!(findings[0]["location"], "api/db.py:2");
+    assert_eq!(findings[0]["target"], "api");
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["--target", "cli"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["--target", "web"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("have: api, cli"), "{stderr}");
+
+    // Named files go to the target that owns them.
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["cli/db.py", "api/db.py"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().count(), 1, "{stdout}");
+    assert!(stdout.contains("(target: api)"), "{stdout}");
+}
+
+#[test]
+fn unparseable_sql_is_reported_and_can_be_turned_off() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INT);",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        b"q = \"SELECT id FROM users WHERE id = AND 1\"\n",
+    )
+    .unwrap();
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {stdout}");
+    let report: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let findings = report.as_array().unwrap();
+    assert_eq!(findings.len(), 1, "{stdout}");
+    assert_eq!(findings[0]["code"], "unparseable-sql");
+    assert_eq!(findings[0]["severity"], "warning");
+
+    fs::write(
+        dir.path().join(".sqlshield.toml"),
+        b"[rules]\nunparseable-sql = \"off\"\n",
+    )
+    .unwrap();
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn extract_lists_every_query_with_its_references() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        b"q = f\"SELECT u.id, name FROM users u WHERE id = {uid}\"\nr = \"SELECT id FROM users WHERE id = AND 1\"\ns = \"not sql\"\n",
+    )
+    .unwrap();
+
+    // No schema needed: extraction doesn't validate.
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .arg("extract")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let queries: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        queries,
+        serde_json::json!([
+            {
+                "file": "app.py",
+                "line": 1,
+                "column": 5,
+                "end_line": 1,
+                "end_column": 55,
+                "language": "python",
+                "sql": "SELECT u.id, name FROM users u WHERE id = 1",
+                "parsed": true,
+                "tables": ["users"],
+                "columns": ["id", "name", "u.id"],
+            },
+            {
+                "file": "app.py",
+                "line": 2,
+                "column": 5,
+                "end_line": 2,
+                "end_column": 44,
+                "language": "python",
+                "sql": "SELECT id FROM users WHERE id = AND 1",
+                "parsed": false,
+                "error": "Query doesn't parse: Expected end of statement, found: 1 at Line: 1, Column 37",
+                "tables": [],
+                "columns": [],
+            },
+        ])
+    );
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["extract", "--format", "ndjson", "app.py"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1]["parsed"], false);
+}
+
+#[test]
+fn usage_reports_reads_writes_and_unused_schema() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INT, name TEXT, email TEXT);\nCREATE TABLE audit (id INT);\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        b"def load(uid):\n    return f\"SELECT name FROM users WHERE id = {uid}\"\n\ndef rename():\n    return \"UPDATE users SET name = 'x' WHERE id = 1\"\n",
+    )
+    .unwrap();
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .arg("usage")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "users
+  read   app.py:2 in load
+  write  app.py:5 in rename
+users.id
+  read   app.py:2 in load
+  read   app.py:5 in rename
+users.name
+  read   app.py:2 in load
+  write  app.py:5 in rename
+Unused (dead-schema candidates):
+  table   audit
+  column  users.email
+"
+    );
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["usage", "--format", "json"])
+        .output()
+        .unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let users = &report[0]["tables"][0];
+    assert_eq!(report[0]["schema"], "schema.sql");
+    assert_eq!(users["table"], "users");
+    assert_eq!(
+        users["columns"][1]["writes"],
+        serde_json::json!([{ "file": "app.py", "line": 5, "function": "rename" }])
+    );
+    assert_eq!(report[0]["unused_tables"], serde_json::json!(["audit"]));
+    assert_eq!(
+        report[0]["unused_columns"],
+        serde_json::json!(["users.email"])
+    );
+}
+
+#[test]
+fn impact_reports_only_the_queries_a_migration_breaks() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INT, name TEXT, nickname TEXT);\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        b"def nick():\n    return \"SELECT nickname FROM users\"\n\ndef names():\n    return \"SELECT name FROM users\"\n",
+    )
+    .unwrap();
+    fs::create_dir(dir.path().join("migrations")).unwrap();
+    fs::write(
+        dir.path().join("migrations/0042_drop_nickname.sql"),
+        b"ALTER TABLE users DROP COLUMN nickname;\n",
+    )
+    .unwrap();
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["impact", "--migration", "migrations/0042_drop_nickname.sql"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "app.py:2 in nick: column users.nickname dropped
+  Column `nickname` not found in table `users` [unknown-column]
+"
+    );
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("migrations/0042_drop_nickname.sql breaks 1 query(ies)"));
+
+    fs::write(
+        dir.path().join("migrations/0043_add_age.sql"),
+        b"ALTER TABLE users ADD COLUMN age INT;\n",
+    )
+    .unwrap();
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args([
+            "impact",
+            "--format",
+            "json",
+            "--migration",
+            "migrations/0043_add_age.sql",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "[]\n");
+}
+
+#[cfg(feature = "introspect")]
+#[test]
+fn schema_diff_reports_drift_against_the_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let conn = rusqlite::Connection::open(dir.path().join("app.db")).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT NOT NULL, age BIGINT);
+         CREATE TABLE audit (id INTEGER);",
+    )
+    .unwrap();
+    drop(conn);
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT NOT NULL, age INT, nickname TEXT);\n",
+    )
+    .unwrap();
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args([
+            "schema",
+            "diff",
+            "--schema",
+            "schema.sql",
+            "--db-url",
+            "app.db",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "table `audit` only in the database
+column `users.nickname` only in the schema file
+column `users.age` is INT in the schema file but BIGINT in the database
+"
+    );
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("schema.sql and the database differ in 3 place(s)"));
+
+    fs::write(
+        dir.path().join("schema.sql"),
+        b"CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT NOT NULL, age BIGINT);\nCREATE TABLE audit (id INTEGER);\n",
+    )
+    .unwrap();
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["schema", "diff", "--db-url", "app.db", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "[]\n");
+}
+
+#[test]
+fn schema_and_db_url_are_exclusive_for_checks() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["--schema", "schema.sql", "--db-url", "app.db"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("--schema and --db-url are mutually exclusive"));
+}
+
+#[cfg(feature = "introspect")]
+#[test]
+fn schema_dump_snapshots_the_database_for_offline_checks() {
+    let dir = tempfile::tempdir().unwrap();
+    let conn = rusqlite::Connection::open(dir.path().join("app.db")).unwrap();
+    conn.execute_batch("CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT NOT NULL);")
+        .unwrap();
+    drop(conn);
+    fs::write(
+        dir.path().join("app.py"),
+        b"Q = \"SELECT nickname FROM users\"\n",
+    )
+    .unwrap();
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["schema", "dump", "--db-url", "sqlite:///app.db"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "CREATE TABLE users (
+    id INTEGER NOT NULL,
+    email TEXT NOT NULL,
+    PRIMARY KEY (id)
+);
+"
+    );
+
+    for format in ["sql", "json"] {
+        let output = Command::new(cli_bin())
+            .current_dir(dir.path())
+            .args(["schema", "dump", "--db-url", "app.db", "--format", format])
+            .output()
+            .unwrap();
+        let snapshot = format!("snapshot.{format}");
+        fs::write(dir.path().join(&snapshot), output.stdout).unwrap();
+
+        let output = Command::new(cli_bin())
+            .current_dir(dir.path())
+            .args(["--schema", &snapshot, "app.py"])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1), "{format}");
+        assert!(String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("Column `nickname` not found in table `users`"));
+    }
+}
+
+#[test]
+fn django_models_serve_as_the_schema() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = dir.path().join("shop");
+    fs::create_dir(&app).unwrap();
+    fs::write(
+        app.join("models.py"),
+        b"from django.db import models\n\n\
+          class Order(models.Model):\n    total = models.IntegerField()\n",
+    )
+    .unwrap();
+    fs::write(
+        app.join("views.py"),
+        b"def totals(cursor):\n    \
+          cursor.execute(\"SELECT id, total FROM shop_order\")\n    \
+          cursor.execute(\"SELECT amount FROM shop_order\")\n",
+    )
+    .unwrap();
+
+    let output = Command::new(cli_bin())
+        .current_dir(dir.path())
+        .args(["--schema", "shop/models.py", "--directory", "shop"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(output.status.code(), Some(1), "{stdout}");
+    assert_eq!(stdout.lines().count(), 1, "{stdout}");
+    assert!(stdout.contains("shop/views.py:3"), "{stdout}");
+    assert!(stdout.contains("Column `amount` not found in table `shop_order`"));
+}
@@ -1,8 +1,9 @@
 //! Live database introspection for sqlshield.
 //!
-//! Connects to a running database and builds a [`TablesAndColumns`] map
-//! directly from system catalogs — no SQL dump required. Driver is selected
-//! by URL scheme:
+//! Connects to a running database and builds a [`TablesAndColumns`] map, or
+//! a full [`Schema`] with column types, nullability and keys, directly from
+//! system catalogs — no SQL dump required. Driver is selected by URL
+//! scheme:
 //!
 //! * `postgres://…` / `postgresql://…` — Postgres via the `postgres` crate.
 //! * `sqlite:///path/to/file.db` (or a bare path) — SQLite via `rusqlite`.
@@ -14,9 +15,7 @@
 //! Each driver is optional behind a Cargo feature; the default build pulls
 //! both currently-supported drivers.
 
-use std::collections::HashMap;
-
-use sqlshield::schema::TablesAndColumns;
+use sqlshield::schema::{Column, ForeignKey, Schema, TablesAndColumns};
 
 #[derive(Debug, thiserror::Error)]
 pub enum IntrospectError {
@@ -42,6 +41,13 @@ pub enum IntrospectError {
 /// pass a URL the running process can actually reach; the function returns
 /// any driver error directly so callers can show a useful message.
 pub fn introspect(db_url: &str) -> Result<TablesAndColumns, IntrospectError> {
+    introspect_schema(db_url).map(|schema| schema.tables)
+}
+
+/// Like [`introspect`], with the typed metadata too: declared column
+/// types, nullability, primary keys and foreign keys of every table.
+/// Views only get their column names, as from a schema file.
+pub fn introspect_schema(db_url: &str) -> Result<Schema, IntrospectError> {
     let scheme = url_scheme(db_url);
     match scheme.as_deref() {
         Some("postgres") | Some("postgresql") => introspect_postgres(db_url),
@@ -53,6 +59,25 @@ pub fn introspect(db_url: &str) -> Result<TablesAndColumns, IntrospectError> {
     }
 }
 
+/// Record `column` of `table` (and of `schema.table`) in `loaded`; `typed`
+/// columns also get a [`Column`].
+fn add_column(loaded: &mut Schema, keys: &[String], column: Option<Column>, name: String) {
+    for key in keys {
+        loaded
+            .tables
+            .entry(key.clone())
+            .or_default()
+            .insert(name.clone());
+        if let Some(column) = &column {
+            loaded
+                .columns
+                .entry(key.clone())
+                .or_default()
+                .push(column.clone());
+        }
+    }
+}
+
 fn url_scheme(s: &str) -> Option<String> {
     // Windows drive letters (`C:\…`) parse as a single-character URL
     // scheme, which would otherwise route a bare path through the
@@ -68,41 +93,105 @@ fn url_scheme(s: &str) -> Option<String> {
 }
 
 #[cfg(feature = "postgres")]
-fn introspect_postgres(db_url: &str) -> Result<TablesAndColumns, IntrospectError> {
+fn introspect_postgres(db_url: &str) -> Result<Schema, IntrospectError> {
     use postgres::{Client, NoTls};
 
     let mut client = Client::connect(db_url, NoTls)?;
-    // information_schema.columns covers user tables plus views, which is
-    // what the validator wants. Skip catalog schemas to keep the map lean.
+    // Tables, partitioned and foreign tables, views and materialized views,
+    // which is what the validator wants. Skip catalog schemas to keep the
+    // map lean. `format_type` spells types the way DDL does
+    // (`character varying(255)`), modifiers included.
     let rows = client.query(
-        "SELECT table_schema, table_name, column_name
-         FROM information_schema.columns
-         WHERE table_schema NOT IN ('pg_catalog', 'information_schema')",
+        "SELECT n.nspname, c.relname, a.attname,
+                format_type(a.atttypid, a.atttypmod), a.attnotnull,
+                c.relkind IN ('r', 'p', 'f')
+         FROM pg_attribute a
+         JOIN pg_class c ON c.oid = a.attrelid
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         WHERE a.attnum > 0 AND NOT a.attisdropped
+           AND c.relkind IN ('r', 'p', 'f', 'v', 'm')
+           AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+           AND n.nspname NOT LIKE 'pg_toast%'
+         ORDER BY n.nspname, c.relname, a.attnum",
         &[],
     )?;
 
-    let mut tables: TablesAndColumns = HashMap::new();
+    let mut loaded = Schema::default();
     for row in rows {
         let schema: String = row.get(0);
         let table: String = row.get(1);
-        let column: String = row.get(2);
+        let name: String = row.get(2);
+        let is_table: bool = row.get(5);
+        let column = is_table.then(|| Column {
+            name: name.clone(),
+            data_type: row.get(3),
+            inline_enum: None,
+            nullable: !row.get::<_, bool>(4),
+        });
         // Postgres returns identifiers already lowercased for unquoted
         // tables. Quoted identifiers come back case-preserved. The
         // validator's PG-mode folding will read both keys correctly.
-        let qualified = format!("{schema}.{table}");
-        tables.entry(table).or_default().insert(column.clone());
-        tables.entry(qualified).or_default().insert(column);
+        let keys = [format!("{schema}.{table}"), table];
+        add_column(&mut loaded, &keys, column, name);
+    }
+
+    // Key columns in key order, and for foreign keys the referenced table
+    // and its columns in the same order.
+    let rows = client.query(
+        "SELECT n.nspname, c.relname, con.contype = 'p',
+                ARRAY(SELECT a.attname::text
+                      FROM unnest(con.conkey) WITH ORDINALITY k(attnum, ord)
+                      JOIN pg_attribute a
+                        ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+                      ORDER BY k.ord),
+                f.relname::text,
+                ARRAY(SELECT a.attname::text
+                      FROM unnest(con.confkey) WITH ORDINALITY k(attnum, ord)
+                      JOIN pg_attribute a
+                        ON a.attrelid = con.confrelid AND a.attnum = k.attnum
+                      ORDER BY k.ord)
+         FROM pg_constraint con
+         JOIN pg_class c ON c.oid = con.conrelid
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         LEFT JOIN pg_class f ON f.oid = con.confrelid
+         WHERE con.contype IN ('p', 'f')
+           AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+         ORDER BY n.nspname, c.relname, con.conname",
+        &[],
+    )?;
+    for row in rows {
+        let schema: String = row.get(0);
+        let table: String = row.get(1);
+        let is_primary: bool = row.get(2);
+        let columns: Vec<String> = row.get(3);
+        let referenced_table: Option<String> = row.get(4);
+        let referenced_columns: Vec<String> = row.get(5);
+        for key in [format!("{schema}.{table}"), table] {
+            if is_primary {
+                loaded.primary_keys.insert(key, columns.clone());
+            } else if let Some(referenced_table) = &referenced_table {
+                loaded
+                    .foreign_keys
+                    .entry(key)
+                    .or_default()
+                    .push(ForeignKey {
+                        columns: columns.clone(),
+                        referenced_table: referenced_table.clone(),
+                        referenced_columns: referenced_columns.clone(),
+                    });
+            }
+        }
     }
-    Ok(tables)
+    Ok(loaded)
 }
 
 #[cfg(not(feature = "postgres"))]
-fn introspect_postgres(_db_url: &str) -> Result<TablesAndColumns, IntrospectError> {
+fn introspect_postgres(_db_url: &str) -> Result<Schema, IntrospectError> {
     Err(IntrospectError::DriverDisabled("postgres"))
 }
 
 #[cfg(feature = "sqlite")]
-fn introspect_sqlite_url(db_url: &str) -> Result<TablesAndColumns, IntrospectError> {
+fn introspect_sqlite_url(db_url: &str) -> Result<Schema, IntrospectError> {
     // sqlite:///abs/path or sqlite://relative. Strip scheme+`//` to get
     // the filesystem path; rusqlite opens by path.
     let parsed = url::Url::parse(db_url).map_err(|e| IntrospectError::InvalidUrl(e.to_string()))?;
@@ -112,14 +201,16 @@ fn introspect_sqlite_url(db_url: &str) -> Result<TablesAndColumns, IntrospectErr
 }
 
 #[cfg(feature = "sqlite")]
-fn introspect_sqlite_path(path: &str) -> Result<TablesAndColumns, IntrospectError> {
+fn introspect_sqlite_path(path: &str) -> Result<Schema, IntrospectError> {
     let conn = rusqlite::Connection::open(path)?;
-    let mut tables: TablesAndColumns = HashMap::new();
+    let mut loaded = Schema::default();
 
-    let names: Vec<String> = {
-        let mut stmt =
-            conn.prepare("SELECT name FROM sqlite_master WHERE type IN ('table','view')")?;
-        let iter = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let relations: Vec<(String, bool)> = {
+        let mut stmt = conn.prepare(
+            "SELECT name, type = 'table' FROM sqlite_master
+             WHERE type IN ('table','view') AND name NOT LIKE 'sqlite_%'",
+        )?;
+        let iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
         let mut out = Vec::new();
         for r in iter {
             out.push(r?);
@@ -127,25 +218,91 @@ fn introspect_sqlite_path(path: &str) -> Result<TablesAndColumns, IntrospectErro
         out
     };
 
-    for table in names {
+    for (table, is_table) in relations {
+        let keys = [table.clone()];
+        // Every relation gets an entry, even one without columns.
+        loaded.tables.entry(table.clone()).or_default();
         let mut col_stmt = conn.prepare(&format!("PRAGMA table_info(\"{}\")", escape(&table)))?;
-        let cols_iter = col_stmt.query_map([], |row| row.get::<_, String>(1))?;
-        let mut cols = std::collections::HashSet::new();
+        let cols_iter = col_stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, bool>(3)?,
+                row.get::<_, i64>(5)?,
+            ))
+        })?;
+        let mut primary_key: Vec<(i64, String)> = Vec::new();
         for c in cols_iter {
-            cols.insert(c?);
+            let (name, data_type, not_null, pk) = c?;
+            if pk > 0 {
+                primary_key.push((pk, name.clone()));
+            }
+            let column = is_table.then(|| Column {
+                name: name.clone(),
+                data_type,
+                inline_enum: None,
+                nullable: !not_null && pk == 0,
+            });
+            add_column(&mut loaded, &keys, column, name);
+        }
+        if !is_table {
+            continue;
+        }
+        if !primary_key.is_empty() {
+            primary_key.sort();
+            loaded.primary_keys.insert(
+                table.clone(),
+                primary_key.into_iter().map(|(_, name)| name).collect(),
+            );
+        }
+
+        // One row per column pair, grouped by constraint id in key order.
+        // `to` is NULL when the key references the primary key implicitly.
+        let mut fk_stmt =
+            conn.prepare(&format!("PRAGMA foreign_key_list(\"{}\")", escape(&table)))?;
+        let fk_iter = fk_stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?;
+        let mut fks: Vec<(i64, ForeignKey)> = Vec::new();
+        for fk in fk_iter {
+            let (id, referenced_table, from, to) = fk?;
+            if fks.last().map(|(last, _)| *last) != Some(id) {
+                fks.push((
+                    id,
+                    ForeignKey {
+                        columns: Vec::new(),
+                        referenced_table,
+                        referenced_columns: Vec::new(),
+                    },
+                ));
+            }
+            let (_, fk) = fks.last_mut().expect("pushed above");
+            fk.columns.push(from);
+            fk.referenced_columns.extend(to);
+        }
+        if !fks.is_empty() {
+            // SQLite lists constraints last-declared first.
+            fks.sort_by_key(|(id, _)| std::cmp::Reverse(*id));
+            loaded
+                .foreign_keys
+                .insert(table, fks.into_iter().map(|(_, fk)| fk).collect());
         }
-        tables.insert(table, cols);
     }
-    Ok(tables)
+    Ok(loaded)
 }
 
 #[cfg(not(feature = "sqlite"))]
-fn introspect_sqlite_url(_db_url: &str) -> Result<TablesAndColumns, IntrospectError> {
+fn introspect_sqlite_url(_db_url: &str) -> Result<Schema, IntrospectError> {
     Err(IntrospectError::DriverDisabled("sqlite"))
 }
 
 #[cfg(not(feature = "sqlite"))]
-fn introspect_sqlite_path(_path: &str) -> Result<TablesAndColumns, IntrospectError> {
+fn introspect_sqlite_path(_path: &str) -> Result<Schema, IntrospectError> {
     Err(IntrospectError::DriverDisabled("sqlite"))
 }
 
@@ -179,6 +336,49 @@ mod tests {
         assert!(tables["orders"].contains("user_id"));
     }
 
+    #[test]
+    fn sqlite_types_nullability_and_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("t.db");
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, email VARCHAR(255) NOT NULL, name TEXT);
+             CREATE TABLE orders (
+                 id INTEGER, user_id INTEGER REFERENCES users, placed_by INTEGER,
+                 PRIMARY KEY (id),
+                 FOREIGN KEY (placed_by) REFERENCES users (id)
+             );
+             CREATE VIEW names AS SELECT name FROM users;",
+        )
+        .unwrap();
+        drop(conn);
+
+        let schema = introspect_schema(path.to_str().unwrap()).unwrap();
+        let email = schema.column("users", "email").unwrap();
+        assert_eq!(email.data_type, "VARCHAR(255)");
+        assert!(!email.nullable);
+        assert!(schema.column("users", "name").unwrap().nullable);
+        assert!(!schema.column("users", "id").unwrap().nullable);
+        assert_eq!(schema.primary_keys["orders"], vec!["id"]);
+        assert_eq!(
+            schema.foreign_keys["orders"],
+            vec![
+                ForeignKey {
+                    columns: vec!["user_id".into()],
+                    referenced_table: "users".into(),
+                    referenced_columns: vec![],
+                },
+                ForeignKey {
+                    columns: vec!["placed_by".into()],
+                    referenced_table: "users".into(),
+                    referenced_columns: vec!["id".into()],
+                },
+            ]
+        );
+        assert!(schema.tables["names"].contains("name"));
+        assert!(!schema.columns.contains_key("names"));
+    }
+
     #[test]
     fn windows_drive_letter_is_not_treated_as_url_scheme() {
         assert_eq!(url_scheme("C:\\foo\\bar.db"), None);
@@ -2,24 +2,19 @@
 //!   1. Editor settings — pushed via `initializationOptions` and
 //!      `workspace/didChangeConfiguration`. Drives the VS Code
 //!      `sqlshield.*` settings UI.
-//!   2. `.sqlshield.toml` — discovered by walking up from the workspace
-//!      root. Used as a fallback for any field the editor leaves blank.
+//!   2. `.sqlshield.toml` — every one from a directory upward, layered
+//!      the same way the CLI does (`sqlshield::config`). Used as a
+//!      fallback for any field the editor leaves blank.
 
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use serde::Deserialize;
+use sqlshield::config;
+use sqlshield::lint::RuleConfig;
+use sqlshield::walk::WalkOptions;
 use sqlshield::Dialect;
 
-pub const CONFIG_FILE_NAME: &str = ".sqlshield.toml";
-
-#[derive(Debug, Default, Deserialize)]
-#[serde(deny_unknown_fields)]
-struct RawTomlConfig {
-    schema: Option<PathBuf>,
-    dialect: Option<String>,
-}
-
 /// Editor-supplied settings. Mirrors the `sqlshield.*` keys declared in
 /// the VS Code extension's `package.json`.
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -62,16 +57,48 @@ impl EditorSettings {
 pub struct ServerConfig {
     pub schema_path: Option<PathBuf>,
     pub dialect: Dialect,
+    /// Extension functions accepted with any arguments. Only settable in
+    /// `.sqlshield.toml`.
+    pub functions: Vec<String>,
+    /// MySQL `ONLY_FULL_GROUP_BY`; unset keeps the MySQL default (on).
+    pub only_full_group_by: Option<bool>,
+    /// Rule severities from `[rules]` and `[[overrides]]`. Only settable in
+    /// `.sqlshield.toml`.
+    pub rules: RuleConfig,
+    /// `include` / `exclude` globs. Only settable in `.sqlshield.toml`.
+    pub walk: WalkOptions,
+    /// `[[targets]]`; a document is checked under the one whose directory
+    /// contains it. Only settable in `.sqlshield.toml`.
+    pub targets: Vec<TargetConfig>,
 }
 
-/// Resolve the effective server config from both sources. Editor settings
-/// win per-field; `.sqlshield.toml` fills any gaps.
-pub fn resolve(workspace_root: &Path, editor: &EditorSettings) -> Result<ServerConfig, String> {
-    let toml_cfg = discover_toml(workspace_root)?;
+/// A `[[targets]]` entry with its paths resolved against the config's
+/// directory.
+#[derive(Debug, Clone)]
+pub struct TargetConfig {
+    pub name: String,
+    pub directory: PathBuf,
+    /// `None` for a target read from `db_url`.
+    pub schema_path: Option<PathBuf>,
+    pub dialect: Dialect,
+    /// The top-level globs plus the target's own.
+    pub walk: WalkOptions,
+}
+
+/// Resolve the effective server config from both sources: `files`, the
+/// config files found from some directory upward (outermost first), and
+/// the editor settings. Editor settings win per-field; `.sqlshield.toml`
+/// fills any gaps.
+pub fn resolve(
+    files: &[PathBuf],
+    workspace_root: &Path,
+    editor: &EditorSettings,
+) -> Result<ServerConfig, String> {
+    let toml_cfg = config::load_chain(files)?;
 
     let dialect = match editor.dialect.as_deref() {
         Some(d) => Dialect::from_str(d)?,
-        None => toml_cfg.dialect,
+        None => toml_cfg.dialect.unwrap_or_default(),
     };
 
     let schema_path = match editor.schema.as_deref() {
@@ -83,64 +110,42 @@ pub fn resolve(workspace_root: &Path, editor: &EditorSettings) -> Result<ServerC
                 workspace_root.join(p)
             })
         }
-        None => toml_cfg.schema_path,
+        None => toml_cfg.schema,
     };
 
-    Ok(ServerConfig {
-        schema_path,
-        dialect,
-    })
-}
-
-/// Walk upward from `start_dir` looking for `.sqlshield.toml`, parse it,
-/// and return the loaded config. Returns defaults if no config is found.
-/// Paths inside the config are resolved relative to the config's directory.
-pub fn discover_toml(start_dir: &Path) -> Result<ServerConfig, String> {
-    let Some((config_dir, path)) = find_config_file(start_dir) else {
-        return Ok(ServerConfig::default());
-    };
-
-    let source = std::fs::read_to_string(&path)
-        .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
-    let raw: RawTomlConfig =
-        toml::from_str(&source).map_err(|e| format!("invalid {}: {e}", path.display()))?;
-
-    let schema_path = raw.schema.map(|p| {
-        if p.is_absolute() {
-            p
-        } else {
-            config_dir.join(p)
-        }
-    });
-    let dialect = raw
-        .dialect
-        .as_deref()
-        .map(Dialect::from_str)
-        .transpose()?
-        .unwrap_or_default();
+    let targets = toml_cfg
+        .targets
+        .into_iter()
+        .map(|t| TargetConfig {
+            name: t.name,
+            directory: t.directory,
+            schema_path: t.schema,
+            dialect: t.dialect.or(toml_cfg.dialect).unwrap_or_default(),
+            walk: t.walk,
+        })
+        .collect();
 
     Ok(ServerConfig {
         schema_path,
         dialect,
+        functions: toml_cfg.functions,
+        only_full_group_by: toml_cfg.only_full_group_by,
+        rules: toml_cfg.rules,
+        walk: toml_cfg.walk,
+        targets,
     })
 }
 
-fn find_config_file(start: &Path) -> Option<(PathBuf, PathBuf)> {
-    let mut current = Some(start.to_path_buf());
-    while let Some(dir) = current {
-        let candidate = dir.join(CONFIG_FILE_NAME);
-        if candidate.is_file() {
-            return Some((dir, candidate));
-        }
-        current = dir.parent().map(|p| p.to_path_buf());
-    }
-    None
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
+    use sqlshield::config::CONFIG_FILE_NAME;
+    use sqlshield::lint::Severity;
+
+    fn discover(dir: &Path) -> Result<ServerConfig, String> {
+        resolve(&config::find(dir), dir, &EditorSettings::default())
+    }
 
     #[test]
     fn editor_empty_strings_become_none() {
@@ -173,4 +178,76 @@ mod tests {
         let s = EditorSettings::from_value(&json!({ "schema": "s.sql", "future_key": 42 }));
         assert_eq!(s.schema.as_deref(), Some("s.sql"));
     }
+
+    #[test]
+    fn toml_rules_and_overrides_are_read() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE_NAME),
+            "[rules]\nselect-star = \"off\"\n\n[[overrides]]\npaths = [\"tests/**\"]\nrules = { unknown-column = false }\n",
+        )
+        .unwrap();
+        let cfg = discover(dir.path()).unwrap();
+        assert_eq!(cfg.rules.severity("select-star"), Severity::Off);
+        let scoped = cfg.rules.for_path(&dir.path().join("tests/a.py"));
+        assert_eq!(scoped.severity("unknown-column"), Severity::Off);
+
+        std::fs::write(
+            dir.path().join(CONFIG_FILE_NAME),
+            "[rules]\nno-such-rule = \"off\"\n",
+        )
+        .unwrap();
+        let err = discover(dir.path()).unwrap_err();
+        assert!(err.contains("unknown rule `no-such-rule`"), "got: {err}");
+    }
+
+    #[test]
+    fn toml_targets_resolve_against_the_config_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE_NAME),
+            "dialect = \"postgres\"\n\n[[targets]]\nname = \"api\"\ndirectory = \"services/api\"\nschema = \"services/api/schema.sql\"\n\n[[targets]]\ndirectory = \"jobs\"\ndb_url = \"snowflake://x\"\ndialect = \"snowflake\"\n",
+        )
+        .unwrap();
+        let cfg = discover(dir.path()).unwrap();
+        assert_eq!(cfg.targets.len(), 2);
+        let api = &cfg.targets[0];
+        assert_eq!(api.name, "api");
+        assert_eq!(api.directory, dir.path().join("services/api"));
+        assert_eq!(
+            api.schema_path.as_deref(),
+            Some(dir.path().join("services/api/schema.sql").as_path())
+        );
+        assert_eq!(api.dialect, Dialect::Postgres);
+        let jobs = &cfg.targets[1];
+        assert_eq!(jobs.name, "jobs");
+        assert!(jobs.schema_path.is_none());
+        assert_eq!(jobs.dialect, Dialect::Snowflake);
+    }
+
+    #[test]
+    fn nested_toml_layers_over_the_workspace_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE_NAME),
+            "schema = \"schema.sql\"\ndialect = \"postgres\"\n",
+        )
+        .unwrap();
+        let legacy = dir.path().join("legacy");
+        std::fs::create_dir(&legacy).unwrap();
+        std::fs::write(
+            legacy.join(CONFIG_FILE_NAME),
+            "schema = \"old.sql\"\n\n[rules]\nselect-star = \"off\"\n",
+        )
+        .unwrap();
+
+        let cfg = discover(&legacy).unwrap();
+        assert_eq!(cfg.schema_path, Some(legacy.join("old.sql")));
+        assert_eq!(cfg.dialect, Dialect::Postgres);
+        assert_eq!(cfg.rules.severity("select-star"), Severity::Off);
+
+        let cfg = discover(dir.path()).unwrap();
+        assert_eq!(cfg.schema_path, Some(dir.path().join("schema.sql")));
+        assert_eq!(cfg.rules.severity("select-star"), Severity::Warning);
+    }
 }
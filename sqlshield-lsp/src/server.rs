@@ -6,36 +6,87 @@ use std::sync::Arc;
 use std::time::SystemTime;
 
 use dashmap::DashMap;
-use sqlshield::schema::{self, TablesAndColumns};
+use sqlparser::ast::Statement;
+use sqlshield::finder::UnparsedKind;
+use sqlshield::lint::{self, RuleConfig, Severity};
+use sqlshield::schema::{self, Schema};
 use sqlshield::validation;
+use sqlshield::walk::WalkOptions;
 use sqlshield::Dialect;
 use tokio::sync::RwLock;
 use tower_lsp::jsonrpc;
 use tower_lsp::lsp_types::{
     Diagnostic, DiagnosticSeverity, DidChangeConfigurationParams, DidChangeTextDocumentParams,
     DidCloseTextDocumentParams, DidOpenTextDocumentParams, InitializeParams, InitializeResult,
-    InitializedParams, MessageType, Position, Range, ServerCapabilities, ServerInfo,
-    TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+    InitializedParams, MessageType, NumberOrString, Position, Range, ServerCapabilities,
+    ServerInfo, TextDocumentSyncCapability, TextDocumentSyncKind, Url,
 };
 use tower_lsp::{Client, LanguageServer};
 use tracing::{debug, error, info, warn};
 
 use crate::config::{self, EditorSettings, ServerConfig};
 
-/// Per-request loaded state: the parsed schema plus the chosen dialect.
+/// Per-request loaded state: the parsed schema, the chosen dialect and the
+/// rule severities.
 /// `schema_mtime` is captured at load time so the server can detect when the
 /// underlying schema file has been edited and trigger a reload.
 struct LoadedState {
-    schema: TablesAndColumns,
+    schema: Schema,
     dialect: Dialect,
     schema_source: Option<PathBuf>,
     schema_mtime: Option<SystemTime>,
+    rules: RuleConfig,
+    /// `include` / `exclude` globs; documents they leave out get no
+    /// diagnostics.
+    walk: WalkOptions,
+}
+
+impl LoadedState {
+    /// Rule severities in effect for the document at `path`.
+    fn rules_for(&self, path: &Path) -> RuleConfig {
+        self.rules.for_path(path)
+    }
+
+    /// Whether the config's `include` / `exclude` globs leave out `path`.
+    fn skips(&self, path: &Path) -> bool {
+        self.walk.skip_reason(path).is_some()
+    }
+}
+
+/// Loaded state per `[[targets]]` entry, plus the top-level state for
+/// documents outside every target.
+struct Workspace {
+    /// The `.sqlshield.toml` files this was loaded from, absolute and
+    /// outermost first.
+    files: Vec<PathBuf>,
+    default: LoadedState,
+    /// Each target's absolute directory and state.
+    targets: Vec<(PathBuf, LoadedState)>,
+}
+
+impl Workspace {
+    /// The state of the target whose directory contains `path` (the
+    /// deepest, for nested targets), or the top-level one.
+    fn state_for(&self, path: &Path) -> &LoadedState {
+        self.targets
+            .iter()
+            .filter(|(dir, _)| path.starts_with(dir))
+            .max_by_key(|(dir, _)| dir.components().count())
+            .map_or(&self.default, |(_, state)| state)
+    }
+
+    fn states(&self) -> impl Iterator<Item = &LoadedState> {
+        std::iter::once(&self.default).chain(self.targets.iter().map(|(_, state)| state))
+    }
 }
 
 pub struct Backend {
     client: Client,
     documents: DashMap<Url, String>,
-    state: RwLock<Option<Arc<LoadedState>>>,
+    state: RwLock<Option<Arc<Workspace>>>,
+    /// Workspaces for documents under a `.sqlshield.toml` below the root's,
+    /// keyed by that innermost config file. Filled on first use.
+    nested: DashMap<PathBuf, Arc<Workspace>>,
     /// Workspace root captured at `initialize` time; needed so reloads can
     /// re-discover `.sqlshield.toml` after the schema file changes.
     root_dir: RwLock<Option<PathBuf>>,
@@ -50,6 +101,7 @@ impl Backend {
             client,
             documents: DashMap::new(),
             state: RwLock::new(None),
+            nested: DashMap::new(),
             root_dir: RwLock::new(None),
             editor_settings: RwLock::new(EditorSettings::default()),
         }
@@ -64,9 +116,17 @@ impl Backend {
 
     async fn load_state_from_dir(&self, dir: &Path) {
         *self.root_dir.write().await = Some(dir.to_path_buf());
+        let workspace = self
+            .load_workspace(&sqlshield::config::find(dir), dir)
+            .await;
+        *self.state.write().await = Some(Arc::new(workspace));
+        self.nested.clear();
+    }
 
+    /// Load the schemas for the config made of `files`, layered.
+    async fn load_workspace(&self, files: &[PathBuf], root: &Path) -> Workspace {
         let editor = self.editor_settings.read().await.clone();
-        let cfg = match config::resolve(dir, &editor) {
+        let cfg = match config::resolve(files, root, &editor) {
             Ok(cfg) => cfg,
             Err(err) => {
                 self.log_error(format!("config problem: {err}")).await;
@@ -74,60 +134,131 @@ impl Backend {
             }
         };
 
-        let loaded = match cfg.schema_path.as_ref() {
-            Some(path) => match schema::load_schema_from_file(path, cfg.dialect) {
-                Ok(schema) => LoadedState {
-                    schema,
-                    dialect: cfg.dialect,
-                    schema_source: Some(path.clone()),
-                    schema_mtime: file_mtime(path),
-                },
-                Err(err) => {
-                    self.log_error(format!("failed to load schema {}: {err}", path.display()))
-                        .await;
-                    // Use empty schema so validation still runs; tables won't match.
-                    LoadedState {
-                        schema: TablesAndColumns::new(),
-                        dialect: cfg.dialect,
-                        schema_source: None,
-                        schema_mtime: None,
-                    }
-                }
-            },
-            None => {
-                self.log_info("no schema configured — LSP will not flag missing tables or columns")
-                    .await;
-                LoadedState {
-                    schema: TablesAndColumns::new(),
-                    dialect: cfg.dialect,
-                    schema_source: None,
-                    schema_mtime: None,
-                }
+        if cfg.schema_path.is_none() && cfg.targets.is_empty() {
+            self.log_info("no schema configured — LSP will not flag missing tables or columns")
+                .await;
+        }
+        let default = self
+            .load_state(
+                cfg.schema_path.as_ref(),
+                cfg.dialect,
+                cfg.walk.clone(),
+                &cfg,
+            )
+            .await;
+        let mut targets = Vec::new();
+        for target in &cfg.targets {
+            if target.schema_path.is_none() {
+                self.log_error(format!(
+                    "target `{}` reads its schema from db_url, which the LSP can't introspect",
+                    target.name
+                ))
+                .await;
             }
+            let state = self
+                .load_state(
+                    target.schema_path.as_ref(),
+                    target.dialect,
+                    target.walk.clone(),
+                    &cfg,
+                )
+                .await;
+            targets.push((target.directory.clone(), state));
+        }
+        Workspace {
+            files: files
+                .iter()
+                .map(|f| sqlshield::config::absolute(f))
+                .collect(),
+            default,
+            targets,
+        }
+    }
+
+    /// The workspace for the document at `path`: the root's, unless a
+    /// `.sqlshield.toml` closer to the document layers settings over it.
+    async fn workspace_for(&self, path: &Path) -> Option<Arc<Workspace>> {
+        let root = self.state.read().await.clone()?;
+        let dir = path.parent()?;
+        let files = sqlshield::config::find(dir);
+        let Some(innermost) = files.last().map(|f| sqlshield::config::absolute(f)) else {
+            return Some(root);
         };
+        if root.files.last() == Some(&innermost) {
+            return Some(root);
+        }
+        if let Some(workspace) = self.nested.get(&innermost) {
+            return Some(workspace.clone());
+        }
+        let root_dir = self.root_dir.read().await.clone().unwrap_or_default();
+        let workspace = Arc::new(self.load_workspace(&files, &root_dir).await);
+        self.nested.insert(innermost, workspace.clone());
+        Some(workspace)
+    }
 
-        if let Some(ref p) = loaded.schema_source {
-            self.log_info(format!("loaded schema from {}", p.display()))
-                .await;
+    /// Load one schema with the config's schema-level settings. A schema
+    /// that fails to load is logged and replaced by an empty one so
+    /// validation still runs; tables won't match.
+    async fn load_state(
+        &self,
+        schema_path: Option<&PathBuf>,
+        dialect: Dialect,
+        walk: WalkOptions,
+        cfg: &ServerConfig,
+    ) -> LoadedState {
+        let mut loaded = LoadedState {
+            schema: Schema::default(),
+            dialect,
+            schema_source: None,
+            schema_mtime: None,
+            rules: cfg.rules.clone(),
+            walk,
+        };
+        let Some(path) = schema_path else {
+            return loaded;
+        };
+        match schema::load_schema_from_file(path, dialect) {
+            Ok(mut schema) => {
+                schema.allow_functions(&cfg.functions, dialect);
+                if let Some(only_full_group_by) = cfg.only_full_group_by {
+                    schema.settings.only_full_group_by = only_full_group_by;
+                }
+                loaded.schema = schema;
+                loaded.schema_source = Some(path.clone());
+                loaded.schema_mtime = file_mtime(path);
+                self.log_info(format!("loaded schema from {}", path.display()))
+                    .await;
+            }
+            Err(err) => {
+                self.log_error(format!("failed to load schema {}: {err}", path.display()))
+                    .await;
+            }
         }
-        *self.state.write().await = Some(Arc::new(loaded));
+        loaded
     }
 
-    /// Poll the schema file's mtime; if it has changed since the last load,
-    /// re-run discovery + load. Cheap enough to call before every validation
+    /// Poll the schema files' mtimes; if any has changed since the last
+    /// load, re-run discovery + load. Cheap enough to call before every validation
     /// (one stat per buffer-change), and avoids the need for a dedicated file
     /// watcher or `workspace/didChangeWatchedFiles` registration.
     async fn maybe_reload(&self) {
         let needs_reload = {
             let guard = self.state.read().await;
-            let Some(state) = guard.as_ref() else {
-                return;
-            };
-            let Some(path) = state.schema_source.as_ref() else {
+            let Some(workspace) = guard.as_ref() else {
                 return;
             };
-            let current = file_mtime(path);
-            current != state.schema_mtime
+            let nested: Vec<Arc<Workspace>> =
+                self.nested.iter().map(|w| w.value().clone()).collect();
+            let changed = std::iter::once(workspace)
+                .chain(&nested)
+                .flat_map(|w| w.states())
+                .any(|state| {
+                    state
+                        .schema_source
+                        .as_ref()
+                        .is_some_and(|path| file_mtime(path) != state.schema_mtime)
+                });
+            changed
         };
         if !needs_reload {
             return;
@@ -138,19 +269,23 @@ impl Backend {
 
     async fn validate_and_publish(&self, uri: Url, text: &str) {
         self.maybe_reload().await;
-        let Some(state) = self.state.read().await.clone() else {
+        let Ok(path) = uri.to_file_path() else {
             return;
         };
-
-        let Some(file_ext) = uri
-            .to_file_path()
-            .ok()
-            .and_then(|p| p.extension().map(|e| e.to_string_lossy().to_string()))
-        else {
+        let Some(workspace) = self.workspace_for(&path).await else {
+            return;
+        };
+        let state = workspace.state_for(&path);
+        let Some(file_ext) = path.extension().map(|e| e.to_string_lossy().to_string()) else {
             return;
         };
 
-        let diagnostics = compute_diagnostics(text, &file_ext, &state);
+        let diagnostics = if state.skips(&path) {
+            Vec::new()
+        } else {
+            let rules = state.rules_for(&path);
+            compute_diagnostics(text, &file_ext, state, &rules)
+        };
         self.client
             .publish_diagnostics(uri, diagnostics, None)
             .await;
@@ -262,10 +397,16 @@ impl LanguageServer for Backend {
     }
 }
 
-/// Pure function: given document text + extension + server state, return the
-/// list of LSP Diagnostics. Separated from `Backend` so it can be unit-tested
-/// without spinning up the transport.
-fn compute_diagnostics(text: &str, file_ext: &str, state: &LoadedState) -> Vec<Diagnostic> {
+/// Pure function: given document text + extension + server state + the
+/// document's rule severities, return the list of LSP Diagnostics.
+/// Separated from `Backend` so it can be unit-tested without spinning up
+/// the transport.
+fn compute_diagnostics(
+    text: &str,
+    file_ext: &str,
+    state: &LoadedState,
+    rules: &RuleConfig,
+) -> Vec<Diagnostic> {
     let mut diagnostics = Vec::new();
 
     match file_ext {
@@ -274,38 +415,65 @@ fn compute_diagnostics(text: &str, file_ext: &str, state: &LoadedState) -> Vec<D
             // against the server's cached schema. Column info isn't threaded
             // through, so every diagnostic points at line 0.
             let dialect = state.dialect.as_sqlparser();
-            match sqlparser::parser::Parser::parse_sql(dialect.as_ref(), text) {
+            match sqlshield::parse::parse_sql(dialect.as_ref(), text) {
                 Ok(statements) => {
-                    for desc in validation::validate_statements_with_schema(
-                        &statements,
-                        &state.schema,
-                        state.dialect,
-                    ) {
-                        diagnostics.push(make_diagnostic(0, 0, 0, 0, desc));
+                    for (severity, code, desc) in findings(&statements, state, rules) {
+                        diagnostics.push(make_diagnostic(0, 0, 0, 0, desc, severity, Some(code)));
                     }
                 }
                 Err(err) => {
-                    diagnostics.push(make_diagnostic(0, 0, 0, 0, err.to_string()));
+                    if let Some(severity) = lsp_severity(rules.severity(lint::UNPARSEABLE_SQL)) {
+                        diagnostics.push(make_diagnostic(
+                            0,
+                            0,
+                            0,
+                            0,
+                            err.to_string(),
+                            severity,
+                            Some(lint::UNPARSEABLE_SQL),
+                        ));
+                    }
                 }
             }
         }
         "py" | "rs" | "go" | "ts" | "tsx" | "js" => {
             let dialect = state.dialect.as_sqlparser();
-            match sqlshield::finder::find_queries_in_code_with_dialect(
-                text.as_bytes(),
-                file_ext,
-                dialect.as_ref(),
-            ) {
-                Ok(queries) => {
-                    let errors = validation::validate_queries_in_code(
-                        &queries,
-                        &state.schema,
-                        state.dialect,
-                    );
-                    for err in errors {
-                        // err.line is 1-based; LSP is 0-based.
-                        let line = err.line.saturating_sub(1) as u32;
-                        diagnostics.push(make_diagnostic(line, 0, line, u32::MAX, err.description));
+            match sqlshield::finder::extract_from_code(text.as_bytes(), file_ext, dialect.as_ref())
+            {
+                Ok(extracted) => {
+                    for query in &extracted.queries {
+                        // query.line is 1-based; LSP is 0-based.
+                        let line = query.line.saturating_sub(1) as u32;
+                        for (severity, code, desc) in findings(&query.statements, state, rules) {
+                            diagnostics.push(make_diagnostic(
+                                line,
+                                0,
+                                line,
+                                u32::MAX,
+                                desc,
+                                severity,
+                                Some(code),
+                            ));
+                        }
+                    }
+                    for unparsed in extracted.unparsed {
+                        let code = match unparsed.kind {
+                            UnparsedKind::Sql => lint::UNPARSEABLE_SQL,
+                            UnparsedKind::Source => lint::SOURCE_PARSE_ERROR,
+                        };
+                        let Some(severity) = lsp_severity(rules.severity(code)) else {
+                            continue;
+                        };
+                        let zero_based = |n: usize| n.saturating_sub(1) as u32;
+                        diagnostics.push(make_diagnostic(
+                            zero_based(unparsed.line),
+                            zero_based(unparsed.column),
+                            zero_based(unparsed.end_line),
+                            zero_based(unparsed.end_column),
+                            unparsed.message,
+                            severity,
+                            Some(code),
+                        ));
                     }
                 }
                 Err(err) => {
@@ -319,6 +487,35 @@ fn compute_diagnostics(text: &str, file_ext: &str, state: &LoadedState) -> Vec<D
     diagnostics
 }
 
+/// Validation errors then lints for one SQL string, at their configured
+/// severities. Rules set to off are dropped.
+fn findings(
+    statements: &[Statement],
+    state: &LoadedState,
+    rules: &RuleConfig,
+) -> Vec<(DiagnosticSeverity, &'static str, String)> {
+    let errors =
+        validation::validate_statements_with_schema(statements, &state.schema, state.dialect)
+            .into_iter()
+            .map(|e| (rules.severity(e.code), e.code, e.message));
+    let lints = lint::lint_statements(statements, &state.schema, state.dialect, rules)
+        .into_iter()
+        .map(|l| (l.severity, l.code, l.message));
+    errors
+        .chain(lints)
+        .filter_map(|(severity, code, desc)| Some((lsp_severity(severity)?, code, desc)))
+        .collect()
+}
+
+/// The LSP severity for a rule severity; `None` for rules set to off.
+fn lsp_severity(severity: Severity) -> Option<DiagnosticSeverity> {
+    match severity {
+        Severity::Off => None,
+        Severity::Warning => Some(DiagnosticSeverity::WARNING),
+        Severity::Error => Some(DiagnosticSeverity::ERROR),
+    }
+}
+
 fn file_mtime(path: &Path) -> Option<SystemTime> {
     std::fs::metadata(path).ok().and_then(|m| m.modified().ok())
 }
@@ -329,6 +526,8 @@ fn make_diagnostic(
     end_line: u32,
     end_char: u32,
     message: String,
+    severity: DiagnosticSeverity,
+    code: Option<&str>,
 ) -> Diagnostic {
     Diagnostic {
         range: Range {
@@ -341,8 +540,8 @@ fn make_diagnostic(
                 character: end_char,
             },
         },
-        severity: Some(DiagnosticSeverity::ERROR),
-        code: None,
+        severity: Some(severity),
+        code: code.map(|c| NumberOrString::String(c.to_string())),
         code_description: None,
         source: Some("sqlshield".to_string()),
         message,
@@ -364,10 +563,12 @@ mod tests {
             HashSet::from(["id".to_string(), "name".to_string()]),
         );
         LoadedState {
-            schema,
+            schema: Schema::from(schema),
             dialect: Dialect::Generic,
             schema_source: None,
             schema_mtime: None,
+            rules: RuleConfig::default(),
+            walk: WalkOptions::default(),
         }
     }
 
@@ -377,7 +578,7 @@ mod tests {
         let source = r#"
 q = "SELECT email FROM users"
 "#;
-        let diags = compute_diagnostics(source, "py", &s);
+        let diags = compute_diagnostics(source, "py", &s, &s.rules);
         assert!(
             diags.iter().any(|d| d.message.contains("email")),
             "got: {:?}",
@@ -389,7 +590,7 @@ q = "SELECT email FROM users"
     fn rust_embedded_sql_flags_missing_table() {
         let s = state();
         let source = r#"fn x() { let _ = "SELECT id FROM ghosts"; }"#;
-        let diags = compute_diagnostics(source, "rs", &s);
+        let diags = compute_diagnostics(source, "rs", &s, &s.rules);
         assert!(diags.iter().any(|d| d.message.contains("ghosts")));
     }
 
@@ -397,14 +598,14 @@ q = "SELECT email FROM users"
     fn valid_python_embedded_sql_has_no_diagnostics() {
         let s = state();
         let source = r#"q = "SELECT id, name FROM users""#;
-        let diags = compute_diagnostics(source, "py", &s);
+        let diags = compute_diagnostics(source, "py", &s, &s.rules);
         assert!(diags.is_empty(), "got: {diags:?}");
     }
 
     #[test]
     fn unknown_extension_yields_nothing() {
         let s = state();
-        let diags = compute_diagnostics("SELECT email FROM users", "txt", &s);
+        let diags = compute_diagnostics("SELECT email FROM users", "txt", &s, &s.rules);
         assert!(diags.is_empty());
     }
 
@@ -412,7 +613,7 @@ q = "SELECT email FROM users"
     fn python_diagnostic_line_is_zero_based() {
         let s = state();
         let source = "# line 1\n# line 2\nq = \"SELECT email FROM users\"\n";
-        let diags = compute_diagnostics(source, "py", &s);
+        let diags = compute_diagnostics(source, "py", &s, &s.rules);
         assert_eq!(diags.len(), 1);
         // Line 3 in 1-based sqlshield output → line 2 in LSP.
         assert_eq!(diags[0].range.start.line, 2);
@@ -422,9 +623,97 @@ q = "SELECT email FROM users"
     fn severity_is_error_and_source_is_sqlshield() {
         let s = state();
         let source = r#"q = "SELECT email FROM users""#;
-        let diags = compute_diagnostics(source, "py", &s);
+        let diags = compute_diagnostics(source, "py", &s, &s.rules);
         assert!(!diags.is_empty());
         assert_eq!(diags[0].severity, Some(DiagnosticSeverity::ERROR));
         assert_eq!(diags[0].source.as_deref(), Some("sqlshield"));
     }
+
+    #[test]
+    fn lints_and_rule_severities_reach_the_editor() {
+        let mut s = state();
+        let source = r#"q = "SELECT * FROM users""#;
+        let diags = compute_diagnostics(source, "py", &s, &s.rules);
+        assert_eq!(diags.len(), 1, "got: {diags:?}");
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(
+            diags[0].code,
+            Some(NumberOrString::String("select-star".to_string()))
+        );
+
+        s.rules.set("select-star", Severity::Off).unwrap();
+        s.rules.set("unknown-column", Severity::Warning).unwrap();
+        assert!(compute_diagnostics(source, "py", &s, &s.rules).is_empty());
+        let diags = compute_diagnostics(r#"q = "SELECT email FROM users""#, "py", &s, &s.rules);
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::WARNING));
+    }
+
+    #[test]
+    fn unparseable_sql_is_flagged_where_it_is() {
+        let mut s = state();
+        let source = "# broken\nq = \"SELECT id FROM users WHERE id = AND 1\"\n";
+        let diags = compute_diagnostics(source, "py", &s, &s.rules);
+        assert_eq!(diags.len(), 1, "got: {diags:?}");
+        assert_eq!(
+            diags[0].code,
+            Some(NumberOrString::String("unparseable-sql".to_string()))
+        );
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(diags[0].range.start.line, 1);
+
+        s.rules.set("unparseable-sql", Severity::Off).unwrap();
+        assert!(compute_diagnostics(source, "py", &s, &s.rules).is_empty());
+    }
+
+    #[test]
+    fn overrides_are_relative_to_the_config_dir() {
+        let mut scoped = RuleConfig::default();
+        scoped.enable("unknown-column", false).unwrap();
+        let mut s = state();
+        s.rules
+            .add_override(&["tests/**".to_string()], scoped)
+            .unwrap();
+        s.rules.rebase(Path::new("/repo"));
+        let source = r#"q = "SELECT email FROM users""#;
+
+        let rules = s.rules_for(Path::new("/repo/tests/test_db.py"));
+        assert!(compute_diagnostics(source, "py", &s, &rules).is_empty());
+        let rules = s.rules_for(Path::new("/repo/app/db.py"));
+        assert_eq!(compute_diagnostics(source, "py", &s, &rules).len(), 1);
+    }
+
+    #[test]
+    fn exclude_globs_are_relative_to_the_config_dir() {
+        let mut s = state();
+        s.walk
+            .exclude(&["gen/".to_string()])
+            .unwrap()
+            .rebase(Path::new("/repo"));
+
+        assert!(s.skips(Path::new("/repo/gen/models.py")));
+        assert!(!s.skips(Path::new("/repo/app/gen/models.py")));
+        assert!(!s.skips(Path::new("/repo/app/db.py")));
+    }
+
+    #[test]
+    fn documents_use_the_deepest_target_containing_them() {
+        let mut api = state();
+        api.dialect = Dialect::Postgres;
+        let mut admin = state();
+        admin.dialect = Dialect::MySql;
+        let workspace = Workspace {
+            files: Vec::new(),
+            default: state(),
+            targets: vec![
+                (PathBuf::from("/repo/services"), api),
+                (PathBuf::from("/repo/services/admin"), admin),
+            ],
+        };
+
+        let dialect = |p: &str| workspace.state_for(Path::new(p)).dialect;
+        assert_eq!(dialect("/repo/services/api/db.py"), Dialect::Postgres);
+        assert_eq!(dialect("/repo/services/admin/db.py"), Dialect::MySql);
+        assert_eq!(dialect("/repo/scripts/once.py"), Dialect::Generic);
+        assert_eq!(dialect("/repo/services-old/db.py"), Dialect::Generic);
+    }
 }
@@ -0,0 +1,381 @@
+//! `.sqlshield.toml`: finding and reading it, for the CLI and the language
+//! server alike. The config for a directory is found by walking up from it,
+//! so a run from anywhere in a project picks up the project's config. A
+//! config in a subdirectory doesn't replace the ones above it: its settings
+//! are layered over theirs for its subtree (see [`Config::overlay`]), and
+//! `root = true` stops the search. Paths in a config are relative to the
+//! directory it's in.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::dialect::Dialect;
+use crate::lint::{RuleConfig, Severity};
+use crate::schema::Schema;
+use crate::walk::WalkOptions;
+
+pub const CONFIG_FILE_NAME: &str = ".sqlshield.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+    /// Read by [`find`] through [`RawRoot`]; listed so it isn't an unknown
+    /// key.
+    #[serde(default)]
+    #[allow(dead_code)]
+    root: bool,
+    schema: Option<PathBuf>,
+    directory: Option<PathBuf>,
+    dialect: Option<String>,
+    db_url: Option<String>,
+    #[serde(default)]
+    functions: Vec<String>,
+    only_full_group_by: Option<bool>,
+    #[serde(default)]
+    rules: HashMap<String, RawRuleSetting>,
+    #[serde(default)]
+    overrides: Vec<RawOverride>,
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+    #[serde(default)]
+    targets: Vec<RawTarget>,
+}
+
+/// A `[[targets]]` entry: one schema, dialect and directory of a monorepo.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawTarget {
+    name: Option<String>,
+    directory: PathBuf,
+    schema: Option<PathBuf>,
+    db_url: Option<String>,
+    dialect: Option<String>,
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+/// An `[[overrides]]` block: rule settings for files matching `paths`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawOverride {
+    paths: Vec<String>,
+    #[serde(default)]
+    rules: HashMap<String, RawRuleSetting>,
+}
+
+/// A `[rules]` entry: a severity (`"off"`, `"warning"`, `"error"`) or a
+/// plain on/off switch that keeps the rule's default severity.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawRuleSetting {
+    Enabled(bool),
+    Severity(String),
+}
+
+/// Just the `root` key, to decide whether to keep searching upward before
+/// the file is read for real.
+#[derive(Deserialize)]
+struct RawRoot {
+    #[serde(default)]
+    root: bool,
+}
+
+/// One config file, or several layered into one. Paths are already
+/// resolved against the directory of the file that set them.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    pub schema: Option<PathBuf>,
+    pub directory: Option<PathBuf>,
+    pub dialect: Option<Dialect>,
+    pub db_url: Option<String>,
+    /// Extension functions to accept with any arguments, on top of the
+    /// schema's `CREATE FUNCTION`s and the dialect's built-ins.
+    pub functions: Vec<String>,
+    /// MySQL `ONLY_FULL_GROUP_BY`; unset keeps the MySQL default (on).
+    pub only_full_group_by: Option<bool>,
+    /// Rule severities from the `[rules]` table and `[[overrides]]`.
+    pub rules: RuleConfig,
+    /// `include` / `exclude` globs for the directory walk.
+    pub walk: WalkOptions,
+    /// `[[targets]]`, each checked on its own; empty for a single-target
+    /// config.
+    pub targets: Vec<TargetConfig>,
+    /// The files this was read from, outermost first.
+    pub files: Vec<PathBuf>,
+}
+
+/// A resolved `[[targets]]` entry.
+#[derive(Debug, Clone)]
+pub struct TargetConfig {
+    /// `name`, or the directory as written when unnamed.
+    pub name: String,
+    pub directory: PathBuf,
+    /// Exactly one of `schema` and `db_url` is set.
+    pub schema: Option<PathBuf>,
+    pub db_url: Option<String>,
+    pub dialect: Option<Dialect>,
+    /// The top-level globs plus the target's own.
+    pub walk: WalkOptions,
+}
+
+impl Config {
+    /// Apply the schema-level settings from the config: allowed extension
+    /// functions and database settings.
+    pub fn apply_to(&self, schema: &mut Schema, dialect: Dialect) {
+        schema.allow_functions(&self.functions, dialect);
+        if let Some(only_full_group_by) = self.only_full_group_by {
+            schema.settings.only_full_group_by = only_full_group_by;
+        }
+    }
+
+    /// The directory of the innermost config file, which unset paths
+    /// default to; `.` when there's none.
+    pub fn dir(&self) -> &Path {
+        self.files
+            .last()
+            .and_then(|f| f.parent())
+            .filter(|d| !d.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+    }
+
+    /// Layer `nested`, a config further down the tree, over this one.
+    /// Settings it sets win, including over the ones of this config's
+    /// targets, since the result only applies to `nested`'s subtree;
+    /// `functions`, rule overrides and globs add up. Targets it declares
+    /// replace these.
+    pub fn overlay(&mut self, nested: Config) {
+        if nested.schema.is_some() || nested.db_url.is_some() {
+            self.schema = nested.schema;
+            self.db_url = nested.db_url;
+            for t in &mut self.targets {
+                t.schema.clone_from(&self.schema);
+                t.db_url.clone_from(&self.db_url);
+            }
+        }
+        if let Some(dialect) = nested.dialect {
+            self.dialect = Some(dialect);
+            for t in &mut self.targets {
+                t.dialect = Some(dialect);
+            }
+        }
+        if nested.directory.is_some() {
+            self.directory = nested.directory;
+        }
+        self.functions.extend(nested.functions);
+        if nested.only_full_group_by.is_some() {
+            self.only_full_group_by = nested.only_full_group_by;
+        }
+        self.rules.extend(nested.rules);
+
+        let outer_walk = self.walk.clone();
+        self.walk.extend(&nested.walk);
+        if nested.targets.is_empty() {
+            for t in &mut self.targets {
+                t.walk.extend(&nested.walk);
+            }
+        } else {
+            self.targets = nested
+                .targets
+                .into_iter()
+                .map(|mut t| {
+                    let mut walk = outer_walk.clone();
+                    walk.extend(&t.walk);
+                    t.walk = walk;
+                    t
+                })
+                .collect();
+        }
+        self.files.extend(nested.files);
+    }
+}
+
+/// Read one config file. Only malformed files error.
+pub fn load(path: &Path) -> Result<Config, String> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+    let raw: RawConfig =
+        toml::from_str(&source).map_err(|e| format!("invalid {}: {e}", path.display()))?;
+
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let in_dir = |p: PathBuf| {
+        if p.is_absolute() || dir.as_os_str().is_empty() || dir == Path::new(".") {
+            p
+        } else {
+            dir.join(p)
+        }
+    };
+    let invalid = |e: String| format!("invalid {}: {e}", path.display());
+
+    let dialect = raw
+        .dialect
+        .as_deref()
+        .map(Dialect::from_str)
+        .transpose()
+        .map_err(invalid)?;
+    let mut rules = rule_config(&raw.rules).map_err(invalid)?;
+    for o in &raw.overrides {
+        let scoped = rule_config(&o.rules).map_err(invalid)?;
+        rules.add_override(&o.paths, scoped).map_err(invalid)?;
+    }
+    rules.rebase(dir);
+    let mut walk = WalkOptions::default();
+    walk.include(&raw.include).map_err(invalid)?;
+    walk.exclude(&raw.exclude).map_err(invalid)?;
+    walk.rebase(dir);
+
+    let mut targets: Vec<TargetConfig> = Vec::new();
+    for t in raw.targets {
+        let name = t
+            .name
+            .unwrap_or_else(|| t.directory.to_string_lossy().into_owned());
+        if targets.iter().any(|other| other.name == name) {
+            return Err(invalid(format!("duplicate target `{name}`")));
+        }
+        if t.schema.is_some() == t.db_url.is_some() {
+            return Err(invalid(format!(
+                "target `{name}` needs exactly one of `schema` and `db_url`"
+            )));
+        }
+        let dialect = t
+            .dialect
+            .as_deref()
+            .map(Dialect::from_str)
+            .transpose()
+            .map_err(|e| invalid(format!("target `{name}`: {e}")))?;
+        let mut target_walk = walk.clone();
+        target_walk.include(&t.include).map_err(invalid)?;
+        target_walk.exclude(&t.exclude).map_err(invalid)?;
+        target_walk.rebase(dir);
+        targets.push(TargetConfig {
+            name,
+            directory: in_dir(t.directory),
+            schema: t.schema.map(in_dir),
+            db_url: t.db_url,
+            dialect,
+            walk: target_walk,
+        });
+    }
+
+    Ok(Config {
+        schema: raw.schema.map(in_dir),
+        directory: raw.directory.map(in_dir),
+        dialect,
+        db_url: raw.db_url,
+        functions: raw.functions,
+        only_full_group_by: raw.only_full_group_by,
+        rules,
+        walk,
+        targets,
+        files: vec![path.to_path_buf()],
+    })
+}
+
+/// The config files that apply to `dir`: every `.sqlshield.toml` from
+/// `dir` upward, up to the first with `root = true`. Outermost first.
+/// Paths are spelled relative to `dir` as given (`..`, `../..` for `.`).
+pub fn find(dir: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut dir = dir.to_path_buf();
+    loop {
+        let candidate = if dir == Path::new(".") {
+            PathBuf::from(CONFIG_FILE_NAME)
+        } else {
+            dir.join(CONFIG_FILE_NAME)
+        };
+        if candidate.is_file() {
+            let root = std::fs::read_to_string(&candidate)
+                .ok()
+                .and_then(|s| toml::from_str::<RawRoot>(&s).ok())
+                .is_some_and(|r| r.root);
+            found.push(candidate);
+            if root {
+                break;
+            }
+        }
+        if absolute(&dir).parent().is_none() {
+            break;
+        }
+        dir = parent(&dir);
+    }
+    found.reverse();
+    found
+}
+
+/// Read `files` (as returned by [`find`]) and layer them, outermost first.
+pub fn load_chain(files: &[PathBuf]) -> Result<Config, String> {
+    let mut config = Config::default();
+    for (i, file) in files.iter().enumerate() {
+        let loaded = load(file)?;
+        if i == 0 {
+            config = loaded;
+        } else {
+            config.overlay(loaded);
+        }
+    }
+    Ok(config)
+}
+
+/// The config in effect for files in `dir`. `Ok(None)` if no file applies;
+/// missing files are just defaults.
+pub fn discover(dir: &Path) -> Result<Option<Config>, String> {
+    let files = find(dir);
+    if files.is_empty() {
+        return Ok(None);
+    }
+    load_chain(&files).map(Some)
+}
+
+/// `path` made absolute against the current directory, with `.` and `..`
+/// folded away lexically (no symlinks resolved, nothing read from disk), so
+/// two spellings of one path compare equal.
+pub fn absolute(path: &Path) -> PathBuf {
+    let joined = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().unwrap_or_default().join(path)
+    };
+    let mut out = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            c => out.push(c.as_os_str()),
+        }
+    }
+    out
+}
+
+/// The directory above `dir`, keeping relative paths relative.
+fn parent(dir: &Path) -> PathBuf {
+    match dir.components().next_back() {
+        Some(Component::Normal(_)) => match dir.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+            _ => PathBuf::from("."),
+        },
+        Some(Component::CurDir) | None => PathBuf::from(".."),
+        _ => dir.join(".."),
+    }
+}
+
+fn rule_config(raw: &HashMap<String, RawRuleSetting>) -> Result<RuleConfig, String> {
+    let mut rules = RuleConfig::default();
+    for (code, setting) in raw {
+        match setting {
+            RawRuleSetting::Enabled(enabled) => rules.enable(code, *enabled)?,
+            RawRuleSetting::Severity(s) => {
+                let severity = Severity::from_str(s).map_err(|e| format!("rule `{code}`: {e}"))?;
+                rules.set(code, severity)?;
+            }
+        }
+    }
+    Ok(rules)
+}
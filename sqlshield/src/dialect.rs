@@ -5,7 +5,7 @@ use std::str::FromStr;
 
 /// Which SQL dialect to parse with. See [sqlparser::dialect] for specifics
 /// of each dialect's grammar.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
 pub enum Dialect {
     /// Permissive superset useful for heterogeneous codebases (the default).
     #[default]
@@ -14,12 +14,23 @@ pub enum SqlShieldError {
     #[error("file {} has no extension", .0.display())]
     MissingExtension(PathBuf),
 
-    #[error("unsupported source file extension `{0}` (expected one of: py, rs)")]
+    #[error(
+        "unsupported source file extension `{0}` (expected one of: py, rs, go, js, ts, tsx, sql)"
+    )]
     UnsupportedFileExtension(String),
 
-    #[error("unsupported schema type `{0}` (expected one of: sql)")]
+    #[error("unsupported schema type `{0}` (expected one of: sql, json, yaml, yml, py)")]
     UnsupportedSchemaType(String),
 
+    #[error("failed to parse JSON schema: {0}")]
+    SchemaJson(#[from] serde_json::Error),
+
+    #[error("failed to parse YAML schema: {0}")]
+    SchemaYaml(#[from] serde_yaml_ng::Error),
+
+    #[error("invalid schema: {0}")]
+    InvalidSchema(String),
+
     #[error("failed to parse SQL: {0}")]
     SqlParse(#[from] sqlparser::parser::ParserError),
 
@@ -1,9 +1,13 @@
 //! Locates SQL strings inside source files by walking a tree-sitter AST.
+//! `.sql` files are split into their statements instead. What can't be
+//! read — SQL that doesn't parse, source the grammar chokes on — comes back
+//! as [`Unparsed`] next to the queries, so callers can report it.
 
 mod go;
 mod javascript;
 mod python;
 mod rust;
+mod sql;
 
 use std::{fs, path::Path};
 
@@ -12,11 +16,80 @@ use crate::error::{Result, SqlShieldError};
 #[derive(Debug)]
 pub struct QueryInCode {
     pub line: usize,
+    /// 1-based column, in characters, where the string literal starts.
+    pub column: usize,
+    /// 1-based line and character column of the literal's end (exclusive).
+    pub end_line: usize,
+    pub end_column: usize,
+    /// The SQL as parsed: the literal's content with placeholders
+    /// substituted, or the statement's text in a `.sql` file.
+    pub text: String,
+    /// Name of the innermost named function or method the literal is in;
+    /// `None` at module level and in `.sql` files.
+    pub function: Option<String>,
     pub statements: Vec<sqlparser::ast::Statement>,
 }
 
+/// Something the finder couldn't turn into queries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unparsed {
+    pub kind: UnparsedKind,
+    /// 1-based line and character column of the start and (exclusive) end.
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub message: String,
+    /// The SQL that didn't parse; empty for [`UnparsedKind::Source`].
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnparsedKind {
+    /// A string that reads like SQL, or a `.sql` statement, that the
+    /// parser rejects.
+    Sql,
+    /// Source code with a syntax error; SQL strings around it may have
+    /// been missed.
+    Source,
+}
+
+/// Everything the finder got out of one file.
+#[derive(Debug, Default)]
+pub struct Extracted {
+    pub queries: Vec<QueryInCode>,
+    pub unparsed: Vec<Unparsed>,
+}
+
 pub const SUPPORTED_CODE_FILE_EXTENSIONS: [&str; 6] = ["py", "rs", "go", "ts", "tsx", "js"];
 
+/// Plain SQL files. Checked when named explicitly; directory walks leave
+/// them out, since they're usually the schema and migrations.
+pub const SQL_FILE_EXTENSION: &str = "sql";
+
+/// Name of the language files with `file_extension` are written in, for
+/// reports: `python`, `rust`, `go`, `javascript`, `typescript`, `tsx` or
+/// `sql`.
+pub fn language(file_extension: &str) -> Option<&'static str> {
+    Some(match file_extension {
+        "py" => "python",
+        "rs" => "rust",
+        "go" => "go",
+        "js" => "javascript",
+        "ts" => "typescript",
+        "tsx" => "tsx",
+        SQL_FILE_EXTENSION => "sql",
+        _ => return None,
+    })
+}
+
+/// Whether `path` is a file the finder can read queries from.
+pub fn is_supported_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e == SQL_FILE_EXTENSION || SUPPORTED_CODE_FILE_EXTENSIONS.contains(&e))
+}
+
 pub fn find_queries_in_file(file_path: &Path) -> Result<Vec<QueryInCode>> {
     let dialect = sqlparser::dialect::GenericDialect {};
     find_queries_in_file_with_dialect(file_path, &dialect)
@@ -26,6 +99,14 @@ pub fn find_queries_in_file_with_dialect(
     file_path: &Path,
     dialect: &dyn sqlparser::dialect::Dialect,
 ) -> Result<Vec<QueryInCode>> {
+    extract_from_file(file_path, dialect).map(|e| e.queries)
+}
+
+/// The queries in `file_path`, and what in it couldn't be parsed.
+pub fn extract_from_file(
+    file_path: &Path,
+    dialect: &dyn sqlparser::dialect::Dialect,
+) -> Result<Extracted> {
     let file_extension = file_path
         .extension()
         .ok_or_else(|| SqlShieldError::MissingExtension(file_path.to_path_buf()))?
@@ -36,7 +117,7 @@ pub fn find_queries_in_file_with_dialect(
         source,
     })?;
 
-    find_queries_in_code_with_dialect(&code, &file_extension, dialect)
+    extract_from_code(&code, &file_extension, dialect)
 }
 
 type NodeQueryExtractor = fn(&tree_sitter::Node, &[u8]) -> Option<String>;
@@ -51,6 +132,22 @@ pub fn find_queries_in_code_with_dialect(
     file_extension: &str,
     dialect: &dyn sqlparser::dialect::Dialect,
 ) -> Result<Vec<QueryInCode>> {
+    extract_from_code(code, file_extension, dialect).map(|e| e.queries)
+}
+
+/// The queries in `code`, and what in it couldn't be parsed.
+pub fn extract_from_code(
+    code: &[u8],
+    file_extension: &str,
+    dialect: &dyn sqlparser::dialect::Dialect,
+) -> Result<Extracted> {
+    if file_extension == SQL_FILE_EXTENSION {
+        return Ok(sql::find_queries_in_sql(
+            &String::from_utf8_lossy(code),
+            dialect,
+        ));
+    }
+
     let (language, query_extractor): (tree_sitter::Language, NodeQueryExtractor) =
         match file_extension {
             "py" => (
@@ -90,7 +187,7 @@ pub fn find_queries_in_code_with_dialect(
         .expect("tree-sitter grammar incompatible with tree-sitter runtime");
 
     let parsed: Option<tree_sitter::Tree> = parser.parse(code, None);
-    let mut queries: Vec<QueryInCode> = Vec::new();
+    let mut extracted = Extracted::default();
 
     let tree = parsed.ok_or(SqlShieldError::CodeParse)?;
     find_queries_in_ast(
@@ -98,10 +195,28 @@ pub fn find_queries_in_code_with_dialect(
         code,
         &query_extractor,
         dialect,
-        &mut queries,
-        None,
+        &mut extracted,
     );
-    Ok(queries)
+    if let Some(node) = first_syntax_error(tree.root_node()) {
+        let what = if node.is_missing() {
+            format!("missing `{}`", node.kind())
+        } else {
+            "syntax error".to_string()
+        };
+        let (start, end) = (node.start_position(), node.end_position());
+        extracted.unparsed.push(Unparsed {
+            kind: UnparsedKind::Source,
+            line: start.row + 1,
+            column: char_column(code, node.start_byte(), start.column),
+            end_line: end.row + 1,
+            end_column: char_column(code, node.end_byte(), end.column),
+            message: format!(
+                "Source doesn't parse ({what}); SQL strings near it may have been missed"
+            ),
+            text: String::new(),
+        });
+    }
+    Ok(extracted)
 }
 
 fn find_queries_in_ast(
@@ -109,8 +224,7 @@ fn find_queries_in_ast(
     code: &[u8],
     query_extractor: &NodeQueryExtractor,
     dialect: &dyn sqlparser::dialect::Dialect,
-    queries: &mut Vec<QueryInCode>,
-    verbose: Option<u8>,
+    extracted: &mut Extracted,
 ) {
     let mut cursor = node.walk();
 
@@ -118,24 +232,126 @@ fn find_queries_in_ast(
         match query_extractor(&child, code) {
             Some(string_content) => {
                 let query_at = child.start_position();
+                let query_end = child.end_position();
+                let line = query_at.row + 1;
+                let column = char_column(code, child.start_byte(), query_at.column);
+                let end_line = query_end.row + 1;
+                let end_column = char_column(code, child.end_byte(), query_end.column);
 
-                let statements = sqlparser::parser::Parser::parse_sql(dialect, &string_content);
-
-                match statements {
+                match crate::parse::parse_sql(dialect, &string_content) {
                     Ok(statements) => {
-                        queries.push(QueryInCode {
-                            line: query_at.row + 1,
+                        extracted.queries.push(QueryInCode {
+                            line,
+                            column,
+                            end_line,
+                            end_column,
+                            text: string_content,
+                            function: enclosing_function(&child, code),
                             statements,
                         });
                     }
-                    Err(err) => {
-                        if verbose.unwrap_or(0) > 0 {
-                            eprintln!("{err} {string_content}");
-                        }
+                    // Most strings aren't SQL; only the ones that read like
+                    // a statement are worth a finding.
+                    Err(err) if looks_like_sql(&string_content) => {
+                        extracted.unparsed.push(Unparsed {
+                            kind: UnparsedKind::Sql,
+                            line,
+                            column,
+                            end_line,
+                            end_column,
+                            message: parse_error_message(&err),
+                            text: string_content,
+                        });
                     }
+                    Err(_) => {}
                 }
             }
-            None => find_queries_in_ast(&child, code, query_extractor, dialect, queries, None),
+            None => find_queries_in_ast(&child, code, query_extractor, dialect, extracted),
+        }
+    }
+}
+
+/// Node kinds of function and method definitions across the supported
+/// grammars. Anonymous ones (arrow functions, function expressions) take
+/// the name of the variable they're assigned to.
+const FUNCTION_KINDS: &[&str] = &[
+    "function_definition",
+    "function_item",
+    "function_declaration",
+    "generator_function_declaration",
+    "method_declaration",
+    "method_definition",
+    "function",
+    "function_expression",
+    "arrow_function",
+];
+
+fn enclosing_function(node: &tree_sitter::Node, code: &[u8]) -> Option<String> {
+    let text = |n: tree_sitter::Node| String::from_utf8_lossy(&code[n.byte_range()]).into_owned();
+    let mut current = node.parent();
+    while let Some(n) = current {
+        if FUNCTION_KINDS.contains(&n.kind()) {
+            if let Some(name) = n.child_by_field_name("name") {
+                return Some(text(name));
+            }
+            if let Some(name) = n
+                .parent()
+                .filter(|p| p.kind() == "variable_declarator")
+                .and_then(|p| p.child_by_field_name("name"))
+            {
+                return Some(text(name));
+            }
         }
+        current = n.parent();
     }
+    None
+}
+
+/// Words a string has to start with, and words it has to contain one of,
+/// to count as SQL that failed to parse rather than prose.
+const STATEMENT_KEYWORDS: &[&str] = &[
+    "select", "insert", "update", "delete", "with", "merge", "create", "alter", "drop", "truncate",
+];
+const CLAUSE_KEYWORDS: &[&str] = &["from", "into", "set", "where", "table", "values", "join"];
+
+fn looks_like_sql(text: &str) -> bool {
+    let mut words = text.split_whitespace().map(|w| {
+        w.trim_matches(|c: char| !c.is_alphanumeric())
+            .to_ascii_lowercase()
+    });
+    words
+        .next()
+        .is_some_and(|w| STATEMENT_KEYWORDS.contains(&w.as_str()))
+        && words.any(|w| CLAUSE_KEYWORDS.contains(&w.as_str()))
+}
+
+/// A finding's description for SQL the parser rejected.
+fn parse_error_message(err: &sqlparser::parser::ParserError) -> String {
+    use sqlparser::parser::ParserError;
+    let reason = match err {
+        ParserError::TokenizerError(s) | ParserError::ParserError(s) => s.as_str(),
+        ParserError::RecursionLimitExceeded => "nested too deeply",
+    };
+    format!("Query doesn't parse: {reason}")
+}
+
+/// The first `ERROR` or missing node in document order, if any.
+fn first_syntax_error(node: tree_sitter::Node) -> Option<tree_sitter::Node> {
+    if node.is_error() || node.is_missing() {
+        return Some(node);
+    }
+    if !node.has_error() {
+        return None;
+    }
+    let mut cursor = node.walk();
+    let children: Vec<_> = node.children(&mut cursor).collect();
+    children.into_iter().find_map(first_syntax_error)
+}
+
+/// 1-based character column of the byte at `byte`, which sits `byte_column`
+/// bytes into its line. tree-sitter counts bytes; editors and report
+/// formats count characters.
+fn char_column(code: &[u8], byte: usize, byte_column: usize) -> usize {
+    let line_prefix = &code[byte - byte_column..byte];
+    String::from_utf8_lossy(line_prefix).chars().count() + 1
 }
@@ -5,6 +5,13 @@ use regex::Regex;
 static INTERPOLATION_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"\{.*?\}").expect("static regex is valid"));
 
+/// DB-API `format` / `pyformat` placeholders (`%s`, `%(name)s`) as in
+/// `cursor.execute("… WHERE id = %s", (uid,))`, plus the `%d` / `%f` of
+/// `%`-formatting. `%%` is the literal-`%` escape and is preserved.
+static PLACEHOLDER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"%(?:\([A-Za-z_][A-Za-z0-9_]*\))?[sdif]").expect("static regex is valid")
+});
+
 pub fn extract_query_string_from_node(node: &tree_sitter::Node, code: &[u8]) -> Option<String> {
     if node.kind() != "string" {
         return None;
@@ -39,5 +46,10 @@ pub fn extract_query_string_from_node(node: &tree_sitter::Node, code: &[u8]) ->
     let substituted = INTERPOLATION_RE.replace_all(&escaped, "1");
     let restored = substituted.replace(ESC_OPEN, "{").replace(ESC_CLOSE, "}");
 
-    Some(restored)
+    // Same `1` for DB-API placeholders; `%%` becomes the `%` the driver
+    // sends, as in the Go finder.
+    const ESC_PCT: char = '\u{0003}';
+    let escaped = restored.replace("%%", &ESC_PCT.to_string());
+    let substituted = PLACEHOLDER_RE.replace_all(&escaped, "1");
+    Some(substituted.replace(ESC_PCT, "%"))
 }
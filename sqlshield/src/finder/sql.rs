@@ -0,0 +1,116 @@
+//! Queries in `.sql` files. The whole file is SQL, so there are no string
+//! literals to find; instead the file is split into statements at
+//! top-level semicolons and each statement is parsed on its own, keeping
+//! its position so findings point at the statement rather than the file.
+//! A statement that doesn't parse is reported as [`Unparsed`] and the rest
+//! are still checked.
+
+use sqlparser::dialect::Dialect;
+use sqlparser::parser::{Parser, ParserError};
+use sqlparser::tokenizer::{Location, Token, TokenWithLocation, Tokenizer};
+
+use super::{parse_error_message, Extracted, QueryInCode, Unparsed, UnparsedKind};
+
+pub fn find_queries_in_sql(code: &str, dialect: &dyn Dialect) -> Extracted {
+    let mut queries = Extracted::default();
+    let tokens = match Tokenizer::new(dialect, code).tokenize_with_location() {
+        Ok(tokens) => tokens,
+        // Without tokens there are no statement boundaries: the whole file
+        // is one finding, at the bad token.
+        Err(err) => {
+            let at = err.location;
+            queries.unparsed.push(Unparsed {
+                kind: UnparsedKind::Sql,
+                line: at.line.max(1) as usize,
+                column: at.column.max(1) as usize,
+                end_line: at.line.max(1) as usize,
+                end_column: at.column.max(1) as usize + 1,
+                message: parse_error_message(&ParserError::from(err)),
+                text: code.to_string(),
+            });
+            return queries;
+        }
+    };
+
+    let mut statement: Vec<TokenWithLocation> = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if token.token == Token::SemiColon {
+            let end = Location {
+                line: token.location.line,
+                column: token.location.column + 1,
+            };
+            push_statement(std::mem::take(&mut statement), end, dialect, &mut queries);
+            continue;
+        }
+        if statement.is_empty() && matches!(token.token, Token::Whitespace(_)) {
+            continue;
+        }
+        statement.push(token.clone());
+        // A trailing statement without `;` ends where the whitespace after
+        // its last token starts, or at the end of the file.
+        if i + 1 == tokens.len() || is_trailing_whitespace(&tokens[i + 1..]) {
+            let end = tokens
+                .get(i + 1)
+                .map_or_else(|| end_of(code), |next| next.location);
+            push_statement(std::mem::take(&mut statement), end, dialect, &mut queries);
+            break;
+        }
+    }
+    queries
+}
+
+fn is_trailing_whitespace(rest: &[TokenWithLocation]) -> bool {
+    rest.iter().all(|t| matches!(t.token, Token::Whitespace(_)))
+}
+
+fn push_statement(
+    tokens: Vec<TokenWithLocation>,
+    end: Location,
+    dialect: &dyn Dialect,
+    queries: &mut Extracted,
+) {
+    let Some(start) = tokens.first().map(|t| t.location) else {
+        return;
+    };
+    let text: String = tokens.iter().map(|t| t.token.to_string()).collect();
+    let statements = match Parser::new(dialect)
+        .with_tokens_with_locations(tokens)
+        .parse_statements()
+    {
+        Ok(statements) => statements,
+        Err(err) => {
+            queries.unparsed.push(Unparsed {
+                kind: UnparsedKind::Sql,
+                line: start.line as usize,
+                column: start.column as usize,
+                end_line: end.line as usize,
+                end_column: end.column as usize,
+                message: parse_error_message(&err),
+                text,
+            });
+            return;
+        }
+    };
+    if statements.is_empty() {
+        return;
+    }
+    queries.queries.push(QueryInCode {
+        line: start.line as usize,
+        column: start.column as usize,
+        end_line: end.line as usize,
+        end_column: end.column as usize,
+        text,
+        function: None,
+        statements,
+    });
+}
+
+/// Position just past the last character of `code`, which doesn't end in
+/// whitespace here.
+fn end_of(code: &str) -> Location {
+    let last_line = code.lines().last().unwrap_or("");
+    Location {
+        line: code.lines().count().max(1) as u64,
+        column: last_line.chars().count() as u64 + 1,
+    }
+}
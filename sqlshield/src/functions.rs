@@ -0,0 +1,1315 @@
+//! Built-in function catalogs per [`Dialect`]: name, accepted argument
+//! count, and a coarse return type.
+//!
+//! Catalogs cover the functions people actually call from application SQL,
+//! not every function an engine ships. Dialects whose catalog is marked
+//! incomplete (see [`has_complete_catalog`]) only get arity checks for the
+//! names we know; unknown names pass silently there. [`Dialect::Generic`]
+//! sees the union of every catalog, so a name is only unknown to it when no
+//! dialect defines it.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::dialect::Dialect;
+
+/// A built-in function signature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Builtin {
+    pub name: &'static str,
+    pub min_args: usize,
+    /// `None` for variadic functions.
+    pub max_args: Option<usize>,
+    /// Coarse result type (`bigint`, `text`, `timestamp`, …). `any` means
+    /// the result type follows the arguments.
+    pub returns: &'static str,
+}
+
+/// Signature of a function contributed by the schema (`CREATE FUNCTION`)
+/// or allowed through configuration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionSignature {
+    pub min_args: usize,
+    /// `None` for variadic functions.
+    pub max_args: Option<usize>,
+    pub return_type: Option<String>,
+    /// Declared types of the parameters a call passes, in order, the
+    /// `VARIADIC` one last. `None` when the source doesn't give them.
+    pub params: Option<Vec<String>>,
+}
+
+impl FunctionSignature {
+    /// A signature accepting any number of arguments. Used for extension
+    /// functions allowed by name only.
+    pub fn any() -> Self {
+        Self {
+            min_args: 0,
+            max_args: None,
+            return_type: None,
+            params: None,
+        }
+    }
+
+    pub fn accepts(&self, arg_count: usize) -> bool {
+        accepts(self.min_args, self.max_args, arg_count)
+    }
+}
+
+impl Builtin {
+    pub fn accepts(&self, arg_count: usize) -> bool {
+        accepts(self.min_args, self.max_args, arg_count)
+    }
+}
+
+fn accepts(min: usize, max: Option<usize>, arg_count: usize) -> bool {
+    arg_count >= min && max.map_or(true, |max| arg_count <= max)
+}
+
+/// Human-readable arity (`2`, `1 to 3`, `at least 1`, `no`), used in
+/// diagnostics as "expects {arity} argument(s)".
+pub fn describe_arity(min: usize, max: Option<usize>) -> String {
+    let noun = |n: usize| if n == 1 { "argument" } else { "arguments" };
+    match max {
+        Some(0) => "no arguments".to_string(),
+        Some(max) if max == min => format!("{min} {}", noun(min)),
+        Some(max) => format!("{min} to {max} {}", noun(max)),
+        None => format!("at least {min} {}", noun(min)),
+    }
+}
+
+/// Look up a built-in by (lowercase) name in the dialect's catalog.
+pub fn builtin(dialect: Dialect, name: &str) -> Option<Builtin> {
+    CATALOGS.get(&dialect)?.get(name).copied()
+}
+
+/// Whether unknown function names should be reported for `dialect`. The
+/// ClickHouse, DuckDB, and Hive libraries are too large to list usefully,
+/// so only the arity of functions we do know is checked for them.
+pub fn has_complete_catalog(dialect: Dialect) -> bool {
+    !matches!(
+        dialect,
+        Dialect::ClickHouse | Dialect::DuckDb | Dialect::Hive
+    )
+}
+
+/// Whether `name` (lowercase) is an aggregate function in some dialect.
+/// Called with an `OVER` clause these are window functions instead; that
+/// distinction is the caller's to make.
+pub fn is_aggregate(name: &str) -> bool {
+    AGGREGATES.contains(&name)
+}
+
+const AGGREGATES: &[&str] = &[
+    "any_value",
+    "approx_count_distinct",
+    "approx_quantiles",
+    "approx_top_count",
+    "approximate",
+    "arg_max",
+    "arg_min",
+    "argmax",
+    "argmin",
+    "any",
+    "anylast",
+    "array_agg",
+    "array_concat_agg",
+    "avg",
+    "avgif",
+    "bit_and",
+    "bit_or",
+    "bit_xor",
+    "bool_and",
+    "bool_or",
+    "booland_agg",
+    "boolor_agg",
+    "checksum_agg",
+    "collect_list",
+    "collect_set",
+    "corr",
+    "count",
+    "count_big",
+    "count_if",
+    "countif",
+    "covar_pop",
+    "covar_samp",
+    "every",
+    "group_concat",
+    "grouparray",
+    "json_agg",
+    "json_agg_strict",
+    "json_arrayagg",
+    "json_group_array",
+    "json_group_object",
+    "json_object_agg",
+    "json_object_agg_strict",
+    "json_object_agg_unique",
+    "json_objectagg",
+    "jsonb_agg",
+    "jsonb_agg_strict",
+    "jsonb_object_agg",
+    "jsonb_object_agg_strict",
+    "jsonb_object_agg_unique",
+    "list",
+    "list_agg",
+    "listagg",
+    "logical_and",
+    "logical_or",
+    "max",
+    "median",
+    "min",
+    "mode",
+    "object_agg",
+    "percentile_cont",
+    "percentile_disc",
+    "quantile_cont",
+    "range_agg",
+    "range_intersect_agg",
+    "regr_avgx",
+    "regr_avgy",
+    "regr_count",
+    "regr_intercept",
+    "regr_r2",
+    "regr_slope",
+    "regr_sxx",
+    "regr_sxy",
+    "regr_syy",
+    "std",
+    "stddev",
+    "stddev_pop",
+    "stddev_samp",
+    "string_agg",
+    "sum",
+    "sumif",
+    "total",
+    "uniq",
+    "uniqexact",
+    "var_pop",
+    "var_samp",
+    "variance",
+    "xmlagg",
+];
+
+const fn f(name: &'static str, min_args: usize, max_args: usize, returns: &'static str) -> Builtin {
+    Builtin {
+        name,
+        min_args,
+        max_args: Some(max_args),
+        returns,
+    }
+}
+
+/// Variadic: at least `min_args` arguments.
+const fn v(name: &'static str, min_args: usize, returns: &'static str) -> Builtin {
+    Builtin {
+        name,
+        min_args,
+        max_args: None,
+        returns,
+    }
+}
+
+/// ANSI functions (plus near-universal extensions) every dialect accepts.
+const COMMON: &[Builtin] = &[
+    // Aggregates.
+    f("count", 0, 1, "bigint"),
+    f("sum", 1, 1, "any"),
+    f("avg", 1, 1, "numeric"),
+    f("min", 1, 1, "any"),
+    f("max", 1, 1, "any"),
+    f("stddev", 1, 1, "double"),
+    f("stddev_pop", 1, 1, "double"),
+    f("stddev_samp", 1, 1, "double"),
+    f("variance", 1, 1, "double"),
+    f("var_pop", 1, 1, "double"),
+    f("var_samp", 1, 1, "double"),
+    f("every", 1, 1, "boolean"),
+    f("corr", 2, 2, "double"),
+    f("covar_pop", 2, 2, "double"),
+    f("covar_samp", 2, 2, "double"),
+    v("grouping", 1, "bigint"),
+    // Window functions.
+    f("row_number", 0, 0, "bigint"),
+    f("rank", 0, 0, "bigint"),
+    f("dense_rank", 0, 0, "bigint"),
+    f("percent_rank", 0, 0, "double"),
+    f("cume_dist", 0, 0, "double"),
+    f("ntile", 1, 1, "bigint"),
+    f("lag", 1, 3, "any"),
+    f("lead", 1, 3, "any"),
+    f("first_value", 1, 1, "any"),
+    f("last_value", 1, 1, "any"),
+    f("nth_value", 2, 2, "any"),
+    // Conditionals.
+    v("coalesce", 1, "any"),
+    f("nullif", 2, 2, "any"),
+    v("greatest", 1, "any"),
+    v("least", 1, "any"),
+    // Math.
+    f("abs", 1, 1, "any"),
+    f("round", 1, 2, "numeric"),
+    f("ceil", 1, 1, "numeric"),
+    f("ceiling", 1, 1, "numeric"),
+    f("floor", 1, 1, "numeric"),
+    f("mod", 2, 2, "numeric"),
+    f("power", 2, 2, "double"),
+    f("sqrt", 1, 1, "double"),
+    f("exp", 1, 1, "double"),
+    f("ln", 1, 1, "double"),
+    f("log", 1, 2, "double"),
+    f("log10", 1, 1, "double"),
+    f("sign", 1, 1, "numeric"),
+    f("sin", 1, 1, "double"),
+    f("cos", 1, 1, "double"),
+    f("tan", 1, 1, "double"),
+    f("asin", 1, 1, "double"),
+    f("acos", 1, 1, "double"),
+    f("atan", 1, 1, "double"),
+    f("atan2", 2, 2, "double"),
+    f("degrees", 1, 1, "double"),
+    f("radians", 1, 1, "double"),
+    f("pi", 0, 0, "double"),
+    // Strings.
+    f("upper", 1, 1, "text"),
+    f("lower", 1, 1, "text"),
+    f("length", 1, 1, "bigint"),
+    f("char_length", 1, 1, "bigint"),
+    f("character_length", 1, 1, "bigint"),
+    f("octet_length", 1, 1, "bigint"),
+    f("trim", 1, 2, "text"),
+    f("ltrim", 1, 2, "text"),
+    f("rtrim", 1, 2, "text"),
+    f("replace", 3, 3, "text"),
+    f("substr", 2, 3, "text"),
+    f("substring", 2, 3, "text"),
+    v("concat", 1, "text"),
+    v("concat_ws", 2, "text"),
+    f("lpad", 2, 3, "text"),
+    f("rpad", 2, 3, "text"),
+    f("reverse", 1, 1, "text"),
+    f("repeat", 2, 2, "text"),
+    f("left", 2, 2, "text"),
+    f("right", 2, 2, "text"),
+    f("ascii", 1, 1, "bigint"),
+    // Date / time.
+    f("current_date", 0, 0, "date"),
+    f("current_time", 0, 1, "time"),
+    f("current_timestamp", 0, 1, "timestamp"),
+    f("localtime", 0, 1, "time"),
+    f("localtimestamp", 0, 1, "timestamp"),
+    f("current_user", 0, 0, "text"),
+    f("session_user", 0, 0, "text"),
+];
+
+const POSTGRES: &[Builtin] = &[
+    f("now", 0, 0, "timestamp"),
+    f("clock_timestamp", 0, 0, "timestamp"),
+    f("statement_timestamp", 0, 0, "timestamp"),
+    f("transaction_timestamp", 0, 0, "timestamp"),
+    f("timeofday", 0, 0, "text"),
+    f("date_trunc", 2, 3, "timestamp"),
+    f("date_part", 2, 2, "double"),
+    f("date_bin", 3, 3, "timestamp"),
+    f("age", 1, 2, "interval"),
+    f("to_char", 2, 2, "text"),
+    f("to_date", 2, 2, "date"),
+    f("to_timestamp", 1, 2, "timestamp"),
+    f("to_number", 2, 2, "numeric"),
+    f("make_date", 3, 3, "date"),
+    f("make_time", 3, 3, "time"),
+    f("make_timestamp", 6, 6, "timestamp"),
+    f("make_timestamptz", 6, 7, "timestamp"),
+    f("make_interval", 0, 7, "interval"),
+    f("justify_days", 1, 1, "interval"),
+    f("justify_hours", 1, 1, "interval"),
+    f("justify_interval", 1, 1, "interval"),
+    f("timezone", 2, 2, "timestamp"),
+    f("isfinite", 1, 1, "boolean"),
+    f("date", 1, 1, "date"),
+    f("string_agg", 2, 2, "text"),
+    f("array_agg", 1, 1, "array"),
+    f("json_agg", 1, 1, "json"),
+    f("jsonb_agg", 1, 1, "json"),
+    f("json_object_agg", 2, 2, "json"),
+    f("jsonb_object_agg", 2, 2, "json"),
+    f("bool_and", 1, 1, "boolean"),
+    f("bool_or", 1, 1, "boolean"),
+    f("bit_and", 1, 1, "any"),
+    f("bit_or", 1, 1, "any"),
+    f("percentile_cont", 1, 1, "double"),
+    f("percentile_disc", 1, 1, "any"),
+    f("mode", 0, 0, "any"),
+    f("regr_slope", 2, 2, "double"),
+    f("regr_intercept", 2, 2, "double"),
+    v("json_build_object", 0, "json"),
+    v("jsonb_build_object", 0, "json"),
+    v("json_build_array", 0, "json"),
+    v("jsonb_build_array", 0, "json"),
+    f("json_object", 1, 2, "json"),
+    f("jsonb_object", 1, 2, "json"),
+    f("to_json", 1, 1, "json"),
+    f("to_jsonb", 1, 1, "json"),
+    f("row_to_json", 1, 2, "json"),
+    f("array_to_json", 1, 2, "json"),
+    f("json_array_length", 1, 1, "bigint"),
+    f("jsonb_array_length", 1, 1, "bigint"),
+    f("json_array_elements", 1, 1, "json"),
+    f("jsonb_array_elements", 1, 1, "json"),
+    f("json_array_elements_text", 1, 1, "text"),
+    f("jsonb_array_elements_text", 1, 1, "text"),
+    f("json_each", 1, 1, "record"),
+    f("jsonb_each", 1, 1, "record"),
+    f("json_each_text", 1, 1, "record"),
+    f("jsonb_each_text", 1, 1, "record"),
+    f("json_object_keys", 1, 1, "text"),
+    f("jsonb_object_keys", 1, 1, "text"),
+    v("json_extract_path", 2, "json"),
+    v("jsonb_extract_path", 2, "json"),
+    v("json_extract_path_text", 2, "text"),
+    v("jsonb_extract_path_text", 2, "text"),
+    f("json_typeof", 1, 1, "text"),
+    f("jsonb_typeof", 1, 1, "text"),
+    f("jsonb_set", 3, 4, "json"),
+    f("jsonb_insert", 3, 4, "json"),
+    f("jsonb_strip_nulls", 1, 1, "json"),
+    f("json_strip_nulls", 1, 1, "json"),
+    f("jsonb_pretty", 1, 1, "text"),
+    f("jsonb_path_query", 2, 4, "json"),
+    f("jsonb_path_query_first", 2, 4, "json"),
+    f("jsonb_path_exists", 2, 4, "boolean"),
+    f("json_populate_record", 2, 2, "record"),
+    f("jsonb_populate_record", 2, 2, "record"),
+    f("json_to_record", 1, 1, "record"),
+    f("jsonb_to_record", 1, 1, "record"),
+    f("json_to_recordset", 1, 1, "record"),
+    f("jsonb_to_recordset", 1, 1, "record"),
+    f("array_length", 2, 2, "bigint"),
+    f("array_upper", 2, 2, "bigint"),
+    f("array_lower", 2, 2, "bigint"),
+    f("array_ndims", 1, 1, "bigint"),
+    f("array_dims", 1, 1, "text"),
+    f("array_position", 2, 3, "bigint"),
+    f("array_positions", 2, 2, "array"),
+    f("array_remove", 2, 2, "array"),
+    f("array_replace", 3, 3, "array"),
+    f("array_append", 2, 2, "array"),
+    f("array_prepend", 2, 2, "array"),
+    f("array_cat", 2, 2, "array"),
+    f("array_fill", 2, 3, "array"),
+    f("array_to_string", 2, 3, "text"),
+    f("string_to_array", 2, 3, "array"),
+    f("cardinality", 1, 1, "bigint"),
+    v("unnest", 1, "any"),
+    f("generate_series", 2, 3, "any"),
+    f("generate_subscripts", 2, 3, "bigint"),
+    f("regexp_replace", 3, 6, "text"),
+    f("regexp_matches", 2, 3, "array"),
+    f("regexp_match", 2, 3, "array"),
+    f("regexp_split_to_array", 2, 3, "array"),
+    f("regexp_split_to_table", 2, 3, "text"),
+    f("regexp_count", 2, 4, "bigint"),
+    f("regexp_like", 2, 3, "boolean"),
+    f("regexp_substr", 2, 6, "text"),
+    f("regexp_instr", 2, 7, "bigint"),
+    f("split_part", 3, 3, "text"),
+    f("strpos", 2, 2, "bigint"),
+    f("starts_with", 2, 2, "boolean"),
+    f("initcap", 1, 1, "text"),
+    f("btrim", 1, 2, "text"),
+    f("chr", 1, 1, "text"),
+    f("translate", 3, 3, "text"),
+    f("quote_ident", 1, 1, "text"),
+    f("quote_literal", 1, 1, "text"),
+    f("quote_nullable", 1, 1, "text"),
+    v("format", 1, "text"),
+    f("md5", 1, 1, "text"),
+    f("sha256", 1, 1, "bytes"),
+    f("encode", 2, 2, "text"),
+    f("decode", 2, 2, "bytes"),
+    f("to_hex", 1, 1, "text"),
+    f("bit_length", 1, 1, "bigint"),
+    f("gen_random_uuid", 0, 0, "uuid"),
+    f("uuid_generate_v4", 0, 0, "uuid"),
+    f("nextval", 1, 1, "bigint"),
+    f("currval", 1, 1, "bigint"),
+    f("setval", 2, 3, "bigint"),
+    f("lastval", 0, 0, "bigint"),
+    f("random", 0, 0, "double"),
+    f("setseed", 1, 1, "void"),
+    f("trunc", 1, 2, "numeric"),
+    f("div", 2, 2, "numeric"),
+    f("cbrt", 1, 1, "double"),
+    f("width_bucket", 2, 4, "bigint"),
+    v("num_nulls", 0, "bigint"),
+    v("num_nonnulls", 0, "bigint"),
+    f("to_tsvector", 1, 2, "tsvector"),
+    f("to_tsquery", 1, 2, "tsquery"),
+    f("plainto_tsquery", 1, 2, "tsquery"),
+    f("phraseto_tsquery", 1, 2, "tsquery"),
+    f("websearch_to_tsquery", 1, 2, "tsquery"),
+    f("ts_rank", 2, 4, "double"),
+    f("ts_rank_cd", 2, 4, "double"),
+    f("ts_headline", 2, 4, "text"),
+    f("setweight", 2, 3, "tsvector"),
+    f("pg_sleep", 1, 1, "void"),
+    f("pg_advisory_lock", 1, 2, "void"),
+    f("pg_advisory_unlock", 1, 2, "boolean"),
+    f("pg_try_advisory_lock", 1, 2, "boolean"),
+    f("pg_advisory_xact_lock", 1, 2, "void"),
+    f("pg_try_advisory_xact_lock", 1, 2, "boolean"),
+    f("txid_current", 0, 0, "bigint"),
+    f("pg_backend_pid", 0, 0, "bigint"),
+    f("pg_typeof", 1, 1, "text"),
+    f("current_setting", 1, 2, "text"),
+    f("set_config", 3, 3, "text"),
+    f("current_database", 0, 0, "text"),
+    f("current_schema", 0, 0, "text"),
+    f("current_catalog", 0, 0, "text"),
+    f("version", 0, 0, "text"),
+    f("user", 0, 0, "text"),
+    f("has_table_privilege", 2, 3, "boolean"),
+    f("pg_column_size", 1, 1, "bigint"),
+    f("pg_total_relation_size", 1, 1, "bigint"),
+    f("pg_relation_size", 1, 2, "bigint"),
+    f("host", 1, 1, "text"),
+    f("inet_client_addr", 0, 0, "inet"),
+    f("inet_client_port", 0, 0, "bigint"),
+    f("inet_server_addr", 0, 0, "inet"),
+    f("inet_server_port", 0, 0, "bigint"),
+    f("abbrev", 1, 1, "text"),
+    f("broadcast", 1, 1, "inet"),
+    f("family", 1, 1, "bigint"),
+    f("hostmask", 1, 1, "inet"),
+    f("masklen", 1, 1, "bigint"),
+    f("netmask", 1, 1, "inet"),
+    f("network", 1, 1, "inet"),
+    f("set_masklen", 2, 2, "inet"),
+    f("inet_same_family", 2, 2, "boolean"),
+    f("inet_merge", 2, 2, "inet"),
+    f("any_value", 1, 1, "any"),
+    f("bit_xor", 1, 1, "any"),
+    f("range_agg", 1, 1, "any"),
+    f("range_intersect_agg", 1, 1, "any"),
+    f("json_agg_strict", 1, 1, "json"),
+    f("jsonb_agg_strict", 1, 1, "json"),
+    f("json_object_agg_strict", 2, 2, "json"),
+    f("jsonb_object_agg_strict", 2, 2, "json"),
+    f("json_object_agg_unique", 2, 2, "json"),
+    f("jsonb_object_agg_unique", 2, 2, "json"),
+    f("xmlagg", 1, 1, "text"),
+    f("regr_count", 2, 2, "bigint"),
+    f("regr_avgx", 2, 2, "double"),
+    f("regr_avgy", 2, 2, "double"),
+    f("regr_r2", 2, 2, "double"),
+    f("regr_sxx", 2, 2, "double"),
+    f("regr_sxy", 2, 2, "double"),
+    f("regr_syy", 2, 2, "double"),
+    f("int4range", 2, 3, "range"),
+    f("int8range", 2, 3, "range"),
+    f("numrange", 2, 3, "range"),
+    f("tsrange", 2, 3, "range"),
+    f("tstzrange", 2, 3, "range"),
+    f("daterange", 2, 3, "range"),
+    f("isempty", 1, 1, "boolean"),
+    f("lower_inc", 1, 1, "boolean"),
+    f("upper_inc", 1, 1, "boolean"),
+    f("lower_inf", 1, 1, "boolean"),
+    f("upper_inf", 1, 1, "boolean"),
+    f("range_merge", 2, 2, "range"),
+    f("gcd", 2, 2, "numeric"),
+    f("lcm", 2, 2, "numeric"),
+    f("factorial", 1, 1, "numeric"),
+    f("pow", 2, 2, "double"),
+    f("scale", 1, 1, "bigint"),
+    f("min_scale", 1, 1, "bigint"),
+    f("trim_scale", 1, 1, "numeric"),
+    f("cot", 1, 1, "double"),
+    f("sinh", 1, 1, "double"),
+    f("cosh", 1, 1, "double"),
+    f("tanh", 1, 1, "double"),
+    f("asinh", 1, 1, "double"),
+    f("acosh", 1, 1, "double"),
+    f("atanh", 1, 1, "double"),
+    f("sind", 1, 1, "double"),
+    f("cosd", 1, 1, "double"),
+    f("tand", 1, 1, "double"),
+    f("asind", 1, 1, "double"),
+    f("acosd", 1, 1, "double"),
+    f("atand", 1, 1, "double"),
+    f("atan2d", 2, 2, "double"),
+    f("random_normal", 0, 2, "double"),
+    f("string_to_table", 2, 3, "text"),
+    f("parse_ident", 1, 2, "array"),
+    f("to_ascii", 1, 2, "text"),
+    f("to_bin", 1, 1, "text"),
+    f("to_oct", 1, 1, "text"),
+    f("unistr", 1, 1, "text"),
+    f("convert_from", 2, 2, "text"),
+    f("convert_to", 2, 2, "bytes"),
+    f("sha224", 1, 1, "bytes"),
+    f("sha384", 1, 1, "bytes"),
+    f("sha512", 1, 1, "bytes"),
+    f("get_byte", 2, 2, "bigint"),
+    f("set_byte", 3, 3, "bytes"),
+    f("get_bit", 2, 2, "bigint"),
+    f("set_bit", 3, 3, "bytes"),
+    f("bit_count", 1, 1, "bigint"),
+    f("date_add", 2, 3, "timestamp"),
+    f("date_subtract", 2, 3, "timestamp"),
+    f("array_sample", 2, 2, "array"),
+    f("array_shuffle", 1, 1, "array"),
+    f("trim_array", 2, 2, "array"),
+    f("json_populate_recordset", 2, 2, "record"),
+    f("jsonb_populate_recordset", 2, 2, "record"),
+    f("jsonb_path_match", 2, 4, "boolean"),
+    f("jsonb_path_query_array", 2, 4, "json"),
+    f("jsonb_set_lax", 3, 5, "json"),
+    f("json_scalar", 1, 1, "json"),
+    f("jsonb_to_tsvector", 2, 3, "tsvector"),
+    f("json_to_tsvector", 2, 3, "tsvector"),
+    f("array_to_tsvector", 1, 1, "tsvector"),
+    f("tsvector_to_array", 1, 1, "array"),
+    f("ts_delete", 2, 2, "tsvector"),
+    f("ts_filter", 2, 2, "tsvector"),
+    f("strip", 1, 1, "tsvector"),
+    f("numnode", 1, 1, "bigint"),
+    f("querytree", 1, 1, "text"),
+    f("pg_notify", 2, 2, "void"),
+    f("pg_listening_channels", 0, 0, "text"),
+    f("pg_notification_queue_usage", 0, 0, "double"),
+    f("pg_advisory_lock_shared", 1, 2, "void"),
+    f("pg_advisory_unlock_shared", 1, 2, "boolean"),
+    f("pg_advisory_unlock_all", 0, 0, "void"),
+    f("pg_try_advisory_lock_shared", 1, 2, "boolean"),
+    f("pg_advisory_xact_lock_shared", 1, 2, "void"),
+    f("pg_try_advisory_xact_lock_shared", 1, 2, "boolean"),
+    f("pg_current_xact_id", 0, 0, "bigint"),
+    f("pg_current_xact_id_if_assigned", 0, 0, "bigint"),
+    f("pg_xact_status", 1, 1, "text"),
+    f("txid_current_if_assigned", 0, 0, "bigint"),
+    f("pg_blocking_pids", 1, 1, "array"),
+    f("pg_cancel_backend", 1, 1, "boolean"),
+    f("pg_terminate_backend", 1, 2, "boolean"),
+    f("pg_reload_conf", 0, 0, "boolean"),
+    f("pg_is_in_recovery", 0, 0, "boolean"),
+    f("pg_postmaster_start_time", 0, 0, "timestamp"),
+    f("pg_conf_load_time", 0, 0, "timestamp"),
+    f("pg_current_wal_lsn", 0, 0, "text"),
+    f("pg_trigger_depth", 0, 0, "bigint"),
+    f("pg_my_temp_schema", 0, 0, "bigint"),
+    f("current_schemas", 1, 1, "array"),
+    f("to_regclass", 1, 1, "text"),
+    f("to_regtype", 1, 1, "text"),
+    f("to_regproc", 1, 1, "text"),
+    f("to_regprocedure", 1, 1, "text"),
+    f("to_regnamespace", 1, 1, "text"),
+    f("to_regrole", 1, 1, "text"),
+    f("pg_get_serial_sequence", 2, 2, "text"),
+    f("pg_get_viewdef", 1, 2, "text"),
+    f("pg_get_indexdef", 1, 3, "text"),
+    f("pg_get_constraintdef", 1, 2, "text"),
+    f("pg_get_functiondef", 1, 1, "text"),
+    f("pg_get_expr", 2, 3, "text"),
+    f("pg_get_userbyid", 1, 1, "text"),
+    f("format_type", 2, 2, "text"),
+    f("obj_description", 1, 2, "text"),
+    f("col_description", 2, 2, "text"),
+    f("shobj_description", 2, 2, "text"),
+    f("pg_table_is_visible", 1, 1, "boolean"),
+    f("pg_input_is_valid", 2, 2, "boolean"),
+    f("has_schema_privilege", 2, 3, "boolean"),
+    f("has_database_privilege", 2, 3, "boolean"),
+    f("has_column_privilege", 3, 4, "boolean"),
+    f("has_function_privilege", 2, 3, "boolean"),
+    f("has_sequence_privilege", 2, 3, "boolean"),
+    f("pg_has_role", 2, 3, "boolean"),
+    f("row_security_active", 1, 1, "boolean"),
+    f("pg_database_size", 1, 1, "bigint"),
+    f("pg_table_size", 1, 1, "bigint"),
+    f("pg_indexes_size", 1, 1, "bigint"),
+    f("pg_size_pretty", 1, 1, "text"),
+    f("pg_size_bytes", 1, 1, "bigint"),
+    f("pg_column_compression", 1, 1, "text"),
+    f("pg_relation_filenode", 1, 1, "bigint"),
+    f("pg_relation_filepath", 1, 1, "text"),
+];
+
+const MYSQL: &[Builtin] = &[
+    f("now", 0, 1, "timestamp"),
+    f("sysdate", 0, 1, "timestamp"),
+    f("curdate", 0, 0, "date"),
+    f("curtime", 0, 1, "time"),
+    f("utc_timestamp", 0, 1, "timestamp"),
+    f("utc_date", 0, 0, "date"),
+    f("unix_timestamp", 0, 1, "bigint"),
+    f("from_unixtime", 1, 2, "timestamp"),
+    f("date_format", 2, 2, "text"),
+    f("time_format", 2, 2, "text"),
+    f("str_to_date", 2, 2, "timestamp"),
+    f("date_add", 2, 2, "timestamp"),
+    f("date_sub", 2, 2, "timestamp"),
+    f("adddate", 2, 2, "timestamp"),
+    f("subdate", 2, 2, "timestamp"),
+    f("addtime", 2, 2, "timestamp"),
+    f("datediff", 2, 2, "bigint"),
+    f("timediff", 2, 2, "time"),
+    f("timestampdiff", 3, 3, "bigint"),
+    f("timestampadd", 3, 3, "timestamp"),
+    f("date", 1, 1, "date"),
+    f("time", 1, 1, "time"),
+    f("year", 1, 1, "bigint"),
+    f("month", 1, 1, "bigint"),
+    f("day", 1, 1, "bigint"),
+    f("hour", 1, 1, "bigint"),
+    f("minute", 1, 1, "bigint"),
+    f("second", 1, 1, "bigint"),
+    f("quarter", 1, 1, "bigint"),
+    f("week", 1, 2, "bigint"),
+    f("weekday", 1, 1, "bigint"),
+    f("yearweek", 1, 2, "bigint"),
+    f("dayofweek", 1, 1, "bigint"),
+    f("dayofmonth", 1, 1, "bigint"),
+    f("dayofyear", 1, 1, "bigint"),
+    f("dayname", 1, 1, "text"),
+    f("monthname", 1, 1, "text"),
+    f("last_day", 1, 1, "date"),
+    f("makedate", 2, 2, "date"),
+    f("to_days", 1, 1, "bigint"),
+    f("convert_tz", 3, 3, "timestamp"),
+    f("ifnull", 2, 2, "any"),
+    f("if", 3, 3, "any"),
+    f("isnull", 1, 1, "boolean"),
+    v("group_concat", 1, "text"),
+    v("json_extract", 2, "json"),
+    f("json_unquote", 1, 1, "text"),
+    v("json_object", 0, "json"),
+    v("json_array", 0, "json"),
+    f("json_contains", 2, 3, "boolean"),
+    v("json_contains_path", 3, "boolean"),
+    v("json_set", 3, "json"),
+    v("json_insert", 3, "json"),
+    v("json_replace", 3, "json"),
+    v("json_remove", 2, "json"),
+    f("json_length", 1, 2, "bigint"),
+    f("json_keys", 1, 2, "json"),
+    f("json_valid", 1, 1, "boolean"),
+    f("json_type", 1, 1, "text"),
+    f("json_arrayagg", 1, 1, "json"),
+    f("json_objectagg", 2, 2, "json"),
+    v("json_search", 3, "json"),
+    f("locate", 2, 3, "bigint"),
+    f("instr", 2, 2, "bigint"),
+    f("position", 2, 2, "bigint"),
+    f("substring_index", 3, 3, "text"),
+    f("find_in_set", 2, 2, "bigint"),
+    v("field", 2, "bigint"),
+    v("elt", 2, "text"),
+    f("format", 2, 3, "text"),
+    f("space", 1, 1, "text"),
+    f("strcmp", 2, 2, "bigint"),
+    f("hex", 1, 1, "text"),
+    f("unhex", 1, 1, "bytes"),
+    f("md5", 1, 1, "text"),
+    f("sha1", 1, 1, "text"),
+    f("sha2", 2, 2, "text"),
+    f("to_base64", 1, 1, "text"),
+    f("from_base64", 1, 1, "bytes"),
+    f("regexp_replace", 3, 6, "text"),
+    f("regexp_like", 2, 3, "boolean"),
+    f("regexp_substr", 2, 5, "text"),
+    f("regexp_instr", 2, 6, "bigint"),
+    f("rand", 0, 1, "double"),
+    f("truncate", 2, 2, "numeric"),
+    f("pow", 2, 2, "double"),
+    f("crc32", 1, 1, "bigint"),
+    f("conv", 3, 3, "text"),
+    f("uuid", 0, 0, "text"),
+    f("uuid_to_bin", 1, 2, "bytes"),
+    f("bin_to_uuid", 1, 2, "text"),
+    f("last_insert_id", 0, 1, "bigint"),
+    f("found_rows", 0, 0, "bigint"),
+    f("row_count", 0, 0, "bigint"),
+    f("database", 0, 0, "text"),
+    f("schema", 0, 0, "text"),
+    f("user", 0, 0, "text"),
+    f("version", 0, 0, "text"),
+    f("connection_id", 0, 0, "bigint"),
+    f("inet_aton", 1, 1, "bigint"),
+    f("inet_ntoa", 1, 1, "text"),
+    f("any_value", 1, 1, "any"),
+    f("bit_and", 1, 1, "bigint"),
+    f("bit_or", 1, 1, "bigint"),
+    f("bit_xor", 1, 1, "bigint"),
+    f("std", 1, 1, "double"),
+    f("get_lock", 2, 2, "bigint"),
+    f("release_lock", 1, 1, "bigint"),
+    f("sleep", 1, 1, "bigint"),
+    f("char", 1, 1, "text"),
+    // `ON DUPLICATE KEY UPDATE c = VALUES(c)`.
+    f("values", 1, 1, "any"),
+];
+
+const SQLITE: &[Builtin] = &[
+    f("ifnull", 2, 2, "any"),
+    f("iif", 3, 3, "any"),
+    f("instr", 2, 2, "bigint"),
+    v("printf", 1, "text"),
+    v("format", 1, "text"),
+    v("strftime", 2, "text"),
+    v("date", 1, "text"),
+    v("time", 1, "text"),
+    v("datetime", 1, "text"),
+    v("julianday", 1, "double"),
+    v("unixepoch", 1, "bigint"),
+    f("typeof", 1, 1, "text"),
+    f("total", 1, 1, "double"),
+    f("group_concat", 1, 2, "text"),
+    v("json_extract", 2, "any"),
+    f("json", 1, 1, "text"),
+    v("json_object", 0, "text"),
+    v("json_array", 0, "text"),
+    f("json_array_length", 1, 2, "bigint"),
+    v("json_insert", 3, "text"),
+    v("json_replace", 3, "text"),
+    v("json_set", 3, "text"),
+    v("json_remove", 2, "text"),
+    f("json_type", 1, 2, "text"),
+    f("json_valid", 1, 1, "bigint"),
+    f("json_quote", 1, 1, "text"),
+    f("json_group_array", 1, 1, "text"),
+    f("json_group_object", 2, 2, "text"),
+    f("json_each", 1, 2, "record"),
+    f("json_tree", 1, 2, "record"),
+    f("random", 0, 0, "bigint"),
+    f("randomblob", 1, 1, "bytes"),
+    f("hex", 1, 1, "text"),
+    f("unhex", 1, 2, "bytes"),
+    f("quote", 1, 1, "text"),
+    f("zeroblob", 1, 1, "bytes"),
+    f("changes", 0, 0, "bigint"),
+    f("total_changes", 0, 0, "bigint"),
+    f("last_insert_rowid", 0, 0, "bigint"),
+    f("likelihood", 2, 2, "any"),
+    f("likely", 1, 1, "any"),
+    f("unlikely", 1, 1, "any"),
+    f("glob", 2, 2, "boolean"),
+    f("like", 2, 3, "boolean"),
+    v("char", 1, "text"),
+    f("unicode", 1, 1, "bigint"),
+    f("soundex", 1, 1, "text"),
+    f("sqlite_version", 0, 0, "text"),
+    f("trunc", 1, 1, "numeric"),
+    f("pow", 2, 2, "double"),
+    f("log2", 1, 1, "double"),
+    f("string_agg", 2, 2, "text"),
+];
+
+const MSSQL: &[Builtin] = &[
+    f("isnull", 2, 2, "any"),
+    f("iif", 3, 3, "any"),
+    v("choose", 2, "any"),
+    f("getdate", 0, 0, "timestamp"),
+    f("getutcdate", 0, 0, "timestamp"),
+    f("sysdatetime", 0, 0, "timestamp"),
+    f("sysutcdatetime", 0, 0, "timestamp"),
+    f("sysdatetimeoffset", 0, 0, "timestamp"),
+    f("dateadd", 3, 3, "timestamp"),
+    f("datediff", 3, 3, "bigint"),
+    f("datediff_big", 3, 3, "bigint"),
+    f("datepart", 2, 2, "bigint"),
+    f("datename", 2, 2, "text"),
+    f("datefromparts", 3, 3, "date"),
+    f("datetimefromparts", 7, 7, "timestamp"),
+    f("eomonth", 1, 2, "date"),
+    f("year", 1, 1, "bigint"),
+    f("month", 1, 1, "bigint"),
+    f("day", 1, 1, "bigint"),
+    f("isdate", 1, 1, "bigint"),
+    f("isnumeric", 1, 1, "bigint"),
+    f("len", 1, 1, "bigint"),
+    f("datalength", 1, 1, "bigint"),
+    f("charindex", 2, 3, "bigint"),
+    f("patindex", 2, 2, "bigint"),
+    f("stuff", 4, 4, "text"),
+    f("replicate", 2, 2, "text"),
+    f("space", 1, 1, "text"),
+    f("str", 1, 3, "text"),
+    f("quotename", 1, 2, "text"),
+    f("format", 2, 3, "text"),
+    f("string_agg", 2, 2, "text"),
+    f("string_split", 2, 3, "record"),
+    f("translate", 3, 3, "text"),
+    f("char", 1, 1, "text"),
+    f("nchar", 1, 1, "text"),
+    f("unicode", 1, 1, "bigint"),
+    f("soundex", 1, 1, "text"),
+    f("difference", 2, 2, "bigint"),
+    f("newid", 0, 0, "uuid"),
+    f("newsequentialid", 0, 0, "uuid"),
+    f("scope_identity", 0, 0, "numeric"),
+    f("ident_current", 1, 1, "numeric"),
+    f("object_id", 1, 2, "bigint"),
+    f("object_name", 1, 2, "text"),
+    f("db_name", 0, 1, "text"),
+    f("db_id", 0, 1, "bigint"),
+    f("schema_name", 0, 1, "text"),
+    f("suser_sname", 0, 1, "text"),
+    f("user_name", 0, 1, "text"),
+    f("host_name", 0, 0, "text"),
+    f("rand", 0, 1, "double"),
+    f("square", 1, 1, "double"),
+    f("count_big", 0, 1, "bigint"),
+    f("checksum_agg", 1, 1, "bigint"),
+    v("checksum", 1, "bigint"),
+    f("hashbytes", 2, 2, "bytes"),
+    f("json_value", 2, 2, "text"),
+    f("json_query", 1, 2, "text"),
+    f("json_modify", 3, 3, "text"),
+    f("isjson", 1, 2, "bigint"),
+    f("openjson", 1, 2, "record"),
+    f("error_message", 0, 0, "text"),
+    f("error_number", 0, 0, "bigint"),
+    f("try_parse", 2, 3, "any"),
+    f("parse", 2, 3, "any"),
+];
+
+const SNOWFLAKE: &[Builtin] = &[
+    f("iff", 3, 3, "any"),
+    f("ifnull", 2, 2, "any"),
+    f("nvl", 2, 2, "any"),
+    f("nvl2", 3, 3, "any"),
+    f("zeroifnull", 1, 1, "numeric"),
+    f("nullifzero", 1, 1, "numeric"),
+    f("equal_null", 2, 2, "boolean"),
+    v("decode", 3, "any"),
+    f("div0", 2, 2, "numeric"),
+    f("div0null", 2, 2, "numeric"),
+    f("square", 1, 1, "double"),
+    f("trunc", 1, 2, "any"),
+    f("truncate", 1, 2, "any"),
+    f("random", 0, 1, "bigint"),
+    f("uniform", 3, 3, "numeric"),
+    f("current_warehouse", 0, 0, "text"),
+    f("current_database", 0, 0, "text"),
+    f("current_schema", 0, 0, "text"),
+    f("current_role", 0, 0, "text"),
+    f("current_region", 0, 0, "text"),
+    f("current_account", 0, 0, "text"),
+    f("sysdate", 0, 0, "timestamp"),
+    f("getdate", 0, 0, "timestamp"),
+    f("dateadd", 3, 3, "timestamp"),
+    f("timeadd", 3, 3, "timestamp"),
+    f("timestampadd", 3, 3, "timestamp"),
+    f("datediff", 3, 3, "bigint"),
+    f("timediff", 3, 3, "bigint"),
+    f("timestampdiff", 3, 3, "bigint"),
+    f("date_trunc", 2, 2, "timestamp"),
+    f("date_part", 2, 2, "bigint"),
+    f("date_from_parts", 3, 3, "date"),
+    f("time_slice", 3, 4, "timestamp"),
+    f("last_day", 1, 2, "date"),
+    f("next_day", 2, 2, "date"),
+    f("previous_day", 2, 2, "date"),
+    f("dayname", 1, 1, "text"),
+    f("monthname", 1, 1, "text"),
+    f("year", 1, 1, "bigint"),
+    f("month", 1, 1, "bigint"),
+    f("day", 1, 1, "bigint"),
+    f("dayofweek", 1, 1, "bigint"),
+    f("dayofyear", 1, 1, "bigint"),
+    f("week", 1, 1, "bigint"),
+    f("quarter", 1, 1, "bigint"),
+    f("hour", 1, 1, "bigint"),
+    f("minute", 1, 1, "bigint"),
+    f("second", 1, 1, "bigint"),
+    f("convert_timezone", 2, 3, "timestamp"),
+    f("to_date", 1, 2, "date"),
+    f("to_time", 1, 2, "time"),
+    f("to_timestamp", 1, 2, "timestamp"),
+    f("to_timestamp_ntz", 1, 2, "timestamp"),
+    f("to_timestamp_ltz", 1, 2, "timestamp"),
+    f("to_timestamp_tz", 1, 2, "timestamp"),
+    f("to_char", 1, 2, "text"),
+    f("to_varchar", 1, 2, "text"),
+    f("to_number", 1, 4, "numeric"),
+    f("to_decimal", 1, 4, "numeric"),
+    f("to_double", 1, 2, "double"),
+    f("to_boolean", 1, 1, "boolean"),
+    f("to_variant", 1, 1, "json"),
+    f("to_array", 1, 1, "array"),
+    f("to_object", 1, 1, "json"),
+    f("to_json", 1, 1, "text"),
+    f("try_to_number", 1, 4, "numeric"),
+    f("try_to_decimal", 1, 4, "numeric"),
+    f("try_to_date", 1, 2, "date"),
+    f("try_to_timestamp", 1, 2, "timestamp"),
+    f("try_to_boolean", 1, 1, "boolean"),
+    f("parse_json", 1, 1, "json"),
+    f("try_parse_json", 1, 1, "json"),
+    v("object_construct", 0, "json"),
+    v("object_construct_keep_null", 0, "json"),
+    f("object_insert", 3, 4, "json"),
+    v("object_delete", 2, "json"),
+    f("object_keys", 1, 1, "array"),
+    v("array_construct", 0, "array"),
+    v("array_construct_compact", 0, "array"),
+    f("array_agg", 1, 1, "array"),
+    f("array_size", 1, 1, "bigint"),
+    f("array_contains", 2, 2, "boolean"),
+    f("array_append", 2, 2, "array"),
+    f("array_cat", 2, 2, "array"),
+    f("array_compact", 1, 1, "array"),
+    f("array_distinct", 1, 1, "array"),
+    f("array_slice", 3, 3, "array"),
+    f("array_to_string", 2, 2, "text"),
+    f("array_position", 2, 2, "bigint"),
+    f("arrays_overlap", 2, 2, "boolean"),
+    f("array_intersection", 2, 2, "array"),
+    f("object_agg", 2, 2, "json"),
+    f("get", 2, 2, "json"),
+    f("get_path", 2, 2, "json"),
+    f("get_ignore_case", 2, 2, "json"),
+    f("is_null_value", 1, 1, "boolean"),
+    f("typeof", 1, 1, "text"),
+    v("flatten", 1, "record"),
+    f("split", 2, 2, "array"),
+    f("split_part", 3, 3, "text"),
+    f("split_to_table", 2, 2, "record"),
+    f("strtok", 1, 3, "text"),
+    f("strtok_to_array", 1, 2, "array"),
+    f("regexp_substr", 2, 6, "text"),
+    f("regexp_replace", 2, 6, "text"),
+    f("regexp_like", 2, 3, "boolean"),
+    f("regexp_count", 2, 4, "bigint"),
+    f("regexp_instr", 2, 7, "bigint"),
+    f("rlike", 2, 3, "boolean"),
+    f("contains", 2, 2, "boolean"),
+    f("startswith", 2, 2, "boolean"),
+    f("endswith", 2, 2, "boolean"),
+    f("charindex", 2, 3, "bigint"),
+    f("position", 2, 3, "bigint"),
+    f("initcap", 1, 2, "text"),
+    f("len", 1, 1, "bigint"),
+    f("translate", 3, 3, "text"),
+    f("editdistance", 2, 3, "bigint"),
+    f("listagg", 1, 2, "text"),
+    f("median", 1, 1, "numeric"),
+    f("any_value", 1, 1, "any"),
+    f("approx_count_distinct", 1, 1, "bigint"),
+    f("count_if", 1, 1, "bigint"),
+    f("booland_agg", 1, 1, "boolean"),
+    f("boolor_agg", 1, 1, "boolean"),
+    f("ratio_to_report", 1, 1, "double"),
+    f("uuid_string", 0, 2, "text"),
+    v("hash", 1, "bigint"),
+    f("md5", 1, 1, "text"),
+    f("sha1", 1, 1, "text"),
+    f("sha2", 1, 2, "text"),
+    f("base64_encode", 1, 3, "text"),
+    f("base64_decode_string", 1, 2, "text"),
+    f("hex_encode", 1, 2, "text"),
+    f("seq4", 0, 1, "bigint"),
+    f("seq8", 0, 1, "bigint"),
+    f("generator", 1, 2, "record"),
+];
+
+const BIGQUERY: &[Builtin] = &[
+    f("ifnull", 2, 2, "any"),
+    f("if", 3, 3, "any"),
+    f("countif", 1, 1, "bigint"),
+    f("logical_and", 1, 1, "boolean"),
+    f("logical_or", 1, 1, "boolean"),
+    f("any_value", 1, 1, "any"),
+    f("array_agg", 1, 1, "array"),
+    f("array_concat_agg", 1, 1, "array"),
+    f("string_agg", 1, 2, "text"),
+    f("approx_count_distinct", 1, 1, "bigint"),
+    f("approx_quantiles", 2, 2, "array"),
+    f("approx_top_count", 2, 2, "array"),
+    f("array_length", 1, 1, "bigint"),
+    f("array_to_string", 2, 3, "text"),
+    v("array_concat", 1, "array"),
+    f("array_reverse", 1, 1, "array"),
+    f("generate_array", 2, 3, "array"),
+    f("generate_date_array", 2, 3, "array"),
+    f("generate_timestamp_array", 3, 3, "array"),
+    f("format_date", 2, 3, "text"),
+    f("format_datetime", 2, 2, "text"),
+    f("format_timestamp", 2, 3, "text"),
+    f("format_time", 2, 2, "text"),
+    f("parse_date", 2, 2, "date"),
+    f("parse_datetime", 2, 2, "timestamp"),
+    f("parse_timestamp", 2, 3, "timestamp"),
+    f("parse_time", 2, 2, "time"),
+    f("date_add", 2, 2, "date"),
+    f("date_sub", 2, 2, "date"),
+    f("date_diff", 3, 3, "bigint"),
+    f("date_trunc", 2, 2, "date"),
+    f("datetime_add", 2, 2, "timestamp"),
+    f("datetime_sub", 2, 2, "timestamp"),
+    f("datetime_diff", 3, 3, "bigint"),
+    f("datetime_trunc", 2, 2, "timestamp"),
+    f("timestamp_add", 2, 2, "timestamp"),
+    f("timestamp_sub", 2, 2, "timestamp"),
+    f("timestamp_diff", 3, 3, "bigint"),
+    f("timestamp_trunc", 2, 3, "timestamp"),
+    f("timestamp_seconds", 1, 1, "timestamp"),
+    f("timestamp_millis", 1, 1, "timestamp"),
+    f("timestamp_micros", 1, 1, "timestamp"),
+    f("unix_seconds", 1, 1, "bigint"),
+    f("unix_millis", 1, 1, "bigint"),
+    f("unix_micros", 1, 1, "bigint"),
+    f("unix_date", 1, 1, "bigint"),
+    f("date_from_unix_date", 1, 1, "date"),
+    f("last_day", 1, 2, "date"),
+    f("date", 1, 3, "date"),
+    f("datetime", 1, 6, "timestamp"),
+    f("time", 1, 3, "time"),
+    f("timestamp", 1, 2, "timestamp"),
+    f("current_datetime", 0, 1, "timestamp"),
+    f("json_extract", 1, 2, "json"),
+    f("json_extract_scalar", 1, 2, "text"),
+    f("json_extract_array", 1, 2, "array"),
+    f("json_value", 1, 2, "text"),
+    f("json_query", 1, 2, "json"),
+    f("json_query_array", 1, 2, "array"),
+    f("json_value_array", 1, 2, "array"),
+    f("to_json", 1, 2, "json"),
+    f("to_json_string", 1, 2, "text"),
+    f("parse_json", 1, 2, "json"),
+    f("regexp_contains", 2, 2, "boolean"),
+    f("regexp_extract", 2, 4, "text"),
+    f("regexp_extract_all", 2, 2, "array"),
+    f("regexp_replace", 3, 3, "text"),
+    f("regexp_instr", 2, 5, "bigint"),
+    f("split", 1, 2, "array"),
+    f("starts_with", 2, 2, "boolean"),
+    f("ends_with", 2, 2, "boolean"),
+    f("strpos", 2, 2, "bigint"),
+    f("instr", 2, 4, "bigint"),
+    v("format", 1, "text"),
+    f("initcap", 1, 2, "text"),
+    f("translate", 3, 3, "text"),
+    f("safe_divide", 2, 2, "double"),
+    f("safe_multiply", 2, 2, "numeric"),
+    f("safe_add", 2, 2, "numeric"),
+    f("safe_subtract", 2, 2, "numeric"),
+    f("safe_negate", 1, 1, "numeric"),
+    f("ieee_divide", 2, 2, "double"),
+    f("div", 2, 2, "bigint"),
+    f("trunc", 1, 2, "numeric"),
+    f("pow", 2, 2, "double"),
+    f("rand", 0, 0, "double"),
+    f("farm_fingerprint", 1, 1, "bigint"),
+    f("md5", 1, 1, "bytes"),
+    f("sha1", 1, 1, "bytes"),
+    f("sha256", 1, 1, "bytes"),
+    f("to_hex", 1, 1, "text"),
+    f("to_base64", 1, 1, "text"),
+    f("from_base64", 1, 1, "bytes"),
+    f("generate_uuid", 0, 0, "text"),
+    f("session_user", 0, 0, "text"),
+    f("error", 1, 1, "void"),
+];
+
+const REDSHIFT: &[Builtin] = &[
+    f("getdate", 0, 0, "timestamp"),
+    f("sysdate", 0, 0, "timestamp"),
+    f("nvl", 2, 2, "any"),
+    f("nvl2", 3, 3, "any"),
+    v("decode", 3, "any"),
+    f("dateadd", 3, 3, "timestamp"),
+    f("datediff", 3, 3, "bigint"),
+    f("date_add", 2, 2, "timestamp"),
+    f("months_between", 2, 2, "double"),
+    f("add_months", 2, 2, "timestamp"),
+    f("last_day", 1, 1, "date"),
+    f("next_day", 2, 2, "date"),
+    f("convert_timezone", 2, 3, "timestamp"),
+    f("trunc", 1, 2, "any"),
+    f("len", 1, 1, "bigint"),
+    f("charindex", 2, 2, "bigint"),
+    f("listagg", 1, 2, "text"),
+    f("median", 1, 1, "numeric"),
+    f("approximate", 1, 1, "bigint"),
+    f("ratio_to_report", 1, 1, "double"),
+    f("json_extract_path_text", 2, 3, "text"),
+    f("json_extract_array_element_text", 2, 3, "text"),
+    f("json_array_length", 1, 2, "bigint"),
+    f("json_parse", 1, 1, "json"),
+    f("json_serialize", 1, 1, "text"),
+    f("is_valid_json", 1, 1, "boolean"),
+    f("strtol", 2, 2, "bigint"),
+    f("func_sha1", 1, 1, "text"),
+    f("fnv_hash", 1, 2, "bigint"),
+    f("bpcharcmp", 2, 2, "bigint"),
+];
+
+/// Extras DuckDB adds on top of its Postgres-compatible core.
+const DUCKDB: &[Builtin] = &[
+    v("list_value", 0, "array"),
+    f("list_agg", 1, 1, "array"),
+    f("list", 1, 1, "array"),
+    f("len", 1, 1, "bigint"),
+    f("strftime", 2, 2, "text"),
+    f("strptime", 2, 2, "timestamp"),
+    f("epoch", 1, 1, "bigint"),
+    f("epoch_ms", 1, 1, "timestamp"),
+    f("ifnull", 2, 2, "any"),
+    f("if", 3, 3, "any"),
+    f("arg_max", 2, 3, "any"),
+    f("arg_min", 2, 3, "any"),
+    f("median", 1, 1, "any"),
+    f("quantile_cont", 2, 2, "double"),
+    f("read_csv_auto", 1, 2, "record"),
+    f("read_parquet", 1, 2, "record"),
+];
+
+const CLICKHOUSE: &[Builtin] = &[
+    f("now", 0, 1, "timestamp"),
+    f("today", 0, 0, "date"),
+    f("yesterday", 0, 0, "date"),
+    f("todate", 1, 2, "date"),
+    f("todatetime", 1, 2, "timestamp"),
+    f("tostartofday", 1, 2, "timestamp"),
+    f("tostartofmonth", 1, 2, "date"),
+    f("tostartofweek", 1, 3, "date"),
+    f("tostartofhour", 1, 2, "timestamp"),
+    f("tostartofinterval", 2, 4, "timestamp"),
+    f("toyyyymm", 1, 2, "bigint"),
+    f("formatdatetime", 2, 3, "text"),
+    f("countif", 1, 1, "bigint"),
+    f("sumif", 2, 2, "any"),
+    f("avgif", 2, 2, "double"),
+    v("uniq", 1, "bigint"),
+    v("uniqexact", 1, "bigint"),
+    f("grouparray", 1, 1, "array"),
+    f("any", 1, 1, "any"),
+    f("anylast", 1, 1, "any"),
+    f("argmax", 2, 2, "any"),
+    f("argmin", 2, 2, "any"),
+    f("tostring", 1, 2, "text"),
+    f("touint64", 1, 1, "bigint"),
+    f("toint64", 1, 1, "bigint"),
+    f("tofloat64", 1, 1, "double"),
+    f("if", 3, 3, "any"),
+    f("ifnull", 2, 2, "any"),
+    v("multiif", 3, "any"),
+    f("length", 1, 1, "bigint"),
+];
+
+const HIVE: &[Builtin] = &[
+    f("nvl", 2, 2, "any"),
+    f("if", 3, 3, "any"),
+    f("unix_timestamp", 0, 2, "bigint"),
+    f("from_unixtime", 1, 2, "text"),
+    f("to_date", 1, 1, "date"),
+    f("date_add", 2, 2, "date"),
+    f("date_sub", 2, 2, "date"),
+    f("datediff", 2, 2, "bigint"),
+    f("date_format", 2, 2, "text"),
+    f("collect_list", 1, 1, "array"),
+    f("collect_set", 1, 1, "array"),
+    f("size", 1, 1, "bigint"),
+    f("split", 2, 2, "array"),
+    f("explode", 1, 1, "any"),
+    f("get_json_object", 2, 2, "text"),
+    f("regexp_extract", 3, 3, "text"),
+    f("regexp_replace", 3, 3, "text"),
+    f("instr", 2, 2, "bigint"),
+    f("year", 1, 1, "bigint"),
+    f("month", 1, 1, "bigint"),
+    f("day", 1, 1, "bigint"),
+];
+
+/// Which lists make up each dialect's catalog. Later lists win on a name
+/// clash, so dialect-specific arities override the [`COMMON`] ones.
+fn catalog_lists(dialect: Dialect) -> &'static [&'static [Builtin]] {
+    match dialect {
+        Dialect::Generic => &[
+            COMMON, POSTGRES, MYSQL, SQLITE, MSSQL, SNOWFLAKE, BIGQUERY, REDSHIFT, DUCKDB,
+            CLICKHOUSE, HIVE,
+        ],
+        Dialect::Postgres => &[COMMON, POSTGRES],
+        Dialect::MySql => &[COMMON, MYSQL],
+        Dialect::Sqlite => &[COMMON, SQLITE],
+        Dialect::MsSql => &[COMMON, MSSQL],
+        Dialect::Snowflake => &[COMMON, SNOWFLAKE],
+        Dialect::BigQuery => &[COMMON, BIGQUERY],
+        // Redshift is a Postgres 8 derivative and keeps most of its library.
+        Dialect::Redshift => &[COMMON, POSTGRES, REDSHIFT],
+        Dialect::DuckDb => &[COMMON, POSTGRES, DUCKDB],
+        Dialect::ClickHouse => &[COMMON, CLICKHOUSE],
+        Dialect::Hive => &[COMMON, HIVE],
+        Dialect::Ansi => &[COMMON],
+    }
+}
+
+const ALL_DIALECTS: [Dialect; 12] = [
+    Dialect::Generic,
+    Dialect::Postgres,
+    Dialect::MySql,
+    Dialect::Sqlite,
+    Dialect::MsSql,
+    Dialect::Snowflake,
+    Dialect::BigQuery,
+    Dialect::Redshift,
+    Dialect::ClickHouse,
+    Dialect::DuckDb,
+    Dialect::Hive,
+    Dialect::Ansi,
+];
+
+static CATALOGS: LazyLock<HashMap<Dialect, HashMap<&'static str, Builtin>>> = LazyLock::new(|| {
+    ALL_DIALECTS
+        .iter()
+        .map(|&dialect| {
+            let mut catalog: HashMap<&'static str, Builtin> = HashMap::new();
+            for list in catalog_lists(dialect) {
+                for builtin in list.iter() {
+                    // Generic merges every dialect; widen the arity range
+                    // when two dialects disagree so neither is flagged.
+                    let merged = match (dialect, catalog.get(builtin.name)) {
+                        (Dialect::Generic, Some(prev)) => Builtin {
+                            min_args: prev.min_args.min(builtin.min_args),
+                            max_args: prev.max_args.zip(builtin.max_args).map(|(a, b)| a.max(b)),
+                            ..*prev
+                        },
+                        _ => *builtin,
+                    };
+                    catalog.insert(builtin.name, merged);
+                }
+            }
+            (dialect, catalog)
+        })
+        .collect()
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generic_widens_conflicting_arities() {
+        // Postgres `date_trunc` takes 2..=3, Snowflake and BigQuery take 2.
+        let generic = builtin(Dialect::Generic, "date_trunc").unwrap();
+        assert_eq!((generic.min_args, generic.max_args), (2, Some(3)));
+        // MySQL `now(fsp)` accepts one argument, Postgres `now()` none.
+        assert!(builtin(Dialect::Generic, "now").unwrap().accepts(1));
+        assert!(!builtin(Dialect::Postgres, "now").unwrap().accepts(1));
+    }
+
+    #[test]
+    fn dialect_catalogs_do_not_leak() {
+        assert!(builtin(Dialect::Postgres, "ifnull").is_none());
+        assert!(builtin(Dialect::MySql, "ifnull").is_some());
+        assert!(builtin(Dialect::Ansi, "coalesce").is_some());
+    }
+
+    #[test]
+    fn arity_descriptions() {
+        assert_eq!(describe_arity(0, Some(0)), "no arguments");
+        assert_eq!(describe_arity(1, Some(1)), "1 argument");
+        assert_eq!(describe_arity(2, Some(3)), "2 to 3 arguments");
+        assert_eq!(describe_arity(1, None), "at least 1 argument");
+    }
+}
@@ -0,0 +1,210 @@
+//! What a schema change breaks: the queries that validate against the
+//! schema as it is but not against the schema after a migration, with the
+//! changes to blame. Both schemas see the same extracted queries, and only
+//! errors the change introduces count, so code that is already broken
+//! doesn't drown out the migration.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+
+use crate::dialect::Dialect;
+use crate::error::Result;
+use crate::finder;
+use crate::schema::sql::{self, Rename};
+use crate::schema::Schema;
+use crate::usage::{self, Reference};
+use crate::validation::{validate_statements_with_schema, ValidationError};
+
+/// A query the change breaks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breakage {
+    pub path: PathBuf,
+    /// 1-based line and character column of the query's start and
+    /// (exclusive) end.
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    /// The function the query is in, if any.
+    pub function: Option<String>,
+    /// The SQL as parsed.
+    pub sql: String,
+    /// The changes to the tables and columns the query uses, e.g. `column
+    /// users.nickname dropped` or `column orders.total renamed to amount`. Empty when the break doesn't trace back to
+    /// one, e.g. a dropped function.
+    pub reasons: Vec<String>,
+    /// Validation errors against the new schema that the old one doesn't
+    /// give.
+    pub errors: Vec<ValidationError>,
+}
+
+/// The tables and columns migrations rename, by the names they had
+/// before the first one, so a break traces back to the rename rather than
+/// to a drop.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Renames {
+    tables: HashMap<String, String>,
+    /// `(table, column)` to the column's new name. The table is named as
+    /// before the migrations.
+    columns: HashMap<(String, String), String>,
+}
+
+impl Renames {
+    /// Add the renames of the migration `ddl`, applied after the ones
+    /// recorded so far.
+    pub fn record(&mut self, ddl: &[u8], dialect: Dialect) -> Result<()> {
+        for rename in sql::renames(ddl, dialect)? {
+            match rename {
+                Rename::Table { from, to } => {
+                    let original = self.original_table(&from);
+                    self.tables.insert(original, to);
+                }
+                Rename::Column { table, from, to } => {
+                    let table = self.original_table(&table);
+                    let original = self
+                        .columns
+                        .iter()
+                        .find(|((t, _), new)| *t == table && **new == from)
+                        .map_or(from, |((_, c), _)| c.clone());
+                    self.columns.insert((table, original), to);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The new name of `table`, if it was renamed.
+    pub fn table(&self, table: &str) -> Option<&str> {
+        self.tables.get(table).map(String::as_str)
+    }
+
+    /// The new name of `table.column`, if it was renamed.
+    pub fn column(&self, table: &str, column: &str) -> Option<&str> {
+        self.columns
+            .get(&(table.to_string(), column.to_string()))
+            .map(String::as_str)
+    }
+
+    /// The name `table` had before the migrations.
+    fn original_table(&self, table: &str) -> String {
+        self.tables
+            .iter()
+            .find(|(_, new)| *new == table)
+            .map_or(table, |(old, _)| old)
+            .to_string()
+    }
+}
+
+/// The queries in `files` that `after` breaks, in file order. `renames`
+/// are the renames of the migrations that turn `before` into `after`.
+/// Files that can't be read or parsed break nothing here; a check reports
+/// them.
+pub fn breakages(
+    files: &[PathBuf],
+    before: &Schema,
+    after: &Schema,
+    renames: &Renames,
+    dialect: Dialect,
+) -> Vec<Breakage> {
+    use rayon::prelude::*;
+
+    files
+        .par_iter()
+        .flat_map_iter(|file_path| {
+            let parser_dialect = dialect.as_sqlparser();
+            let queries =
+                finder::find_queries_in_file_with_dialect(file_path, parser_dialect.as_ref())
+                    .unwrap_or_default();
+            queries
+                .into_iter()
+                .filter_map(|query| {
+                    let old = validate_statements_with_schema(&query.statements, before, dialect);
+                    let errors: Vec<ValidationError> =
+                        validate_statements_with_schema(&query.statements, after, dialect)
+                            .into_iter()
+                            .filter(|e| !old.contains(e))
+                            .collect();
+                    if errors.is_empty() {
+                        return None;
+                    }
+                    let used = usage::references(&query.statements, before, dialect);
+                    Some(Breakage {
+                        path: file_path.clone(),
+                        line: query.line,
+                        column: query.column,
+                        end_line: query.end_line,
+                        end_column: query.end_column,
+                        function: query.function,
+                        sql: query.text,
+                        reasons: reasons(&used, before, after, renames),
+                        errors,
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// How `after` changes the tables and columns in `used` from `before`:
+/// dropped or renamed tables and columns, changed column types and removed
+/// enum labels, in reference order.
+pub fn reasons(
+    used: &BTreeSet<Reference>,
+    before: &Schema,
+    after: &Schema,
+    renames: &Renames,
+) -> Vec<String> {
+    let mut reasons: Vec<String> = Vec::new();
+    let mut push = |reason: String| {
+        if !reasons.contains(&reason) {
+            reasons.push(reason);
+        }
+    };
+    for reference in used {
+        let table = &reference.table;
+        let Some(columns) = after.get(table) else {
+            push(match renames.table(table) {
+                Some(new) => format!("table {table} renamed to {new}"),
+                None => format!("table {table} dropped"),
+            });
+            continue;
+        };
+        let Some(column) = &reference.column else {
+            continue;
+        };
+        if !columns.contains(column) {
+            push(match renames.column(table, column) {
+                Some(new) => format!("column {table}.{column} renamed to {new}"),
+                None => format!("column {table}.{column} dropped"),
+            });
+            continue;
+        }
+        if let (Some(old), Some(new)) = (before.column(table, column), after.column(table, column))
+        {
+            if old.data_type != new.data_type {
+                push(format!(
+                    "column {table}.{column} changed type from {} to {}",
+                    old.data_type, new.data_type
+                ));
+            }
+        }
+        if let (Some(old), Some(new)) = (
+            before.enum_labels(table, column),
+            after.enum_labels(table, column),
+        ) {
+            let removed: Vec<String> = old
+                .labels
+                .iter()
+                .filter(|l| !new.labels.contains(l))
+                .map(|l| format!("'{l}'"))
+                .collect();
+            if !removed.is_empty() {
+                push(format!(
+                    "column {table}.{column} lost enum label(s) {}",
+                    removed.join(", ")
+                ));
+            }
+        }
+    }
+    reasons
+}
@@ -6,56 +6,28 @@
 //! Both have `_with_dialect` variants if you need to target a specific SQL
 //! flavor; the defaults use [`Dialect::Generic`].
 
+pub mod config;
 pub mod dialect;
 pub mod error;
 pub mod finder;
+pub mod functions;
+pub mod impact;
+pub mod lint;
+pub mod parse;
 pub mod schema;
+pub mod usage;
 pub mod validation;
+pub mod walk;
 
 use std::path::Path;
-use std::sync::LazyLock;
 
-use regex::Regex;
-use validation::{validate_queries_in_code, validate_statements_with_schema, SqlValidationError};
-use walkdir::WalkDir;
+use validation::{
+    validate_queries_in_code, validate_statements_with_schema, Diagnostic, SqlValidationError,
+};
 
 pub use dialect::Dialect;
 pub use error::{Result, SqlShieldError};
-
-static CODE_FILE_RE: LazyLock<Regex> = LazyLock::new(|| {
-    let extensions = finder::SUPPORTED_CODE_FILE_EXTENSIONS.join("|");
-    Regex::new(&format!(r"\.({extensions})$")).expect("static regex built from known extensions")
-});
-
-/// Directory names that are pruned from the file walker by default. These
-/// hold generated artefacts, caches, and vendored code — scanning them
-/// produces noise without value.
-const IGNORED_DIRS: &[&str] = &[
-    ".git",
-    "target",
-    "node_modules",
-    ".venv",
-    "venv",
-    "__pycache__",
-    ".pytest_cache",
-    ".mypy_cache",
-    ".ruff_cache",
-    ".tox",
-    "dist",
-    "build",
-    ".idea",
-    ".vscode",
-];
-
-fn is_ignored_dir(entry: &walkdir::DirEntry) -> bool {
-    if !entry.file_type().is_dir() {
-        return false;
-    }
-    entry
-        .file_name()
-        .to_str()
-        .is_some_and(|name| IGNORED_DIRS.contains(&name))
-}
+pub use walk::{code_files, sql_files};
 
 /// Validate a single SQL query against a schema using the [`Dialect::Generic`]
 /// parser. For dialect-specific parsing, see [`validate_query_with_dialect`].
@@ -69,13 +41,44 @@ pub fn validate_query_with_dialect(
     dialect: Dialect,
 ) -> Result<Vec<String>> {
     let parser_dialect = dialect.as_sqlparser();
-    let statements = sqlparser::parser::Parser::parse_sql(parser_dialect.as_ref(), query)?;
+    let statements = parse::parse_sql(parser_dialect.as_ref(), query)?;
     let loaded_schema = schema::load_schema(schema.as_bytes(), "sql", dialect)?;
-    Ok(validate_statements_with_schema(
-        &statements,
-        &loaded_schema,
-        dialect,
-    ))
+    Ok(
+        validate_statements_with_schema(&statements, &loaded_schema, dialect)
+            .into_iter()
+            .map(|e| e.message)
+            .collect(),
+    )
+}
+
+/// Variant of [`validate_query_with_dialect`] for callers that already
+/// hold a loaded (and possibly amended) [`schema::Schema`].
+pub fn validate_query_against_schema(
+    query: &str,
+    schema: &schema::Schema,
+    dialect: Dialect,
+) -> Result<Vec<String>> {
+    let parser_dialect = dialect.as_sqlparser();
+    let statements = parse::parse_sql(parser_dialect.as_ref(), query)?;
+    Ok(
+        validate_statements_with_schema(&statements, schema, dialect)
+            .into_iter()
+            .map(|e| e.message)
+            .collect(),
+    )
+}
+
+/// Run the [`lint`] rules over a single SQL string. Validation errors are
+/// not included; see [`validate_query_against_schema`].
+pub fn lint_query(
+    query: &str,
+    schema: &schema::Schema,
+    dialect: Dialect,
+    rules: &lint::RuleConfig,
+) -> Result<Vec<lint::Lint>> {
+    let parser_dialect = dialect.as_sqlparser();
+    let statements = parse::parse_sql(parser_dialect.as_ref(), query)?;
+    Ok(lint::lint_statements(&statements, schema, dialect, rules))
 }
 
 /// Walk `dir`, extract SQL from each supported source file, and validate
@@ -91,7 +94,7 @@ pub fn validate_files_with_dialect(
     schema_file_path: &Path,
     dialect: Dialect,
 ) -> Result<Vec<SqlValidationError>> {
-    let tables_and_columns: schema::TablesAndColumns =
+    let tables_and_columns: schema::Schema =
         schema::load_schema_from_file(schema_file_path, dialect)?;
     Ok(validate_files_with_schema(
         dir,
@@ -100,52 +103,157 @@ pub fn validate_files_with_dialect(
     ))
 }
 
-/// Variant for callers that already hold a [`schema::TablesAndColumns`] —
+/// Variant for callers that already hold a [`schema::Schema`] —
 /// e.g., live database introspection in the CLI. Skips the schema file
 /// load and goes straight to the parallel walker.
 pub fn validate_files_with_schema(
     dir: &Path,
-    tables_and_columns: &schema::TablesAndColumns,
+    tables_and_columns: &schema::Schema,
     dialect: Dialect,
 ) -> Vec<SqlValidationError> {
     use rayon::prelude::*;
 
-    // Collect file paths first so rayon can parallelize cleanly over them.
-    // Filtering in one pass so the eventual parallel work is dominated by
-    // parsing + validation rather than directory traversal.
-    let paths: Vec<std::path::PathBuf> = WalkDir::new(dir)
-        .into_iter()
-        .filter_entry(|e| !is_ignored_dir(e))
-        .filter_map(|e| e.ok())
-        .filter_map(|entry| {
-            let path = entry.into_path();
-            let path_str = path.to_str()?;
-            (path.is_file() && CODE_FILE_RE.is_match(path_str)).then_some(path)
-        })
-        .collect();
-
-    // Per-file: extract queries then validate. Per-file parsing errors are
-    // swallowed (same as sequential behavior); only the top-level schema
-    // failure above aborts the whole run.
-    let validation_errors: Vec<SqlValidationError> = paths
+    // Per-file: extract queries then validate. A file that can't be read,
+    // or SQL in it that doesn't parse, is an error of its own; only the
+    // top-level schema failure above aborts the whole run.
+    code_files(dir)
         .par_iter()
         .flat_map_iter(|file_path| {
             // Each worker builds its own boxed dialect — sqlparser's Dialect
             // trait isn't Sync, but the Dialect enum is Copy.
             let parser_dialect = dialect.as_sqlparser();
-            let Ok(queries) =
-                finder::find_queries_in_file_with_dialect(file_path, parser_dialect.as_ref())
-            else {
-                return Vec::new();
+            let extracted = match finder::extract_from_file(file_path, parser_dialect.as_ref()) {
+                Ok(extracted) => extracted,
+                Err(err) => return vec![SqlValidationError::new(file_path, 1, err.to_string())],
             };
-            validate_queries_in_code(&queries, tables_and_columns, dialect)
+            let mut errors: Vec<(usize, String)> =
+                validate_queries_in_code(&extracted.queries, tables_and_columns, dialect)
+                    .into_iter()
+                    .map(|query_error| (query_error.line, query_error.description))
+                    .chain(extracted.unparsed.into_iter().map(|u| (u.line, u.message)))
+                    .collect();
+            errors.sort_by_key(|(line, _)| *line);
+            errors
                 .into_iter()
-                .map(|query_error| {
-                    SqlValidationError::new(file_path, query_error.line, query_error.description)
-                })
+                .map(|(line, description)| SqlValidationError::new(file_path, line, description))
                 .collect()
         })
-        .collect();
+        .collect()
+}
+
+/// Like [`validate_files_with_schema`], plus the [`lint`] rules, with every
+/// finding at the severity `rules` gives its code for the file (rules set
+/// to off are dropped). Each query's validation errors come first, then
+/// its lints.
+pub fn check_files_with_schema(
+    dir: &Path,
+    schema: &schema::Schema,
+    dialect: Dialect,
+    rules: &lint::RuleConfig,
+) -> Vec<Diagnostic> {
+    check_paths_with_schema(&code_files(dir), schema, dialect, rules)
+}
+
+/// [`check_files_with_schema`] over an explicit list of source files, e.g.
+/// the ones [`code_files`] found, in that order.
+pub fn check_paths_with_schema(
+    files: &[std::path::PathBuf],
+    schema: &schema::Schema,
+    dialect: Dialect,
+    rules: &lint::RuleConfig,
+) -> Vec<Diagnostic> {
+    use rayon::prelude::*;
 
-    validation_errors
+    files
+        .par_iter()
+        .flat_map_iter(|file_path| {
+            let parser_dialect = dialect.as_sqlparser();
+            let rules = rules.for_path(file_path);
+            let extracted = match finder::extract_from_file(file_path, parser_dialect.as_ref()) {
+                Ok(extracted) => extracted,
+                Err(err) => {
+                    let code = file_error_rule(&err);
+                    let severity = rules.severity(code);
+                    return (severity != lint::Severity::Off)
+                        .then(|| Diagnostic {
+                            location: validation::location(file_path, 1),
+                            path: file_path.clone(),
+                            line: 1,
+                            column: 1,
+                            end_line: 1,
+                            end_column: 1,
+                            severity,
+                            code,
+                            description: err.to_string(),
+                            query_hash: validation::text_hash(""),
+                            target: None,
+                        })
+                        .into_iter()
+                        .collect();
+                }
+            };
+            let mut diagnostics = Vec::new();
+            for query in &extracted.queries {
+                let location = validation::location(file_path, query.line);
+                let query_hash = validation::query_hash(&query.statements);
+                let diagnostic = |severity, code, description| Diagnostic {
+                    location: location.clone(),
+                    path: file_path.clone(),
+                    line: query.line,
+                    column: query.column,
+                    end_line: query.end_line,
+                    end_column: query.end_column,
+                    severity,
+                    code,
+                    description,
+                    query_hash,
+                    target: None,
+                };
+                for error in validate_statements_with_schema(&query.statements, schema, dialect) {
+                    let severity = rules.severity(error.code);
+                    if severity != lint::Severity::Off {
+                        diagnostics.push(diagnostic(severity, error.code, error.message));
+                    }
+                }
+                for lint in lint::lint_statements(&query.statements, schema, dialect, &rules) {
+                    diagnostics.push(diagnostic(lint.severity, lint.code, lint.message));
+                }
+            }
+            for unparsed in extracted.unparsed {
+                let code = match unparsed.kind {
+                    finder::UnparsedKind::Sql => lint::UNPARSEABLE_SQL,
+                    finder::UnparsedKind::Source => lint::SOURCE_PARSE_ERROR,
+                };
+                let severity = rules.severity(code);
+                if severity == lint::Severity::Off {
+                    continue;
+                }
+                diagnostics.push(Diagnostic {
+                    location: validation::location(file_path, unparsed.line),
+                    path: file_path.clone(),
+                    line: unparsed.line,
+                    column: unparsed.column,
+                    end_line: unparsed.end_line,
+                    end_column: unparsed.end_column,
+                    severity,
+                    code,
+                    description: unparsed.message,
+                    query_hash: validation::text_hash(&unparsed.text),
+                    target: None,
+                });
+            }
+            // Stable, so each query's findings keep their order.
+            diagnostics.sort_by_key(|d| d.line);
+            diagnostics
+        })
+        .collect()
+}
+
+/// The rule for a file the finder couldn't read at all.
+fn file_error_rule(err: &SqlShieldError) -> &'static str {
+    match err {
+        SqlShieldError::CodeParse => lint::SOURCE_PARSE_ERROR,
+        SqlShieldError::SqlParse(_) => lint::UNPARSEABLE_SQL,
+        _ => lint::UNREADABLE_FILE,
+    }
 }
@@ -0,0 +1,416 @@
+//! The lint rules. Checks push `(code, message)` pairs; the caller applies
+//! the configured severities.
+
+use std::collections::HashSet;
+use std::ops::ControlFlow;
+
+use sqlparser::ast::{
+    visit_expressions, BinaryOperator, Expr, Ident, JoinConstraint, JoinOperator, Query, Select,
+    SelectItem, SetExpr, Statement, TableFactor, TableWithJoins, Value, Visit, Visitor,
+};
+
+use super::join_keys;
+use super::{
+    DELETE_WITHOUT_WHERE, IMPLICIT_CROSS_JOIN, LIMIT_WITHOUT_ORDER_BY, NOT_IN_NULLABLE,
+    NULL_COMPARISON, SELECT_STAR, UPDATE_WITHOUT_WHERE,
+};
+use crate::dialect::Dialect;
+use crate::schema::sql::fold_ident;
+use crate::schema::Schema;
+use crate::validation::clauses::select::{bind_schema_column, collect_visible_relations};
+use crate::validation::table_function::table_function;
+use crate::validation::Extras;
+
+pub(super) type Found = Vec<(&'static str, String)>;
+
+pub(super) fn check_statement(
+    statement: &Statement,
+    schema: &Schema,
+    dialect: Dialect,
+    found: &mut Found,
+) {
+    match statement {
+        Statement::Update {
+            table,
+            selection: None,
+            ..
+        } => found.push((
+            UPDATE_WITHOUT_WHERE,
+            format!(
+                "UPDATE of `{}` has no WHERE clause and changes every row",
+                relation_name(&table.relation)
+            ),
+        )),
+        Statement::Delete {
+            from,
+            selection: None,
+            ..
+        } => {
+            let names: Vec<String> = from.iter().map(|t| relation_name(&t.relation)).collect();
+            found.push((
+                DELETE_WITHOUT_WHERE,
+                format!(
+                    "DELETE from `{}` has no WHERE clause and removes every row",
+                    names.join(", ")
+                ),
+            ));
+        }
+        Statement::Query(query) => check_select_star(&query.body, found),
+        Statement::Insert {
+            source: Some(query),
+            ..
+        } => check_select_star(&query.body, found),
+        _ => {}
+    }
+
+    let mut visitor = LintVisitor {
+        schema,
+        dialect,
+        found,
+        existence_subqueries: HashSet::new(),
+        ctes: Extras::new(),
+    };
+    let _ = statement.visit(&mut visitor);
+}
+
+fn relation_name(factor: &TableFactor) -> String {
+    match factor {
+        TableFactor::Table { name, .. } => name.to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// `SELECT *` in the statement's own result. Wildcards inside subqueries
+/// (`EXISTS (SELECT * …)`, CTE bodies) don't reach the application.
+fn check_select_star(body: &SetExpr, found: &mut Found) {
+    match body {
+        SetExpr::Select(select) => {
+            for item in &select.projection {
+                if let SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(..) = item {
+                    found.push((
+                        SELECT_STAR,
+                        format!("`SELECT {item}` depends on the table's column order and width; list the columns explicitly"),
+                    ));
+                }
+            }
+        }
+        SetExpr::SetOperation { left, right, .. } => {
+            check_select_star(left, found);
+            check_select_star(right, found);
+        }
+        SetExpr::Query(inner) => check_select_star(&inner.body, found),
+        _ => {}
+    }
+}
+
+/// Walks every query and expression of a statement, subqueries included.
+struct LintVisitor<'a> {
+    schema: &'a Schema,
+    dialect: Dialect,
+    found: &'a mut Found,
+    /// `EXISTS` subqueries, where `LIMIT` without `ORDER BY` is harmless.
+    existence_subqueries: HashSet<*const Query>,
+    /// Names of the CTEs seen so far, which shadow schema tables.
+    ctes: Extras,
+}
+
+impl Visitor for LintVisitor<'_> {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, query: &Query) -> ControlFlow<()> {
+        for cte in query.with.iter().flat_map(|with| &with.cte_tables) {
+            self.ctes
+                .entry(fold_ident(&cte.alias.name, self.dialect))
+                .or_default();
+        }
+        if query.limit.is_some()
+            && query.order_by.is_empty()
+            && !self.existence_subqueries.contains(&(query as *const Query))
+        {
+            self.found.push((
+                LIMIT_WITHOUT_ORDER_BY,
+                "LIMIT without ORDER BY returns an arbitrary subset of rows".to_string(),
+            ));
+        }
+        for_each_select(&query.body, &mut |select| {
+            check_cross_join(select, self.schema, self.dialect, self.found);
+            let shadowing = shadowing(&self.ctes, None, select, self.dialect);
+            join_keys::check_joins(select, self.schema, self.dialect, &shadowing, self.found);
+        });
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<()> {
+        match expr {
+            Expr::BinaryOp { left, op, right }
+                if matches!(op, BinaryOperator::Eq | BinaryOperator::NotEq)
+                    && (is_null(left) || is_null(right)) =>
+            {
+                let fix = if *op == BinaryOperator::Eq {
+                    "IS NULL"
+                } else {
+                    "IS NOT NULL"
+                };
+                self.found.push((
+                    NULL_COMPARISON,
+                    format!("`{expr}` is never true; use `{fix}`"),
+                ));
+            }
+            Expr::InSubquery {
+                subquery,
+                negated: true,
+                ..
+            } => {
+                if let Some(column) =
+                    nullable_projection(subquery, self.schema, self.dialect, &self.ctes)
+                {
+                    self.found.push((
+                        NOT_IN_NULLABLE,
+                        format!(
+                            "NOT IN subquery returns nullable `{column}`; a single NULL makes it match no rows (use NOT EXISTS or filter `IS NOT NULL`)"
+                        ),
+                    ));
+                }
+            }
+            Expr::Exists { subquery, .. } => {
+                self.existence_subqueries
+                    .insert(subquery.as_ref() as *const Query);
+            }
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+fn is_null(expr: &Expr) -> bool {
+    matches!(expr, Expr::Value(Value::Null))
+}
+
+/// Selects directly in a query body. Nested `Query` bodies are visited on
+/// their own.
+fn for_each_select(body: &SetExpr, f: &mut impl FnMut(&Select)) {
+    match body {
+        SetExpr::Select(select) => f(select),
+        SetExpr::SetOperation { left, right, .. } => {
+            for_each_select(left, f);
+            for_each_select(right, f);
+        }
+        _ => {}
+    }
+}
+
+/// `table.column` when the subquery projects a single nullable schema
+/// column and doesn't filter its NULLs out. Relations named in `ctes`, or
+/// derived tables, aren't schema tables.
+fn nullable_projection(
+    subquery: &Query,
+    schema: &Schema,
+    dialect: Dialect,
+    ctes: &Extras,
+) -> Option<String> {
+    let SetExpr::Select(select) = subquery.body.as_ref() else {
+        return None;
+    };
+    let [SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. }] =
+        select.projection.as_slice()
+    else {
+        return None;
+    };
+    let relations = collect_visible_relations(&select.from);
+    let shadowing = shadowing(ctes, Some(subquery), select, dialect);
+    let (table, ident, display) =
+        bind_schema_column(expr, &relations, schema, dialect, &shadowing)?;
+    let column = fold_ident(ident, dialect);
+    if !schema.column(&table, &column)?.nullable {
+        return None;
+    }
+    let filtered = select.selection.as_ref().is_some_and(|selection| {
+        visit_expressions(selection, |e| match e {
+            Expr::IsNotNull(inner)
+                if last_ident(inner).is_some_and(|i| fold_ident(i, dialect) == column) =>
+            {
+                ControlFlow::Break(())
+            }
+            _ => ControlFlow::Continue(()),
+        })
+        .is_break()
+    });
+    (!filtered).then(|| format!("{display}.{}", ident.value))
+}
+
+/// `ctes` plus the names that shadow schema tables inside `select`: the
+/// CTEs of `query` (the query whose body it is) and its derived tables.
+fn shadowing(ctes: &Extras, query: Option<&Query>, select: &Select, dialect: Dialect) -> Extras {
+    let mut shadowing = ctes.clone();
+    for cte in query
+        .iter()
+        .flat_map(|q| &q.with)
+        .flat_map(|with| &with.cte_tables)
+    {
+        shadowing
+            .entry(fold_ident(&cte.alias.name, dialect))
+            .or_default();
+    }
+    for table in &select.from {
+        for factor in
+            std::iter::once(&table.relation).chain(table.joins.iter().map(|j| &j.relation))
+        {
+            if let TableFactor::Derived {
+                alias: Some(alias), ..
+            } = factor
+            {
+                shadowing
+                    .entry(fold_ident(&alias.name, dialect))
+                    .or_default();
+            }
+        }
+    }
+    shadowing
+}
+
+fn last_ident(expr: &Expr) -> Option<&Ident> {
+    match expr {
+        Expr::Identifier(ident) => Some(ident),
+        Expr::CompoundIdentifier(idents) => idents.last(),
+        _ => None,
+    }
+}
+
+/// Comma-separated FROM items that no predicate connects. Each item (with
+/// its explicit joins) is a group; `WHERE` conjuncts and `ON` conditions
+/// referencing two groups connect them. Table functions and LATERAL
+/// subqueries are correlated with what precedes them and count as joined.
+fn check_cross_join(select: &Select, schema: &Schema, dialect: Dialect, found: &mut Found) {
+    if select.from.len() < 2 {
+        return;
+    }
+    let groups: Vec<Vec<String>> = select
+        .from
+        .iter()
+        .map(|t| {
+            collect_visible_relations(std::slice::from_ref(t))
+                .iter()
+                .map(|r| fold_ident(r.qualifier(), dialect))
+                .collect()
+        })
+        .collect();
+    // Component id per group; groups are merged by relabeling.
+    let mut component: Vec<usize> = (0..groups.len()).collect();
+    for (i, t) in select.from.iter().enumerate() {
+        if i > 0 && is_correlated(t) {
+            merge(&mut component, 0, i);
+        }
+    }
+
+    let mut predicates: Vec<&Expr> = Vec::new();
+    if let Some(selection) = &select.selection {
+        collect_conjuncts(selection, &mut predicates);
+    }
+    for t in &select.from {
+        for join in &t.joins {
+            if let Some(JoinConstraint::On(on)) = join_on(&join.join_operator) {
+                collect_conjuncts(on, &mut predicates);
+            }
+        }
+    }
+    for predicate in predicates {
+        let touched = groups_referenced(predicate, &groups, &select.from, schema, dialect);
+        for pair in touched.windows(2) {
+            merge(&mut component, pair[0], pair[1]);
+        }
+    }
+
+    for (i, group) in groups.iter().enumerate().skip(1) {
+        if component[i] != component[0] {
+            let (Some(first), Some(this)) = (groups[0].first(), group.first()) else {
+                continue;
+            };
+            found.push((
+                IMPLICIT_CROSS_JOIN,
+                format!(
+                    "`{this}` is comma-joined with `{first}` but no predicate connects them; the result is a cartesian product"
+                ),
+            ));
+        }
+    }
+}
+
+/// Put group `b`'s component into group `a`'s.
+fn merge(component: &mut [usize], a: usize, b: usize) {
+    let (from, to) = (component[b], component[a]);
+    for c in component.iter_mut() {
+        if *c == from {
+            *c = to;
+        }
+    }
+}
+
+fn is_correlated(t: &TableWithJoins) -> bool {
+    table_function(&t.relation).is_some()
+        || matches!(t.relation, TableFactor::Derived { lateral: true, .. })
+}
+
+pub(super) fn join_on(op: &JoinOperator) -> Option<&JoinConstraint> {
+    match op {
+        JoinOperator::Inner(c)
+        | JoinOperator::LeftOuter(c)
+        | JoinOperator::RightOuter(c)
+        | JoinOperator::FullOuter(c) => Some(c),
+        _ => None,
+    }
+}
+
+/// Split a predicate into its `AND`-ed parts.
+pub(super) fn collect_conjuncts<'a>(expr: &'a Expr, out: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            collect_conjuncts(left, out);
+            collect_conjuncts(right, out);
+        }
+        Expr::Nested(inner) => collect_conjuncts(inner, out),
+        _ => out.push(expr),
+    }
+}
+
+/// Indexes of the FROM groups a predicate references, sorted. Unqualified
+/// columns are attributed through the schema to the groups whose tables
+/// have them.
+fn groups_referenced(
+    predicate: &Expr,
+    groups: &[Vec<String>],
+    from: &[TableWithJoins],
+    schema: &Schema,
+    dialect: Dialect,
+) -> Vec<usize> {
+    let mut touched = HashSet::new();
+    let _ = visit_expressions(predicate, |e| {
+        match e {
+            Expr::CompoundIdentifier(idents) if idents.len() >= 2 => {
+                let qualifier = fold_ident(&idents[idents.len() - 2], dialect);
+                touched.extend(groups.iter().position(|g| g.contains(&qualifier)));
+            }
+            Expr::Identifier(ident) => {
+                let column = fold_ident(ident, dialect);
+                for (i, t) in from.iter().enumerate() {
+                    let relations = collect_visible_relations(std::slice::from_ref(t));
+                    if relations.iter().any(|r| {
+                        schema
+                            .get(&fold_ident(r.name, dialect))
+                            .is_some_and(|cols| cols.contains(&column))
+                    }) {
+                        touched.insert(i);
+                    }
+                }
+            }
+            _ => {}
+        }
+        ControlFlow::<()>::Continue(())
+    });
+    let mut touched: Vec<usize> = touched.into_iter().collect();
+    touched.sort_unstable();
+    touched
+}
@@ -0,0 +1,315 @@
+//! Join-condition lint. Equalities in `JOIN … ON` between two schema
+//! columns are checked against the declared foreign keys and column types:
+//! `orders.id = users.id` where the schema says `orders.user_id REFERENCES
+//! users (id)`, or an `INT` compared with a `TEXT`, are valid SQL that
+//! returns the wrong rows. `JOIN … USING (id)` is checked as the
+//! equalities it stands for.
+//!
+//! Table pairs with no declared relationship are left alone — the schema
+//! doesn't say how they should be joined.
+
+use std::collections::BTreeMap;
+
+use sqlparser::ast::{
+    BinaryOperator, Expr, Ident, JoinConstraint, Select, TableFactor, TableWithJoins,
+};
+
+use super::checks::{collect_conjuncts, join_on, Found};
+use super::{JOIN_TYPE_MISMATCH, JOIN_WITHOUT_FOREIGN_KEY};
+use crate::dialect::Dialect;
+use crate::schema;
+use crate::schema::sql::fold_ident;
+use crate::validation::clauses::select::{
+    bind_schema_column, collect_from_factor, collect_visible_relations, VisibleRelation,
+};
+use crate::validation::Extras;
+
+/// A column reference bound to a schema table.
+struct Side<'a> {
+    table: String,
+    column: String,
+    display: &'a str,
+    ident: &'a Ident,
+}
+
+/// An `a = b` conjunct of the ON condition with both sides bound.
+struct Equality<'a> {
+    expr: &'a Expr,
+    left: Side<'a>,
+    right: Side<'a>,
+}
+
+/// Check every `JOIN … ON` / `USING` of `select`, parenthesized join
+/// groups included. Relations named in `shadowing` (CTEs, derived tables)
+/// aren't schema tables and are never judged.
+pub(super) fn check_joins(
+    select: &Select,
+    schema: &schema::Schema,
+    dialect: Dialect,
+    shadowing: &Extras,
+    found: &mut Found,
+) {
+    let relations = collect_visible_relations(&select.from);
+    for table in &select.from {
+        check_join_chain(table, &relations, schema, dialect, shadowing, found);
+    }
+}
+
+fn check_join_chain(
+    table: &TableWithJoins,
+    relations: &[VisibleRelation<'_>],
+    schema: &schema::Schema,
+    dialect: Dialect,
+    shadowing: &Extras,
+    found: &mut Found,
+) {
+    check_nested_joins(
+        &table.relation,
+        relations,
+        schema,
+        dialect,
+        shadowing,
+        found,
+    );
+    // The relations joined so far: the left side of the next join.
+    let mut left = Vec::new();
+    collect_from_factor(&table.relation, &mut left);
+    for join in &table.joins {
+        match join_on(&join.join_operator) {
+            Some(JoinConstraint::On(on)) => {
+                check_join_condition(on, relations, schema, dialect, shadowing, found);
+            }
+            Some(JoinConstraint::Using(columns)) => {
+                if let Some(on) =
+                    using_condition(columns, &left, &join.relation, schema, dialect, shadowing)
+                {
+                    check_join_condition(&on, relations, schema, dialect, shadowing, found);
+                }
+            }
+            _ => {}
+        }
+        check_nested_joins(&join.relation, relations, schema, dialect, shadowing, found);
+        collect_from_factor(&join.relation, &mut left);
+    }
+}
+
+fn check_nested_joins(
+    factor: &TableFactor,
+    relations: &[VisibleRelation<'_>],
+    schema: &schema::Schema,
+    dialect: Dialect,
+    shadowing: &Extras,
+    found: &mut Found,
+) {
+    if let TableFactor::NestedJoin {
+        table_with_joins, ..
+    } = factor
+    {
+        check_join_chain(
+            table_with_joins,
+            relations,
+            schema,
+            dialect,
+            shadowing,
+            found,
+        );
+    }
+}
+
+/// The condition `JOIN right USING (columns)` stands for: `l.c = r.c` for
+/// each column `c`, where `l` is the one relation of `left` that has it.
+/// Columns no schema table on either side (or several on the left) has
+/// are left out; `None` if that leaves nothing.
+fn using_condition(
+    columns: &[Ident],
+    left: &[VisibleRelation<'_>],
+    right: &TableFactor,
+    schema: &schema::Schema,
+    dialect: Dialect,
+    shadowing: &Extras,
+) -> Option<Expr> {
+    let mut right_relations = Vec::new();
+    collect_from_factor(right, &mut right_relations);
+    let [right] = right_relations.as_slice() else {
+        return None;
+    };
+    let has_column = |rel: &VisibleRelation, column: &str| {
+        let key = fold_ident(rel.name, dialect);
+        !rel.is_table_function()
+            && !shadowing.contains_key(&key)
+            && schema.get(&key).is_some_and(|cols| cols.contains(column))
+    };
+    let qualified = |rel: &VisibleRelation, column: &Ident| {
+        Box::new(Expr::CompoundIdentifier(vec![
+            rel.qualifier().clone(),
+            column.clone(),
+        ]))
+    };
+    columns
+        .iter()
+        .filter_map(|column| {
+            let key = fold_ident(column, dialect);
+            let mut owners = left.iter().filter(|rel| has_column(rel, &key));
+            let (Some(owner), None) = (owners.next(), owners.next()) else {
+                return None;
+            };
+            has_column(right, &key).then(|| Expr::BinaryOp {
+                left: qualified(owner, column),
+                op: BinaryOperator::Eq,
+                right: qualified(right, column),
+            })
+        })
+        .reduce(|a, b| Expr::BinaryOp {
+            left: Box::new(a),
+            op: BinaryOperator::And,
+            right: Box::new(b),
+        })
+}
+
+fn check_join_condition(
+    on: &Expr,
+    relations: &[VisibleRelation<'_>],
+    schema: &schema::Schema,
+    dialect: Dialect,
+    shadowing: &Extras,
+    found: &mut Found,
+) {
+    let mut conjuncts = Vec::new();
+    collect_conjuncts(on, &mut conjuncts);
+    let bind = |e| {
+        let (table, ident, display) = bind_schema_column(e, relations, schema, dialect, shadowing)?;
+        Some(Side {
+            table,
+            column: fold_ident(ident, dialect),
+            display,
+            ident,
+        })
+    };
+    let equalities: Vec<Equality> = conjuncts
+        .into_iter()
+        .filter_map(|expr| match expr {
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::Eq,
+                right,
+            } => Some(Equality {
+                expr,
+                left: bind(left)?,
+                right: bind(right)?,
+            }),
+            _ => None,
+        })
+        .collect();
+
+    for eq in &equalities {
+        check_types(eq, schema, found);
+    }
+
+    // Foreign keys are judged per table pair: one equality following a
+    // key is enough, extra conditions on other columns are the author's
+    // business.
+    let mut by_pair: BTreeMap<(&str, &str), Vec<&Equality>> = BTreeMap::new();
+    for eq in &equalities {
+        let (a, b) = (eq.left.table.as_str(), eq.right.table.as_str());
+        by_pair.entry((a.min(b), a.max(b))).or_default().push(eq);
+    }
+    for ((a, b), eqs) in by_pair {
+        let mut keys = schema.foreign_key_pairs(a, b);
+        if a != b {
+            keys.extend(
+                schema
+                    .foreign_key_pairs(b, a)
+                    .into_iter()
+                    .map(|(c, r)| (r, c)),
+            );
+        }
+        if keys.is_empty() || eqs.iter().any(|eq| follows_key(eq, a, &keys)) {
+            continue;
+        }
+        let (a_display, b_display) = table_displays(eqs[0], a);
+        let expected = keys
+            .iter()
+            .map(|(ca, cb)| format!("`{a_display}.{ca} = {b_display}.{cb}`"))
+            .collect::<Vec<_>>()
+            .join(" or ");
+        for eq in eqs {
+            found.push((
+                JOIN_WITHOUT_FOREIGN_KEY,
+                format!(
+                    "JOIN condition `{}` follows no foreign key between `{a_display}` and `{b_display}`; expected {expected}",
+                    eq.expr
+                ),
+            ));
+        }
+    }
+}
+
+/// Whether `eq` compares a column pair of `keys`, which are oriented as
+/// `(column of a, column of b)`. Comparing a column with itself (an
+/// identity self-join) always passes.
+fn follows_key(eq: &Equality, a: &str, keys: &[(&str, &str)]) -> bool {
+    let (l, r) = (eq.left.column.as_str(), eq.right.column.as_str());
+    if eq.left.table == eq.right.table && l == r {
+        return true;
+    }
+    keys.iter()
+        .any(|&key| (eq.left.table == a && key == (l, r)) || (eq.right.table == a && key == (r, l)))
+}
+
+/// User-facing names of the pair's tables, in `(a, b)` order.
+fn table_displays<'a>(eq: &Equality<'a>, a: &str) -> (&'a str, &'a str) {
+    if eq.left.table == a {
+        (eq.left.display, eq.right.display)
+    } else {
+        (eq.right.display, eq.left.display)
+    }
+}
+
+fn check_types(eq: &Equality, schema: &schema::Schema, found: &mut Found) {
+    let (Some(l), Some(r)) = (
+        schema.column(&eq.left.table, &eq.left.column),
+        schema.column(&eq.right.table, &eq.right.column),
+    ) else {
+        return;
+    };
+    let (Some(lf), Some(rf)) = (type_family(&l.data_type), type_family(&r.data_type)) else {
+        return;
+    };
+    if lf != rf {
+        found.push((
+            JOIN_TYPE_MISMATCH,
+            format!(
+                "JOIN condition `{}` compares `{}.{}` ({}) with `{}.{}` ({})",
+                eq.expr,
+                eq.left.display,
+                eq.left.ident.value,
+                l.data_type,
+                eq.right.display,
+                eq.right.ident.value,
+                r.data_type
+            ),
+        ));
+    }
+}
+
+/// Broad comparability class of a declared column type. Types outside
+/// these classes (enums, arrays, binary, domains) aren't judged.
+fn type_family(data_type: &str) -> Option<&'static str> {
+    let base = data_type
+        .split(|c: char| c == '(' || c.is_whitespace())
+        .next()?
+        .to_ascii_lowercase();
+    let family = match base.as_str() {
+        "int" | "integer" | "smallint" | "bigint" | "tinyint" | "mediumint" | "int2" | "int4"
+        | "int8" | "serial" | "smallserial" | "bigserial" | "decimal" | "numeric" | "number"
+        | "real" | "float" | "float4" | "float8" | "double" | "money" => "numeric",
+        "char" | "character" | "varchar" | "nchar" | "nvarchar" | "text" | "tinytext"
+        | "mediumtext" | "longtext" | "citext" | "string" | "clob" => "text",
+        "date" | "time" | "timestamp" | "timestamptz" | "datetime" | "datetime2" => "temporal",
+        "bool" | "boolean" => "boolean",
+        "uuid" | "uniqueidentifier" => "uuid",
+        "json" | "jsonb" => "json",
+        _ => return None,
+    };
+    Some(family)
+}
@@ -0,0 +1,345 @@
+//! Safety lints: queries that are valid against the schema but likely
+//! wrong — `UPDATE` without `WHERE`, accidental cartesian products,
+//! `= NULL`, and the like.
+//!
+//! Every rule has a stable code and a default severity. The catalog also
+//! covers the validators' errors (`unknown-column`, …), which carry their
+//! code in [`crate::validation::ValidationError`], so one [`RuleConfig`]
+//! tunes both.
+//! A `RuleConfig` overrides severities per code (`"off"` disables a rule),
+//! optionally only for paths matching a set of globs; callers build one
+//! from `.sqlshield.toml`. Lints are reported separately from the
+//! validators' errors, through [`lint_statements`].
+
+mod checks;
+mod join_keys;
+
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use sqlparser::ast::Statement;
+
+use crate::dialect::Dialect;
+use crate::schema::Schema;
+
+/// How a finding is reported. `Off` silences the rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Off,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "off" | "allow" => Ok(Self::Off),
+            "warning" | "warn" => Ok(Self::Warning),
+            "error" | "deny" => Ok(Self::Error),
+            other => Err(format!(
+                "unknown severity `{other}` (expected one of: off, warning, error)"
+            )),
+        }
+    }
+}
+
+/// A rule in the catalog.
+#[derive(Debug)]
+pub struct Rule {
+    pub code: &'static str,
+    pub default_severity: Severity,
+    pub description: &'static str,
+}
+
+const fn rule(code: &'static str, description: &'static str) -> Rule {
+    Rule {
+        code,
+        default_severity: Severity::Warning,
+        description,
+    }
+}
+
+/// A validation rule: on by default, as an error.
+const fn error_rule(code: &'static str, description: &'static str) -> Rule {
+    Rule {
+        code,
+        default_severity: Severity::Error,
+        description,
+    }
+}
+
+pub const UNKNOWN_TABLE: &str = "unknown-table";
+pub const UNKNOWN_COLUMN: &str = "unknown-column";
+pub const AMBIGUOUS_COLUMN: &str = "ambiguous-column";
+pub const UNKNOWN_FUNCTION: &str = "unknown-function";
+pub const FUNCTION_ARITY: &str = "function-arity";
+pub const INVALID_ENUM_LABEL: &str = "invalid-enum-label";
+pub const UNGROUPED_COLUMN: &str = "ungrouped-column";
+pub const MISPLACED_AGGREGATE: &str = "misplaced-aggregate";
+pub const UNKNOWN_WINDOW: &str = "unknown-window";
+pub const COLUMN_COUNT_MISMATCH: &str = "column-count-mismatch";
+pub const NATURAL_JOIN_WITHOUT_COLUMNS: &str = "natural-join-without-columns";
+pub const UPDATE_WITHOUT_WHERE: &str = "update-without-where";
+pub const DELETE_WITHOUT_WHERE: &str = "delete-without-where";
+pub const IMPLICIT_CROSS_JOIN: &str = "implicit-cross-join";
+pub const JOIN_TYPE_MISMATCH: &str = "join-type-mismatch";
+pub const JOIN_WITHOUT_FOREIGN_KEY: &str = "join-without-foreign-key";
+pub const SELECT_STAR: &str = "select-star";
+pub const NOT_IN_NULLABLE: &str = "not-in-nullable";
+pub const NULL_COMPARISON: &str = "null-comparison";
+pub const LIMIT_WITHOUT_ORDER_BY: &str = "limit-without-order-by";
+pub const UNPARSEABLE_SQL: &str = "unparseable-sql";
+pub const SOURCE_PARSE_ERROR: &str = "source-parse-error";
+pub const UNREADABLE_FILE: &str = "unreadable-file";
+
+/// Every rule, validation first, then lints, then what kept a file from
+/// being checked; in documentation order.
+pub const RULES: &[Rule] = &[
+    error_rule(
+        UNKNOWN_TABLE,
+        "table not in the schema, a CTE or a subquery",
+    ),
+    error_rule(UNKNOWN_COLUMN, "column not in any table it could come from"),
+    error_rule(
+        AMBIGUOUS_COLUMN,
+        "unqualified column present in several tables",
+    ),
+    error_rule(UNKNOWN_FUNCTION, "function neither built in nor declared"),
+    error_rule(
+        FUNCTION_ARITY,
+        "function called with the wrong number of arguments",
+    ),
+    error_rule(
+        INVALID_ENUM_LABEL,
+        "literal that isn't a label of the column's enum",
+    ),
+    error_rule(
+        UNGROUPED_COLUMN,
+        "column neither in `GROUP BY` nor inside an aggregate",
+    ),
+    error_rule(
+        MISPLACED_AGGREGATE,
+        "aggregate in `WHERE` or nested in another aggregate",
+    ),
+    error_rule(UNKNOWN_WINDOW, "`OVER w` naming no `WINDOW` definition"),
+    error_rule(
+        COLUMN_COUNT_MISMATCH,
+        "set operation whose sides return different column counts",
+    ),
+    error_rule(
+        NATURAL_JOIN_WITHOUT_COLUMNS,
+        "`NATURAL JOIN` sharing no column with the left-hand side",
+    ),
+    rule(
+        UPDATE_WITHOUT_WHERE,
+        "`UPDATE` with no `WHERE` changes every row",
+    ),
+    rule(
+        DELETE_WITHOUT_WHERE,
+        "`DELETE` with no `WHERE` removes every row",
+    ),
+    rule(
+        IMPLICIT_CROSS_JOIN,
+        "comma-joined tables with no join predicate (cartesian product)",
+    ),
+    rule(
+        JOIN_TYPE_MISMATCH,
+        "`JOIN … ON` comparing columns of incompatible types",
+    ),
+    rule(
+        JOIN_WITHOUT_FOREIGN_KEY,
+        "`JOIN … ON` following none of the declared foreign keys",
+    ),
+    rule(SELECT_STAR, "`SELECT *` in application code"),
+    rule(
+        NOT_IN_NULLABLE,
+        "`NOT IN (subquery)` over a nullable column matches nothing once a NULL appears",
+    ),
+    rule(NULL_COMPARISON, "`= NULL` / `<> NULL` is never true"),
+    rule(
+        LIMIT_WITHOUT_ORDER_BY,
+        "`LIMIT` without `ORDER BY` returns an arbitrary subset",
+    ),
+    rule(
+        UNPARSEABLE_SQL,
+        "string that reads like SQL, or `.sql` statement, the parser rejects",
+    ),
+    rule(
+        SOURCE_PARSE_ERROR,
+        "source file with a syntax error; SQL near it may be missed",
+    ),
+    error_rule(UNREADABLE_FILE, "file that couldn't be read at all"),
+];
+
+/// Look up a rule by code.
+pub fn rule_by_code(code: &str) -> Option<&'static Rule> {
+    RULES.iter().find(|r| r.code == code)
+}
+
+/// Per-rule severity overrides on top of the catalog defaults, with
+/// path-scoped overrides applied on top by [`RuleConfig::for_path`].
+#[derive(Debug, Clone, Default)]
+pub struct RuleConfig {
+    levels: HashMap<&'static str, Severity>,
+    overrides: Vec<PathOverride>,
+}
+
+/// Severities that apply only to files matching `globs`.
+#[derive(Debug, Clone)]
+struct PathOverride {
+    /// Absolute directory the globs are relative to; `None` matches paths
+    /// as given.
+    base: Option<PathBuf>,
+    globs: GlobSet,
+    levels: HashMap<&'static str, Severity>,
+}
+
+impl RuleConfig {
+    /// Override the severity of `code`. Unknown codes are an error so
+    /// typos in the config file don't silently do nothing.
+    pub fn set(&mut self, code: &str, severity: Severity) -> Result<(), String> {
+        let rule = rule_by_code(code).ok_or_else(|| format!("unknown rule `{code}`"))?;
+        self.levels.insert(rule.code, severity);
+        Ok(())
+    }
+
+    /// Turn `code` on at its default severity, or off.
+    pub fn enable(&mut self, code: &str, enabled: bool) -> Result<(), String> {
+        let rule = rule_by_code(code).ok_or_else(|| format!("unknown rule `{code}`"))?;
+        let severity = if enabled {
+            rule.default_severity
+        } else {
+            Severity::Off
+        };
+        self.levels.insert(rule.code, severity);
+        Ok(())
+    }
+
+    /// Apply the severities of `rules` to files matching any of `paths`.
+    /// Patterns are gitignore-style globs (`tests/**`, `**/*_test.py`)
+    /// matched against paths relative to the config's directory. Later
+    /// overrides win over earlier ones.
+    pub fn add_override(&mut self, paths: &[String], rules: RuleConfig) -> Result<(), String> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in paths {
+            let glob = GlobBuilder::new(pattern)
+                .literal_separator(true)
+                .build()
+                .map_err(|e| format!("invalid path pattern `{pattern}`: {e}"))?;
+            builder.add(glob);
+        }
+        let globs = builder
+            .build()
+            .map_err(|e| format!("invalid path patterns: {e}"))?;
+        self.overrides.push(PathOverride {
+            base: None,
+            globs,
+            levels: rules.levels,
+        });
+        Ok(())
+    }
+
+    /// Make the override patterns added so far relative to `dir` rather
+    /// than to the paths passed to [`for_path`](Self::for_path). Patterns
+    /// that already have a directory keep it.
+    pub fn rebase(&mut self, dir: &Path) {
+        let base = crate::config::absolute(dir);
+        for o in self.overrides.iter_mut().filter(|o| o.base.is_none()) {
+            o.base = Some(base.clone());
+        }
+    }
+
+    /// Layer `other` on top: its severities win, and its overrides apply
+    /// after this config's.
+    pub fn extend(&mut self, other: RuleConfig) {
+        self.levels.extend(other.levels);
+        self.overrides.extend(other.overrides);
+    }
+
+    /// The severities in effect for `path`: the base levels with every
+    /// matching override applied. A leading `./` is ignored; an override
+    /// with a directory only applies below it.
+    pub fn for_path(&self, path: &Path) -> RuleConfig {
+        let relative = path.strip_prefix(".").unwrap_or(path);
+        let absolute = OnceCell::new();
+        let mut levels = self.levels.clone();
+        for o in &self.overrides {
+            let matched = match &o.base {
+                None => o.globs.is_match(relative),
+                Some(base) => absolute
+                    .get_or_init(|| crate::config::absolute(path))
+                    .strip_prefix(base)
+                    .is_ok_and(|p| o.globs.is_match(p)),
+            };
+            if matched {
+                levels.extend(o.levels.iter().map(|(&code, &severity)| (code, severity)));
+            }
+        }
+        RuleConfig {
+            levels,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Effective severity of `code`.
+    pub fn severity(&self, code: &str) -> Severity {
+        self.levels
+            .get(code)
+            .copied()
+            .unwrap_or_else(|| rule_by_code(code).map_or(Severity::Warning, |r| r.default_severity))
+    }
+}
+
+/// A lint finding in one SQL string.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lint {
+    pub code: &'static str,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Run every enabled lint rule over `statements`.
+pub fn lint_statements(
+    statements: &[Statement],
+    schema: &Schema,
+    dialect: Dialect,
+    rules: &RuleConfig,
+) -> Vec<Lint> {
+    let mut found = Vec::new();
+    for statement in statements {
+        checks::check_statement(statement, schema, dialect, &mut found);
+    }
+    found
+        .into_iter()
+        .filter_map(|(code, message)| {
+            let severity = rules.severity(code);
+            (severity != Severity::Off).then_some(Lint {
+                code,
+                severity,
+                message,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,107 @@
+//! SQL parsing entry point. Wraps `sqlparser` with a token pass for syntax
+//! the pinned parser doesn't accept yet.
+//!
+//! Postgres lets a record-returning function in FROM declare its output
+//! columns with types: `jsonb_to_recordset(x) AS r(a int, b text)`.
+//! sqlparser only takes bare names in an alias column list, so the types
+//! are dropped before parsing and the list reaches the AST as `r(a, b)`.
+//! Only the names matter for column resolution.
+
+use sqlparser::ast::Statement;
+use sqlparser::dialect::Dialect;
+use sqlparser::keywords::Keyword;
+use sqlparser::parser::{Parser, ParserError};
+use sqlparser::tokenizer::{Token, TokenWithLocation, Tokenizer};
+
+/// Parse `sql` like `sqlparser::parser::Parser::parse_sql`, accepting typed
+/// column-definition lists on table function aliases.
+pub fn parse_sql(dialect: &dyn Dialect, sql: &str) -> Result<Vec<Statement>, ParserError> {
+    let tokens = Tokenizer::new(dialect, sql).tokenize_with_location()?;
+    Parser::new(dialect)
+        .with_tokens_with_locations(strip_column_definition_types(tokens))
+        .parse_statements()
+}
+
+/// Drop the types from every `) [AS] alias (name type, …)` list. A list
+/// whose entries are all bare names is an ordinary alias list, and one that
+/// doesn't follow a closing parenthesis isn't a function alias (`INSERT INTO
+/// t (a, b)`, `CREATE TABLE t (a int)`), so both are left alone.
+fn strip_column_definition_types(tokens: Vec<TokenWithLocation>) -> Vec<TokenWithLocation> {
+    // Positions of the non-whitespace tokens, so the pattern can be matched
+    // without caring about spacing and comments.
+    let significant: Vec<usize> = (0..tokens.len())
+        .filter(|&i| !matches!(tokens[i].token, Token::Whitespace(_)))
+        .collect();
+    let mut dropped = vec![false; tokens.len()];
+    for (n, &i) in significant.iter().enumerate() {
+        if tokens[i].token != Token::RParen {
+            continue;
+        }
+        // Without `AS` the alias must be a plain name, or `count(*) OVER
+        // (PARTITION BY …)` and `(a) AND (b c)` would read as aliases.
+        let with_as = significant
+            .get(n + 1)
+            .is_some_and(|&j| is_keyword(&tokens[j].token, Keyword::AS));
+        let at = if with_as { n + 2 } else { n + 1 };
+        let (Some(&alias), Some(&open)) = (significant.get(at), significant.get(at + 1)) else {
+            continue;
+        };
+        let is_alias = match &tokens[alias].token {
+            // `WITH t (a) AS MATERIALIZED (SELECT …)` is a CTE body.
+            Token::Word(w) if with_as => !matches!(w.keyword, Keyword::MATERIALIZED | Keyword::NOT),
+            Token::Word(w) => w.keyword == Keyword::NoKeyword,
+            _ => false,
+        };
+        if !is_alias || tokens[open].token != Token::LParen {
+            continue;
+        }
+        if let Some(types) = definition_types(&tokens, &significant[at + 2..]) {
+            for t in types {
+                dropped[t] = true;
+            }
+        }
+    }
+    tokens
+        .into_iter()
+        .zip(dropped)
+        .filter_map(|(token, dropped)| (!dropped).then_some(token))
+        .collect()
+}
+
+/// Given the tokens after a list's opening parenthesis, return the token
+/// positions that make up the entries' types, or `None` when the list isn't
+/// a column-definition list (an entry doesn't start with a name, or no entry
+/// carries a type).
+fn definition_types(tokens: &[TokenWithLocation], list: &[usize]) -> Option<Vec<usize>> {
+    let mut types = Vec::new();
+    let mut depth = 0usize;
+    let mut entry_start = true;
+    for &i in list {
+        let token = &tokens[i].token;
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen if depth == 0 => return (!types.is_empty()).then_some(types),
+            Token::RParen => depth -= 1,
+            Token::Comma if depth == 0 => {
+                entry_start = true;
+                continue;
+            }
+            _ => {}
+        }
+        if entry_start {
+            if !matches!(token, Token::Word(_)) {
+                return None;
+            }
+            entry_start = false;
+            continue;
+        }
+        // Everything between an entry's name and the next top-level comma
+        // is its type.
+        types.push(i);
+    }
+    None
+}
+
+fn is_keyword(token: &Token, keyword: Keyword) -> bool {
+    matches!(token, Token::Word(w) if w.quote_style.is_none() && w.keyword == keyword)
+}
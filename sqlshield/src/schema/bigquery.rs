@@ -0,0 +1,213 @@
+//! BigQuery table schemas, as `bq show --schema` prints them (a bare field
+//! list, named after its file) or as `bq show --format=json` does (a table
+//! resource, or a list of them). `RECORD` fields become `STRUCT<…>`
+//! columns, `REPEATED` ones `ARRAY<…>`, and `REQUIRED` ones `NOT NULL`.
+
+use serde::Deserialize;
+
+use super::native::{NativeColumn, NativeForeignKey, NativeSchema, NativeTable};
+use crate::dialect::Dialect;
+use crate::error::{Result, SqlShieldError};
+use crate::schema::sql::fold_str;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Shape {
+    Fields(Vec<Field>),
+    Tables(Vec<Table>),
+    Table(Table),
+    Schema(TableSchema),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Table {
+    table_reference: TableReference,
+    #[serde(default)]
+    schema: Option<TableSchema>,
+    #[serde(default)]
+    table_constraints: TableConstraints,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TableReference {
+    #[serde(default)]
+    project_id: Option<String>,
+    dataset_id: String,
+    table_id: String,
+}
+
+#[derive(Deserialize)]
+struct TableSchema {
+    fields: Vec<Field>,
+}
+
+#[derive(Deserialize)]
+struct Field {
+    name: String,
+    #[serde(rename = "type")]
+    field_type: String,
+    #[serde(default)]
+    mode: Option<String>,
+    #[serde(default)]
+    fields: Vec<Field>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct TableConstraints {
+    #[serde(default)]
+    primary_key: Option<PrimaryKey>,
+    #[serde(default)]
+    foreign_keys: Vec<ForeignKey>,
+}
+
+#[derive(Deserialize)]
+struct PrimaryKey {
+    columns: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ForeignKey {
+    referenced_table: TableReference,
+    column_references: Vec<ColumnReference>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ColumnReference {
+    referencing_column: String,
+    referenced_column: String,
+}
+
+/// `schema` in sqlshield's format. Names are folded as unquoted
+/// identifiers; `name` names the table of a bare field list.
+pub fn translate(
+    schema: serde_json::Value,
+    dialect: Dialect,
+    name: Option<&str>,
+) -> Result<NativeSchema> {
+    let shape: Shape = serde_json::from_value(schema).map_err(|_| {
+        SqlShieldError::InvalidSchema(
+            "expected a `sqlshield_schema` key or a BigQuery table schema (a field list or table resource)"
+                .to_string(),
+        )
+    })?;
+    let tables = match shape {
+        Shape::Fields(fields) | Shape::Schema(TableSchema { fields }) => {
+            let name = name.ok_or_else(|| {
+                SqlShieldError::InvalidSchema(
+                    "a BigQuery field list doesn't name its table; load it from a file named after the table"
+                        .to_string(),
+                )
+            })?;
+            vec![NativeTable {
+                name: fold_str(name, dialect),
+                columns: columns(&fields, dialect),
+                primary_key: None,
+                unique: Vec::new(),
+                foreign_keys: Vec::new(),
+            }]
+        }
+        Shape::Table(table) => tables(table, dialect),
+        Shape::Tables(list) => list
+            .into_iter()
+            .flat_map(|table| tables(table, dialect))
+            .collect(),
+    };
+    Ok(NativeSchema::of_tables(tables))
+}
+
+/// A table resource under `dataset.table`, and `project.dataset.table`
+/// too when it names its project, as queries use both.
+fn tables(table: Table, dialect: Dialect) -> Vec<NativeTable> {
+    let fold = |name: &str| fold_str(name, dialect);
+    let fields = table.schema.map(|s| s.fields).unwrap_or_default();
+    let constraints = table.table_constraints;
+    let translated = NativeTable {
+        name: String::new(),
+        columns: columns(&fields, dialect),
+        primary_key: constraints
+            .primary_key
+            .map(|key| key.columns.iter().map(|c| fold(c)).collect()),
+        unique: Vec::new(),
+        foreign_keys: constraints
+            .foreign_keys
+            .into_iter()
+            .map(|fk| NativeForeignKey {
+                columns: fk
+                    .column_references
+                    .iter()
+                    .map(|c| fold(&c.referencing_column))
+                    .collect(),
+                references: fold(&fk.referenced_table.table_id),
+                referenced_columns: fk
+                    .column_references
+                    .iter()
+                    .map(|c| fold(&c.referenced_column))
+                    .collect(),
+            })
+            .collect(),
+    };
+    let reference = table.table_reference;
+    let dataset_table = format!(
+        "{}.{}",
+        fold(&reference.dataset_id),
+        fold(&reference.table_id)
+    );
+    let mut names = vec![dataset_table.clone()];
+    if let Some(project) = &reference.project_id {
+        names.push(format!("{}.{dataset_table}", fold(project)));
+    }
+    names
+        .into_iter()
+        .map(|name| NativeTable {
+            name,
+            ..translated.clone()
+        })
+        .collect()
+}
+
+fn columns(fields: &[Field], dialect: Dialect) -> Vec<NativeColumn> {
+    fields
+        .iter()
+        .map(|field| NativeColumn {
+            name: fold_str(&field.name, dialect),
+            data_type: Some(field_type(field)),
+            nullable: !has_mode(field, "REQUIRED"),
+            labels: None,
+        })
+        .collect()
+}
+
+/// The column type of `field`: `STRUCT<name TYPE, …>` for a record, with
+/// its fields spelled as declared, wrapped in `ARRAY<…>` when repeated.
+fn field_type(field: &Field) -> String {
+    let data_type = if matches!(
+        field.field_type.to_ascii_uppercase().as_str(),
+        "RECORD" | "STRUCT"
+    ) {
+        let fields: Vec<String> = field
+            .fields
+            .iter()
+            .map(|f| format!("{} {}", f.name, field_type(f)))
+            .collect();
+        format!("STRUCT<{}>", fields.join(", "))
+    } else {
+        field.field_type.clone()
+    };
+    if has_mode(field, "REPEATED") {
+        format!("ARRAY<{data_type}>")
+    } else {
+        data_type
+    }
+}
+
+fn has_mode(field: &Field, mode: &str) -> bool {
+    field
+        .mode
+        .as_deref()
+        .is_some_and(|m| m.eq_ignore_ascii_case(mode))
+}
@@ -0,0 +1,215 @@
+//! dbt `schema.yml` files: the tables of `sources` (under the source's
+//! schema, or its name when it sets none) and the relations of `models`,
+//! `seeds` and `snapshots` (under their `alias` and `schema`). Columns are
+//! typed when every one has a `data_type`; a `not_null` test or
+//! constraint makes one `NOT NULL`, and `primary_key` and `foreign_key`
+//! constraints declare keys.
+
+use serde::Deserialize;
+use serde_yaml_ng::Value;
+
+use super::native::{NativeColumn, NativeForeignKey, NativeSchema, NativeTable};
+use crate::dialect::Dialect;
+use crate::schema::sql::{fold, fold_str};
+
+/// Top-level keys that mark a YAML file as a dbt properties file.
+pub const KEYS: [&str; 4] = ["sources", "models", "seeds", "snapshots"];
+
+#[derive(Deserialize)]
+pub(crate) struct Properties {
+    #[serde(default)]
+    sources: Vec<Source>,
+    #[serde(default)]
+    models: Vec<Model>,
+    #[serde(default)]
+    seeds: Vec<Model>,
+    #[serde(default)]
+    snapshots: Vec<Model>,
+}
+
+#[derive(Deserialize)]
+struct Source {
+    name: String,
+    #[serde(default)]
+    schema: Option<String>,
+    #[serde(default)]
+    tables: Vec<SourceTable>,
+}
+
+#[derive(Deserialize)]
+struct SourceTable {
+    name: String,
+    #[serde(default)]
+    identifier: Option<String>,
+    #[serde(default)]
+    columns: Vec<DbtColumn>,
+}
+
+#[derive(Deserialize)]
+struct Model {
+    name: String,
+    #[serde(default)]
+    config: ModelConfig,
+    #[serde(default)]
+    columns: Vec<DbtColumn>,
+    #[serde(default)]
+    constraints: Vec<Constraint>,
+}
+
+#[derive(Deserialize, Default)]
+struct ModelConfig {
+    #[serde(default)]
+    alias: Option<String>,
+    #[serde(default)]
+    schema: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DbtColumn {
+    name: String,
+    #[serde(default)]
+    data_type: Option<String>,
+    #[serde(default)]
+    quote: bool,
+    #[serde(default)]
+    tests: Vec<Value>,
+    #[serde(default)]
+    data_tests: Vec<Value>,
+    #[serde(default)]
+    constraints: Vec<Constraint>,
+}
+
+#[derive(Deserialize)]
+struct Constraint {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    columns: Vec<String>,
+    #[serde(default)]
+    to: Option<String>,
+    #[serde(default)]
+    to_columns: Vec<String>,
+}
+
+/// `properties` in sqlshield's format. Names are folded as unquoted
+/// identifiers, except columns with `quote: true`.
+pub fn translate(properties: Properties, dialect: Dialect) -> NativeSchema {
+    let mut tables = Vec::new();
+    for source in properties.sources {
+        let schema = source.schema.as_deref().unwrap_or(&source.name);
+        for table in source.tables {
+            let identifier = table.identifier.as_deref().unwrap_or(&table.name);
+            tables.push(translate_table(
+                Some(schema),
+                identifier,
+                &table.columns,
+                &[],
+                dialect,
+            ));
+        }
+    }
+    let models = properties
+        .models
+        .into_iter()
+        .chain(properties.seeds)
+        .chain(properties.snapshots);
+    for model in models {
+        let alias = model.config.alias.as_deref().unwrap_or(&model.name);
+        tables.push(translate_table(
+            model.config.schema.as_deref(),
+            alias,
+            &model.columns,
+            &model.constraints,
+            dialect,
+        ));
+    }
+    NativeSchema::of_tables(tables)
+}
+
+fn translate_table(
+    schema: Option<&str>,
+    name: &str,
+    columns: &[DbtColumn],
+    constraints: &[Constraint],
+    dialect: Dialect,
+) -> NativeTable {
+    let fold_name = |name: &str| fold_str(name, dialect);
+    let column_name = |c: &DbtColumn| fold(&c.name, c.quote, dialect);
+
+    let mut primary_key = None;
+    let mut foreign_keys = Vec::new();
+    let mut add_constraint =
+        |constraint: &Constraint, columns: Vec<String>| match constraint.kind.as_str() {
+            "primary_key" => primary_key = Some(columns),
+            "foreign_key" => {
+                if let Some(to) = &constraint.to {
+                    foreign_keys.push(NativeForeignKey {
+                        columns,
+                        references: fold_name(referenced_relation(to)),
+                        referenced_columns: constraint
+                            .to_columns
+                            .iter()
+                            .map(|c| fold_name(c))
+                            .collect(),
+                    });
+                }
+            }
+            _ => {}
+        };
+    for constraint in constraints {
+        add_constraint(
+            constraint,
+            constraint.columns.iter().map(|c| fold_name(c)).collect(),
+        );
+    }
+    for column in columns {
+        for constraint in &column.constraints {
+            add_constraint(constraint, vec![column_name(column)]);
+        }
+    }
+
+    let columns = columns
+        .iter()
+        .map(|c| {
+            let not_null = c.tests.iter().chain(&c.data_tests).any(is_not_null_test)
+                || c.constraints
+                    .iter()
+                    .any(|k| k.kind == "not_null" || k.kind == "primary_key");
+            NativeColumn {
+                name: column_name(c),
+                data_type: c.data_type.clone(),
+                nullable: !not_null,
+                labels: None,
+            }
+        })
+        .collect();
+    let name = match schema {
+        Some(schema) => format!("{}.{}", fold_name(schema), fold_name(name)),
+        None => fold_name(name),
+    };
+    NativeTable {
+        name,
+        columns,
+        primary_key,
+        unique: Vec::new(),
+        foreign_keys,
+    }
+}
+
+/// `not_null`, written bare or with arguments (`- not_null: {…}`).
+fn is_not_null_test(test: &Value) -> bool {
+    match test {
+        Value::String(name) => name == "not_null",
+        Value::Mapping(test) => test.contains_key("not_null"),
+        _ => false,
+    }
+}
+
+/// The relation a foreign key's `to` names: the last argument of
+/// `ref('orgs')` or `source('raw', 'orgs')`, or `to` itself.
+fn referenced_relation(to: &str) -> &str {
+    match to.trim().strip_suffix(')') {
+        Some(call) => call.rsplit(['\'', '"']).nth(1).unwrap_or(to),
+        None => to,
+    }
+}
@@ -0,0 +1,264 @@
+//! Drift between two schemas, e.g. a schema file and the live database it
+//! is meant to describe: tables, columns, column types, nullability and
+//! keys that one has and the other doesn't.
+
+use std::collections::BTreeSet;
+
+use super::{ForeignKey, Schema};
+
+/// Which of the two compared schemas something is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// One difference between two schemas. Table and column keys are folded
+/// as in [`Schema`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Drift {
+    /// A table (or view) only one side has.
+    Table { table: String, only_in: Side },
+    /// A column of a table both have that only one side has.
+    Column {
+        table: String,
+        column: String,
+        only_in: Side,
+    },
+    /// A column whose declared types differ once aliases are resolved
+    /// (`INT` and `integer` are the same type).
+    Type {
+        table: String,
+        column: String,
+        left: String,
+        right: String,
+    },
+    /// A column that is `NOT NULL` on one side only.
+    Nullability {
+        table: String,
+        column: String,
+        left_nullable: bool,
+    },
+    /// Different primary keys; an empty side has none.
+    PrimaryKey {
+        table: String,
+        left: Vec<String>,
+        right: Vec<String>,
+    },
+    /// A foreign key only one side declares.
+    ForeignKey {
+        table: String,
+        foreign_key: ForeignKey,
+        only_in: Side,
+    },
+}
+
+impl Drift {
+    /// The difference in words, naming the sides `left` and `right`.
+    pub fn describe(&self, left: &str, right: &str) -> String {
+        let side = |s: &Side| match s {
+            Side::Left => left,
+            Side::Right => right,
+        };
+        match self {
+            Self::Table { table, only_in } => {
+                format!("table `{table}` only in the {}", side(only_in))
+            }
+            Self::Column {
+                table,
+                column,
+                only_in,
+            } => format!("column `{table}.{column}` only in the {}", side(only_in)),
+            Self::Type {
+                table,
+                column,
+                left: l,
+                right: r,
+            } => format!("column `{table}.{column}` is {l} in the {left} but {r} in the {right}"),
+            Self::Nullability {
+                table,
+                column,
+                left_nullable,
+            } => {
+                let (nullable, not_null) = if *left_nullable {
+                    (left, right)
+                } else {
+                    (right, left)
+                };
+                format!("column `{table}.{column}` is NOT NULL in the {not_null} but nullable in the {nullable}")
+            }
+            Self::PrimaryKey {
+                table,
+                left: l,
+                right: r,
+            } => {
+                let key = |columns: &[String]| match columns {
+                    [] => "none".to_string(),
+                    columns => format!("({})", columns.join(", ")),
+                };
+                format!(
+                    "table `{table}` has primary key {} in the {left} but {} in the {right}",
+                    key(l),
+                    key(r)
+                )
+            }
+            Self::ForeignKey {
+                table,
+                foreign_key,
+                only_in,
+            } => format!(
+                "foreign key `{table}` ({}) REFERENCES `{}` ({}) only in the {}",
+                foreign_key.columns.join(", "),
+                foreign_key.referenced_table,
+                foreign_key.referenced_columns.join(", "),
+                side(only_in)
+            ),
+        }
+    }
+}
+
+/// Everything `left` and `right` disagree on, by table. Tables are
+/// compared by their bare key when a schema stores both; types,
+/// nullability and keys only for tables both sides declare typed columns
+/// for, since sources without that information (views, bare column lists)
+/// have nothing to compare.
+pub fn diff(left: &Schema, right: &Schema) -> Vec<Drift> {
+    let tables: BTreeSet<&String> = table_keys(left).chain(table_keys(right)).collect();
+    let mut drift = Vec::new();
+    for table in tables {
+        let (Some(left_columns), Some(right_columns)) = (left.get(table), right.get(table)) else {
+            drift.push(Drift::Table {
+                table: table.clone(),
+                only_in: if left.contains_key(table) {
+                    Side::Left
+                } else {
+                    Side::Right
+                },
+            });
+            continue;
+        };
+        let columns: BTreeSet<&String> = left_columns.iter().chain(right_columns).collect();
+        for column in columns {
+            let only_in = match (
+                left_columns.contains(column),
+                right_columns.contains(column),
+            ) {
+                (true, true) => continue,
+                (true, false) => Side::Left,
+                _ => Side::Right,
+            };
+            drift.push(Drift::Column {
+                table: table.clone(),
+                column: column.clone(),
+                only_in,
+            });
+        }
+
+        if !(left.columns.contains_key(table) && right.columns.contains_key(table)) {
+            continue;
+        }
+        for l in &left.columns[table] {
+            let Some(r) = right.column(table, &l.name) else {
+                continue;
+            };
+            if canonical_type(&l.data_type) != canonical_type(&r.data_type) {
+                drift.push(Drift::Type {
+                    table: table.clone(),
+                    column: l.name.clone(),
+                    left: l.data_type.clone(),
+                    right: r.data_type.clone(),
+                });
+            }
+            if l.nullable != r.nullable {
+                drift.push(Drift::Nullability {
+                    table: table.clone(),
+                    column: l.name.clone(),
+                    left_nullable: l.nullable,
+                });
+            }
+        }
+        let primary_key = |schema: &Schema| schema.primary_keys.get(table).cloned();
+        let (left_key, right_key) = (primary_key(left), primary_key(right));
+        if left_key != right_key {
+            drift.push(Drift::PrimaryKey {
+                table: table.clone(),
+                left: left_key.unwrap_or_default(),
+                right: right_key.unwrap_or_default(),
+            });
+        }
+        let (left_fks, right_fks) = (foreign_keys(left, table), foreign_keys(right, table));
+        for (fks, others, only_in) in [
+            (&left_fks, &right_fks, Side::Left),
+            (&right_fks, &left_fks, Side::Right),
+        ] {
+            for fk in fks.iter().filter(|fk| !others.contains(fk)) {
+                drift.push(Drift::ForeignKey {
+                    table: table.clone(),
+                    foreign_key: fk.clone(),
+                    only_in,
+                });
+            }
+        }
+    }
+    drift
+}
+
+/// The table keys of `schema`, leaving out qualified keys whose bare key
+/// is also there.
+fn table_keys(schema: &Schema) -> impl Iterator<Item = &String> {
+    schema.keys().filter(|key| {
+        !key.rsplit_once('.')
+            .is_some_and(|(_, bare)| schema.contains_key(bare))
+    })
+}
+
+/// The foreign keys of `table`, with implicit primary-key targets spelled
+/// out so both sides compare alike.
+fn foreign_keys(schema: &Schema, table: &str) -> Vec<ForeignKey> {
+    let Some(fks) = schema.foreign_keys.get(table) else {
+        return Vec::new();
+    };
+    fks.iter()
+        .map(|fk| {
+            let mut fk = fk.clone();
+            if fk.referenced_columns.is_empty() {
+                fk.referenced_columns = schema
+                    .primary_keys
+                    .get(&fk.referenced_table)
+                    .cloned()
+                    .unwrap_or_default();
+            }
+            fk
+        })
+        .collect()
+}
+
+/// A column type with aliases resolved to one spelling, lower case and
+/// without spaces in its modifiers: `INT4` and `serial` become `integer`,
+/// `VARCHAR(255)` becomes `character varying(255)`.
+pub fn canonical_type(data_type: &str) -> String {
+    let lower = data_type.to_ascii_lowercase();
+    let (base, modifiers) = match lower.find(['(', '[']) {
+        Some(at) => lower.split_at(at),
+        None => (lower.as_str(), ""),
+    };
+    let base = base.split_whitespace().collect::<Vec<_>>().join(" ");
+    let base = match base.as_str() {
+        "int" | "int4" | "integer" | "serial" | "serial4" => "integer",
+        "bigint" | "int8" | "bigserial" | "serial8" => "bigint",
+        "smallint" | "int2" | "smallserial" | "serial2" => "smallint",
+        "varchar" | "character varying" => "character varying",
+        "char" | "character" | "bpchar" => "character",
+        "bool" | "boolean" => "boolean",
+        "float8" | "double" | "double precision" => "double precision",
+        "float4" | "real" => "real",
+        "decimal" | "numeric" => "numeric",
+        "timestamp" | "timestamp without time zone" => "timestamp",
+        "timestamptz" | "timestamp with time zone" => "timestamp with time zone",
+        "time" | "time without time zone" => "time",
+        "timetz" | "time with time zone" => "time with time zone",
+        other => other,
+    };
+    let modifiers: String = modifiers.chars().filter(|c| !c.is_whitespace()).collect();
+    format!("{base}{modifiers}")
+}
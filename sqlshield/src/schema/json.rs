@@ -0,0 +1,16 @@
+//! JSON schemas: sqlshield's own format, recognized by its
+//! `sqlshield_schema` key, or a BigQuery table schema.
+
+use super::{bigquery, native, Schema};
+use crate::dialect::Dialect;
+use crate::error::Result;
+
+/// Load `schema`; `name` names the table of a bare BigQuery field list,
+/// which doesn't carry one.
+pub fn load_schema(schema: &[u8], dialect: Dialect, name: Option<&str>) -> Result<Schema> {
+    let value: serde_json::Value = serde_json::from_slice(schema)?;
+    if value.get("sqlshield_schema").is_some() {
+        return native::load(serde_json::from_value(value)?, dialect);
+    }
+    native::load(bigquery::translate(value, dialect, name)?, dialect)
+}
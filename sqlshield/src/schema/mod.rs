@@ -1,19 +1,320 @@
-//! Parses schema definitions into the `TablesAndColumns` map consumed by validation.
+//! Parses schema definitions into the [`Schema`] consumed by validation.
+//!
+//! # JSON format
+//!
+//! Besides DDL, [`load_schema`] reads a JSON snapshot, as written by
+//! [`dump_json`]. It is versioned by its `sqlshield_schema` key; this
+//! build reads version [`JSON_FORMAT_VERSION`]:
+//!
+//! ```json
+//! {
+//!   "sqlshield_schema": 1,
+//!   "tables": [
+//!     {
+//!       "name": "public.users",
+//!       "columns": [
+//!         { "name": "id", "type": "INT", "nullable": false },
+//!         { "name": "state", "type": "status" }
+//!       ],
+//!       "primary_key": ["id"],
+//!       "unique": [["email"]],
+//!       "foreign_keys": [
+//!         { "columns": ["org_id"], "references": "orgs", "referenced_columns": ["id"] }
+//!       ]
+//!     },
+//!     { "name": "active_users", "columns": [{ "name": "id" }] }
+//!   ],
+//!   "enums": [{ "name": "status", "labels": ["active", "banned"] }],
+//!   "functions": [
+//!     { "name": "slugify", "min_args": 1, "max_args": 2, "returns": "TEXT", "params": ["TEXT", "TEXT"] }
+//!   ]
+//! }
+//! ```
+//!
+//! Names are folded like quoted identifiers, and a qualified table name
+//! also registers its bare name, as in DDL. A table whose columns all have
+//! a `type` gets typed columns; one without (a view) only column names.
+//! `nullable` defaults to true, an `enum` list on a column declares inline
+//! enum labels, `max_args: null` means variadic and `params` lists a
+//! function's declared parameter types. Everything but
+//! `sqlshield_schema` and each entry's `name` is optional. The same
+//! document can be written as YAML.
+//!
+//! # Other formats
+//!
+//! JSON without a `sqlshield_schema` key is read as a BigQuery table
+//! schema: a field list as `bq show --schema` prints it, named after its
+//! file (see [`load_file_schema`]), or table resources as `bq show
+//! --format=json` prints them. `RECORD` fields load as `STRUCT<…>`
+//! columns, whose fields validation checks on access, and `REPEATED` ones
+//! as `ARRAY<…>`.
+//!
+//! YAML without one is read as a dbt `schema.yml`: the tables of its
+//! `sources` and the relations of its `models`, `seeds` and `snapshots`,
+//! typed when every column has a `data_type`. Both fold names as unquoted
+//! identifiers.
+//!
+//! A Python file (`py`) is read for SQLAlchemy and Django models; see
+//! [`load_file_schema`] for the table names Django derives from its path.
 
+pub(crate) mod bigquery;
+pub(crate) mod dbt;
+pub mod diff;
+pub(crate) mod json;
+pub(crate) mod native;
+pub(crate) mod python;
 pub(crate) mod sql;
+pub(crate) mod yaml;
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
     fs,
+    ops::Deref,
     path::Path,
 };
 
 use crate::dialect::Dialect;
 use crate::error::{Result, SqlShieldError};
+use crate::functions::FunctionSignature;
 
 pub type TablesAndColumns = HashMap<String, HashSet<String>>;
 
-pub fn load_schema_from_file(file_path: &Path, dialect: Dialect) -> Result<TablesAndColumns> {
+/// A loaded schema: the table → column-name map every validator consults,
+/// plus the typed metadata that only some checks need.
+///
+/// All keys (tables, columns, type names) are stored dialect-folded, the
+/// same way [`TablesAndColumns`] is. Sources that carry no type
+/// information (e.g. live introspection) simply leave the typed maps empty;
+/// checks that depend on them then stay quiet.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Schema {
+    pub tables: TablesAndColumns,
+    /// Declared column definitions per table key, in declaration order.
+    /// Tables whose columns were inferred (views, `CREATE TABLE … AS
+    /// SELECT`) have no entry.
+    pub columns: HashMap<String, Vec<Column>>,
+    /// Enum types declared with `CREATE TYPE … AS ENUM`, keyed by type name.
+    /// Labels are kept in declaration order and compared case-sensitively,
+    /// as Postgres does.
+    pub enums: HashMap<String, Vec<String>>,
+    /// User-defined functions from `CREATE FUNCTION`, keyed by name. Each
+    /// overload contributes one signature.
+    pub functions: HashMap<String, Vec<FunctionSignature>>,
+    /// Primary key columns per table key, in key order.
+    pub primary_keys: HashMap<String, Vec<String>>,
+    /// `UNIQUE` constraints per table key, each as its columns in key
+    /// order.
+    pub unique_keys: HashMap<String, Vec<Vec<String>>>,
+    /// Foreign keys declared by each table (the referencing side), from
+    /// `REFERENCES` column options and `FOREIGN KEY` constraints.
+    pub foreign_keys: HashMap<String, Vec<ForeignKey>>,
+    /// Settings of the target database that change which queries it
+    /// accepts.
+    pub settings: DatabaseSettings,
+}
+
+/// Database settings the validator honors. Only set through
+/// configuration; schema files don't carry them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatabaseSettings {
+    /// MySQL's `ONLY_FULL_GROUP_BY` SQL mode (on by default since 5.7).
+    /// When off, MySQL accepts non-aggregated columns missing from `GROUP
+    /// BY` and so does the GROUP BY check. Other dialects ignore it.
+    pub only_full_group_by: bool,
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        Self {
+            only_full_group_by: true,
+        }
+    }
+}
+
+/// A single typed column declaration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: String,
+    /// Declared type as written in the DDL (`INT`, `VARCHAR(255)`,
+    /// `order_status`).
+    pub data_type: String,
+    /// Labels of an inline `ENUM('a', 'b')` column type (MySQL). Named enum
+    /// types are resolved through [`Schema::enums`] instead, so `ALTER TYPE
+    /// … ADD VALUE` is seen by every column that uses them.
+    pub inline_enum: Option<Vec<String>>,
+    /// False for `NOT NULL` and primary key columns.
+    pub nullable: bool,
+}
+
+/// A `FOREIGN KEY (columns) REFERENCES referenced_table (referenced_columns)`
+/// relationship. Names are folded; the table is the bare (unqualified) key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForeignKey {
+    pub columns: Vec<String>,
+    pub referenced_table: String,
+    /// Empty when the constraint names no columns, which means the
+    /// referenced table's primary key.
+    pub referenced_columns: Vec<String>,
+}
+
+/// The fields of a `STRUCT<name TYPE, …>` column type, as `(name, type)`
+/// pairs in declaration order; `None` for any other type. Nested STRUCTs
+/// are returned as their type, for the caller to descend into.
+pub(crate) fn struct_fields(data_type: &str) -> Option<Vec<(&str, &str)>> {
+    let data_type = data_type.trim();
+    let prefix = data_type.get(..7)?;
+    if !prefix.eq_ignore_ascii_case("STRUCT<") {
+        return None;
+    }
+    let body = data_type[7..].strip_suffix('>')?;
+    let mut fields = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in body.char_indices().chain([(body.len(), ',')]) {
+        match c {
+            '<' | '(' => depth += 1,
+            '>' | ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                let entry = body[start..i].trim();
+                start = i + 1;
+                if entry.is_empty() {
+                    continue;
+                }
+                let (name, field_type) = entry.split_once(char::is_whitespace)?;
+                fields.push((name.trim_matches('`'), field_type.trim()));
+            }
+            _ => {}
+        }
+    }
+    Some(fields)
+}
+
+/// Enum labels a column accepts, as resolved by [`Schema::enum_labels`].
+pub struct EnumLabels<'a> {
+    /// Enum type name, or the column type spelling for inline enums.
+    pub type_name: &'a str,
+    pub labels: &'a [String],
+}
+
+impl Schema {
+    /// Look up the declared definition of `column` in `table`. Both keys
+    /// must already be dialect-folded.
+    pub fn column(&self, table: &str, column: &str) -> Option<&Column> {
+        self.columns.get(table)?.iter().find(|c| c.name == column)
+    }
+
+    /// Labels accepted by `table.column` if its type is an enum — inline or
+    /// a named `CREATE TYPE … AS ENUM`. Keys must already be folded.
+    pub fn enum_labels(&self, table: &str, column: &str) -> Option<EnumLabels<'_>> {
+        let col = self.column(table, column)?;
+        if let Some(labels) = &col.inline_enum {
+            return Some(EnumLabels {
+                type_name: &col.data_type,
+                labels,
+            });
+        }
+        let (type_name, labels) = self.enums.get_key_value(&col.data_type)?;
+        Some(EnumLabels { type_name, labels })
+    }
+
+    /// Column pairs `(referencing, referenced)` of every foreign key from
+    /// `table` to `referenced`, with implicit primary-key targets resolved.
+    /// Keys must already be folded.
+    pub fn foreign_key_pairs(&self, table: &str, referenced: &str) -> Vec<(&str, &str)> {
+        let Some(fks) = self.foreign_keys.get(table) else {
+            return Vec::new();
+        };
+        let mut pairs = Vec::new();
+        for fk in fks.iter().filter(|fk| fk.referenced_table == referenced) {
+            let targets = if fk.referenced_columns.is_empty() {
+                self.primary_keys
+                    .get(referenced)
+                    .map_or(&[][..], Vec::as_slice)
+            } else {
+                &fk.referenced_columns
+            };
+            pairs.extend(
+                fk.columns
+                    .iter()
+                    .zip(targets)
+                    .map(|(c, r)| (c.as_str(), r.as_str())),
+            );
+        }
+        pairs
+    }
+
+    /// Table keys whose columns differ between `self` and `other`,
+    /// including tables only one of them has, plus the tables with a column
+    /// of an enum type whose labels differ (`ALTER TYPE … ADD VALUE`).
+    pub fn changed_tables(&self, other: &Schema) -> BTreeSet<String> {
+        let enums = changed_keys(&self.enums, &other.enums);
+        let uses_changed_enum = |columns: Option<&Vec<Column>>| {
+            columns.is_some_and(|columns| columns.iter().any(|c| enums.contains(&c.data_type)))
+        };
+        self.tables
+            .keys()
+            .chain(other.tables.keys())
+            .filter(|key| {
+                self.tables.get(*key) != other.tables.get(*key)
+                    || self.columns.get(*key) != other.columns.get(*key)
+                    || uses_changed_enum(self.columns.get(*key))
+                    || uses_changed_enum(other.columns.get(*key))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Function keys whose signatures differ between `self` and `other`,
+    /// including functions only one of them has (`CREATE` / `DROP
+    /// FUNCTION`).
+    pub fn changed_functions(&self, other: &Schema) -> BTreeSet<String> {
+        changed_keys(&self.functions, &other.functions)
+    }
+
+    /// Accept calls to `names` with any number of arguments. This is the
+    /// escape hatch for extension functions (PostGIS, pgcrypto, …) that
+    /// neither the schema file nor the built-in catalogs know about.
+    pub fn allow_functions<S: AsRef<str>>(&mut self, names: &[S], dialect: Dialect) {
+        for name in names {
+            self.functions
+                .entry(sql::fold_str(name.as_ref(), dialect))
+                .or_default()
+                .push(FunctionSignature::any());
+        }
+    }
+}
+
+/// Keys whose values differ between `a` and `b`, including keys only one
+/// of them has.
+fn changed_keys<V: PartialEq>(a: &HashMap<String, V>, b: &HashMap<String, V>) -> BTreeSet<String> {
+    a.keys()
+        .chain(b.keys())
+        .filter(|key| a.get(*key) != b.get(*key))
+        .cloned()
+        .collect()
+}
+
+impl From<TablesAndColumns> for Schema {
+    fn from(tables: TablesAndColumns) -> Self {
+        Self {
+            tables,
+            ..Self::default()
+        }
+    }
+}
+
+/// Most callers only need the table → columns map; deref to it so plain
+/// lookups (`schema.get(…)`, `schema["users"]`) read the same as before the
+/// typed metadata existed.
+impl Deref for Schema {
+    type Target = TablesAndColumns;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tables
+    }
+}
+
+pub fn load_schema_from_file(file_path: &Path, dialect: Dialect) -> Result<Schema> {
     let file_extension = file_path
         .extension()
         .ok_or_else(|| SqlShieldError::MissingExtension(file_path.to_path_buf()))?
@@ -24,12 +325,74 @@ pub fn load_schema_from_file(file_path: &Path, dialect: Dialect) -> Result<Table
         source,
     })?;
 
-    load_schema(&schema, &file_extension, dialect)
+    load_file_schema(&schema, &file_extension, dialect, file_path)
 }
 
-pub fn load_schema(schema: &[u8], schema_type: &str, dialect: Dialect) -> Result<TablesAndColumns> {
+/// Apply a DDL migration (`CREATE` / `ALTER` / `DROP TABLE`, …) on top of
+/// `schema`.
+pub fn apply_ddl(schema: &mut Schema, ddl: &[u8], dialect: Dialect) -> Result<()> {
+    sql::apply_ddl(schema, ddl, dialect)
+}
+
+/// Version of the JSON schema format [`dump_json`] writes; the `json`
+/// loader reads this version only.
+pub const JSON_FORMAT_VERSION: u32 = native::FORMAT_VERSION;
+
+/// `schema` as canonical DDL: loading it back as `sql` gives the same
+/// schema.
+pub fn dump_sql(schema: &Schema) -> String {
+    sql::dump(schema)
+}
+
+/// `schema` in sqlshield's versioned JSON format, which loads back as
+/// `json`; the format is described [above](crate::schema#json-format).
+pub fn dump_json(schema: &Schema) -> String {
+    native::dump(schema)
+}
+
+/// Load a schema of type `sql` (DDL), `json` (the format [`dump_json`]
+/// writes, or a BigQuery table schema), `yaml`/`yml` (the same format, or
+/// a dbt `schema.yml`) or `py` (SQLAlchemy or Django models).
+pub fn load_schema(schema: &[u8], schema_type: &str, dialect: Dialect) -> Result<Schema> {
+    load(schema, schema_type, dialect, None)
+}
+
+/// [`load_schema`] for the contents of the schema file at `path`, which
+/// names what the contents may not: the table of a BigQuery field list
+/// (the file stem) and the app of a Django model (its directory).
+pub fn load_file_schema(
+    schema: &[u8],
+    schema_type: &str,
+    dialect: Dialect,
+    path: &Path,
+) -> Result<Schema> {
+    load(schema, schema_type, dialect, Some(path))
+}
+
+fn load(schema: &[u8], schema_type: &str, dialect: Dialect, path: Option<&Path>) -> Result<Schema> {
     match schema_type {
         "sql" => sql::load_schema(schema, dialect),
+        "json" => {
+            let name = path.and_then(Path::file_stem).map(|s| s.to_string_lossy());
+            json::load_schema(schema, dialect, name.as_deref())
+        }
+        "yaml" | "yml" => yaml::load_schema(schema, dialect),
+        "py" => native::load(python::translate(schema, path)?, dialect),
         other => Err(SqlShieldError::UnsupportedSchemaType(other.to_string())),
     }
 }
+
+/// The keys of `map` to dump, sorted: qualified keys stand for their bare
+/// key too, since loading a qualified name stores both.
+fn dump_keys<V>(map: &HashMap<String, V>) -> Vec<&String> {
+    let covered: HashSet<&str> = map
+        .keys()
+        .filter_map(|k| k.rsplit_once('.').map(|(_, bare)| bare))
+        .collect();
+    let mut keys: Vec<&String> = map
+        .keys()
+        .filter(|k| k.contains('.') || !covered.contains(k.as_str()))
+        .collect();
+    keys.sort();
+    keys
+}
@@ -0,0 +1,284 @@
+//! sqlshield's own schema format, for snapshots of a schema (e.g. an
+//! introspected database) that CI can check against offline, written as
+//! JSON or YAML. The format is described in the [module
+//! docs](super#json-format). The other structured formats translate into
+//! it, so names are folded and keys registered in one place.
+
+use serde::{Deserialize, Serialize};
+
+use super::{dump_keys, Column, ForeignKey, Schema};
+use crate::dialect::Dialect;
+use crate::error::{Result, SqlShieldError};
+use crate::functions::FunctionSignature;
+use crate::schema::sql::fold;
+
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct NativeSchema {
+    pub(crate) sqlshield_schema: u32,
+    #[serde(default)]
+    pub(crate) tables: Vec<NativeTable>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) enums: Vec<NativeEnum>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) functions: Vec<NativeFunction>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct NativeTable {
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) columns: Vec<NativeColumn>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) primary_key: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) unique: Vec<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) foreign_keys: Vec<NativeForeignKey>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct NativeColumn {
+    pub(crate) name: String,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub(crate) data_type: Option<String>,
+    #[serde(
+        default = "nullable_default",
+        skip_serializing_if = "is_nullable_default"
+    )]
+    pub(crate) nullable: bool,
+    #[serde(rename = "enum", default, skip_serializing_if = "Option::is_none")]
+    pub(crate) labels: Option<Vec<String>>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct NativeForeignKey {
+    pub(crate) columns: Vec<String>,
+    pub(crate) references: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) referenced_columns: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct NativeEnum {
+    pub(crate) name: String,
+    pub(crate) labels: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct NativeFunction {
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) min_args: usize,
+    #[serde(default)]
+    pub(crate) max_args: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) returns: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) params: Option<Vec<String>>,
+}
+
+impl NativeSchema {
+    /// A schema of just `tables`, as the other formats translate to.
+    pub(crate) fn of_tables(tables: Vec<NativeTable>) -> Self {
+        Self {
+            sqlshield_schema: FORMAT_VERSION,
+            tables,
+            enums: Vec::new(),
+            functions: Vec::new(),
+        }
+    }
+}
+
+fn nullable_default() -> bool {
+    true
+}
+
+fn is_nullable_default(nullable: &bool) -> bool {
+    *nullable
+}
+
+pub fn load(parsed: NativeSchema, dialect: Dialect) -> Result<Schema> {
+    if parsed.sqlshield_schema != FORMAT_VERSION {
+        return Err(SqlShieldError::InvalidSchema(format!(
+            "unsupported sqlshield_schema version {} (this build reads {FORMAT_VERSION})",
+            parsed.sqlshield_schema
+        )));
+    }
+    let fold = |name: &str| fold(name, true, dialect);
+
+    let mut loaded = Schema::default();
+    for e in parsed.enums {
+        let labels = e.labels;
+        for key in name_keys(&e.name, dialect) {
+            loaded.enums.insert(key, labels.clone());
+        }
+    }
+    for f in parsed.functions {
+        let signature = FunctionSignature {
+            min_args: f.min_args,
+            max_args: f.max_args,
+            return_type: f.returns,
+            params: f.params,
+        };
+        for key in name_keys(&f.name, dialect) {
+            loaded
+                .functions
+                .entry(key)
+                .or_default()
+                .push(signature.clone());
+        }
+    }
+    for table in parsed.tables {
+        let typed =
+            !table.columns.is_empty() && table.columns.iter().all(|c| c.data_type.is_some());
+        let columns: Vec<Column> = table
+            .columns
+            .into_iter()
+            .map(|c| {
+                let data_type = c.data_type.unwrap_or_default();
+                let folded = name_keys(&data_type, dialect).pop().unwrap_or_default();
+                Column {
+                    name: fold(&c.name),
+                    data_type: if loaded.enums.contains_key(&folded) {
+                        folded
+                    } else {
+                        data_type
+                    },
+                    inline_enum: c.labels,
+                    nullable: c.nullable,
+                }
+            })
+            .collect();
+        let primary_key = table
+            .primary_key
+            .map(|key| key.iter().map(|c| fold(c)).collect::<Vec<_>>());
+        let unique: Vec<Vec<String>> = table
+            .unique
+            .iter()
+            .map(|key| key.iter().map(|c| fold(c)).collect())
+            .collect();
+        let foreign_keys: Vec<ForeignKey> = table
+            .foreign_keys
+            .into_iter()
+            .map(|fk| ForeignKey {
+                columns: fk.columns.iter().map(|c| fold(c)).collect(),
+                referenced_table: name_keys(&fk.references, dialect)
+                    .into_iter()
+                    .next()
+                    .unwrap_or_default(),
+                referenced_columns: fk.referenced_columns.iter().map(|c| fold(c)).collect(),
+            })
+            .collect();
+        for key in name_keys(&table.name, dialect) {
+            loaded.tables.insert(
+                key.clone(),
+                columns.iter().map(|c| c.name.clone()).collect(),
+            );
+            if typed {
+                loaded.columns.insert(key.clone(), columns.clone());
+            } else {
+                loaded.columns.remove(&key);
+            }
+            if let Some(primary_key) = &primary_key {
+                loaded.primary_keys.insert(key.clone(), primary_key.clone());
+            }
+            if !unique.is_empty() {
+                loaded.unique_keys.insert(key.clone(), unique.clone());
+            }
+            if !foreign_keys.is_empty() {
+                loaded.foreign_keys.insert(key, foreign_keys.clone());
+            }
+        }
+    }
+    Ok(loaded)
+}
+
+/// Keys of a possibly qualified name, as DDL registers them: the bare
+/// name, then the qualified one.
+fn name_keys(name: &str, dialect: Dialect) -> Vec<String> {
+    let parts: Vec<String> = name.split('.').map(|p| fold(p, true, dialect)).collect();
+    let mut keys = vec![parts.last().cloned().unwrap_or_default()];
+    if parts.len() > 1 {
+        keys.push(parts.join("."));
+    }
+    keys
+}
+
+pub fn dump(schema: &Schema) -> String {
+    let tables = dump_keys(&schema.tables)
+        .into_iter()
+        .map(|name| {
+            let columns = match schema.columns.get(name) {
+                Some(columns) => columns
+                    .iter()
+                    .map(|c| NativeColumn {
+                        name: c.name.clone(),
+                        data_type: Some(c.data_type.clone()),
+                        nullable: c.nullable,
+                        labels: c.inline_enum.clone(),
+                    })
+                    .collect(),
+                None => {
+                    let mut names: Vec<&String> = schema.tables[name].iter().collect();
+                    names.sort();
+                    names
+                        .into_iter()
+                        .map(|c| NativeColumn {
+                            name: c.clone(),
+                            data_type: None,
+                            nullable: true,
+                            labels: None,
+                        })
+                        .collect()
+                }
+            };
+            NativeTable {
+                name: name.clone(),
+                columns,
+                primary_key: schema.primary_keys.get(name).cloned(),
+                unique: schema.unique_keys.get(name).cloned().unwrap_or_default(),
+                foreign_keys: schema
+                    .foreign_keys
+                    .get(name)
+                    .into_iter()
+                    .flatten()
+                    .map(|fk| NativeForeignKey {
+                        columns: fk.columns.clone(),
+                        references: fk.referenced_table.clone(),
+                        referenced_columns: fk.referenced_columns.clone(),
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+    let enums = dump_keys(&schema.enums)
+        .into_iter()
+        .map(|name| NativeEnum {
+            name: name.clone(),
+            labels: schema.enums[name].clone(),
+        })
+        .collect();
+    let functions = dump_keys(&schema.functions)
+        .into_iter()
+        .flat_map(|name| {
+            schema.functions[name].iter().map(|f| NativeFunction {
+                name: name.clone(),
+                min_args: f.min_args,
+                max_args: f.max_args,
+                returns: f.return_type.clone(),
+                params: f.params.clone(),
+            })
+        })
+        .collect();
+    let snapshot = NativeSchema {
+        sqlshield_schema: FORMAT_VERSION,
+        tables,
+        enums,
+        functions,
+    };
+    let mut out = serde_json::to_string_pretty(&snapshot).expect("schema serializes");
+    out.push('\n');
+    out
+}
@@ -0,0 +1,1195 @@
+//! Schemas from Python model definitions, for projects that never write
+//! DDL: SQLAlchemy declarative classes (`__tablename__` with `Column` /
+//! `mapped_column` attributes or `Mapped[…]` annotations), SQLAlchemy
+//! `Table(…)` objects, and Django `models.Model` subclasses.
+//!
+//! The file is parsed with tree-sitter, never imported, so only what it
+//! spells out counts: types and models defined in other modules are
+//! unknown, and a table with a column of unknown type gets column names
+//! only.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::native::{NativeColumn, NativeForeignKey, NativeSchema, NativeTable};
+use crate::error::{Result, SqlShieldError};
+
+/// A Python expression, as far as model definitions need one.
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Str(String),
+    Num(String),
+    Bool(bool),
+    None,
+    /// An identifier or dotted name (`Integer`, `sa.Integer`).
+    Name(String),
+    Call {
+        callee: String,
+        args: Vec<Expr>,
+        kwargs: Vec<(String, Expr)>,
+    },
+    /// `Mapped[int]`, `Optional[str]`.
+    Subscript {
+        value: String,
+        items: Vec<Expr>,
+    },
+    /// `str | None`.
+    Union(Vec<Expr>),
+    /// A tuple or list.
+    Seq(Vec<Expr>),
+    Dict(Vec<(Expr, Expr)>),
+    Other,
+}
+
+impl Expr {
+    fn str(&self) -> Option<&str> {
+        match self {
+            Self::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The dotted name of a name or the callee of a call.
+    fn name(&self) -> Option<&str> {
+        match self {
+            Self::Name(name) | Self::Call { callee: name, .. } => Some(name),
+            _ => None,
+        }
+    }
+
+    fn kwarg(&self, key: &str) -> Option<&Expr> {
+        match self {
+            Self::Call { kwargs, .. } => kwargs.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn is_true(&self, key: &str) -> bool {
+        self.kwarg(key) == Some(&Self::Bool(true))
+    }
+
+    fn args(&self) -> &[Expr] {
+        match self {
+            Self::Call { args, .. } => args,
+            _ => &[],
+        }
+    }
+}
+
+/// How far inheritance chains and relations are followed, which also
+/// stops at cycles (`class A(B)` with `class B(A)`).
+const MAX_DEPTH: usize = 16;
+
+/// The last segment of a dotted name: `sa.Integer` → `Integer`.
+fn last(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+struct Class {
+    name: String,
+    bases: Vec<String>,
+    attributes: Vec<Attribute>,
+    /// Assignments in a nested `class Meta:` (Django).
+    meta: HashMap<String, Expr>,
+}
+
+struct Attribute {
+    name: String,
+    annotation: Option<Expr>,
+    value: Option<Expr>,
+}
+
+impl Class {
+    fn attribute(&self, name: &str) -> Option<&Expr> {
+        self.attributes
+            .iter()
+            .find(|a| a.name == name)
+            .and_then(|a| a.value.as_ref())
+    }
+}
+
+/// `source` in sqlshield's format. `path` is where it was read from; a
+/// Django model without `db_table` or `app_label` takes its app label from
+/// the directory, as Django does.
+pub fn translate(source: &[u8], path: Option<&Path>) -> Result<NativeSchema> {
+    let mut parser = tree_sitter::Parser::new();
+    parser
+        .set_language(tree_sitter_python::language())
+        .expect("tree-sitter grammar incompatible with tree-sitter runtime");
+    let tree = parser
+        .parse(source, None)
+        .ok_or(SqlShieldError::CodeParse)?;
+
+    let mut module = Module::default();
+    module.collect(tree.root_node(), source);
+    let app_label = path.and_then(app_label);
+    Ok(NativeSchema::of_tables(module.tables(app_label.as_deref())))
+}
+
+/// The directory naming a Django app: the one holding `models.py`, or the
+/// one holding the `models/` package.
+fn app_label(path: &Path) -> Option<String> {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let mut dir = path.parent()?;
+    if dir.file_name()? == "models" {
+        dir = dir.parent()?;
+    }
+    Some(dir.file_name()?.to_string_lossy().into_owned())
+}
+
+#[derive(Default)]
+struct Module {
+    classes: Vec<Class>,
+    /// `Table("name", metadata, Column(…), …)` calls.
+    table_calls: Vec<Expr>,
+}
+
+impl Module {
+    fn collect(&mut self, node: tree_sitter::Node, code: &[u8]) {
+        match node.kind() {
+            "class_definition" => {
+                if let Some(class) = class(node, code) {
+                    self.classes.push(class);
+                }
+            }
+            "call" => {
+                let call = expr(node, code);
+                if call.name().map(last) == Some("Table")
+                    && call.args().first().and_then(Expr::str).is_some()
+                {
+                    self.table_calls.push(call);
+                    return;
+                }
+            }
+            _ => {}
+        }
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.collect(child, code);
+        }
+    }
+
+    fn class(&self, name: &str) -> Option<&Class> {
+        self.classes.iter().find(|c| c.name == last(name))
+    }
+
+    /// The classes `class` derives from that are defined in this file,
+    /// nearest first.
+    fn bases<'a>(&'a self, class: &'a Class) -> impl Iterator<Item = &'a Class> {
+        class
+            .bases
+            .iter()
+            .filter_map(|b| self.class(b))
+            .filter(move |b| b.name != class.name)
+    }
+
+    fn tables(&self, app_label: Option<&str>) -> Vec<NativeTable> {
+        let mut tables: Vec<NativeTable> = Vec::new();
+        for class in &self.classes {
+            let family = self.family(class, 0);
+            let translated = match family {
+                Some(Family::SqlAlchemy) => self.sqlalchemy_model(class),
+                Some(Family::Django) => self.django_model(class, app_label),
+                None => Vec::new(),
+            };
+            for table in translated {
+                match tables.iter_mut().find(|t| t.name == table.name) {
+                    // Single-table inheritance: a subclass adds its
+                    // columns to its parent's table.
+                    Some(existing) => {
+                        for column in table.columns {
+                            if !existing.columns.iter().any(|c| c.name == column.name) {
+                                existing.columns.push(column);
+                            }
+                        }
+                        existing.foreign_keys.extend(table.foreign_keys);
+                    }
+                    None => tables.push(table),
+                }
+            }
+        }
+        for call in &self.table_calls {
+            tables.push(self.sqlalchemy_table(call));
+        }
+        infer_foreign_key_types(&mut tables);
+        tables
+    }
+
+    fn family(&self, class: &Class, depth: usize) -> Option<Family> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let sqlalchemy = class.attributes.iter().any(|a| {
+            matches!(
+                a.name.as_str(),
+                "__tablename__" | "__table_args__" | "__abstract__"
+            ) || sqlalchemy_column(a).is_some()
+        });
+        if sqlalchemy {
+            return Some(Family::SqlAlchemy);
+        }
+        if let Some(family) = self.bases(class).find_map(|b| self.family(b, depth + 1)) {
+            return Some(family);
+        }
+        if class.bases.iter().any(|b| last(b) == "Model") {
+            return Some(Family::Django);
+        }
+        None
+    }
+
+    /// The attributes of `class` and of the bases it inherits columns
+    /// from (mixins, abstract models), bases first; a redefined attribute
+    /// replaces the inherited one in place.
+    fn attributes<'a>(
+        &'a self,
+        class: &'a Class,
+        inherits: &dyn Fn(&Class) -> bool,
+        depth: usize,
+    ) -> Vec<&'a Attribute> {
+        let mut attributes: Vec<&Attribute> = Vec::new();
+        if depth <= MAX_DEPTH {
+            for base in self.bases(class).collect::<Vec<_>>().into_iter().rev() {
+                if inherits(base) {
+                    for a in self.attributes(base, inherits, depth + 1) {
+                        attributes.retain(|b| b.name != a.name);
+                        attributes.push(a);
+                    }
+                }
+            }
+        }
+        for a in &class.attributes {
+            match attributes.iter_mut().find(|b| b.name == a.name) {
+                Some(slot) => *slot = a,
+                None => attributes.push(a),
+            }
+        }
+        attributes
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Family {
+    SqlAlchemy,
+    Django,
+}
+
+/// A column whose type SQLAlchemy infers from the column its foreign key
+/// references gets that column's type, when the table is in the file.
+fn infer_foreign_key_types(tables: &mut [NativeTable]) {
+    let types: HashMap<(String, String), String> = tables
+        .iter()
+        .flat_map(|t| {
+            let bare = last(&t.name).to_string();
+            t.columns.iter().filter_map(move |c| {
+                let data_type = c.data_type.clone()?;
+                Some([
+                    ((t.name.clone(), c.name.clone()), data_type.clone()),
+                    ((bare.clone(), c.name.clone()), data_type),
+                ])
+            })
+        })
+        .flatten()
+        .collect();
+    for table in tables {
+        for fk in &table.foreign_keys {
+            let ([column], [referenced]) = (&fk.columns[..], &fk.referenced_columns[..]) else {
+                continue;
+            };
+            let Some(data_type) = types.get(&(fk.references.clone(), referenced.clone())) else {
+                continue;
+            };
+            if let Some(c) = table
+                .columns
+                .iter_mut()
+                .find(|c| &c.name == column && c.data_type.is_none())
+            {
+                c.data_type = Some(data_type.clone());
+            }
+        }
+    }
+}
+
+// SQLAlchemy
+
+/// A column as SQLAlchemy declares it.
+struct SqlAlchemyColumn {
+    column: NativeColumn,
+    primary_key: bool,
+    foreign_key: Option<NativeForeignKey>,
+}
+
+/// The `Column(…)` / `mapped_column(…)` call of a declarative attribute,
+/// or `Expr::None` for a bare `Mapped[…]` annotation; `None` when the
+/// attribute isn't a column.
+fn sqlalchemy_column(attribute: &Attribute) -> Option<&Expr> {
+    match &attribute.value {
+        Some(value) => {
+            matches!(value.name().map(last), Some("Column" | "mapped_column")).then_some(value)
+        }
+        None => match &attribute.annotation {
+            Some(Expr::Subscript { value, .. }) if last(value) == "Mapped" => Some(&Expr::None),
+            _ => None,
+        },
+    }
+}
+
+impl Module {
+    fn sqlalchemy_model(&self, class: &Class) -> Vec<NativeTable> {
+        let Some(name) = self.sqlalchemy_table_name(class, 0) else {
+            return Vec::new();
+        };
+        let abstract_or_mixin = |c: &Class| self.sqlalchemy_table_name(c, 0).is_none();
+        let columns = self
+            .attributes(class, &abstract_or_mixin, 0)
+            .into_iter()
+            .filter_map(|a| {
+                let call = sqlalchemy_column(a)?;
+                Some(self.sqlalchemy_column(&a.name, a.annotation.as_ref(), call))
+            })
+            .collect();
+        vec![sqlalchemy_table(name, columns)]
+    }
+
+    /// The table `class` maps to: its `__tablename__`, qualified by a
+    /// `schema` in `__table_args__`; its parent's table when it subclasses
+    /// a mapped class (single-table inheritance); or, for a Flask-SQLAlchemy
+    /// model, its snake-cased name. `None` for mixins and abstract classes.
+    fn sqlalchemy_table_name(&self, class: &Class, depth: usize) -> Option<String> {
+        if depth > MAX_DEPTH || class.attribute("__abstract__") == Some(&Expr::Bool(true)) {
+            return None;
+        }
+        let name = match class.attribute("__tablename__").and_then(Expr::str) {
+            Some(name) => name.to_string(),
+            None => {
+                if let Some(parent) = self
+                    .bases(class)
+                    .find_map(|b| self.sqlalchemy_table_name(b, depth + 1))
+                {
+                    return Some(parent);
+                }
+                if !class.bases.iter().any(|b| last(b) == "Model") {
+                    return None;
+                }
+                snake_case(&class.name)
+            }
+        };
+        let schema = std::iter::once(class)
+            .chain(self.bases(class))
+            .find_map(|c| table_args_schema(c.attribute("__table_args__")?));
+        Some(match schema {
+            Some(schema) if !name.contains('.') => format!("{schema}.{name}"),
+            _ => name,
+        })
+    }
+
+    /// A `Table("name", metadata, Column("id", …), …, schema=…)` call.
+    fn sqlalchemy_table(&self, call: &Expr) -> NativeTable {
+        let name = call.args()[0].str().unwrap_or_default();
+        let name = match call.kwarg("schema").and_then(Expr::str) {
+            Some(schema) => format!("{schema}.{name}"),
+            None => name.to_string(),
+        };
+        let columns = call
+            .args()
+            .iter()
+            .filter(|a| a.name().map(last) == Some("Column"))
+            .filter_map(|c| {
+                let name = c.args().first()?.str()?;
+                Some(self.sqlalchemy_column(name, None, c))
+            })
+            .collect();
+        sqlalchemy_table(name, columns)
+    }
+
+    /// The column `call` declares for attribute `name`. The type comes from
+    /// the call, or else from a `Mapped[…]` annotation; so does
+    /// nullability, unless the call sets `nullable` or `primary_key`.
+    fn sqlalchemy_column(
+        &self,
+        name: &str,
+        annotation: Option<&Expr>,
+        call: &Expr,
+    ) -> SqlAlchemyColumn {
+        let args = call.args();
+        let name = args.first().and_then(Expr::str).unwrap_or(name).to_string();
+        let explicit_type = args
+            .iter()
+            .filter(|a| a.str().is_none())
+            .filter(|a| {
+                !matches!(
+                    a.name().map(last),
+                    Some("ForeignKey" | "Sequence" | "Identity" | "Computed" | "FetchedValue")
+                )
+            })
+            .find_map(|a| self.sqlalchemy_type(a));
+        let mapped = match annotation {
+            Some(Expr::Subscript { value, items }) if last(value) == "Mapped" => items.first(),
+            _ => None,
+        };
+        let (annotated_type, optional) = match mapped {
+            Some(mapped) => {
+                let (inner, optional) = optional_inner(mapped);
+                (self.python_type(inner), optional)
+            }
+            None => (None, true),
+        };
+        let (data_type, labels) = match explicit_type.or(annotated_type) {
+            Some((data_type, labels)) => (Some(data_type), labels),
+            None => (None, None),
+        };
+
+        let primary_key = call.is_true("primary_key");
+        let nullable = match call.kwarg("nullable") {
+            Some(Expr::Bool(nullable)) => *nullable,
+            _ => !primary_key && optional,
+        };
+        let foreign_key = args
+            .iter()
+            .find(|a| a.name().map(last) == Some("ForeignKey"))
+            .and_then(|fk| self.sqlalchemy_reference(fk.args().first()?))
+            .map(|(table, column)| NativeForeignKey {
+                columns: vec![name.clone()],
+                references: table,
+                referenced_columns: vec![column],
+            });
+        SqlAlchemyColumn {
+            column: NativeColumn {
+                name,
+                data_type,
+                nullable,
+                labels,
+            },
+            primary_key,
+            foreign_key,
+        }
+    }
+
+    /// The table and column a `ForeignKey` target names: `"orgs.id"`, or
+    /// `Org.id` for a model in this file.
+    fn sqlalchemy_reference(&self, target: &Expr) -> Option<(String, String)> {
+        match target {
+            Expr::Str(target) => {
+                let (table, column) = target.rsplit_once('.')?;
+                Some((table.to_string(), column.to_string()))
+            }
+            Expr::Name(target) => {
+                let (class, column) = target.rsplit_once('.')?;
+                let table = self.class(class)?.attribute("__tablename__")?.str()?;
+                Some((table.to_string(), column.to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    /// The SQL type (and inline enum labels) of a SQLAlchemy type such as
+    /// `String(50)` or `sa.Enum(Status)`; `None` for types it doesn't know.
+    fn sqlalchemy_type(&self, ty: &Expr) -> Option<(String, Option<Vec<String>>)> {
+        let name = last(ty.name()?);
+        let args = ty.args();
+        let num = |i: usize, key: &str| match args.get(i).or_else(|| ty.kwarg(key)) {
+            Some(Expr::Num(n)) => Some(n.clone()),
+            _ => None,
+        };
+        let sized = |base: &str| match num(0, "length") {
+            Some(length) => format!("{base}({length})"),
+            None => base.to_string(),
+        };
+        let data_type = match name {
+            "Integer" => "INTEGER".to_string(),
+            "BigInteger" => "BIGINT".to_string(),
+            "SmallInteger" => "SMALLINT".to_string(),
+            "String" | "Unicode" => sized("VARCHAR"),
+            "Text" | "UnicodeText" => "TEXT".to_string(),
+            "Boolean" => "BOOLEAN".to_string(),
+            "Date" => "DATE".to_string(),
+            "DateTime" if ty.is_true("timezone") => "TIMESTAMP WITH TIME ZONE".to_string(),
+            "DateTime" => "TIMESTAMP".to_string(),
+            "Time" => "TIME".to_string(),
+            "Interval" => "INTERVAL".to_string(),
+            "Float" => "FLOAT".to_string(),
+            "Double" => "DOUBLE PRECISION".to_string(),
+            "Numeric" => match (num(0, "precision"), num(1, "scale")) {
+                (Some(p), Some(s)) => format!("NUMERIC({p}, {s})"),
+                (Some(p), None) => format!("NUMERIC({p})"),
+                _ => "NUMERIC".to_string(),
+            },
+            "LargeBinary" => "BLOB".to_string(),
+            "Uuid" => "UUID".to_string(),
+            "Enum" => return self.sqlalchemy_enum(ty),
+            "ARRAY" => format!("{}[]", self.sqlalchemy_type(args.first()?)?.0),
+            // Dialect and SQL-standard types (`VARCHAR(20)`, `JSONB`) are
+            // spelled as in SQL already.
+            upper
+                if upper
+                    .bytes()
+                    .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_') =>
+            {
+                let modifiers: Vec<&str> = args
+                    .iter()
+                    .filter_map(|a| match a {
+                        Expr::Num(n) => Some(n.as_str()),
+                        _ => None,
+                    })
+                    .collect();
+                if modifiers.is_empty() {
+                    upper.to_string()
+                } else {
+                    format!("{upper}({})", modifiers.join(", "))
+                }
+            }
+            _ => return None,
+        };
+        Some((data_type, None))
+    }
+
+    /// `Enum("a", "b", name="status")`, or `Enum(Status)` for a Python enum
+    /// in this file, which stores member names unless `values_callable`
+    /// says otherwise. The type is the enum's `name` (the lowercased class
+    /// name by default), with the labels inline.
+    fn sqlalchemy_enum(&self, ty: &Expr) -> Option<(String, Option<Vec<String>>)> {
+        let mut labels: Vec<String> = ty
+            .args()
+            .iter()
+            .filter_map(Expr::str)
+            .map(str::to_string)
+            .collect();
+        let mut name = ty.kwarg("name").and_then(Expr::str).map(str::to_string);
+        if labels.is_empty() {
+            let class = self.class(ty.args().first()?.name()?)?;
+            labels = python_enum_labels(class, ty.kwarg("values_callable").is_some())?;
+            name = name.or_else(|| Some(class.name.to_lowercase()));
+        }
+        Some((name.unwrap_or_else(|| "VARCHAR".to_string()), Some(labels)))
+    }
+
+    /// The SQL type SQLAlchemy maps a `Mapped[…]` Python type to.
+    fn python_type(&self, ty: &Expr) -> Option<(String, Option<Vec<String>>)> {
+        let name = last(ty.name().or_else(|| ty.str())?);
+        let data_type = match name {
+            "int" => "INTEGER",
+            "str" => "VARCHAR",
+            "float" => "FLOAT",
+            "bool" => "BOOLEAN",
+            "bytes" => "BLOB",
+            "datetime" => "TIMESTAMP",
+            "date" => "DATE",
+            "time" => "TIME",
+            "timedelta" => "INTERVAL",
+            "Decimal" => "NUMERIC",
+            "UUID" => "UUID",
+            _ => {
+                let class = self.class(name)?;
+                return Some((
+                    class.name.to_lowercase(),
+                    Some(python_enum_labels(class, false)?),
+                ));
+            }
+        };
+        Some((data_type.to_string(), None))
+    }
+}
+
+/// The type in `Optional[T]`, `Union[T, None]` or `T | None`, and whether
+/// it was optional.
+fn optional_inner(ty: &Expr) -> (&Expr, bool) {
+    let items = match ty {
+        Expr::Subscript { value, items } if last(value) == "Optional" => {
+            return match items.first() {
+                Some(inner) => (inner, true),
+                None => (ty, false),
+            };
+        }
+        Expr::Subscript { value, items } if last(value) == "Union" => items,
+        Expr::Union(items) => items,
+        _ => return (ty, false),
+    };
+    let rest: Vec<&Expr> = items.iter().filter(|i| !is_none_type(i)).collect();
+    match rest[..] {
+        [inner] if rest.len() < items.len() => (inner, true),
+        _ => (ty, false),
+    }
+}
+
+fn is_none_type(ty: &Expr) -> bool {
+    matches!(ty, Expr::None) || ty.name() == Some("NoneType")
+}
+
+/// The member names (or values) of a Python `Enum` subclass.
+fn python_enum_labels(class: &Class, values: bool) -> Option<Vec<String>> {
+    if !class.bases.iter().any(|b| last(b).ends_with("Enum")) {
+        return None;
+    }
+    let labels = class
+        .attributes
+        .iter()
+        .filter(|a| !a.name.starts_with('_'))
+        .filter_map(|a| match (values, &a.value) {
+            (false, Some(_)) => Some(a.name.clone()),
+            (true, Some(Expr::Str(value))) => Some(value.clone()),
+            (true, Some(Expr::Num(value))) => Some(value.clone()),
+            _ => None,
+        })
+        .collect();
+    Some(labels)
+}
+
+/// The `schema` of `__table_args__`: a dict, or a tuple ending in one.
+fn table_args_schema(args: &Expr) -> Option<&str> {
+    let dict = match args {
+        Expr::Seq(items) => items.last()?,
+        dict => dict,
+    };
+    let Expr::Dict(pairs) = dict else {
+        return None;
+    };
+    pairs
+        .iter()
+        .find(|(k, _)| k.str() == Some("schema"))
+        .and_then(|(_, v)| v.str())
+}
+
+fn sqlalchemy_table(name: String, columns: Vec<SqlAlchemyColumn>) -> NativeTable {
+    let primary_key: Vec<String> = columns
+        .iter()
+        .filter(|c| c.primary_key)
+        .map(|c| c.column.name.clone())
+        .collect();
+    let foreign_keys = columns
+        .iter()
+        .filter_map(|c| c.foreign_key.clone())
+        .collect();
+    NativeTable {
+        name,
+        columns: columns.into_iter().map(|c| c.column).collect(),
+        primary_key: (!primary_key.is_empty()).then_some(primary_key),
+        unique: Vec::new(),
+        foreign_keys,
+    }
+}
+
+/// `UserAccount` → `user_account`.
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.char_indices() {
+        if c.is_uppercase() && i > 0 {
+            out.push('_');
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+// Django
+
+/// The implicit primary key type of a model without one: `BigAutoField`,
+/// the default of new projects.
+const DJANGO_AUTO_TYPE: &str = "bigint";
+
+impl Module {
+    fn django_model(&self, class: &Class, app_label: Option<&str>) -> Vec<NativeTable> {
+        let meta_true = |c: &Class, key: &str| c.meta.get(key) == Some(&Expr::Bool(true));
+        if meta_true(class, "abstract") || meta_true(class, "proxy") {
+            return Vec::new();
+        }
+        let table = self.django_table(class, app_label);
+        let abstract_base = |c: &Class| meta_true(c, "abstract");
+        let fields = self.attributes(class, &abstract_base, 0);
+
+        let mut columns = Vec::new();
+        let mut primary_key = Vec::new();
+        let mut foreign_keys = Vec::new();
+        let mut tables = Vec::new();
+
+        // Multi-table inheritance links a child to its concrete parent by a
+        // one-to-one `<parent>_ptr_id` primary key.
+        let parent = self
+            .bases(class)
+            .find(|b| self.family(b, 0) == Some(Family::Django) && !abstract_base(b));
+        if let Some(parent) = parent {
+            let column = format!("{}_ptr_id", parent.name.to_lowercase());
+            columns.push(NativeColumn {
+                name: column.clone(),
+                data_type: Some(self.django_pk_type(parent, 0)),
+                nullable: false,
+                labels: None,
+            });
+            primary_key.push(column.clone());
+            foreign_keys.push(NativeForeignKey {
+                columns: vec![column],
+                references: self.django_table(parent, app_label),
+                referenced_columns: Vec::new(),
+            });
+        }
+
+        for attribute in fields {
+            let Some(field) = attribute.value.as_ref().filter(|v| is_django_field(v)) else {
+                continue;
+            };
+            let kind = last(field.name().unwrap_or_default());
+            if kind == "ManyToManyField" {
+                if field.kwarg("through").is_none() {
+                    tables.extend(self.django_join_table(
+                        class,
+                        &table,
+                        &attribute.name,
+                        field,
+                        app_label,
+                    ));
+                }
+                continue;
+            }
+            let relation = matches!(kind, "ForeignKey" | "OneToOneField");
+            let column = match field.kwarg("db_column").and_then(Expr::str) {
+                Some(column) => column.to_string(),
+                None if relation => format!("{}_id", attribute.name),
+                None => attribute.name.clone(),
+            };
+            let data_type = if relation {
+                self.django_target(class, field)
+                    .map(|target| self.django_pk_type(target, 0))
+                    .or_else(|| Some(DJANGO_AUTO_TYPE.to_string()))
+            } else {
+                django_type(field)
+            };
+            let is_primary_key = field.is_true("primary_key");
+            if is_primary_key {
+                primary_key = vec![column.clone()];
+            }
+            if relation {
+                foreign_keys.push(NativeForeignKey {
+                    columns: vec![column.clone()],
+                    references: self.django_target_table(class, field, app_label),
+                    referenced_columns: field
+                        .kwarg("to_field")
+                        .and_then(Expr::str)
+                        .map(|c| vec![c.to_string()])
+                        .unwrap_or_default(),
+                });
+            }
+            columns.push(NativeColumn {
+                name: column,
+                data_type,
+                nullable: !is_primary_key && (field.is_true("null") || kind == "NullBooleanField"),
+                labels: None,
+            });
+        }
+
+        if primary_key.is_empty() {
+            columns.insert(0, django_id());
+            primary_key.push("id".to_string());
+        }
+        tables.insert(
+            0,
+            NativeTable {
+                name: table,
+                columns,
+                primary_key: Some(primary_key),
+                unique: Vec::new(),
+                foreign_keys,
+            },
+        );
+        tables
+    }
+
+    /// `Meta.db_table`, or `<app_label>_<model>` as Django names it.
+    fn django_table(&self, class: &Class, app_label: Option<&str>) -> String {
+        if let Some(table) = class.meta.get("db_table").and_then(Expr::str) {
+            return table.to_string();
+        }
+        let app_label = class
+            .meta
+            .get("app_label")
+            .and_then(Expr::str)
+            .or(app_label);
+        let model = class.name.to_lowercase();
+        match app_label {
+            Some(app_label) => format!("{}_{model}", app_label.to_lowercase()),
+            None => model,
+        }
+    }
+
+    /// The model in this file a relation field points to.
+    fn django_target<'a>(&'a self, class: &'a Class, field: &Expr) -> Option<&'a Class> {
+        let target = field.args().first().or_else(|| field.kwarg("to"))?;
+        match target.str().or_else(|| target.name())? {
+            "self" => Some(class),
+            target => self.class(target),
+        }
+    }
+
+    /// The table a relation field points to; for a model outside this file,
+    /// the name Django gives it (`"billing.Invoice"` → `billing_invoice`).
+    fn django_target_table(&self, class: &Class, field: &Expr, app_label: Option<&str>) -> String {
+        if let Some(target) = self.django_target(class, field) {
+            return self.django_table(target, app_label);
+        }
+        let target = field.args().first().or_else(|| field.kwarg("to"));
+        // A lazy reference names its app: `"billing.Invoice"`.
+        if let Some((app, model)) = target.and_then(Expr::str).and_then(|t| t.split_once('.')) {
+            return format!("{}_{}", app.to_lowercase(), model.to_lowercase());
+        }
+        let model = target
+            .and_then(|t| t.str().or_else(|| t.name()))
+            .map(|t| last(t).to_lowercase())
+            .unwrap_or_default();
+        match app_label {
+            Some(app_label) => format!("{}_{model}", app_label.to_lowercase()),
+            None => model,
+        }
+    }
+
+    /// The type of `class`'s primary key, as a referencing column has it.
+    fn django_pk_type(&self, class: &Class, depth: usize) -> String {
+        let abstract_base = |c: &Class| c.meta.get("abstract") == Some(&Expr::Bool(true));
+        let explicit = self
+            .attributes(class, &abstract_base, 0)
+            .into_iter()
+            .filter_map(|a| a.value.as_ref())
+            .find(|v| is_django_field(v) && v.is_true("primary_key"))
+            .and_then(|pk| match last(pk.name().unwrap_or_default()) {
+                "ForeignKey" | "OneToOneField" if depth < MAX_DEPTH => self
+                    .django_target(class, pk)
+                    .map(|target| self.django_pk_type(target, depth + 1)),
+                "AutoField" => Some("integer".to_string()),
+                "BigAutoField" => Some("bigint".to_string()),
+                "SmallAutoField" => Some("smallint".to_string()),
+                _ => django_type(pk),
+            });
+        explicit.unwrap_or_else(|| DJANGO_AUTO_TYPE.to_string())
+    }
+
+    /// The join table of a `ManyToManyField` without `through`.
+    fn django_join_table(
+        &self,
+        class: &Class,
+        table: &str,
+        field_name: &str,
+        field: &Expr,
+        app_label: Option<&str>,
+    ) -> Option<NativeTable> {
+        let name = field
+            .kwarg("db_table")
+            .and_then(Expr::str)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{table}_{field_name}"));
+        let model = class.name.to_lowercase();
+        let target = self.django_target(class, field);
+        let target_model = match target {
+            Some(target) => target.name.to_lowercase(),
+            None => {
+                let target = field.args().first().or_else(|| field.kwarg("to"))?;
+                last(target.str().or_else(|| target.name())?).to_lowercase()
+            }
+        };
+        let (from, to) = if target_model == model {
+            (format!("from_{model}_id"), format!("to_{model}_id"))
+        } else {
+            (format!("{model}_id"), format!("{target_model}_id"))
+        };
+        let target_type = target
+            .map(|t| self.django_pk_type(t, 0))
+            .unwrap_or_else(|| DJANGO_AUTO_TYPE.to_string());
+        let column = |name: &str, data_type: String| NativeColumn {
+            name: name.to_string(),
+            data_type: Some(data_type),
+            nullable: false,
+            labels: None,
+        };
+        Some(NativeTable {
+            name,
+            columns: vec![
+                django_id(),
+                column(&from, self.django_pk_type(class, 0)),
+                column(&to, target_type),
+            ],
+            primary_key: Some(vec!["id".to_string()]),
+            unique: Vec::new(),
+            foreign_keys: vec![
+                NativeForeignKey {
+                    columns: vec![from],
+                    references: table.to_string(),
+                    referenced_columns: Vec::new(),
+                },
+                NativeForeignKey {
+                    columns: vec![to],
+                    references: self.django_target_table(class, field, app_label),
+                    referenced_columns: Vec::new(),
+                },
+            ],
+        })
+    }
+}
+
+fn is_django_field(value: &Expr) -> bool {
+    matches!(value, Expr::Call { .. })
+        && value.name().is_some_and(|name| {
+            let name = last(name);
+            name.ends_with("Field") || name == "ForeignKey"
+        })
+}
+
+fn django_id() -> NativeColumn {
+    NativeColumn {
+        name: "id".to_string(),
+        data_type: Some(DJANGO_AUTO_TYPE.to_string()),
+        nullable: false,
+        labels: None,
+    }
+}
+
+/// The column type Django's Postgres backend gives a field; `None` for
+/// fields it doesn't know.
+fn django_type(field: &Expr) -> Option<String> {
+    let num = |key: &str| match field.kwarg(key) {
+        Some(Expr::Num(n)) => Some(n.clone()),
+        _ => None,
+    };
+    let varchar = |default: &str| {
+        format!(
+            "varchar({})",
+            num("max_length").unwrap_or_else(|| default.to_string())
+        )
+    };
+    let data_type = match last(field.name()?) {
+        "AutoField" | "IntegerField" | "PositiveIntegerField" => "integer".to_string(),
+        "BigAutoField" | "BigIntegerField" | "PositiveBigIntegerField" => "bigint".to_string(),
+        "SmallAutoField" | "SmallIntegerField" | "PositiveSmallIntegerField" => {
+            "smallint".to_string()
+        }
+        "BooleanField" | "NullBooleanField" => "boolean".to_string(),
+        "CharField" => match num("max_length") {
+            Some(length) => format!("varchar({length})"),
+            None => "varchar".to_string(),
+        },
+        "SlugField" => varchar("50"),
+        "EmailField" => varchar("254"),
+        "URLField" => varchar("200"),
+        "FileField" | "ImageField" | "FilePathField" => varchar("100"),
+        "TextField" => "text".to_string(),
+        "FloatField" => "double precision".to_string(),
+        "DecimalField" => match (num("max_digits"), num("decimal_places")) {
+            (Some(p), Some(s)) => format!("numeric({p}, {s})"),
+            _ => "numeric".to_string(),
+        },
+        "DateField" => "date".to_string(),
+        "DateTimeField" => "timestamp with time zone".to_string(),
+        "TimeField" => "time".to_string(),
+        "DurationField" => "interval".to_string(),
+        "UUIDField" => "uuid".to_string(),
+        "JSONField" => "jsonb".to_string(),
+        "BinaryField" => "bytea".to_string(),
+        "GenericIPAddressField" => "inet".to_string(),
+        _ => return None,
+    };
+    Some(data_type)
+}
+
+// Parsing
+
+fn text<'a>(node: tree_sitter::Node, code: &'a [u8]) -> &'a str {
+    node.utf8_text(code).unwrap_or_default()
+}
+
+fn class(node: tree_sitter::Node, code: &[u8]) -> Option<Class> {
+    let name = text(node.child_by_field_name("name")?, code).to_string();
+    let mut bases = Vec::new();
+    if let Some(superclasses) = node.child_by_field_name("superclasses") {
+        let mut cursor = superclasses.walk();
+        for base in superclasses.named_children(&mut cursor) {
+            if let Some(base) = expr(base, code).name() {
+                bases.push(base.to_string());
+            }
+        }
+    }
+    let mut attributes = Vec::new();
+    let mut meta = HashMap::new();
+    let body = node.child_by_field_name("body")?;
+    let mut cursor = body.walk();
+    for statement in body.named_children(&mut cursor) {
+        match statement.kind() {
+            "expression_statement" => {
+                if let Some(attribute) = statement.named_child(0).and_then(|a| assignment(a, code))
+                {
+                    attributes.push(attribute);
+                }
+            }
+            "class_definition" => {
+                let Some(inner) = self::class(statement, code) else {
+                    continue;
+                };
+                if inner.name == "Meta" {
+                    meta.extend(
+                        inner
+                            .attributes
+                            .into_iter()
+                            .filter_map(|a| Some((a.name, a.value?))),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(Class {
+        name,
+        bases,
+        attributes,
+        meta,
+    })
+}
+
+/// `name = value`, `name: annotation = value` or `name: annotation`.
+fn assignment(node: tree_sitter::Node, code: &[u8]) -> Option<Attribute> {
+    if node.kind() != "assignment" {
+        return None;
+    }
+    let left = node.child_by_field_name("left")?;
+    if left.kind() != "identifier" {
+        return None;
+    }
+    Some(Attribute {
+        name: text(left, code).to_string(),
+        annotation: node.child_by_field_name("type").map(|t| expr(t, code)),
+        value: node.child_by_field_name("right").map(|r| expr(r, code)),
+    })
+}
+
+fn expr(node: tree_sitter::Node, code: &[u8]) -> Expr {
+    let children = |node: tree_sitter::Node| -> Vec<Expr> {
+        let mut cursor = node.walk();
+        let items: Vec<Expr> = node
+            .named_children(&mut cursor)
+            .filter(|c| c.kind() != "comment")
+            .map(|c| expr(c, code))
+            .collect();
+        items
+    };
+    match node.kind() {
+        "string" => {
+            let mut cursor = node.walk();
+            let mut value = String::new();
+            for part in node.named_children(&mut cursor) {
+                match part.kind() {
+                    "string_content" | "escape_sequence" => value.push_str(text(part, code)),
+                    "interpolation" => return Expr::Other,
+                    _ => {}
+                }
+            }
+            Expr::Str(value)
+        }
+        "concatenated_string" => {
+            let mut value = String::new();
+            for part in children(node) {
+                match part {
+                    Expr::Str(s) => value.push_str(&s),
+                    _ => return Expr::Other,
+                }
+            }
+            Expr::Str(value)
+        }
+        "integer" | "float" => Expr::Num(text(node, code).to_string()),
+        "true" => Expr::Bool(true),
+        "false" => Expr::Bool(false),
+        "none" => Expr::None,
+        "identifier" | "attribute" => {
+            Expr::Name(text(node, code).split_whitespace().collect::<String>())
+        }
+        "call" => {
+            let Some(callee) = node.child_by_field_name("function").map(|f| expr(f, code)) else {
+                return Expr::Other;
+            };
+            let Some(callee) = callee.name().map(str::to_string) else {
+                return Expr::Other;
+            };
+            let mut args = Vec::new();
+            let mut kwargs = Vec::new();
+            if let Some(arguments) = node.child_by_field_name("arguments") {
+                let mut cursor = arguments.walk();
+                for argument in arguments.named_children(&mut cursor) {
+                    match argument.kind() {
+                        "keyword_argument" => {
+                            let (Some(name), Some(value)) = (
+                                argument.child_by_field_name("name"),
+                                argument.child_by_field_name("value"),
+                            ) else {
+                                continue;
+                            };
+                            kwargs.push((text(name, code).to_string(), expr(value, code)));
+                        }
+                        "comment" => {}
+                        _ => args.push(expr(argument, code)),
+                    }
+                }
+            }
+            Expr::Call {
+                callee,
+                args,
+                kwargs,
+            }
+        }
+        "subscript" => {
+            let Some(value) = node.child_by_field_name("value").map(|v| expr(v, code)) else {
+                return Expr::Other;
+            };
+            let Some(value) = value.name().map(str::to_string) else {
+                return Expr::Other;
+            };
+            let mut cursor = node.walk();
+            let items = node
+                .children_by_field_name("subscript", &mut cursor)
+                .map(|s| expr(s, code))
+                .collect();
+            Expr::Subscript { value, items }
+        }
+        // Annotations: `Mapped[int]` is a generic type, not a subscript.
+        "generic_type" => {
+            let Some(value) = node.named_child(0).map(|v| expr(v, code)) else {
+                return Expr::Other;
+            };
+            let Some(value) = value.name().map(str::to_string) else {
+                return Expr::Other;
+            };
+            let items = match node.named_child(1) {
+                Some(parameters) => children(parameters),
+                None => Vec::new(),
+            };
+            Expr::Subscript { value, items }
+        }
+        "binary_operator" => {
+            let operator = node.child_by_field_name("operator").map(|o| text(o, code));
+            let (Some("|"), Some(left), Some(right)) = (
+                operator,
+                node.child_by_field_name("left"),
+                node.child_by_field_name("right"),
+            ) else {
+                return Expr::Other;
+            };
+            let mut items = Vec::new();
+            for side in [expr(left, code), expr(right, code)] {
+                match side {
+                    Expr::Union(inner) => items.extend(inner),
+                    other => items.push(other),
+                }
+            }
+            Expr::Union(items)
+        }
+        "tuple" | "list" | "expression_list" => Expr::Seq(children(node)),
+        "dictionary" => {
+            let mut cursor = node.walk();
+            let pairs = node
+                .named_children(&mut cursor)
+                .filter(|p| p.kind() == "pair")
+                .filter_map(|p| {
+                    let key = expr(p.child_by_field_name("key")?, code);
+                    let value = expr(p.child_by_field_name("value")?, code);
+                    Some((key, value))
+                })
+                .collect();
+            Expr::Dict(pairs)
+        }
+        "parenthesized_expression" | "type" => match node.named_child(0) {
+            Some(inner) => expr(inner, code),
+            None => Expr::Other,
+        },
+        _ => Expr::Other,
+    }
+}
@@ -1,66 +1,620 @@
 use sqlparser::{
     ast::{
-        AlterTableOperation, ColumnDef, Expr, Ident, ObjectName, Query, SelectItem, SetExpr,
-        Statement, ViewColumnDef,
+        AlterColumnOperation, AlterTableOperation, ColumnDef, ColumnOption, DataType, Expr, Ident,
+        ObjectName, ObjectType, Query, SelectItem, SetExpr, Statement, TableConstraint,
+        ViewColumnDef,
     },
     dialect::GenericDialect,
+    keywords::Keyword,
     parser::Parser,
+    tokenizer::Token,
 };
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
+use super::{dump_keys, Column, ForeignKey, Schema, TablesAndColumns};
 use crate::dialect::Dialect;
 use crate::error::Result;
+use crate::functions::{describe_arity, FunctionSignature};
 
-pub fn load_schema(schema: &[u8], dialect: Dialect) -> Result<super::TablesAndColumns> {
-    let schema_str = String::from_utf8_lossy(schema);
+pub fn load_schema(schema: &[u8], dialect: Dialect) -> Result<Schema> {
+    let mut loaded = Schema::default();
+    apply_ddl(&mut loaded, schema, dialect)?;
+    Ok(loaded)
+}
+
+/// Apply the DDL in `ddl` on top of `loaded`, as running a migration
+/// against the database would.
+pub fn apply_ddl(loaded: &mut Schema, ddl: &[u8], dialect: Dialect) -> Result<()> {
+    let ddl_str = String::from_utf8_lossy(ddl);
 
     // Always parse with GenericDialect: schema files often mix DDL syntax
     // and `Generic` is the most permissive. Identifier folding is the part
     // that varies by dialect, not parsing.
-    let parser_dialect = GenericDialect {};
-    let statements = Parser::parse_sql(&parser_dialect, schema_str.as_ref())?;
+    let statements = parse_schema_statements(ddl_str.as_ref())?;
 
-    let mut tables: HashMap<String, HashSet<String>> = HashMap::new();
     for statement in statements {
         match statement {
-            Statement::CreateTable {
-                columns,
-                name,
-                query,
-                ..
-            } => {
-                ingest_create_table(&name, &columns, query.as_deref(), dialect, &mut tables);
+            SchemaStatement::Sql(statement) => ingest_statement(*statement, dialect, loaded),
+            SchemaStatement::CreateEnum { name, labels } => {
+                for key in name_keys(&name, dialect) {
+                    loaded.enums.insert(key, labels.clone());
+                }
             }
-            Statement::AlterTable {
-                name, operations, ..
-            } => {
-                apply_alters(&name, &operations, dialect, &mut tables);
+            SchemaStatement::AlterEnum { name, op } => {
+                for key in name_keys(&name, dialect) {
+                    if let Some(labels) = loaded.enums.get_mut(&key) {
+                        op.apply(labels);
+                    }
+                }
             }
-            Statement::CreateView {
-                name,
-                columns,
-                query,
-                ..
+            SchemaStatement::CreateFunction { name, signature } => {
+                for key in name_keys(&name, dialect) {
+                    loaded
+                        .functions
+                        .entry(key)
+                        .or_default()
+                        .push(signature.clone());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn ingest_statement(statement: Statement, dialect: Dialect, schema: &mut Schema) {
+    match statement {
+        Statement::CreateTable {
+            columns,
+            constraints,
+            name,
+            query,
+            ..
+        } => {
+            ingest_create_table(
+                &name,
+                &columns,
+                &constraints,
+                query.as_deref(),
+                dialect,
+                schema,
+            );
+        }
+        Statement::AlterTable {
+            name, operations, ..
+        } => {
+            apply_alters(&name, &operations, dialect, schema);
+        }
+        Statement::CreateView {
+            name,
+            columns,
+            query,
+            ..
+        } => {
+            ingest_create_view(&name, &columns, &query, dialect, schema);
+        }
+        Statement::Drop {
+            object_type: ObjectType::Table | ObjectType::View,
+            names,
+            ..
+        } => {
+            for name in &names {
+                for key in target_keys(name, dialect, &schema.tables) {
+                    schema.tables.remove(&key);
+                    schema.columns.remove(&key);
+                    schema.primary_keys.remove(&key);
+                    schema.unique_keys.remove(&key);
+                    schema.foreign_keys.remove(&key);
+                }
+            }
+        }
+        Statement::DropFunction { func_desc, .. } => {
+            for desc in &func_desc {
+                for key in name_keys(&desc.name, dialect) {
+                    let Some(signatures) = schema.functions.get_mut(&key) else {
+                        continue;
+                    };
+                    // `DROP FUNCTION f(int)` drops the overload taking that
+                    // many arguments; a bare name drops them all.
+                    match &desc.args {
+                        Some(args) => signatures.retain(|s| !s.accepts(args.len())),
+                        None => signatures.clear(),
+                    }
+                    if signatures.is_empty() {
+                        schema.functions.remove(&key);
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A schema-file statement. sqlparser has no AST for `CREATE TYPE … AS
+/// ENUM` or `ALTER TYPE` (it rejects both), and only parses `CREATE
+/// FUNCTION` for Postgres and Hive, so those are recognized here and
+/// everything else goes through the regular statement parser.
+enum SchemaStatement {
+    Sql(Box<Statement>),
+    CreateEnum {
+        name: ObjectName,
+        labels: Vec<String>,
+    },
+    AlterEnum {
+        name: ObjectName,
+        op: AlterEnumOp,
+    },
+    CreateFunction {
+        name: ObjectName,
+        signature: FunctionSignature,
+    },
+}
+
+enum AlterEnumOp {
+    /// `ADD VALUE [IF NOT EXISTS] 'x' [BEFORE | AFTER 'y']`
+    AddValue {
+        label: String,
+        before: Option<String>,
+        after: Option<String>,
+    },
+    /// `RENAME VALUE 'a' TO 'b'`
+    RenameValue { from: String, to: String },
+}
+
+impl AlterEnumOp {
+    fn apply(&self, labels: &mut Vec<String>) {
+        match self {
+            Self::AddValue {
+                label,
+                before,
+                after,
             } => {
-                ingest_create_view(&name, &columns, &query, dialect, &mut tables);
+                if labels.contains(label) {
+                    return;
+                }
+                let position = match (before, after) {
+                    (Some(b), _) => labels.iter().position(|l| l == b),
+                    (_, Some(a)) => labels.iter().position(|l| l == a).map(|i| i + 1),
+                    _ => None,
+                };
+                labels.insert(position.unwrap_or(labels.len()), label.clone());
+            }
+            Self::RenameValue { from, to } => {
+                if let Some(l) = labels.iter_mut().find(|l| *l == from) {
+                    *l = to.clone();
+                }
+            }
+        }
+    }
+}
+
+/// Same loop as `Parser::parse_statements`, with a detour for the enum
+/// type statements sqlparser doesn't model. Other `CREATE TYPE` / `ALTER
+/// TYPE` forms (composite types, `OWNER TO`, …) carry nothing the validator
+/// tracks and are skipped up to the next `;`.
+fn parse_schema_statements(sql: &str) -> Result<Vec<SchemaStatement>> {
+    let parser_dialect = GenericDialect {};
+    let mut parser = Parser::new(&parser_dialect).try_with_sql(sql)?;
+    let mut statements = Vec::new();
+    let mut expecting_statement_delimiter = false;
+    loop {
+        while parser.consume_token(&Token::SemiColon) {
+            expecting_statement_delimiter = false;
+        }
+        if parser.peek_token().token == Token::EOF {
+            break;
+        }
+        if expecting_statement_delimiter {
+            return Ok(parser.expected("end of statement", parser.peek_token())?);
+        }
+
+        let statement = if parser.parse_keywords(&[Keyword::CREATE, Keyword::TYPE]) {
+            parse_create_type(&mut parser)?
+        } else if parser.parse_keywords(&[Keyword::ALTER, Keyword::TYPE]) {
+            parse_alter_type(&mut parser)?
+        } else if parser.parse_keywords(&[Keyword::CREATE, Keyword::FUNCTION])
+            || parser.parse_keywords(&[
+                Keyword::CREATE,
+                Keyword::OR,
+                Keyword::REPLACE,
+                Keyword::FUNCTION,
+            ])
+        {
+            parse_create_function(&mut parser)?
+        } else {
+            Some(SchemaStatement::Sql(Box::new(parser.parse_statement()?)))
+        };
+        statements.extend(statement);
+        expecting_statement_delimiter = true;
+    }
+    Ok(statements)
+}
+
+/// Parses the remainder of `CREATE TYPE` (keywords already consumed).
+fn parse_create_type(parser: &mut Parser) -> Result<Option<SchemaStatement>> {
+    let name = parser.parse_object_name(false)?;
+    parser.expect_keyword(Keyword::AS)?;
+    if !parser.parse_keyword(Keyword::ENUM) {
+        skip_statement(parser);
+        return Ok(None);
+    }
+    parser.expect_token(&Token::LParen)?;
+    let labels = if parser.consume_token(&Token::RParen) {
+        Vec::new()
+    } else {
+        let labels = parser.parse_comma_separated(Parser::parse_literal_string)?;
+        parser.expect_token(&Token::RParen)?;
+        labels
+    };
+    Ok(Some(SchemaStatement::CreateEnum { name, labels }))
+}
+
+/// Parses the remainder of `ALTER TYPE` (keywords already consumed).
+fn parse_alter_type(parser: &mut Parser) -> Result<Option<SchemaStatement>> {
+    let name = parser.parse_object_name(false)?;
+    let op = if parser.parse_keywords(&[Keyword::ADD, Keyword::VALUE]) {
+        // Adding an existing label is a no-op here with or without
+        // `IF NOT EXISTS`.
+        let _ = parser.parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let label = parser.parse_literal_string()?;
+        let mut before = None;
+        let mut after = None;
+        if parse_word(parser, "BEFORE") {
+            before = Some(parser.parse_literal_string()?);
+        } else if parse_word(parser, "AFTER") {
+            after = Some(parser.parse_literal_string()?);
+        }
+        AlterEnumOp::AddValue {
+            label,
+            before,
+            after,
+        }
+    } else if parser.parse_keywords(&[Keyword::RENAME, Keyword::VALUE]) {
+        let from = parser.parse_literal_string()?;
+        parser.expect_keyword(Keyword::TO)?;
+        let to = parser.parse_literal_string()?;
+        AlterEnumOp::RenameValue { from, to }
+    } else {
+        skip_statement(parser);
+        return Ok(None);
+    };
+    Ok(Some(SchemaStatement::AlterEnum { name, op }))
+}
+
+/// Parses the remainder of `CREATE [OR REPLACE] FUNCTION` (keywords
+/// already consumed) into a call signature. Parameters are split on
+/// top-level commas rather than parsed, since only their count and
+/// optionality matter: `OUT` parameters aren't passed by the caller,
+/// parameters with a `DEFAULT` may be omitted, and `VARIADIC` lifts the
+/// upper bound. The body — dollar-quoted, a string, or a `BEGIN … END`
+/// block — is skipped.
+fn parse_create_function(parser: &mut Parser) -> Result<Option<SchemaStatement>> {
+    let name = parser.parse_object_name(false)?;
+    let mut signature = FunctionSignature {
+        min_args: 0,
+        max_args: Some(0),
+        return_type: None,
+        params: Some(Vec::new()),
+    };
+    if parser.consume_token(&Token::LParen) {
+        for param in split_parameters(parser) {
+            let mode = match param.first() {
+                Some(Token::Word(w)) if w.quote_style.is_none() => w.value.to_ascii_uppercase(),
+                _ => String::new(),
+            };
+            let default = param.iter().position(|t| match t {
+                Token::Word(w) => w.keyword == Keyword::DEFAULT,
+                t => *t == Token::Eq,
+            });
+            match mode.as_str() {
+                "OUT" => continue,
+                "VARIADIC" => signature.max_args = None,
+                _ => signature.max_args = signature.max_args.map(|n| n + 1),
+            }
+            if default.is_none() && mode != "VARIADIC" {
+                signature.min_args += 1;
+            }
+            let declared = &param[..default.unwrap_or(param.len())];
+            let declared = match mode.as_str() {
+                "IN" | "INOUT" | "VARIADIC" => &declared[1..],
+                _ => declared,
+            };
+            match (&mut signature.params, parameter_type(declared)) {
+                (Some(params), Some(data_type)) => params.push(data_type),
+                _ => signature.params = None,
+            }
+        }
+    } else {
+        // Hive's `CREATE FUNCTION f AS 'com.example.Udf'` declares no
+        // parameters; accept any call.
+        signature = FunctionSignature::any();
+    }
+    while !matches!(parser.peek_token().token, Token::SemiColon | Token::EOF) {
+        if parser.parse_keyword(Keyword::RETURNS) {
+            signature.return_type = if parser.parse_keyword(Keyword::TABLE) {
+                Some("record".to_string())
+            } else {
+                parser.parse_data_type().ok().map(|t| t.to_string())
+            };
+            continue;
+        }
+        if parse_word(parser, "BEGIN") {
+            skip_block(parser);
+            continue;
+        }
+        parser.next_token();
+    }
+    Ok(Some(SchemaStatement::CreateFunction { name, signature }))
+}
+
+/// The type a parameter declares, from its tokens without the mode and
+/// the default: `name TYPE`, or a bare `TYPE`.
+fn parameter_type(tokens: &[Token]) -> Option<String> {
+    let parse = |tokens: &[Token]| {
+        let dialect = GenericDialect {};
+        let mut parser = Parser::new(&dialect).with_tokens(tokens.to_vec());
+        let data_type = parser.parse_data_type().ok()?;
+        (parser.peek_token().token == Token::EOF).then(|| data_type.to_string())
+    };
+    parse(tokens).or_else(|| parse(tokens.get(1..)?))
+}
+
+/// Consume a parenthesised parameter list (opening paren already consumed)
+/// and return the tokens of each top-level parameter.
+fn split_parameters(parser: &mut Parser) -> Vec<Vec<Token>> {
+    let mut params = Vec::new();
+    let mut current = Vec::new();
+    let mut depth = 0usize;
+    loop {
+        let token = parser.next_token().token;
+        match token {
+            Token::EOF | Token::SemiColon => {
+                parser.prev_token();
+                break;
             }
+            Token::LParen => depth += 1,
+            Token::RParen if depth == 0 => break,
+            Token::RParen => depth -= 1,
+            Token::Comma if depth == 0 => {
+                params.push(std::mem::take(&mut current));
+                continue;
+            }
+            Token::Whitespace(_) => continue,
             _ => {}
         }
+        current.push(token);
+    }
+    if !current.is_empty() {
+        params.push(current);
+    }
+    params
+}
+
+/// Skip a `BEGIN … END` routine body (`BEGIN` already consumed), including
+/// the `;`-terminated statements inside it. `CASE` and nested `BEGIN` open
+/// further levels; `END IF` / `END LOOP` / … close constructs that don't.
+fn skip_block(parser: &mut Parser) {
+    let mut depth = 1usize;
+    while depth > 0 {
+        let token = parser.next_token().token;
+        let Token::Word(w) = token else {
+            if token == Token::EOF {
+                parser.prev_token();
+                return;
+            }
+            continue;
+        };
+        if w.quote_style.is_some() {
+            continue;
+        }
+        if w.value.eq_ignore_ascii_case("BEGIN") || w.keyword == Keyword::CASE {
+            depth += 1;
+        } else if w.keyword == Keyword::END {
+            let closes_unnested = ["IF", "LOOP", "WHILE", "REPEAT", "FOR"]
+                .iter()
+                .any(|kw| parse_word(parser, kw));
+            if !closes_unnested {
+                // `END CASE` closes a CASE statement; don't let its `CASE`
+                // open a new level.
+                let _ = parse_word(parser, "CASE");
+                depth -= 1;
+            }
+        }
+    }
+}
+
+/// Consume the next token if it's the (non-reserved) word `word`. `BEFORE`
+/// and `AFTER` aren't in sqlparser's keyword table, so `parse_keyword`
+/// can't match them.
+fn parse_word(parser: &mut Parser, word: &str) -> bool {
+    match parser.peek_token().token {
+        Token::Word(w) if w.quote_style.is_none() && w.value.eq_ignore_ascii_case(word) => {
+            parser.next_token();
+            true
+        }
+        _ => false,
+    }
+}
+
+fn skip_statement(parser: &mut Parser) {
+    while !matches!(parser.peek_token().token, Token::SemiColon | Token::EOF) {
+        parser.next_token();
+    }
+}
+
+/// Keys under which a declared table or type is registered: the bare name,
+/// plus the qualified form when the declaration was schema-qualified. Both
+/// are folded by the active dialect.
+pub(crate) fn name_keys(name: &ObjectName, dialect: Dialect) -> Vec<String> {
+    let Some(last) = name.0.last() else {
+        return Vec::new();
+    };
+    let mut keys = vec![fold_ident(last, dialect)];
+    if name.0.len() > 1 {
+        keys.push(qualified_key(name, dialect));
+    }
+    keys
+}
+
+/// Typed column record for a `ColumnDef`. Named (custom) types are stored
+/// folded so they match the keys in [`Schema::enums`].
+fn column_from_def(def: &ColumnDef, dialect: Dialect) -> Column {
+    let (data_type, inline_enum) = match &def.data_type {
+        DataType::Custom(name, modifiers) if modifiers.is_empty() => {
+            (qualified_key(name, dialect), None)
+        }
+        DataType::Enum(labels) => (def.data_type.to_string(), Some(labels.clone())),
+        other => (other.to_string(), None),
+    };
+    let nullable = !def.options.iter().any(|o| {
+        matches!(
+            o.option,
+            ColumnOption::NotNull
+                | ColumnOption::Unique {
+                    is_primary: true,
+                    ..
+                }
+        )
+    });
+    Column {
+        name: fold_ident(&def.name, dialect),
+        data_type,
+        inline_enum,
+        nullable,
+    }
+}
+
+/// Primary key columns declared inline (`id INT PRIMARY KEY`) or as a
+/// table constraint (`PRIMARY KEY (a, b)`), folded.
+fn primary_key(
+    columns: &[ColumnDef],
+    constraints: &[TableConstraint],
+    dialect: Dialect,
+) -> Option<Vec<String>> {
+    let from_constraint = constraints.iter().find_map(|c| match c {
+        TableConstraint::Unique {
+            columns,
+            is_primary: true,
+            ..
+        } => Some(columns.iter().map(|c| fold_ident(c, dialect)).collect()),
+        _ => None,
+    });
+    from_constraint.or_else(|| {
+        columns
+            .iter()
+            .find(|c| {
+                c.options.iter().any(|o| {
+                    matches!(
+                        o.option,
+                        ColumnOption::Unique {
+                            is_primary: true,
+                            ..
+                        }
+                    )
+                })
+            })
+            .map(|c| vec![fold_ident(&c.name, dialect)])
+    })
+}
+
+/// `UNIQUE` keys declared inline (`email TEXT UNIQUE`) or as table
+/// constraints (`UNIQUE (a, b)`), folded.
+fn unique_keys(
+    columns: &[ColumnDef],
+    constraints: &[TableConstraint],
+    dialect: Dialect,
+) -> Vec<Vec<String>> {
+    let inline = columns
+        .iter()
+        .filter(|c| {
+            c.options.iter().any(|o| {
+                matches!(
+                    o.option,
+                    ColumnOption::Unique {
+                        is_primary: false,
+                        ..
+                    }
+                )
+            })
+        })
+        .map(|c| vec![fold_ident(&c.name, dialect)]);
+    let from_constraints = constraints.iter().filter_map(|c| match c {
+        TableConstraint::Unique {
+            columns,
+            is_primary: false,
+            ..
+        } => Some(columns.iter().map(|c| fold_ident(c, dialect)).collect()),
+        _ => None,
+    });
+    inline.chain(from_constraints).collect()
+}
+
+/// Foreign keys declared inline (`user_id INT REFERENCES users (id)`) or
+/// as table constraints, folded.
+fn foreign_keys(
+    columns: &[ColumnDef],
+    constraints: &[TableConstraint],
+    dialect: Dialect,
+) -> Vec<ForeignKey> {
+    let inline = columns.iter().flat_map(|c| {
+        c.options.iter().filter_map(move |o| match &o.option {
+            ColumnOption::ForeignKey {
+                foreign_table,
+                referred_columns,
+                ..
+            } => Some(foreign_key(
+                std::slice::from_ref(&c.name),
+                foreign_table,
+                referred_columns,
+                dialect,
+            )),
+            _ => None,
+        })
+    });
+    let from_constraints = constraints.iter().filter_map(|c| match c {
+        TableConstraint::ForeignKey {
+            columns,
+            foreign_table,
+            referred_columns,
+            ..
+        } => Some(foreign_key(
+            columns,
+            foreign_table,
+            referred_columns,
+            dialect,
+        )),
+        _ => None,
+    });
+    inline.chain(from_constraints).collect()
+}
+
+fn foreign_key(
+    columns: &[Ident],
+    foreign_table: &ObjectName,
+    referred_columns: &[Ident],
+    dialect: Dialect,
+) -> ForeignKey {
+    ForeignKey {
+        columns: columns.iter().map(|c| fold_ident(c, dialect)).collect(),
+        referenced_table: foreign_table
+            .0
+            .last()
+            .map(|t| fold_ident(t, dialect))
+            .unwrap_or_default(),
+        referenced_columns: referred_columns
+            .iter()
+            .map(|c| fold_ident(c, dialect))
+            .collect(),
     }
-    Ok(tables)
 }
 
 fn ingest_create_table(
     name: &ObjectName,
     columns: &[ColumnDef],
+    constraints: &[TableConstraint],
     query: Option<&Query>,
     dialect: Dialect,
-    tables: &mut HashMap<String, HashSet<String>>,
+    schema: &mut Schema,
 ) {
-    let Some(last_ident) = name.0.last() else {
-        return;
-    };
-
     // CREATE TABLE … AS SELECT carries no explicit column list; infer the
     // names from the source query's projection. Plain CREATE TABLE uses
     // the explicit list. If both are present, the explicit list wins.
@@ -81,10 +635,46 @@ fn ingest_create_table(
     // Store the bare table name so unqualified queries resolve; if the
     // schema was declared as `schema.table`, ALSO store the fully
     // qualified form so qualified queries can be resolved strictly.
-    // Both keys are folded by the active dialect.
-    tables.insert(fold_ident(last_ident, dialect), columns_set.clone());
-    if name.0.len() > 1 {
-        tables.insert(qualified_key(name, dialect), columns_set);
+    let primary_key = primary_key(columns, constraints, dialect);
+    let typed: Vec<Column> = columns
+        .iter()
+        .map(|c| {
+            let mut col = column_from_def(c, dialect);
+            // Primary key columns are implicitly NOT NULL.
+            if primary_key
+                .as_ref()
+                .is_some_and(|pk| pk.contains(&col.name))
+            {
+                col.nullable = false;
+            }
+            col
+        })
+        .collect();
+    let unique_keys = unique_keys(columns, constraints, dialect);
+    let foreign_keys = foreign_keys(columns, constraints, dialect);
+    for key in name_keys(name, dialect) {
+        schema.tables.insert(key.clone(), columns_set.clone());
+        match &primary_key {
+            Some(pk) => schema.primary_keys.insert(key.clone(), pk.clone()),
+            None => schema.primary_keys.remove(&key),
+        };
+        if unique_keys.is_empty() {
+            schema.unique_keys.remove(&key);
+        } else {
+            schema.unique_keys.insert(key.clone(), unique_keys.clone());
+        }
+        if foreign_keys.is_empty() {
+            schema.foreign_keys.remove(&key);
+        } else {
+            schema
+                .foreign_keys
+                .insert(key.clone(), foreign_keys.clone());
+        }
+        if typed.is_empty() {
+            schema.columns.remove(&key);
+        } else {
+            schema.columns.insert(key, typed.clone());
+        }
     }
 }
 
@@ -93,11 +683,8 @@ fn ingest_create_view(
     columns: &[ViewColumnDef],
     query: &Query,
     dialect: Dialect,
-    tables: &mut HashMap<String, HashSet<String>>,
+    schema: &mut Schema,
 ) {
-    let Some(last_ident) = name.0.last() else {
-        return;
-    };
     // Explicit column list `CREATE VIEW v(a, b) AS …` overrides whatever
     // names the body projects.
     let columns_set: HashSet<String> = if !columns.is_empty() {
@@ -111,9 +698,9 @@ fn ingest_create_view(
             .map(|i| fold_ident(&i, dialect))
             .collect()
     };
-    tables.insert(fold_ident(last_ident, dialect), columns_set.clone());
-    if name.0.len() > 1 {
-        tables.insert(qualified_key(name, dialect), columns_set);
+    for key in name_keys(name, dialect) {
+        schema.tables.insert(key.clone(), columns_set.clone());
+        schema.columns.remove(&key);
     }
 }
 
@@ -192,27 +779,156 @@ fn apply_alters(
     name: &ObjectName,
     operations: &[AlterTableOperation],
     dialect: Dialect,
-    tables: &mut HashMap<String, HashSet<String>>,
+    schema: &mut Schema,
 ) {
     // ALTER TABLE updates both the bare and (if applicable) the qualified
     // twin so the two keep in sync after migrations. Unknown tables are
     // silently skipped — schema files often list ops in dependency order
     // and over-strict validation here trips real-world dumps.
-    for key in target_keys(name, dialect, tables) {
-        let Some(cols) = tables.get_mut(&key) else {
+    for mut key in target_keys(name, dialect, &schema.tables) {
+        for op in operations {
+            if let AlterTableOperation::RenameTable { table_name } = op {
+                key = rename_table(schema, &key, table_name, dialect);
+                continue;
+            }
+            let Some(cols) = schema.tables.get_mut(&key) else {
+                break;
+            };
+            apply_one(cols, schema.columns.get_mut(&key), op, dialect);
+            match op {
+                AlterTableOperation::AddConstraint(TableConstraint::Unique {
+                    columns,
+                    is_primary: true,
+                    ..
+                }) => {
+                    let pk = columns.iter().map(|c| fold_ident(c, dialect)).collect();
+                    schema.primary_keys.insert(key.clone(), pk);
+                }
+                AlterTableOperation::AddConstraint(TableConstraint::Unique {
+                    columns,
+                    is_primary: false,
+                    ..
+                }) => {
+                    let unique = columns.iter().map(|c| fold_ident(c, dialect)).collect();
+                    schema
+                        .unique_keys
+                        .entry(key.clone())
+                        .or_default()
+                        .push(unique);
+                }
+                AlterTableOperation::DropPrimaryKey => {
+                    schema.primary_keys.remove(&key);
+                }
+                AlterTableOperation::AddConstraint(TableConstraint::ForeignKey {
+                    columns,
+                    foreign_table,
+                    referred_columns,
+                    ..
+                }) => {
+                    let fk = foreign_key(columns, foreign_table, referred_columns, dialect);
+                    schema.foreign_keys.entry(key.clone()).or_default().push(fk);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// `ALTER TABLE … RENAME TO new_name` of the table at `key`: moves what
+/// the schema knows about it, and foreign keys pointing at it, to the new
+/// name. A qualified key keeps its schema unless the new name has one.
+/// Returns the new key.
+fn rename_table(schema: &mut Schema, key: &str, new_name: &ObjectName, dialect: Dialect) -> String {
+    let Some(last) = new_name.0.last() else {
+        return key.to_string();
+    };
+    let bare = fold_ident(last, dialect);
+    let new_key = match key.rsplit_once('.') {
+        Some(_) if new_name.0.len() > 1 => qualified_key(new_name, dialect),
+        Some((prefix, _)) => format!("{prefix}.{bare}"),
+        None => bare.clone(),
+    };
+    if let Some(cols) = schema.tables.remove(key) {
+        schema.tables.insert(new_key.clone(), cols);
+    }
+    if let Some(typed) = schema.columns.remove(key) {
+        schema.columns.insert(new_key.clone(), typed);
+    }
+    if let Some(pk) = schema.primary_keys.remove(key) {
+        schema.primary_keys.insert(new_key.clone(), pk);
+    }
+    if let Some(unique) = schema.unique_keys.remove(key) {
+        schema.unique_keys.insert(new_key.clone(), unique);
+    }
+    if let Some(fks) = schema.foreign_keys.remove(key) {
+        schema.foreign_keys.insert(new_key.clone(), fks);
+    }
+    let old_bare = key.rsplit('.').next().unwrap_or(key);
+    for fk in schema.foreign_keys.values_mut().flatten() {
+        if fk.referenced_table == old_bare {
+            fk.referenced_table = bare.clone();
+        }
+    }
+    new_key
+}
+
+/// A rename in an `ALTER TABLE`, with folded names; tables by their bare
+/// name, as [`crate::usage::Reference`] names them.
+pub(crate) enum Rename {
+    Table {
+        from: String,
+        to: String,
+    },
+    Column {
+        table: String,
+        from: String,
+        to: String,
+    },
+}
+
+/// The table and column renames of `ddl`, in order.
+pub(crate) fn renames(ddl: &[u8], dialect: Dialect) -> Result<Vec<Rename>> {
+    let mut renames = Vec::new();
+    for statement in parse_schema_statements(String::from_utf8_lossy(ddl).as_ref())? {
+        let SchemaStatement::Sql(statement) = statement else {
+            continue;
+        };
+        let Statement::AlterTable {
+            name, operations, ..
+        } = *statement
+        else {
+            continue;
+        };
+        let Some(mut table) = name.0.last().map(|t| fold_ident(t, dialect)) else {
             continue;
         };
         for op in operations {
-            apply_one(cols, op, dialect);
+            match op {
+                AlterTableOperation::RenameTable { table_name } => {
+                    let Some(to) = table_name.0.last().map(|t| fold_ident(t, dialect)) else {
+                        continue;
+                    };
+                    renames.push(Rename::Table {
+                        from: std::mem::replace(&mut table, to.clone()),
+                        to,
+                    });
+                }
+                AlterTableOperation::RenameColumn {
+                    old_column_name,
+                    new_column_name,
+                } => renames.push(Rename::Column {
+                    table: table.clone(),
+                    from: fold_ident(&old_column_name, dialect),
+                    to: fold_ident(&new_column_name, dialect),
+                }),
+                _ => {}
+            }
         }
     }
+    Ok(renames)
 }
 
-fn target_keys(
-    name: &ObjectName,
-    dialect: Dialect,
-    tables: &HashMap<String, HashSet<String>>,
-) -> Vec<String> {
+fn target_keys(name: &ObjectName, dialect: Dialect, tables: &TablesAndColumns) -> Vec<String> {
     let Some(last) = name.0.last() else {
         return Vec::new();
     };
@@ -240,28 +956,216 @@ fn target_keys(
         .collect()
 }
 
-fn apply_one(cols: &mut HashSet<String>, op: &AlterTableOperation, dialect: Dialect) {
+/// Apply one ALTER TABLE operation to a table's column set and, when the
+/// table was declared with typed columns, to its typed definitions too.
+fn apply_one(
+    cols: &mut HashSet<String>,
+    typed: Option<&mut Vec<Column>>,
+    op: &AlterTableOperation,
+    dialect: Dialect,
+) {
     match op {
         AlterTableOperation::AddColumn { column_def, .. } => {
             cols.insert(fold_ident(&column_def.name, dialect));
+            if let Some(typed) = typed {
+                typed.push(column_from_def(column_def, dialect));
+            }
         }
         AlterTableOperation::DropColumn { column_name, .. } => {
-            cols.remove(fold_ident(column_name, dialect).as_str());
+            let key = fold_ident(column_name, dialect);
+            cols.remove(key.as_str());
+            if let Some(typed) = typed {
+                typed.retain(|c| c.name != key);
+            }
         }
         AlterTableOperation::RenameColumn {
             old_column_name,
             new_column_name,
         } => {
-            if cols.remove(fold_ident(old_column_name, dialect).as_str()) {
-                cols.insert(fold_ident(new_column_name, dialect));
+            let old = fold_ident(old_column_name, dialect);
+            let new = fold_ident(new_column_name, dialect);
+            if cols.remove(old.as_str()) {
+                cols.insert(new.clone());
+            }
+            if let Some(col) = typed.and_then(|t| t.iter_mut().find(|c| c.name == old)) {
+                col.name = new;
+            }
+        }
+        AlterTableOperation::AlterColumn {
+            column_name,
+            op: AlterColumnOperation::SetDataType { data_type, .. },
+        } => {
+            let key = fold_ident(column_name, dialect);
+            if let Some(col) = typed.and_then(|t| t.iter_mut().find(|c| c.name == key)) {
+                let def = ColumnDef {
+                    name: column_name.clone(),
+                    data_type: data_type.clone(),
+                    collation: None,
+                    options: Vec::new(),
+                };
+                let nullable = col.nullable;
+                *col = Column {
+                    nullable,
+                    ..column_from_def(&def, dialect)
+                };
             }
         }
-        // Other ops (constraints, RLS, RENAME TABLE, …) don't change the
-        // column set we track.
+        // Other ops (constraints, RLS, …) don't change the column set we
+        // track; RENAME TABLE is handled by the caller.
         _ => {}
     }
 }
 
+/// The schema as canonical DDL that [`load_schema`] reads back into the
+/// same [`Schema`]: enum types, functions by their declared parameter
+/// types (bodies aren't kept, and defaults are written as `NULL`), tables
+/// with their keys, and tables without typed columns (views, introspected
+/// views) as views with an explicit column list. Functions whose parameter
+/// types aren't known are listed as comments. Sorted by name, so dumps of
+/// the same schema are identical.
+pub fn dump(schema: &Schema) -> String {
+    let mut out = String::new();
+    for name in dump_keys(&schema.enums) {
+        let labels: Vec<String> = schema.enums[name].iter().map(|l| literal(l)).collect();
+        out.push_str(&format!(
+            "CREATE TYPE {} AS ENUM ({});\n",
+            quote_name(name),
+            labels.join(", ")
+        ));
+    }
+    for name in dump_keys(&schema.functions) {
+        for signature in &schema.functions[name] {
+            let returns = signature
+                .return_type
+                .as_ref()
+                .map(|t| format!(" RETURNS {t}"))
+                .unwrap_or_default();
+            // Without the parameter types there's no declaration to write.
+            let Some(types) = &signature.params else {
+                out.push_str(&format!(
+                    "-- FUNCTION {}: {}{returns}, parameter types unknown\n",
+                    quote_name(name),
+                    describe_arity(signature.min_args, signature.max_args)
+                ));
+                continue;
+            };
+            let params: Vec<String> = types
+                .iter()
+                .enumerate()
+                .map(|(i, data_type)| {
+                    if signature.max_args.is_none() && i + 1 == types.len() {
+                        format!("VARIADIC {data_type}")
+                    } else if i >= signature.min_args {
+                        format!("{data_type} DEFAULT NULL")
+                    } else {
+                        data_type.clone()
+                    }
+                })
+                .collect();
+            out.push_str(&format!(
+                "CREATE FUNCTION {}({}){returns};\n",
+                quote_name(name),
+                params.join(", ")
+            ));
+        }
+    }
+    for name in dump_keys(&schema.tables) {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        let Some(columns) = schema.columns.get(name) else {
+            let mut columns: Vec<&String> = schema.tables[name].iter().collect();
+            columns.sort();
+            let nulls = vec!["NULL"; columns.len().max(1)];
+            let list = if columns.is_empty() {
+                String::new()
+            } else {
+                let names: Vec<String> = columns.iter().map(|c| quote(c)).collect();
+                format!(" ({})", names.join(", "))
+            };
+            out.push_str(&format!(
+                "CREATE VIEW {}{list} AS SELECT {};\n",
+                quote_name(name),
+                nulls.join(", ")
+            ));
+            continue;
+        };
+        let mut lines: Vec<String> = columns
+            .iter()
+            .map(|c| {
+                let data_type = if schema.enums.contains_key(&c.data_type) {
+                    quote_name(&c.data_type)
+                } else if c.data_type.is_empty() {
+                    // SQLite columns declared without a type; BLOB is the
+                    // affinity they get.
+                    "BLOB".to_string()
+                } else {
+                    c.data_type.clone()
+                };
+                let not_null = if c.nullable { "" } else { " NOT NULL" };
+                format!("    {} {data_type}{not_null}", quote(&c.name))
+            })
+            .collect();
+        if let Some(key) = schema.primary_keys.get(name) {
+            lines.push(format!("    PRIMARY KEY ({})", quote_list(key)));
+        }
+        for key in schema.unique_keys.get(name).into_iter().flatten() {
+            lines.push(format!("    UNIQUE ({})", quote_list(key)));
+        }
+        for fk in schema.foreign_keys.get(name).into_iter().flatten() {
+            let mut line = format!(
+                "    FOREIGN KEY ({}) REFERENCES {}",
+                quote_list(&fk.columns),
+                quote(&fk.referenced_table)
+            );
+            if !fk.referenced_columns.is_empty() {
+                line.push_str(&format!(" ({})", quote_list(&fk.referenced_columns)));
+            }
+            lines.push(line);
+        }
+        out.push_str(&format!(
+            "CREATE TABLE {} (\n{}\n);\n",
+            quote_name(name),
+            lines.join(",\n")
+        ));
+    }
+    out
+}
+
+/// A folded identifier as DDL that folds back to it: bare when it's plain
+/// lower case, double-quoted otherwise.
+fn quote(ident: &str) -> String {
+    let plain = ident
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && ident
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if plain {
+        ident.to_string()
+    } else {
+        format!("\"{}\"", ident.replace('"', "\"\""))
+    }
+}
+
+/// [`quote`] per part of a qualified key.
+fn quote_name(key: &str) -> String {
+    key.split('.').map(quote).collect::<Vec<_>>().join(".")
+}
+
+fn quote_list(idents: &[String]) -> String {
+    idents
+        .iter()
+        .map(|i| quote(i))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn literal(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::{HashMap, HashSet};
@@ -294,7 +1198,7 @@ mod tests {
 
         let result = load_schema(schema.as_bytes(), Dialect::default()).unwrap();
 
-        assert_eq!(result, expected_result);
+        assert_eq!(result.tables, expected_result);
     }
 
     #[test]
@@ -328,4 +1232,19 @@ mod tests {
         assert!(result.contains_key("users"));
         assert!(!result.contains_key("Users"));
     }
+
+    #[test]
+    fn enum_types_keep_declaration_order() {
+        let schema = "
+            CREATE TYPE mood AS ENUM ('sad', 'happy');
+            ALTER TYPE mood ADD VALUE 'ok' BEFORE 'happy';
+            ALTER TYPE mood ADD VALUE 'sad';
+            CREATE TABLE people (id INT, current_mood mood);
+            ALTER TABLE people RENAME COLUMN current_mood TO feeling;
+        ";
+        let result = load_schema(schema.as_bytes(), Dialect::default()).unwrap();
+        assert_eq!(result.enums["mood"], vec!["sad", "ok", "happy"]);
+        let labels = result.enum_labels("people", "feeling").unwrap();
+        assert_eq!(labels.type_name, "mood");
+    }
 }
@@ -0,0 +1,24 @@
+//! YAML schemas: sqlshield's own format, recognized by its
+//! `sqlshield_schema` key, or a dbt `schema.yml` with `sources` and
+//! `models`.
+
+use super::{dbt, native, Schema};
+use crate::dialect::Dialect;
+use crate::error::{Result, SqlShieldError};
+
+pub fn load_schema(schema: &[u8], dialect: Dialect) -> Result<Schema> {
+    let value: serde_yaml_ng::Value = serde_yaml_ng::from_slice(schema)?;
+    if value.get("sqlshield_schema").is_some() {
+        return native::load(serde_yaml_ng::from_value(value)?, dialect);
+    }
+    if dbt::KEYS.iter().any(|key| value.get(key).is_some()) {
+        return native::load(
+            dbt::translate(serde_yaml_ng::from_value(value)?, dialect),
+            dialect,
+        );
+    }
+    Err(SqlShieldError::InvalidSchema(
+        "expected a `sqlshield_schema` key or a dbt schema.yml with `sources` or `models`"
+            .to_string(),
+    ))
+}
@@ -0,0 +1,842 @@
+//! Which source files reference which tables and columns. Built from the
+//! same query extraction as validation, so a schema change can be traced to
+//! the files whose queries it could break, and [`uses`] resolves each
+//! column reference to the schema table it binds to the way validation
+//! does, telling reads from writes.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+
+use sqlparser::ast::{
+    visit_expressions, visit_relations, Assignment, Expr, GroupByExpr, Ident, JoinConstraint,
+    MergeClause, Query, Select, SelectItem, SetExpr, Statement, TableFactor, TableWithJoins, Visit,
+    Visitor,
+};
+
+use crate::dialect::Dialect;
+use crate::finder;
+use crate::schema::sql::{fold_ident, name_keys};
+use crate::schema::Schema;
+use crate::validation::clauses::select::{
+    collect_visible_relations, join_constraint, VisibleRelation,
+};
+use crate::validation::table_function::table_function;
+
+/// Table key → files whose queries name that table, and function key →
+/// files whose queries call that function. Keys are folded like schema
+/// keys: the bare name, plus the qualified one for qualified names.
+#[derive(Debug, Default)]
+pub struct TableUsageIndex {
+    files_by_table: HashMap<String, BTreeSet<PathBuf>>,
+    files_by_function: HashMap<String, BTreeSet<PathBuf>>,
+}
+
+impl TableUsageIndex {
+    /// Extract the queries of every file in `files` and record the tables
+    /// they name and the functions they call. Files that can't be read or
+    /// parsed reference nothing.
+    pub fn build(files: &[PathBuf], dialect: Dialect) -> Self {
+        use rayon::prelude::*;
+
+        type Names = BTreeSet<String>;
+        let per_file: Vec<(&PathBuf, Names, Names)> = files
+            .par_iter()
+            .map(|file_path| {
+                let parser_dialect = dialect.as_sqlparser();
+                let queries =
+                    finder::find_queries_in_file_with_dialect(file_path, parser_dialect.as_ref())
+                        .unwrap_or_default();
+                let tables = queries
+                    .iter()
+                    .flat_map(|q| referenced_tables(&q.statements, dialect))
+                    .collect();
+                let functions = queries
+                    .iter()
+                    .flat_map(|q| called_functions(&q.statements, dialect))
+                    .collect();
+                (file_path, tables, functions)
+            })
+            .collect();
+
+        let mut index = Self::default();
+        for (file_path, tables, functions) in per_file {
+            for table in tables {
+                index
+                    .files_by_table
+                    .entry(table)
+                    .or_default()
+                    .insert(file_path.clone());
+            }
+            for function in functions {
+                index
+                    .files_by_function
+                    .entry(function)
+                    .or_default()
+                    .insert(file_path.clone());
+            }
+        }
+        index
+    }
+
+    /// Files referencing any of `tables`.
+    pub fn files_using<'a, I, S>(&self, tables: I) -> BTreeSet<&Path>
+    where
+        I: IntoIterator<Item = &'a S>,
+        S: AsRef<str> + ?Sized + 'a,
+    {
+        tables
+            .into_iter()
+            .filter_map(|t| self.files_by_table.get(t.as_ref()))
+            .flatten()
+            .map(PathBuf::as_path)
+            .collect()
+    }
+
+    /// Files calling any of `functions`, in expressions or in `FROM`.
+    pub fn files_calling<'a, I, S>(&self, functions: I) -> BTreeSet<&Path>
+    where
+        I: IntoIterator<Item = &'a S>,
+        S: AsRef<str> + ?Sized + 'a,
+    {
+        functions
+            .into_iter()
+            .filter_map(|f| self.files_by_function.get(f.as_ref()))
+            .flatten()
+            .map(PathBuf::as_path)
+            .collect()
+    }
+}
+
+/// Keys of every relation named in `statements`, CTE names included.
+pub fn referenced_tables(statements: &[Statement], dialect: Dialect) -> BTreeSet<String> {
+    let mut tables = BTreeSet::new();
+    for statement in statements {
+        let _ = visit_relations(statement, |name| {
+            tables.extend(name_keys(name, dialect));
+            ControlFlow::<()>::Continue(())
+        });
+    }
+    tables
+}
+
+/// Keys of every function `statements` call, table functions in `FROM`
+/// included.
+pub fn called_functions(statements: &[Statement], dialect: Dialect) -> BTreeSet<String> {
+    struct Calls {
+        dialect: Dialect,
+        functions: BTreeSet<String>,
+    }
+
+    impl Visitor for Calls {
+        type Break = ();
+
+        fn pre_visit_table_factor(&mut self, factor: &TableFactor) -> ControlFlow<()> {
+            if let TableFactor::Table {
+                name,
+                args: Some(_),
+                ..
+            }
+            | TableFactor::Function { name, .. } = factor
+            {
+                self.functions.extend(name_keys(name, self.dialect));
+            }
+            ControlFlow::Continue(())
+        }
+
+        fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<()> {
+            if let Expr::Function(function) = expr {
+                self.functions
+                    .extend(name_keys(&function.name, self.dialect));
+            }
+            ControlFlow::Continue(())
+        }
+    }
+
+    let mut calls = Calls {
+        dialect,
+        functions: BTreeSet::new(),
+    };
+    for statement in statements {
+        let _ = statement.visit(&mut calls);
+    }
+    calls.functions
+}
+
+/// Every relation named in `statements` as written (`public.users`), CTE
+/// names included.
+pub fn relation_names(statements: &[Statement]) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    for statement in statements {
+        let _ = visit_relations(statement, |name| {
+            names.insert(name.to_string());
+            ControlFlow::<()>::Continue(())
+        });
+    }
+    names
+}
+
+/// Every column reference in `statements` as written (`u.id`, `name`),
+/// without resolving it to a table: identifiers in expressions, plus the
+/// column lists of `INSERT` and the targets of `UPDATE … SET`.
+pub fn column_references(statements: &[Statement]) -> BTreeSet<String> {
+    let dotted = |parts: &[Ident]| {
+        parts
+            .iter()
+            .map(|p| p.value.as_str())
+            .collect::<Vec<_>>()
+            .join(".")
+    };
+    let mut columns = BTreeSet::new();
+    for statement in statements {
+        match statement {
+            Statement::Insert { columns: named, .. } => {
+                columns.extend(named.iter().map(|c| c.value.clone()));
+            }
+            Statement::Update { assignments, .. } => {
+                columns.extend(assignments.iter().map(|a| dotted(&a.id)));
+            }
+            _ => {}
+        }
+        let _ = visit_expressions(statement, |expr| {
+            match expr {
+                Expr::Identifier(ident) => {
+                    columns.insert(ident.value.clone());
+                }
+                Expr::CompoundIdentifier(parts) => {
+                    columns.insert(dotted(parts));
+                }
+                _ => {}
+            }
+            ControlFlow::<()>::Continue(())
+        });
+    }
+    columns
+}
+
+/// Whether a query reads a table or column, or writes it: `INSERT`,
+/// `UPDATE` and `DELETE` targets and the columns they set are writes,
+/// everything else is a read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+impl Access {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+        }
+    }
+}
+
+/// A schema table, or one of its columns, that a query uses. Keys are
+/// folded like schema keys, tables by their bare name.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Reference {
+    pub table: String,
+    /// `None` for the table itself: it's in a `FROM`, or a DML target.
+    pub column: Option<String>,
+    pub access: Access,
+}
+
+/// A [`Reference`] made by a query in a source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Use {
+    pub path: PathBuf,
+    /// 1-based line of the query.
+    pub line: usize,
+    /// The function the query is in, if any.
+    pub function: Option<String>,
+    pub reference: Reference,
+}
+
+/// Every use of `schema`'s tables and columns by the queries in `files`,
+/// in file order. Files that can't be read or parsed use nothing.
+pub fn uses(files: &[PathBuf], schema: &Schema, dialect: Dialect) -> Vec<Use> {
+    use rayon::prelude::*;
+
+    files
+        .par_iter()
+        .flat_map_iter(|file_path| {
+            let parser_dialect = dialect.as_sqlparser();
+            let queries =
+                finder::find_queries_in_file_with_dialect(file_path, parser_dialect.as_ref())
+                    .unwrap_or_default();
+            queries
+                .into_iter()
+                .flat_map(|query| {
+                    references(&query.statements, schema, dialect)
+                        .into_iter()
+                        .map(move |reference| Use {
+                            path: file_path.clone(),
+                            line: query.line,
+                            function: query.function.clone(),
+                            reference,
+                        })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// What no use names: tables, and columns of the other tables as
+/// `(table, column)`, sorted. Candidates for dropping from the schema.
+#[derive(Debug, Default, PartialEq)]
+pub struct Unused {
+    pub tables: Vec<String>,
+    pub columns: Vec<(String, String)>,
+}
+
+pub fn unused(schema: &Schema, uses: &[Use]) -> Unused {
+    let used_tables: HashSet<&str> = uses.iter().map(|u| u.reference.table.as_str()).collect();
+    let used_columns: HashSet<(&str, &str)> = uses
+        .iter()
+        .filter_map(|u| Some((u.reference.table.as_str(), u.reference.column.as_deref()?)))
+        .collect();
+    let mut unused = Unused::default();
+    for (table, columns) in schema.iter() {
+        // Qualified tables are also stored under their bare name, which is
+        // the one references use.
+        if table
+            .rsplit_once('.')
+            .is_some_and(|(_, bare)| schema.contains_key(bare))
+        {
+            continue;
+        }
+        if !used_tables.contains(table.as_str()) {
+            unused.tables.push(table.clone());
+            continue;
+        }
+        for column in columns {
+            if !used_columns.contains(&(table.as_str(), column.as_str())) {
+                unused.columns.push((table.clone(), column.clone()));
+            }
+        }
+    }
+    unused.tables.sort();
+    unused.columns.sort();
+    unused
+}
+
+/// The schema tables and columns `statements` read and write. Column
+/// references are bound like validation binds them: a qualified one to the
+/// relation with that alias or name, an unqualified one to the only
+/// visible table that has the column, falling back to enclosing queries
+/// for correlated subqueries. Ambiguous and unknown references, and ones
+/// through CTEs or derived tables, bind to nothing; `*` uses every column.
+pub fn references(
+    statements: &[Statement],
+    schema: &Schema,
+    dialect: Dialect,
+) -> BTreeSet<Reference> {
+    let mut collector = Collector {
+        schema,
+        dialect,
+        found: BTreeSet::new(),
+    };
+    for statement in statements {
+        collector.statement(statement, &[], &HashSet::new());
+    }
+    collector.found
+}
+
+/// The relations visible at each level of nesting, innermost last.
+type Scopes<'q, 'a> = [&'q [VisibleRelation<'a>]];
+
+struct Collector<'s> {
+    schema: &'s Schema,
+    dialect: Dialect,
+    found: BTreeSet<Reference>,
+}
+
+impl Collector<'_> {
+    fn add(&mut self, table: &str, column: Option<&str>, access: Access) {
+        self.found.insert(Reference {
+            table: table.to_string(),
+            column: column.map(str::to_string),
+            access,
+        });
+    }
+
+    /// Schema key of the table `name` names, unless a CTE shadows it.
+    fn table_key(&self, name: &Ident, ctes: &HashSet<String>) -> Option<String> {
+        let key = fold_ident(name, self.dialect);
+        (!ctes.contains(&key) && self.schema.contains_key(&key)).then_some(key)
+    }
+
+    fn relation_key(&self, rel: &VisibleRelation<'_>, ctes: &HashSet<String>) -> Option<String> {
+        if rel.is_table_function() {
+            return None;
+        }
+        self.table_key(rel.name, ctes)
+    }
+
+    fn statement(&mut self, statement: &Statement, scopes: &Scopes, ctes: &HashSet<String>) {
+        match statement {
+            Statement::Query(query) => self.query(query, scopes, ctes),
+            Statement::Insert {
+                table_name,
+                columns,
+                source,
+                ..
+            } => {
+                if let Some(key) = table_name.0.last().and_then(|n| self.table_key(n, ctes)) {
+                    self.write_columns(&key, columns);
+                }
+                if let Some(source) = source {
+                    self.query(source, scopes, ctes);
+                }
+            }
+            Statement::Update {
+                table,
+                assignments,
+                from,
+                selection,
+                returning,
+            } => {
+                let mut rels = collect_visible_relations(std::slice::from_ref(table));
+                if let Some(from) = from {
+                    rels.extend(collect_visible_relations(std::slice::from_ref(from)));
+                }
+                let mut inner: Vec<&[VisibleRelation]> = scopes.to_vec();
+                inner.push(&rels);
+                let target = &table.relation;
+                if let Some(key) = self.factor_key(target, ctes) {
+                    self.add(&key, None, Access::Write);
+                    self.assign(&key, assignments);
+                }
+                self.table_with_joins(table, &inner, ctes, Some(target));
+                if let Some(from) = from {
+                    self.table_with_joins(from, &inner, ctes, None);
+                }
+                let exprs = assignments
+                    .iter()
+                    .map(|a| &a.value)
+                    .chain(selection)
+                    .chain(returning.iter().flatten().filter_map(select_item_expr));
+                for expr in exprs {
+                    self.expr(expr, &inner, ctes);
+                }
+            }
+            Statement::Delete {
+                tables,
+                from,
+                using,
+                selection,
+                returning,
+                ..
+            } => {
+                let mut rels = collect_visible_relations(from);
+                rels.extend(collect_visible_relations(
+                    using.as_deref().unwrap_or_default(),
+                ));
+                let mut inner: Vec<&[VisibleRelation]> = scopes.to_vec();
+                inner.push(&rels);
+                // `DELETE t1 FROM t1 JOIN t2` (MySQL) names its targets;
+                // otherwise they are the tables after `FROM`.
+                let targets: Vec<&TableFactor> = if tables.is_empty() {
+                    from.iter().map(|t| &t.relation).collect()
+                } else {
+                    Vec::new()
+                };
+                for name in tables {
+                    let Some(last) = name.0.last() else { continue };
+                    let qualifier = fold_ident(last, self.dialect);
+                    let key = rels
+                        .iter()
+                        .find(|r| fold_ident(r.qualifier(), self.dialect) == qualifier)
+                        .and_then(|r| self.relation_key(r, ctes));
+                    if let Some(key) = key {
+                        self.add(&key, None, Access::Write);
+                    }
+                }
+                for target in &targets {
+                    if let Some(key) = self.factor_key(target, ctes) {
+                        self.add(&key, None, Access::Write);
+                    }
+                }
+                for twj in from.iter().chain(using.iter().flatten()) {
+                    let target = targets
+                        .iter()
+                        .copied()
+                        .find(|t| std::ptr::eq(*t, &twj.relation));
+                    self.table_with_joins(twj, &inner, ctes, target);
+                }
+                let exprs = selection
+                    .iter()
+                    .chain(returning.iter().flatten().filter_map(select_item_expr));
+                for expr in exprs {
+                    self.expr(expr, &inner, ctes);
+                }
+            }
+            Statement::Merge {
+                table,
+                source,
+                on,
+                clauses,
+                ..
+            } => {
+                let joined = [table, source].map(|factor| TableWithJoins {
+                    relation: factor.clone(),
+                    joins: Vec::new(),
+                });
+                let rels = collect_visible_relations(&joined);
+                let mut inner: Vec<&[VisibleRelation]> = scopes.to_vec();
+                inner.push(&rels);
+                let key = self.factor_key(table, ctes);
+                if let Some(key) = &key {
+                    self.add(key, None, Access::Write);
+                }
+                self.factor(source, &inner, ctes, None);
+                self.expr(on, &inner, ctes);
+                for clause in clauses {
+                    match clause {
+                        MergeClause::MatchedUpdate {
+                            predicate,
+                            assignments,
+                        } => {
+                            if let Some(key) = &key {
+                                self.assign(key, assignments);
+                            }
+                            for expr in predicate.iter().chain(assignments.iter().map(|a| &a.value))
+                            {
+                                self.expr(expr, &inner, ctes);
+                            }
+                        }
+                        MergeClause::MatchedDelete(predicate) => {
+                            if let Some(expr) = predicate {
+                                self.expr(expr, &inner, ctes);
+                            }
+                        }
+                        MergeClause::NotMatched {
+                            predicate,
+                            columns,
+                            values,
+                        } => {
+                            if let Some(key) = &key {
+                                self.write_columns(key, columns);
+                            }
+                            for expr in predicate.iter().chain(values.rows.iter().flatten()) {
+                                self.expr(expr, &inner, ctes);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Write `table`, and `columns` of it, or all of them for an insert
+    /// without a column list.
+    fn write_columns(&mut self, table: &str, columns: &[Ident]) {
+        self.add(table, None, Access::Write);
+        let known = &self.schema[table];
+        let written: Vec<String> = if columns.is_empty() {
+            known.iter().cloned().collect()
+        } else {
+            columns
+                .iter()
+                .map(|c| fold_ident(c, self.dialect))
+                .filter(|c| known.contains(c))
+                .collect()
+        };
+        for column in written {
+            self.add(table, Some(&column), Access::Write);
+        }
+    }
+
+    fn assign(&mut self, table: &str, assignments: &[Assignment]) {
+        for assignment in assignments {
+            let Some(column) = assignment.id.last() else {
+                continue;
+            };
+            let column = fold_ident(column, self.dialect);
+            if self.schema[table].contains(&column) {
+                self.add(table, Some(&column), Access::Write);
+            }
+        }
+    }
+
+    fn factor_key(&self, factor: &TableFactor, ctes: &HashSet<String>) -> Option<String> {
+        match factor {
+            TableFactor::Table {
+                name, args: None, ..
+            } => self.table_key(name.0.last()?, ctes),
+            _ => None,
+        }
+    }
+
+    fn query(&mut self, query: &Query, scopes: &Scopes, ctes: &HashSet<String>) {
+        let mut ctes = ctes.clone();
+        if let Some(with) = &query.with {
+            for cte in &with.cte_tables {
+                let name = fold_ident(&cte.alias.name, self.dialect);
+                if with.recursive {
+                    ctes.insert(name.clone());
+                }
+                self.query(&cte.query, scopes, &ctes);
+                ctes.insert(name);
+            }
+        }
+        let order_by: Vec<&Expr> = query.order_by.iter().map(|o| &o.expr).collect();
+        self.set_expr(&query.body, scopes, &ctes, &order_by);
+    }
+
+    fn set_expr(
+        &mut self,
+        body: &SetExpr,
+        scopes: &Scopes,
+        ctes: &HashSet<String>,
+        order_by: &[&Expr],
+    ) {
+        match body {
+            SetExpr::Select(select) => self.select(select, scopes, ctes, order_by),
+            SetExpr::Query(query) => self.query(query, scopes, ctes),
+            SetExpr::SetOperation { left, right, .. } => {
+                self.set_expr(left, scopes, ctes, &[]);
+                self.set_expr(right, scopes, ctes, &[]);
+            }
+            SetExpr::Values(values) => {
+                for expr in values.rows.iter().flatten() {
+                    self.expr(expr, scopes, ctes);
+                }
+            }
+            SetExpr::Insert(statement) | SetExpr::Update(statement) => {
+                self.statement(statement, scopes, ctes);
+            }
+            _ => {}
+        }
+    }
+
+    fn select(
+        &mut self,
+        select: &Select,
+        scopes: &Scopes,
+        ctes: &HashSet<String>,
+        order_by: &[&Expr],
+    ) {
+        let rels = collect_visible_relations(&select.from);
+        let mut inner: Vec<&[VisibleRelation]> = scopes.to_vec();
+        inner.push(&rels);
+        for twj in &select.from {
+            self.table_with_joins(twj, &inner, ctes, None);
+        }
+
+        let mut exprs: Vec<&Expr> = Vec::new();
+        for item in &select.projection {
+            match item {
+                SelectItem::Wildcard(_) => {
+                    for rel in &rels {
+                        self.all_columns(rel, ctes);
+                    }
+                }
+                SelectItem::QualifiedWildcard(name, _) => {
+                    let Some(last) = name.0.last() else { continue };
+                    let qualifier = fold_ident(last, self.dialect);
+                    for rel in &rels {
+                        if fold_ident(rel.qualifier(), self.dialect) == qualifier {
+                            self.all_columns(rel, ctes);
+                        }
+                    }
+                }
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                    exprs.push(expr);
+                }
+            }
+        }
+        exprs.extend(&select.selection);
+        exprs.extend(&select.having);
+        exprs.extend(&select.qualify);
+        if let GroupByExpr::Expressions(group_by) = &select.group_by {
+            exprs.extend(group_by);
+        }
+        for window in &select.named_window {
+            exprs.extend(&window.1.partition_by);
+            exprs.extend(window.1.order_by.iter().map(|o| &o.expr));
+        }
+        exprs.extend(order_by);
+        for expr in exprs {
+            self.expr(expr, &inner, ctes);
+        }
+    }
+
+    fn all_columns(&mut self, rel: &VisibleRelation<'_>, ctes: &HashSet<String>) {
+        let Some(key) = self.relation_key(rel, ctes) else {
+            return;
+        };
+        let columns: Vec<String> = self.schema[&key].iter().cloned().collect();
+        for column in columns {
+            self.add(&key, Some(&column), Access::Read);
+        }
+    }
+
+    /// Read the tables of a `FROM` item and its join conditions; `target`
+    /// is a DML target among them, already recorded as a write.
+    fn table_with_joins(
+        &mut self,
+        twj: &TableWithJoins,
+        scopes: &Scopes,
+        ctes: &HashSet<String>,
+        target: Option<&TableFactor>,
+    ) {
+        self.factor(&twj.relation, scopes, ctes, target);
+        for join in &twj.joins {
+            self.factor(&join.relation, scopes, ctes, target);
+            match join_constraint(&join.join_operator) {
+                Some(JoinConstraint::On(expr)) => self.expr(expr, scopes, ctes),
+                // `USING (col)` reads the column of every table joined on it.
+                Some(JoinConstraint::Using(columns)) => {
+                    let rels = scopes.last().copied().unwrap_or_default();
+                    for column in columns {
+                        let column = fold_ident(column, self.dialect);
+                        for rel in rels {
+                            let Some(key) = self.relation_key(rel, ctes) else {
+                                continue;
+                            };
+                            if self.schema[&key].contains(&column) {
+                                self.add(&key, Some(&column), Access::Read);
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn factor(
+        &mut self,
+        factor: &TableFactor,
+        scopes: &Scopes,
+        ctes: &HashSet<String>,
+        target: Option<&TableFactor>,
+    ) {
+        if let Some(tf) = table_function(factor) {
+            for arg in tf.args {
+                self.expr(arg, scopes, ctes);
+            }
+            return;
+        }
+        match factor {
+            TableFactor::Table { .. } => {
+                if target.is_some_and(|t| std::ptr::eq(t, factor)) {
+                    return;
+                }
+                if let Some(key) = self.factor_key(factor, ctes) {
+                    self.add(&key, None, Access::Read);
+                }
+            }
+            TableFactor::Derived { subquery, .. } => self.query(subquery, scopes, ctes),
+            TableFactor::NestedJoin {
+                table_with_joins, ..
+            } => self.table_with_joins(table_with_joins, scopes, ctes, target),
+            _ => {}
+        }
+    }
+
+    /// Read the columns `expr` references. Subqueries in it get a scope of
+    /// their own, nested in `scopes`.
+    fn expr(&mut self, expr: &Expr, scopes: &Scopes, ctes: &HashSet<String>) {
+        let _ = expr.visit(&mut ExprVisitor {
+            collector: self,
+            scopes,
+            ctes,
+            depth: 0,
+        });
+    }
+
+    fn column(&mut self, expr: &Expr, scopes: &Scopes, ctes: &HashSet<String>) {
+        let (qualifier, column) = match expr {
+            Expr::Identifier(ident) => (None, ident),
+            Expr::CompoundIdentifier(idents) if idents.len() >= 2 => {
+                (Some(&idents[idents.len() - 2]), &idents[idents.len() - 1])
+            }
+            _ => return,
+        };
+        let column = fold_ident(column, self.dialect);
+        for scope in scopes.iter().rev() {
+            match qualifier {
+                Some(qualifier) => {
+                    let qualifier = fold_ident(qualifier, self.dialect);
+                    let Some(rel) = scope
+                        .iter()
+                        .find(|r| fold_ident(r.qualifier(), self.dialect) == qualifier)
+                    else {
+                        continue;
+                    };
+                    if let Some(key) = self.relation_key(rel, ctes) {
+                        if self.schema[&key].contains(&column) {
+                            self.add(&key, Some(&column), Access::Read);
+                        }
+                    }
+                    return;
+                }
+                None => {
+                    let keys: Vec<Option<String>> =
+                        scope.iter().map(|r| self.relation_key(r, ctes)).collect();
+                    let bound: Vec<&String> = keys
+                        .iter()
+                        .flatten()
+                        .filter(|key| self.schema[*key].contains(&column))
+                        .collect();
+                    match bound.as_slice() {
+                        [key] => {
+                            let key = (*key).clone();
+                            self.add(&key, Some(&column), Access::Read);
+                            return;
+                        }
+                        // Only look outward past relations whose columns
+                        // are all known.
+                        [] if keys.iter().all(Option::is_some) => continue,
+                        _ => return,
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn select_item_expr(item: &SelectItem) -> Option<&Expr> {
+    match item {
+        SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => Some(expr),
+        _ => None,
+    }
+}
+
+/// Visits the column references of one expression, handing each
+/// subquery in it to the collector as a query of its own.
+struct ExprVisitor<'c, 's, 'q, 'a> {
+    collector: &'c mut Collector<'s>,
+    scopes: &'q Scopes<'q, 'a>,
+    ctes: &'q HashSet<String>,
+    /// How many subqueries deep the visit is; only depth 0 is ours.
+    depth: usize,
+}
+
+impl Visitor for ExprVisitor<'_, '_, '_, '_> {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, query: &Query) -> ControlFlow<()> {
+        if self.depth == 0 {
+            self.collector.query(query, self.scopes, self.ctes);
+        }
+        self.depth += 1;
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, _query: &Query) -> ControlFlow<()> {
+        self.depth -= 1;
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<()> {
+        if self.depth == 0 {
+            self.collector.column(expr, self.scopes, self.ctes);
+        }
+        ControlFlow::Continue(())
+    }
+}
@@ -12,11 +12,14 @@ use super::Extras;
 /// Identifier comparisons honor the dialect's folding rules.
 pub fn is_relation_in_schema(
     relation: &sqlparser::ast::TableFactor,
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &Extras,
 ) -> Option<String> {
     match relation {
+        // `FROM f(...)` parses as a table with arguments: it's a table-valued
+        // function, not a table reference.
+        sqlparser::ast::TableFactor::Table { args: Some(_), .. } => None,
         sqlparser::ast::TableFactor::Table { name, .. } => {
             let display = name
                 .0
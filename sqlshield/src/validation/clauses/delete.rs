@@ -5,8 +5,8 @@ use std::collections::HashSet;
 use sqlparser::ast::{Expr, TableWithJoins};
 
 use crate::dialect::Dialect;
-use crate::schema::TablesAndColumns;
-use crate::validation::{asserts, Extras};
+use crate::schema::Schema;
+use crate::validation::{asserts, Extras, ValidationError};
 
 use super::select::{collect_visible_relations, validate_expr_column_refs};
 
@@ -14,10 +14,10 @@ pub(crate) fn validate_delete(
     from: &[TableWithJoins],
     using: Option<&[TableWithJoins]>,
     selection: Option<&Expr>,
-    schema: &TablesAndColumns,
+    schema: &Schema,
     dialect: Dialect,
     parent_extras: &Extras,
-) -> Vec<String> {
+) -> Vec<ValidationError> {
     let mut errors = Vec::new();
     let extras: Extras = parent_extras.clone();
 
@@ -25,13 +25,13 @@ pub(crate) fn validate_delete(
         if let Some(name) =
             asserts::is_relation_in_schema(&table.relation, schema, dialect, &extras)
         {
-            errors.push(format!("Table `{name}` not found in schema nor subqueries"));
+            errors.push(ValidationError::unknown_table(&name));
         }
         for join in &table.joins {
             if let Some(name) =
                 asserts::is_relation_in_schema(&join.relation, schema, dialect, &extras)
             {
-                errors.push(format!("Table `{name}` not found in schema nor subqueries"));
+                errors.push(ValidationError::unknown_table(&name));
             }
         }
     }
@@ -41,7 +41,7 @@ pub(crate) fn validate_delete(
             if let Some(name) =
                 asserts::is_relation_in_schema(&table.relation, schema, dialect, &extras)
             {
-                errors.push(format!("Table `{name}` not found in schema nor subqueries"));
+                errors.push(ValidationError::unknown_table(&name));
             }
         }
     }
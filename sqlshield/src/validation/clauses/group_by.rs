@@ -0,0 +1,330 @@
+//! GROUP BY / aggregate correctness. In an aggregated SELECT (one with
+//! `GROUP BY`, or with an aggregate in the projection or `HAVING`), every
+//! column referenced in the projection, `HAVING`, and `ORDER BY` outside
+//! an aggregate must be grouped — or, as in Postgres, belong to a table
+//! whose primary key is grouped. Aggregates are never allowed in `WHERE`
+//! or inside another aggregate.
+//!
+//! Only columns that bind to a relation of the current SELECT are judged;
+//! outer (correlated) references and columns of tables the schema doesn't
+//! know pass silently.
+
+use std::collections::HashSet;
+
+use sqlparser::ast::{Expr, Function, GroupByExpr, Ident, Select, SelectItem, Value, WindowType};
+
+use crate::dialect::Dialect;
+use crate::functions;
+use crate::lint::{MISPLACED_AGGREGATE, UNGROUPED_COLUMN};
+use crate::schema::{self, sql::fold_ident};
+use crate::validation::clauses::select::{column_in_relation, VisibleRelation};
+use crate::validation::expr::{children, function_args};
+use crate::validation::{Extras, ValidationError};
+
+/// The SELECT under check, with its GROUP BY resolved once up front.
+pub(crate) struct GroupScope<'a> {
+    select: &'a Select,
+    relations: &'a [VisibleRelation<'a>],
+    schema: &'a schema::Schema,
+    dialect: Dialect,
+    extras: &'a Extras,
+    /// Grouped column references as `(owning relation qualifier, column)`.
+    /// The owner is `None` when the reference couldn't be bound.
+    grouped_columns: Vec<(Option<String>, String)>,
+    /// Grouped expressions that aren't plain column references, matched
+    /// structurally (`GROUP BY date_trunc('day', ts)`).
+    grouped_exprs: Vec<&'a Expr>,
+    /// Projection aliases (folded); `HAVING` and `ORDER BY` may use them.
+    aliases: HashSet<String>,
+    aggregated: bool,
+}
+
+impl<'a> GroupScope<'a> {
+    pub(crate) fn new(
+        select: &'a Select,
+        relations: &'a [VisibleRelation<'a>],
+        schema: &'a schema::Schema,
+        dialect: Dialect,
+        extras: &'a Extras,
+    ) -> Self {
+        let mut scope = Self {
+            select,
+            relations,
+            schema,
+            dialect,
+            extras,
+            grouped_columns: Vec::new(),
+            grouped_exprs: Vec::new(),
+            aliases: HashSet::new(),
+            aggregated: false,
+        };
+        for item in &select.projection {
+            if let SelectItem::ExprWithAlias { alias, .. } = item {
+                scope.aliases.insert(fold_ident(alias, dialect));
+            }
+        }
+
+        let group_exprs: &[Expr] = match &select.group_by {
+            GroupByExpr::Expressions(exprs) => exprs,
+            // `GROUP BY ALL` groups by every non-aggregate projection item.
+            GroupByExpr::All => return scope,
+        };
+        for expr in group_exprs {
+            scope.add_grouped(expr);
+        }
+        scope.aggregated = !group_exprs.is_empty()
+            || select.projection.iter().any(|item| match item {
+                SelectItem::UnnamedExpr(e) | SelectItem::ExprWithAlias { expr: e, .. } => {
+                    find_aggregate(e).is_some()
+                }
+                _ => false,
+            })
+            || select
+                .having
+                .as_ref()
+                .is_some_and(|h| find_aggregate(h).is_some());
+        scope
+    }
+
+    fn add_grouped(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Rollup(sets) | Expr::Cube(sets) | Expr::GroupingSets(sets) => {
+                for e in sets.iter().flatten() {
+                    self.add_grouped(e);
+                }
+            }
+            Expr::Nested(inner) => self.add_grouped(inner),
+            // `GROUP BY 1`: the first projection item.
+            Expr::Value(Value::Number(n, _)) => {
+                let item = n
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| self.select.projection.get(i.checked_sub(1)?));
+                if let Some(
+                    SelectItem::UnnamedExpr(e) | SelectItem::ExprWithAlias { expr: e, .. },
+                ) = item
+                {
+                    self.add_grouped(e);
+                }
+            }
+            _ => {
+                if let Some((qualifier, col)) = column_ref(expr) {
+                    // `GROUP BY alias` groups by the aliased expression;
+                    // input columns of the same name take precedence in
+                    // Postgres, so keep the column reading too.
+                    if qualifier.is_none() {
+                        if let Some(aliased) = self.aliased_expr(col) {
+                            self.add_grouped(aliased);
+                        }
+                    }
+                    let owner = self.owner(qualifier, col);
+                    self.grouped_columns
+                        .push((owner, fold_ident(col, self.dialect)));
+                } else {
+                    self.grouped_exprs.push(expr);
+                }
+            }
+        }
+    }
+
+    fn aliased_expr(&self, name: &Ident) -> Option<&'a Expr> {
+        let key = fold_ident(name, self.dialect);
+        self.select.projection.iter().find_map(|item| match item {
+            SelectItem::ExprWithAlias { expr, alias } if fold_ident(alias, self.dialect) == key => {
+                Some(expr)
+            }
+            _ => None,
+        })
+    }
+
+    /// Qualifier key of the local relation `col` binds to, if any.
+    fn owner(&self, qualifier: Option<&Ident>, col: &Ident) -> Option<String> {
+        if let Some(q) = qualifier {
+            let key = fold_ident(q, self.dialect);
+            return self
+                .relations
+                .iter()
+                .any(|r| fold_ident(r.qualifier(), self.dialect) == key)
+                .then_some(key);
+        }
+        let mut owners = self.relations.iter().filter(|rel| {
+            column_in_relation(col, rel, self.schema, self.dialect, self.extras) == Some(true)
+        });
+        match (owners.next(), owners.next()) {
+            (Some(rel), None) => Some(fold_ident(rel.qualifier(), self.dialect)),
+            _ => None,
+        }
+    }
+
+    fn is_grouped(&self, owner: &str, col_key: &str) -> bool {
+        if self.groups_column(owner, col_key) {
+            return true;
+        }
+        // Functional dependency: grouping by a table's primary key makes
+        // every other column of that table single-valued per group.
+        let Some(rel) = self
+            .relations
+            .iter()
+            .find(|r| fold_ident(r.qualifier(), self.dialect) == owner)
+        else {
+            return false;
+        };
+        self.schema
+            .primary_keys
+            .get(&fold_ident(rel.name, self.dialect))
+            .is_some_and(|pk| !pk.is_empty() && pk.iter().all(|c| self.groups_column(owner, c)))
+    }
+
+    fn groups_column(&self, owner: &str, col_key: &str) -> bool {
+        self.grouped_columns
+            .iter()
+            .any(|(o, c)| c == col_key && o.as_deref().map_or(true, |o| o == owner))
+    }
+
+    /// Whether non-aggregated, ungrouped columns are errors for the target
+    /// database. SQLite always allows them (bare columns); MySQL does when
+    /// `ONLY_FULL_GROUP_BY` is off.
+    fn full_group_by(&self) -> bool {
+        match self.dialect {
+            Dialect::Sqlite => false,
+            Dialect::MySql => self.schema.settings.only_full_group_by,
+            _ => true,
+        }
+    }
+
+    /// Check the projection, `HAVING`, and `WHERE` of the SELECT.
+    pub(crate) fn check_select(&self, errors: &mut Vec<ValidationError>) {
+        if let Some(selection) = &self.select.selection {
+            if let Some(f) = find_aggregate(selection) {
+                errors.push(ValidationError::new(
+                    MISPLACED_AGGREGATE,
+                    format!("Aggregate function `{}` is not allowed in WHERE", f.name),
+                ));
+            }
+        }
+        for item in &self.select.projection {
+            if let SelectItem::UnnamedExpr(e) | SelectItem::ExprWithAlias { expr: e, .. } = item {
+                self.check_expr(e, "SELECT", false, errors);
+            }
+        }
+        if let Some(having) = &self.select.having {
+            self.check_expr(having, "HAVING", true, errors);
+        }
+    }
+
+    /// Check `ORDER BY` expressions of the query this SELECT heads.
+    pub(crate) fn check_order_by(&self, exprs: &[&Expr], errors: &mut Vec<ValidationError>) {
+        for expr in exprs {
+            self.check_expr(expr, "ORDER BY", true, errors);
+        }
+    }
+
+    fn check_expr(
+        &self,
+        expr: &Expr,
+        clause: &str,
+        aliases_visible: bool,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        if self.grouped_exprs.contains(&expr) {
+            return;
+        }
+        if let Some((qualifier, col)) = column_ref(expr) {
+            if !self.aggregated || !self.full_group_by() {
+                return;
+            }
+            if aliases_visible
+                && qualifier.is_none()
+                && self.aliases.contains(&fold_ident(col, self.dialect))
+            {
+                return;
+            }
+            let Some(owner) = self.owner(qualifier, col) else {
+                return;
+            };
+            if !self.is_grouped(&owner, &fold_ident(col, self.dialect)) {
+                errors.push(ValidationError::new(
+                    UNGROUPED_COLUMN,
+                    format!(
+                        "Column `{expr}` in {clause} must appear in GROUP BY or be used in an aggregate function"
+                    ),
+                ));
+            }
+            return;
+        }
+        match expr {
+            Expr::Function(f) if is_aggregate_call(f) => {
+                for arg in function_args(f) {
+                    if let Some(inner) = find_aggregate(arg) {
+                        errors.push(ValidationError::new(
+                            MISPLACED_AGGREGATE,
+                            format!(
+                                "Aggregate function `{}` cannot be nested inside aggregate `{}`",
+                                inner.name, f.name
+                            ),
+                        ));
+                    }
+                }
+            }
+            // Arguments of functions we know nothing about might be
+            // aggregated (user-defined aggregates); don't judge them.
+            Expr::Function(f) if !self.is_known_function(f) => {}
+            Expr::Function(f) => {
+                for arg in function_args(f) {
+                    self.check_expr(arg, clause, aliases_visible, errors);
+                }
+                if let Some(WindowType::WindowSpec(spec)) = &f.over {
+                    for e in &spec.partition_by {
+                        self.check_expr(e, clause, aliases_visible, errors);
+                    }
+                    for ob in &spec.order_by {
+                        self.check_expr(&ob.expr, clause, aliases_visible, errors);
+                    }
+                }
+            }
+            _ => {
+                for child in children(expr) {
+                    self.check_expr(child, clause, aliases_visible, errors);
+                }
+            }
+        }
+    }
+
+    fn is_known_function(&self, f: &Function) -> bool {
+        let [name] = f.name.0.as_slice() else {
+            return false;
+        };
+        !self
+            .schema
+            .functions
+            .contains_key(&fold_ident(name, self.dialect))
+            && functions::builtin(self.dialect, &name.value.to_lowercase()).is_some()
+    }
+}
+
+fn column_ref(expr: &Expr) -> Option<(Option<&Ident>, &Ident)> {
+    match expr {
+        Expr::Identifier(ident) => Some((None, ident)),
+        Expr::CompoundIdentifier(idents) if idents.len() >= 2 => {
+            Some((Some(&idents[idents.len() - 2]), &idents[idents.len() - 1]))
+        }
+        _ => None,
+    }
+}
+
+fn is_aggregate_call(f: &Function) -> bool {
+    f.over.is_none()
+        && matches!(f.name.0.as_slice(), [name] if functions::is_aggregate(&name.value.to_lowercase()))
+}
+
+/// First aggregate call in `expr`, not descending into subqueries (they
+/// aggregate in their own scope).
+fn find_aggregate(expr: &Expr) -> Option<&Function> {
+    if let Expr::Function(f) = expr {
+        if is_aggregate_call(f) {
+            return Some(f);
+        }
+        return function_args(f).find_map(find_aggregate);
+    }
+    children(expr).into_iter().find_map(find_aggregate)
+}
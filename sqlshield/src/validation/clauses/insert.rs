@@ -1,38 +1,84 @@
-//! INSERT INTO ... validation: target table and explicit column list.
+//! INSERT INTO ... validation: target table, explicit column list, and
+//! enum labels in `VALUES` rows.
 
-use sqlparser::ast::{Ident, ObjectName};
+use sqlparser::ast::{Ident, ObjectName, Query, SetExpr};
 
 use crate::dialect::Dialect;
 use crate::schema::sql::fold_ident;
-use crate::schema::TablesAndColumns;
+use crate::schema::Schema;
+use crate::validation::{enums, ValidationError};
 
-use super::table_ref::{display_name, resolve_table_columns};
+use super::table_ref::{display_name, resolve_table_columns, table_key};
 
 pub(crate) fn validate_insert(
     table_name: &ObjectName,
     columns: &[Ident],
-    schema: &TablesAndColumns,
+    source: Option<&Query>,
+    schema: &Schema,
     dialect: Dialect,
-) -> Vec<String> {
+) -> Vec<ValidationError> {
     let mut errors = Vec::new();
 
     let Some(cols) = resolve_table_columns(table_name, schema, dialect) else {
-        errors.push(format!(
-            "Table `{}` not found in schema nor subqueries",
-            display_name(table_name)
-        ));
+        errors.push(ValidationError::unknown_table(&display_name(table_name)));
         return errors;
     };
 
     for col in columns {
         if !cols.contains(&fold_ident(col, dialect)) {
-            errors.push(format!(
-                "Column `{}` not found in table `{}`",
-                col.value,
-                display_name(table_name)
+            errors.push(ValidationError::unknown_column(
+                &col.value,
+                &display_name(table_name),
             ));
         }
     }
 
+    if let Some(source) = source {
+        validate_values_labels(table_name, columns, source, schema, dialect, &mut errors);
+    }
+
     errors
 }
+
+/// Check `VALUES` rows position-by-position against the target columns —
+/// the explicit column list, or the table's declared order when the list
+/// is omitted.
+fn validate_values_labels(
+    table_name: &ObjectName,
+    columns: &[Ident],
+    source: &Query,
+    schema: &Schema,
+    dialect: Dialect,
+    errors: &mut Vec<ValidationError>,
+) {
+    let SetExpr::Values(values) = source.body.as_ref() else {
+        return;
+    };
+    let Some(key) = table_key(table_name, dialect) else {
+        return;
+    };
+    let targets: Vec<(String, String)> = if columns.is_empty() {
+        let Some(declared) = schema.columns.get(&key) else {
+            return;
+        };
+        declared
+            .iter()
+            .map(|c| (c.name.clone(), c.name.clone()))
+            .collect()
+    } else {
+        columns
+            .iter()
+            .map(|c| (fold_ident(c, dialect), c.value.clone()))
+            .collect()
+    };
+    let display = display_name(table_name);
+    for row in &values.rows {
+        for ((col_key, col_display), value) in targets.iter().zip(row) {
+            if let Some(err) =
+                enums::check_label(schema, &key, col_key, value, &display, col_display)
+            {
+                errors.push(err);
+            }
+        }
+    }
+}
@@ -18,8 +18,8 @@ use sqlparser::ast::{Expr, MergeClause, TableFactor, TableWithJoins};
 
 use crate::dialect::Dialect;
 use crate::schema::sql::fold_ident;
-use crate::schema::TablesAndColumns;
-use crate::validation::{asserts, Extras};
+use crate::schema::Schema;
+use crate::validation::{asserts, Extras, ValidationError};
 
 use super::select::{collect_visible_relations, validate_expr_column_refs};
 use super::table_ref::{display_name, resolve_table_columns};
@@ -29,10 +29,10 @@ pub(crate) fn validate_merge(
     source: &TableFactor,
     on: &Expr,
     clauses: &[MergeClause],
-    schema: &TablesAndColumns,
+    schema: &Schema,
     dialect: Dialect,
     parent_extras: &Extras,
-) -> Vec<String> {
+) -> Vec<ValidationError> {
     let mut errors = Vec::new();
     let extras = parent_extras.clone();
 
@@ -41,10 +41,10 @@ pub(crate) fn validate_merge(
     // here without error, which is what we want; their columns surface via
     // the visible-relations machinery below.
     if let Some(name) = asserts::is_relation_in_schema(target, schema, dialect, &extras) {
-        errors.push(format!("Table `{name}` not found in schema nor subqueries"));
+        errors.push(ValidationError::unknown_table(&name));
     }
     if let Some(name) = asserts::is_relation_in_schema(source, schema, dialect, &extras) {
-        errors.push(format!("Table `{name}` not found in schema nor subqueries"));
+        errors.push(ValidationError::unknown_table(&name));
     }
 
     // Build a TableWithJoins for each side so we can reuse the
@@ -103,10 +103,9 @@ pub(crate) fn validate_merge(
                             continue;
                         };
                         if !cols.contains(&fold_ident(last, dialect)) {
-                            errors.push(format!(
-                                "Column `{}` not found in table `{}`",
-                                last.value,
-                                display_name(name)
+                            errors.push(ValidationError::unknown_column(
+                                &last.value,
+                                &display_name(name),
                             ));
                         }
                     }
@@ -155,10 +154,9 @@ pub(crate) fn validate_merge(
                 if let Some((name, Some(cols))) = &target_cols {
                     for col in columns {
                         if !cols.contains(&fold_ident(col, dialect)) {
-                            errors.push(format!(
-                                "Column `{}` not found in table `{}`",
-                                col.value,
-                                display_name(name)
+                            errors.push(ValidationError::unknown_column(
+                                &col.value,
+                                &display_name(name),
                             ));
                         }
                     }
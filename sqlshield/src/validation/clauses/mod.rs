@@ -3,21 +3,23 @@
 //! schema plus any CTE-derived visible relations.
 
 pub(crate) mod delete;
+pub(crate) mod group_by;
 pub(crate) mod insert;
 pub(crate) mod merge;
 pub(crate) mod select;
 pub(crate) mod table_ref;
 pub(crate) mod update;
+pub(crate) mod window;
 
 use crate::dialect::Dialect;
 use crate::schema;
-use crate::validation::Extras;
+use crate::validation::{Extras, ValidationError};
 
 pub trait ClauseValidation {
     fn validate(
         &self,
-        schema: &schema::TablesAndColumns,
+        schema: &schema::Schema,
         dialect: Dialect,
         extras: &Extras,
-    ) -> Vec<String>;
+    ) -> Vec<ValidationError>;
 }
@@ -1,27 +1,35 @@
 use std::collections::HashSet;
 
 use sqlparser::ast::{
-    Expr, FunctionArg, FunctionArgExpr, GroupByExpr, Ident, JoinConstraint, JoinOperator, Select,
-    SelectItem, TableFactor, TableWithJoins,
+    BinaryOperator, Expr, FunctionArg, FunctionArgExpr, GroupByExpr, Ident, JoinConstraint,
+    JoinOperator, Select, SelectItem, TableFactor, TableWithJoins, WindowType,
 };
 
 use crate::dialect::Dialect;
+use crate::lint::{AMBIGUOUS_COLUMN, NATURAL_JOIN_WITHOUT_COLUMNS, UNKNOWN_COLUMN};
 use crate::schema::sql::{fold_ident, fold_str};
-use crate::validation::{asserts, Extras};
+use crate::validation::clauses::group_by::GroupScope;
+use crate::validation::clauses::window;
+use crate::validation::table_function::table_function;
+use crate::validation::{asserts, enums, functions, Extras, ValidationError};
 use crate::{schema, validation::ClauseValidation};
 
 /// A table (or CTE-derived relation) visible to the current Select scope.
 pub(crate) struct VisibleRelation<'a> {
     /// Last segment of the table name (`users` in `public.users`).
-    name: &'a Ident,
+    pub(crate) name: &'a Ident,
     /// Alias if one was given (`u` in `users u`).
     alias: Option<&'a Ident>,
+    /// A table-valued function, `UNNEST`, or `JSON_TABLE`. Its columns come
+    /// only from what the query declares (tracked in `extras`), never from
+    /// a schema table that happens to share the alias.
+    table_function: bool,
 }
 
 impl<'a> VisibleRelation<'a> {
     /// The Ident the caller should use when referring to this relation with
     /// a qualifier (the alias if present, otherwise the name).
-    fn qualifier(&self) -> &'a Ident {
+    pub(crate) fn qualifier(&self) -> &'a Ident {
         self.alias.unwrap_or(self.name)
     }
 
@@ -31,11 +39,24 @@ impl<'a> VisibleRelation<'a> {
         self.name.value.as_str()
     }
 
+    /// Whether this is a table-valued function rather than a table.
+    pub(crate) fn is_table_function(&self) -> bool {
+        self.table_function
+    }
+
     fn from_factor(factor: &'a TableFactor) -> Option<Self> {
+        if let Some(tf) = table_function(factor) {
+            return Some(Self {
+                name: tf.name,
+                alias: None,
+                table_function: true,
+            });
+        }
         match factor {
             TableFactor::Table { name, alias, .. } => Some(Self {
                 name: name.0.last()?,
                 alias: alias.as_ref().map(|a| &a.name),
+                table_function: false,
             }),
             // A derived table `(SELECT …) alias` — its alias doubles as the
             // relation name. Its projected columns are tracked in `extras`.
@@ -44,6 +65,7 @@ impl<'a> VisibleRelation<'a> {
                 Some(Self {
                     name: &alias_ref.name,
                     alias: None,
+                    table_function: false,
                 })
             }
             _ => None,
@@ -51,13 +73,16 @@ impl<'a> VisibleRelation<'a> {
     }
 }
 
-pub(crate) fn validate_exprs_in_select_scope(
+/// Validate a query's `ORDER BY` expressions in the scope of the SELECT
+/// it orders: column references, plus GROUP BY membership when that SELECT
+/// aggregates.
+pub(crate) fn validate_order_by_exprs(
     exprs: &[&Expr],
     select: &Select,
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &Extras,
-) -> Vec<String> {
+) -> Vec<ValidationError> {
     // Make derived tables in the FROM clause visible to expressions
     // evaluated outside the regular Select::validate pass (ORDER BY).
     let mut local_extras = extras.clone();
@@ -81,6 +106,9 @@ pub(crate) fn validate_exprs_in_select_scope(
             &mut errors,
         );
     }
+    window::validate_window_references(select, exprs, dialect, &mut errors);
+    GroupScope::new(select, &visible, schema, dialect, &local_extras)
+        .check_order_by(exprs, &mut errors);
     errors
 }
 
@@ -103,13 +131,13 @@ fn collect_projection_aliases(select: &Select) -> HashSet<&str> {
 fn validate_from_factor(
     factor: &TableFactor,
     visible: &[VisibleRelation<'_>],
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &Extras,
-    errors: &mut Vec<String>,
+    errors: &mut Vec<ValidationError>,
 ) {
     if let Some(name) = asserts::is_relation_in_schema(factor, schema, dialect, extras) {
-        errors.push(format!("Table `{name}` not found in schema nor subqueries"));
+        errors.push(ValidationError::unknown_table(&name));
     }
     if let TableFactor::NestedJoin {
         table_with_joins, ..
@@ -146,10 +174,10 @@ fn validate_join_op(
     op: &JoinOperator,
     right: &TableFactor,
     visible: &[VisibleRelation<'_>],
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &Extras,
-    errors: &mut Vec<String>,
+    errors: &mut Vec<ValidationError>,
 ) {
     let Some(constraint) = join_constraint(op) else {
         return;
@@ -183,7 +211,7 @@ fn validate_join_op(
 /// skip those.
 fn factor_cols(
     factor: &TableFactor,
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &Extras,
 ) -> Option<HashSet<String>> {
@@ -215,10 +243,10 @@ fn factor_cols(
 fn validate_natural_join(
     right: &TableFactor,
     visible: &[VisibleRelation<'_>],
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &Extras,
-) -> Option<String> {
+) -> Option<ValidationError> {
     let right_cols = factor_cols(right, schema, dialect, extras)?;
     let right_qualifier_key = match right {
         TableFactor::Table { name, alias, .. } => {
@@ -263,8 +291,9 @@ fn validate_natural_join(
                 .unwrap_or_default(),
             _ => String::new(),
         };
-        return Some(format!(
-            "NATURAL JOIN of `{display}` shares no column with the left-hand relations"
+        return Some(ValidationError::new(
+            NATURAL_JOIN_WITHOUT_COLUMNS,
+            format!("NATURAL JOIN of `{display}` shares no column with the left-hand relations"),
         ));
     }
     None
@@ -272,7 +301,7 @@ fn validate_natural_join(
 
 /// Borrow the `JoinConstraint` out of any `JoinOperator` that carries one.
 /// CrossJoin / CrossApply / OuterApply have no constraint.
-fn join_constraint(op: &JoinOperator) -> Option<&JoinConstraint> {
+pub(crate) fn join_constraint(op: &JoinOperator) -> Option<&JoinConstraint> {
     match op {
         JoinOperator::Inner(c)
         | JoinOperator::LeftOuter(c)
@@ -301,7 +330,7 @@ pub(crate) fn collect_visible_relations<'a>(
 
 /// Recurse into `TableFactor::NestedJoin` so the relations inside a
 /// parenthesized join group still appear in the visible set.
-fn collect_from_factor<'a>(factor: &'a TableFactor, out: &mut Vec<VisibleRelation<'a>>) {
+pub(crate) fn collect_from_factor<'a>(factor: &'a TableFactor, out: &mut Vec<VisibleRelation<'a>>) {
     if let Some(r) = VisibleRelation::from_factor(factor) {
         out.push(r);
         return;
@@ -322,15 +351,18 @@ fn collect_from_factor<'a>(factor: &'a TableFactor, out: &mut Vec<VisibleRelatio
 /// `Some(false)` if the relation is known but the column isn't, and `None`
 /// if the relation is entirely unknown (caller should not over-report).
 /// All identifier comparisons honor the dialect's folding rules.
-fn column_in_relation(
+pub(crate) fn column_in_relation(
     col: &Ident,
     rel: &VisibleRelation<'_>,
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &Extras,
 ) -> Option<bool> {
     let rel_key = fold_ident(rel.name, dialect);
     let col_key = fold_ident(col, dialect);
+    if rel.table_function {
+        return asserts::extras_get(extras, &rel_key).map(|cols| cols.contains(&col_key));
+    }
     if let Some(cols) = schema.get(&rel_key) {
         return Some(cols.contains(&col_key));
     }
@@ -349,10 +381,10 @@ fn column_in_relation(
 fn resolve_unqualified(
     col: &Ident,
     relations: &[VisibleRelation<'_>],
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &Extras,
-) -> Option<String> {
+) -> Option<ValidationError> {
     resolve_unqualified_inner(col, relations, schema, dialect, extras, true)
 }
 
@@ -362,50 +394,53 @@ fn resolve_unqualified(
 fn resolve_unqualified_for_using(
     col: &Ident,
     relations: &[VisibleRelation<'_>],
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &Extras,
-) -> Option<String> {
+) -> Option<ValidationError> {
     resolve_unqualified_inner(col, relations, schema, dialect, extras, false)
 }
 
 fn resolve_unqualified_inner(
     col: &Ident,
     relations: &[VisibleRelation<'_>],
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &Extras,
     flag_ambiguity: bool,
-) -> Option<String> {
+) -> Option<ValidationError> {
     let mut found_in: Vec<&str> = Vec::new();
     let mut not_found_in: Vec<&str> = Vec::new();
+    let mut undeclared_function = false;
     for rel in relations {
         match column_in_relation(col, rel, schema, dialect, extras) {
             Some(true) => found_in.push(rel.name_display()),
             Some(false) => not_found_in.push(rel.name_display()),
-            None => {}
+            None => undeclared_function |= rel.table_function,
         }
     }
     if flag_ambiguity && found_in.len() >= 2 {
         let names = found_in.join(",");
-        return Some(format!(
-            "Column `{}` is ambiguous; appears in: {names}",
-            col.value
+        return Some(ValidationError::new(
+            AMBIGUOUS_COLUMN,
+            format!("Column `{}` is ambiguous; appears in: {names}", col.value),
         ));
     }
-    if !found_in.is_empty() {
+    // A table function without a column list may well produce the column
+    // (`SELECT g FROM generate_series(1, 3) g`).
+    if !found_in.is_empty() || undeclared_function {
         return None;
     }
     if let [table] = not_found_in.as_slice() {
-        Some(format!(
-            "Column `{}` not found in table `{table}`",
-            col.value
-        ))
+        Some(ValidationError::unknown_column(&col.value, table))
     } else if !not_found_in.is_empty() {
         let names = not_found_in.join(",");
-        Some(format!(
-            "Column `{}` not found in none of the tables: {names}",
-            col.value
+        Some(ValidationError::new(
+            UNKNOWN_COLUMN,
+            format!(
+                "Column `{}` not found in none of the tables: {names}",
+                col.value
+            ),
         ))
     } else {
         None
@@ -416,32 +451,192 @@ fn resolve_qualified(
     qualifier: &Ident,
     col: &Ident,
     relations: &[VisibleRelation<'_>],
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &Extras,
-) -> Option<String> {
+) -> Option<ValidationError> {
     let qualifier_key = fold_ident(qualifier, dialect);
-    let matched = relations
+    let Some(matched) = relations
         .iter()
-        .find(|r| fold_ident(r.qualifier(), dialect) == qualifier_key)?;
+        .find(|r| fold_ident(r.qualifier(), dialect) == qualifier_key)
+    else {
+        // Not a local relation: an outer one published into `extras` (a
+        // LATERAL subquery's preceding FROM items, or a CTE), or unknown.
+        let cols = asserts::extras_get(extras, &qualifier_key)?;
+        let table = extras
+            .table_name(&qualifier_key)
+            .unwrap_or(&qualifier.value);
+        return (!cols.contains(&fold_ident(col, dialect)))
+            .then(|| ValidationError::unknown_column(&col.value, table));
+    };
     match column_in_relation(col, matched, schema, dialect, extras) {
-        Some(false) => Some(format!(
-            "Column `{}` not found in table `{}`",
-            col.value,
-            matched.name_display()
+        Some(false) => Some(ValidationError::unknown_column(
+            &col.value,
+            matched.name_display(),
         )),
         _ => None,
     }
 }
 
+/// Split `idents` into the column reference it starts with and the STRUCT
+/// fields accessed on it: `addr.zip` → (`addr`, `zip`), and `u.addr.zip` →
+/// (`u.addr`, `zip`) when `u` is a relation. A relation qualifier wins over
+/// a column of the same name, as the other resolvers have it.
+fn struct_path<'i>(
+    idents: &'i [Ident],
+    relations: &[VisibleRelation<'_>],
+    dialect: Dialect,
+) -> Option<(Expr, &'i [Ident])> {
+    let first = fold_ident(idents.first()?, dialect);
+    let qualified = relations
+        .iter()
+        .any(|r| fold_ident(r.qualifier(), dialect) == first);
+    let split = if qualified { 2 } else { 1 };
+    if idents.len() <= split {
+        return None;
+    }
+    let column = match split {
+        1 => Expr::Identifier(idents[0].clone()),
+        _ => Expr::CompoundIdentifier(idents[..split].to_vec()),
+    };
+    Some((column, &idents[split..]))
+}
+
+/// The declared type of the schema column `column` binds to.
+fn bound_column_type<'s>(
+    column: &Expr,
+    relations: &[VisibleRelation<'_>],
+    schema: &'s schema::Schema,
+    dialect: Dialect,
+    extras: &Extras,
+) -> Option<&'s str> {
+    let (table, col, _) = bind_schema_column(column, relations, schema, dialect, extras)?;
+    let column = schema.column(&table, &fold_ident(col, dialect))?;
+    Some(&column.data_type)
+}
+
+/// Whether `idents` accesses a field of a STRUCT column (BigQuery `RECORD`
+/// fields load as `STRUCT<…>` columns). Anything else is a table-qualified
+/// column reference.
+fn is_struct_path(
+    idents: &[Ident],
+    relations: &[VisibleRelation<'_>],
+    schema: &schema::Schema,
+    dialect: Dialect,
+    extras: &Extras,
+) -> bool {
+    struct_path(idents, relations, dialect)
+        .and_then(|(column, _)| bound_column_type(&column, relations, schema, dialect, extras))
+        .is_some_and(|data_type| schema::struct_fields(data_type).is_some())
+}
+
+/// Check that the fields accessed on a STRUCT column exist, descending
+/// through nested STRUCTs. A field of a non-STRUCT type isn't checked.
+fn resolve_struct_field(
+    idents: &[Ident],
+    relations: &[VisibleRelation<'_>],
+    schema: &schema::Schema,
+    dialect: Dialect,
+    extras: &Extras,
+) -> Option<ValidationError> {
+    let (column, fields) = struct_path(idents, relations, dialect)?;
+    let mut data_type = bound_column_type(&column, relations, schema, dialect, extras)?;
+    let mut path = idents[idents.len() - fields.len() - 1].value.clone();
+    for field in fields {
+        let declared = schema::struct_fields(data_type)?;
+        let key = fold_ident(field, dialect);
+        let Some((_, field_type)) = declared
+            .into_iter()
+            .find(|(name, _)| fold_str(name, dialect) == key)
+        else {
+            return Some(ValidationError::new(
+                UNKNOWN_COLUMN,
+                format!("Field `{}` not found in struct `{path}`", field.value),
+            ));
+        };
+        path = format!("{path}.{}", field.value);
+        data_type = field_type;
+    }
+    None
+}
+
+/// Resolve a column reference to the schema table it binds to, as
+/// `(table key, column key, table display)`. Only real schema tables count —
+/// CTE and derived columns carry no type information, so a relation named
+/// in `extras` is skipped even when a schema table shares its name.
+/// Unknown, ambiguous, and non-column expressions yield `None`.
+pub(crate) fn bind_schema_column<'a>(
+    expr: &'a Expr,
+    relations: &[VisibleRelation<'a>],
+    schema: &schema::Schema,
+    dialect: Dialect,
+    extras: &Extras,
+) -> Option<(String, &'a Ident, &'a str)> {
+    let (qualifier, col) = match expr {
+        Expr::Identifier(ident) => (None, ident),
+        Expr::CompoundIdentifier(idents) if idents.len() >= 2 => {
+            (Some(&idents[idents.len() - 2]), &idents[idents.len() - 1])
+        }
+        _ => return None,
+    };
+    let col_key = fold_ident(col, dialect);
+    let candidates = relations.iter().filter(|rel| match qualifier {
+        Some(q) => fold_ident(rel.qualifier(), dialect) == fold_ident(q, dialect),
+        None => true,
+    });
+    let mut bound = None;
+    for rel in candidates.filter(|rel| !rel.table_function) {
+        let key = fold_ident(rel.name, dialect);
+        if extras.contains_key(&key) {
+            continue;
+        }
+        if schema.get(&key).is_some_and(|cols| cols.contains(&col_key)) {
+            if bound.is_some() {
+                return None;
+            }
+            bound = Some((key, col, rel.name_display()));
+        }
+    }
+    bound
+}
+
+/// Flag `col = 'label'` / `'label' <> col` comparisons where `col` is an
+/// enum-typed schema column and the literal isn't one of its labels.
+fn check_enum_comparison(
+    column: &Expr,
+    value: &Expr,
+    relations: &[VisibleRelation<'_>],
+    schema: &schema::Schema,
+    dialect: Dialect,
+    extras: &Extras,
+    errors: &mut Vec<ValidationError>,
+) {
+    let Some((table_key, col, table_display)) =
+        bind_schema_column(column, relations, schema, dialect, extras)
+    else {
+        return;
+    };
+    let col_key = fold_ident(col, dialect);
+    if let Some(err) = enums::check_label(
+        schema,
+        &table_key,
+        &col_key,
+        value,
+        table_display,
+        &col.value,
+    ) {
+        errors.push(err);
+    }
+}
+
 pub(crate) fn validate_expr_column_refs(
     root: &Expr,
     relations: &[VisibleRelation<'_>],
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &Extras,
     aliases: &HashSet<&str>,
-    errors: &mut Vec<String>,
+    errors: &mut Vec<ValidationError>,
 ) {
     walk_expr(root, relations, schema, dialect, extras, aliases, errors);
 }
@@ -453,11 +648,11 @@ pub(crate) fn validate_expr_column_refs(
 fn walk_expr(
     expr: &Expr,
     relations: &[VisibleRelation<'_>],
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &Extras,
     aliases: &HashSet<&str>,
-    errors: &mut Vec<String>,
+    errors: &mut Vec<ValidationError>,
 ) {
     match expr {
         Expr::Identifier(ident) => {
@@ -476,6 +671,14 @@ fn walk_expr(
                 errors.push(err);
             }
         }
+        // `addr.zip`, `u.addr.zip`: a field of a STRUCT column.
+        Expr::CompoundIdentifier(idents)
+            if is_struct_path(idents, relations, schema, dialect, extras) =>
+        {
+            errors.extend(resolve_struct_field(
+                idents, relations, schema, dialect, extras,
+            ));
+        }
         Expr::CompoundIdentifier(idents) if idents.len() == 2 => {
             if let Some(err) =
                 resolve_qualified(&idents[0], &idents[1], relations, schema, dialect, extras)
@@ -493,7 +696,11 @@ fn walk_expr(
                 errors.push(err);
             }
         }
-        Expr::BinaryOp { left, right, .. } => {
+        Expr::BinaryOp { left, op, right } => {
+            if matches!(op, BinaryOperator::Eq | BinaryOperator::NotEq) {
+                check_enum_comparison(left, right, relations, schema, dialect, extras, errors);
+                check_enum_comparison(right, left, relations, schema, dialect, extras, errors);
+            }
             walk_expr(left, relations, schema, dialect, extras, aliases, errors);
             walk_expr(right, relations, schema, dialect, extras, aliases, errors);
         }
@@ -523,6 +730,7 @@ fn walk_expr(
         Expr::InList { expr, list, .. } => {
             walk_expr(expr, relations, schema, dialect, extras, aliases, errors);
             for item in list {
+                check_enum_comparison(expr, item, relations, schema, dialect, extras, errors);
                 walk_expr(item, relations, schema, dialect, extras, aliases, errors);
             }
         }
@@ -553,6 +761,7 @@ fn walk_expr(
             }
         }
         Expr::Function(f) => {
+            errors.extend(functions::check_call(f, schema, dialect));
             for arg in &f.args {
                 match arg {
                     FunctionArg::Named {
@@ -565,6 +774,17 @@ fn walk_expr(
                     _ => {}
                 }
             }
+            // Inline `OVER (PARTITION BY … ORDER BY …)` resolves in the same
+            // scope as the call. Named windows are checked per SELECT.
+            if let Some(WindowType::WindowSpec(spec)) = &f.over {
+                let exprs = spec
+                    .partition_by
+                    .iter()
+                    .chain(spec.order_by.iter().map(|ob| &ob.expr));
+                for e in exprs {
+                    walk_expr(e, relations, schema, dialect, extras, aliases, errors);
+                }
+            }
         }
         Expr::AnyOp { left, right, .. } | Expr::AllOp { left, right, .. } => {
             walk_expr(left, relations, schema, dialect, extras, aliases, errors);
@@ -608,10 +828,10 @@ fn walk_expr(
 impl ClauseValidation for Select {
     fn validate(
         &self,
-        schema: &schema::TablesAndColumns,
+        schema: &schema::Schema,
         dialect: Dialect,
         extras: &Extras,
-    ) -> Vec<String> {
+    ) -> Vec<ValidationError> {
         let select = self;
         let mut errors = vec![];
 
@@ -620,6 +840,32 @@ impl ClauseValidation for Select {
         let visible = collect_visible_relations(&select.from);
         let no_aliases: HashSet<&str> = HashSet::new();
 
+        // Arguments of table-valued functions may reference the FROM items
+        // before them (implicitly LATERAL), but not the ones after.
+        let mut preceding = 0;
+        for factor in select
+            .from
+            .iter()
+            .flat_map(|t| std::iter::once(&t.relation).chain(t.joins.iter().map(|j| &j.relation)))
+        {
+            if let Some(tf) = table_function(factor) {
+                for arg in tf.args {
+                    validate_expr_column_refs(
+                        arg,
+                        &visible[..preceding],
+                        schema,
+                        dialect,
+                        extras,
+                        &no_aliases,
+                        &mut errors,
+                    );
+                }
+            }
+            let mut contributed = Vec::new();
+            collect_from_factor(factor, &mut contributed);
+            preceding += contributed.len();
+        }
+
         for item in &select.from {
             validate_from_factor(
                 &item.relation,
@@ -711,6 +957,31 @@ impl ClauseValidation for Select {
             }
         }
 
+        if let Some(qualify) = &select.qualify {
+            validate_expr_column_refs(
+                qualify,
+                &visible,
+                schema,
+                dialect,
+                extras,
+                &aliases,
+                &mut errors,
+            );
+        }
+        window::validate_window_definitions(select, &visible, schema, dialect, extras, &mut errors);
+        let windowed: Vec<&Expr> = select
+            .projection
+            .iter()
+            .filter_map(|item| match item {
+                SelectItem::UnnamedExpr(e) | SelectItem::ExprWithAlias { expr: e, .. } => Some(e),
+                _ => None,
+            })
+            .chain(select.qualify.as_ref())
+            .collect();
+        window::validate_window_references(select, &windowed, dialect, &mut errors);
+
+        GroupScope::new(select, &visible, schema, dialect, extras).check_select(&mut errors);
+
         errors
     }
 }
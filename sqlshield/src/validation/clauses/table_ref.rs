@@ -6,7 +6,7 @@ use sqlparser::ast::ObjectName;
 
 use crate::dialect::Dialect;
 use crate::schema::sql::{fold_ident, qualified_key};
-use crate::schema::TablesAndColumns;
+use crate::schema::Schema;
 
 /// Human-readable form of `ObjectName`: `public.users` or `users`.
 /// Preserves the user's casing for error messages.
@@ -18,17 +18,22 @@ pub(crate) fn display_name(name: &ObjectName) -> String {
         .join(".")
 }
 
+/// Schema key for a table reference: the qualified key for `schema.table`,
+/// the bare folded name otherwise.
+pub(crate) fn table_key(name: &ObjectName, dialect: Dialect) -> Option<String> {
+    if name.0.len() > 1 {
+        return Some(qualified_key(name, dialect));
+    }
+    Some(fold_ident(name.0.last()?, dialect))
+}
+
 /// Resolve a table reference to its column set, respecting qualified vs.
 /// unqualified lookup semantics (see `asserts::is_relation_in_schema`).
 /// Identifier matching honors the dialect's folding rules.
 pub(crate) fn resolve_table_columns<'a>(
     name: &ObjectName,
-    schema: &'a TablesAndColumns,
+    schema: &'a Schema,
     dialect: Dialect,
 ) -> Option<&'a HashSet<String>> {
-    if name.0.len() > 1 {
-        return schema.get(&qualified_key(name, dialect));
-    }
-    let last = name.0.last()?;
-    schema.get(&fold_ident(last, dialect))
+    schema.get(&table_key(name, dialect)?)
 }
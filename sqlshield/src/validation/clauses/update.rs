@@ -6,42 +6,56 @@ use sqlparser::ast::{Assignment, Expr, TableFactor, TableWithJoins};
 
 use crate::dialect::Dialect;
 use crate::schema::sql::fold_ident;
-use crate::schema::TablesAndColumns;
-use crate::validation::{asserts, Extras};
+use crate::schema::Schema;
+use crate::validation::{asserts, enums, Extras, ValidationError};
 
 use super::select::{collect_visible_relations, validate_expr_column_refs};
-use super::table_ref::display_name;
+use super::table_ref::{display_name, table_key};
 
 pub(crate) fn validate_update(
     table: &TableWithJoins,
     assignments: &[Assignment],
     from: Option<&TableWithJoins>,
     selection: Option<&Expr>,
-    schema: &TablesAndColumns,
+    schema: &Schema,
     dialect: Dialect,
     parent_extras: &Extras,
-) -> Vec<String> {
+) -> Vec<ValidationError> {
     let mut errors = Vec::new();
     let extras: Extras = parent_extras.clone();
 
     // Target table must exist.
     if let Some(name) = asserts::is_relation_in_schema(&table.relation, schema, dialect, &extras) {
-        errors.push(format!("Table `{name}` not found in schema nor subqueries"));
+        errors.push(ValidationError::unknown_table(&name));
     }
 
-    // Assignment targets: each `SET col = ...` column must exist in the target table.
+    // Assignment targets: each `SET col = ...` column must exist in the
+    // target table, and a literal assigned to an enum column must be one of
+    // its labels.
     if let TableFactor::Table { name, .. } = &table.relation {
         if let Some(cols) = super::table_ref::resolve_table_columns(name, schema, dialect) {
+            let key = table_key(name, dialect).unwrap_or_default();
             for assignment in assignments {
                 let Some(last) = assignment.id.last() else {
                     continue;
                 };
-                if !cols.contains(&fold_ident(last, dialect)) {
-                    errors.push(format!(
-                        "Column `{}` not found in table `{}`",
-                        last.value,
-                        display_name(name)
+                let col_key = fold_ident(last, dialect);
+                if !cols.contains(&col_key) {
+                    errors.push(ValidationError::unknown_column(
+                        &last.value,
+                        &display_name(name),
                     ));
+                    continue;
+                }
+                if let Some(err) = enums::check_label(
+                    schema,
+                    &key,
+                    &col_key,
+                    &assignment.value,
+                    &display_name(name),
+                    &last.value,
+                ) {
+                    errors.push(err);
                 }
             }
         }
@@ -0,0 +1,101 @@
+//! Named windows: `WINDOW w AS (PARTITION BY … ORDER BY …)` definitions are
+//! resolved in the SELECT's scope, and every `OVER w` must name a window
+//! the SELECT defines. Inline `OVER (…)` specs are resolved by `walk_expr`.
+
+use std::collections::HashSet;
+
+use sqlparser::ast::{Expr, Select, WindowType};
+
+use crate::dialect::Dialect;
+use crate::lint::UNKNOWN_WINDOW;
+use crate::schema::{self, sql::fold_ident};
+use crate::validation::clauses::select::{validate_expr_column_refs, VisibleRelation};
+use crate::validation::expr::{children, function_args};
+use crate::validation::{Extras, ValidationError};
+
+/// Resolve the column references of the SELECT's `WINDOW` definitions.
+pub(crate) fn validate_window_definitions(
+    select: &Select,
+    relations: &[VisibleRelation<'_>],
+    schema: &schema::Schema,
+    dialect: Dialect,
+    extras: &Extras,
+    errors: &mut Vec<ValidationError>,
+) {
+    let no_aliases: HashSet<&str> = HashSet::new();
+    for definition in &select.named_window {
+        let spec = &definition.1;
+        let exprs = spec
+            .partition_by
+            .iter()
+            .chain(spec.order_by.iter().map(|ob| &ob.expr));
+        for expr in exprs {
+            validate_expr_column_refs(
+                expr,
+                relations,
+                schema,
+                dialect,
+                extras,
+                &no_aliases,
+                errors,
+            );
+        }
+    }
+}
+
+/// Report `OVER w` references in `exprs` that name no window of `select`.
+/// Callers pass the projection and `QUALIFY`, or the enclosing query's
+/// `ORDER BY`.
+pub(crate) fn validate_window_references(
+    select: &Select,
+    exprs: &[&Expr],
+    dialect: Dialect,
+    errors: &mut Vec<ValidationError>,
+) {
+    let defined: HashSet<String> = select
+        .named_window
+        .iter()
+        .map(|definition| fold_ident(&definition.0, dialect))
+        .collect();
+    for expr in exprs {
+        check_references(expr, &defined, dialect, errors);
+    }
+}
+
+fn check_references(
+    expr: &Expr,
+    defined: &HashSet<String>,
+    dialect: Dialect,
+    errors: &mut Vec<ValidationError>,
+) {
+    let Expr::Function(f) = expr else {
+        for child in children(expr) {
+            check_references(child, defined, dialect, errors);
+        }
+        return;
+    };
+    match &f.over {
+        Some(WindowType::NamedWindow(name)) if !defined.contains(&fold_ident(name, dialect)) => {
+            errors.push(ValidationError::new(
+                UNKNOWN_WINDOW,
+                format!(
+                    "Window `{}` is not defined (used by `{}`)",
+                    name.value, f.name
+                ),
+            ));
+        }
+        Some(WindowType::WindowSpec(spec)) => {
+            let exprs = spec
+                .partition_by
+                .iter()
+                .chain(spec.order_by.iter().map(|ob| &ob.expr));
+            for e in exprs {
+                check_references(e, defined, dialect, errors);
+            }
+        }
+        _ => {}
+    }
+    for arg in function_args(f) {
+        check_references(arg, defined, dialect, errors);
+    }
+}
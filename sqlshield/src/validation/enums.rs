@@ -0,0 +1,37 @@
+//! Enum label checks: string literals compared with, inserted into, or
+//! assigned to an enum-typed column must be one of the type's labels.
+
+use sqlparser::ast::{Expr, Value};
+
+use crate::lint::INVALID_ENUM_LABEL;
+use crate::schema::Schema;
+use crate::validation::ValidationError;
+
+/// Check a literal against the enum labels of `table.column` (keys already
+/// dialect-folded). Returns an error when `value` is a string
+/// literal that isn't a declared label. Non-literal expressions, non-enum
+/// columns, and columns without typed metadata pass silently.
+pub(crate) fn check_label(
+    schema: &Schema,
+    table_key: &str,
+    column_key: &str,
+    value: &Expr,
+    table_display: &str,
+    column_display: &str,
+) -> Option<ValidationError> {
+    let Expr::Value(Value::SingleQuotedString(label)) = value else {
+        return None;
+    };
+    let enum_labels = schema.enum_labels(table_key, column_key)?;
+    if enum_labels.labels.iter().any(|l| l == label) {
+        return None;
+    }
+    Some(ValidationError::new(
+        INVALID_ENUM_LABEL,
+        format!(
+            "Invalid label `{label}` for enum `{}` in column `{column_display}` of table `{table_display}` (expected one of: {})",
+            enum_labels.type_name,
+            enum_labels.labels.join(", ")
+        ),
+    ))
+}
@@ -0,0 +1,89 @@
+//! Expression traversal helpers shared by the scope-level checks (GROUP BY,
+//! window references). Unlike `walk_expr` these don't resolve anything;
+//! they only enumerate sub-expressions within one scope.
+
+use sqlparser::ast::{Expr, Function, FunctionArg, FunctionArgExpr};
+
+/// Expression arguments of a call; wildcards (`count(*)`) are skipped.
+pub(crate) fn function_args(f: &Function) -> impl Iterator<Item = &Expr> {
+    f.args.iter().filter_map(arg_expr)
+}
+
+/// The expression of a single call argument, if it is one.
+pub(crate) fn arg_expr(arg: &FunctionArg) -> Option<&Expr> {
+    match arg {
+        FunctionArg::Named {
+            arg: FunctionArgExpr::Expr(e),
+            ..
+        }
+        | FunctionArg::Unnamed(FunctionArgExpr::Expr(e)) => Some(e),
+        _ => None,
+    }
+}
+
+/// Direct sub-expressions of `expr` evaluated in the same scope. Function
+/// arguments and subqueries are left to the callers.
+pub(crate) fn children(expr: &Expr) -> Vec<&Expr> {
+    match expr {
+        Expr::BinaryOp { left, right, .. }
+        | Expr::AnyOp { left, right, .. }
+        | Expr::AllOp { left, right, .. } => vec![left, right],
+        Expr::UnaryOp { expr, .. }
+        | Expr::Nested(expr)
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::IsTrue(expr)
+        | Expr::IsFalse(expr)
+        | Expr::IsUnknown(expr)
+        | Expr::IsNotTrue(expr)
+        | Expr::IsNotFalse(expr)
+        | Expr::IsNotUnknown(expr)
+        | Expr::Cast { expr, .. }
+        | Expr::TryCast { expr, .. }
+        | Expr::SafeCast { expr, .. }
+        | Expr::Collate { expr, .. }
+        | Expr::Extract { expr, .. }
+        | Expr::Ceil { expr, .. }
+        | Expr::Floor { expr, .. }
+        | Expr::InSubquery { expr, .. } => vec![expr],
+        Expr::AtTimeZone { timestamp, .. } => vec![timestamp],
+        Expr::Between {
+            expr, low, high, ..
+        } => vec![expr, low, high],
+        Expr::InList { expr, list, .. } => std::iter::once(expr.as_ref()).chain(list).collect(),
+        Expr::Like { expr, pattern, .. }
+        | Expr::ILike { expr, pattern, .. }
+        | Expr::SimilarTo { expr, pattern, .. }
+        | Expr::RLike { expr, pattern, .. } => vec![expr, pattern],
+        Expr::Position { expr, r#in } => vec![expr, r#in],
+        Expr::Substring {
+            expr,
+            substring_from,
+            substring_for,
+            ..
+        } => std::iter::once(expr.as_ref())
+            .chain(substring_from.as_deref())
+            .chain(substring_for.as_deref())
+            .collect(),
+        Expr::Trim {
+            expr, trim_what, ..
+        } => std::iter::once(expr.as_ref())
+            .chain(trim_what.as_deref())
+            .collect(),
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => operand
+            .as_deref()
+            .into_iter()
+            .chain(conditions)
+            .chain(results)
+            .chain(else_result.as_deref())
+            .collect(),
+        Expr::Tuple(items) => items.iter().collect(),
+        Expr::Array(array) => array.elem.iter().collect(),
+        _ => Vec::new(),
+    }
+}
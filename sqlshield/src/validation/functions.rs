@@ -0,0 +1,78 @@
+//! Function call checks: the called function must exist — in the schema
+//! (`CREATE FUNCTION`, config-allowed names) or the dialect's built-in
+//! catalog — and accept the number of arguments passed.
+
+use sqlparser::ast::Function;
+
+use crate::dialect::Dialect;
+use crate::functions::{self, describe_arity, FunctionSignature};
+use crate::lint::{FUNCTION_ARITY, UNKNOWN_FUNCTION};
+use crate::schema::{
+    sql::{fold_ident, qualified_key},
+    Schema,
+};
+use crate::validation::ValidationError;
+
+/// Check one call. Schema-declared functions shadow built-ins of the same
+/// name. Keyword pseudo-functions (`CURRENT_TIMESTAMP` without parens) are
+/// not checked, and neither are qualified names the schema doesn't declare:
+/// they usually live in an extension schema we know nothing about.
+pub(crate) fn check_call(
+    f: &Function,
+    schema: &Schema,
+    dialect: Dialect,
+) -> Option<ValidationError> {
+    if f.special {
+        return None;
+    }
+    let last = f.name.0.last()?;
+    let arg_count = f.args.len();
+    let display = f.name.to_string();
+
+    let key = if f.name.0.len() > 1 {
+        qualified_key(&f.name, dialect)
+    } else {
+        fold_ident(last, dialect)
+    };
+    if let Some(overloads) = schema.functions.get(&key) {
+        if overloads.iter().any(|sig| sig.accepts(arg_count)) {
+            return None;
+        }
+        return Some(arity_error(&display, overloads, arg_count));
+    }
+    if f.name.0.len() > 1 {
+        return None;
+    }
+
+    match functions::builtin(dialect, &last.value.to_lowercase()) {
+        Some(builtin) if builtin.accepts(arg_count) => None,
+        Some(builtin) => Some(ValidationError::new(
+            FUNCTION_ARITY,
+            format!(
+                "Function `{display}` expects {}, got {arg_count}",
+                describe_arity(builtin.min_args, builtin.max_args)
+            ),
+        )),
+        None if functions::has_complete_catalog(dialect) => Some(ValidationError::new(
+            UNKNOWN_FUNCTION,
+            format!("Function `{display}` not found in schema nor built-in functions"),
+        )),
+        None => None,
+    }
+}
+
+fn arity_error(
+    display: &str,
+    overloads: &[FunctionSignature],
+    arg_count: usize,
+) -> ValidationError {
+    let expected = overloads
+        .iter()
+        .map(|sig| describe_arity(sig.min_args, sig.max_args))
+        .collect::<Vec<_>>()
+        .join(" or ");
+    ValidationError::new(
+        FUNCTION_ARITY,
+        format!("Function `{display}` expects {expected}, got {arg_count}"),
+    )
+}
@@ -3,12 +3,17 @@
 
 pub mod asserts;
 pub mod clauses;
+pub(crate) mod enums;
+pub(crate) mod expr;
+pub(crate) mod functions;
+pub(crate) mod table_function;
 
 use sqlparser::ast::{Expr, Ident, Query, Select, SelectItem, SetExpr, Statement, TableFactor};
 use std::collections::{HashMap, HashSet};
 use std::fmt;
 
 use crate::dialect::Dialect;
+use crate::lint::{Severity, COLUMN_COUNT_MISMATCH, UNKNOWN_COLUMN, UNKNOWN_TABLE};
 use crate::schema::sql::{fold_ident, qualified_key};
 use crate::{finder, schema};
 use colored::Colorize;
@@ -17,8 +22,78 @@ use self::clauses::ClauseValidation;
 
 /// Per-relation extras: keys (CTE / derived-table names) and column names
 /// stored in dialect-folded form so lookups are direct equality rather than
-/// case-insensitive scans.
-pub(crate) type Extras = HashMap<String, HashSet<String>>;
+/// case-insensitive scans. It derefs to that map; the table names behind
+/// aliased relations ride along so diagnostics can name the table.
+#[derive(Clone, Debug, Default)]
+pub struct Extras {
+    columns: HashMap<String, HashSet<String>>,
+    /// Folded alias → display name of the schema table it stands for, for
+    /// relations published under an alias (a LATERAL subquery's preceding
+    /// `users u`).
+    tables: HashMap<String, String>,
+}
+
+impl Extras {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Display name of the relation published under `key`: the schema table
+    /// behind an alias, else `None` (the key is the relation's own name).
+    pub(crate) fn table_name(&self, key: &str) -> Option<&str> {
+        self.tables.get(key).map(String::as_str)
+    }
+}
+
+impl std::ops::Deref for Extras {
+    type Target = HashMap<String, HashSet<String>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.columns
+    }
+}
+
+impl std::ops::DerefMut for Extras {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.columns
+    }
+}
+
+/// A validation finding in one SQL string: the code of the rule it falls
+/// under (see [`crate::lint::RULES`]) and its message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub(crate) fn new(code: &'static str, message: String) -> Self {
+        Self { code, message }
+    }
+
+    /// A column missing from the one table it was looked up in.
+    pub(crate) fn unknown_column(column: &str, table: &str) -> Self {
+        Self::new(
+            UNKNOWN_COLUMN,
+            format!("Column `{column}` not found in table `{table}`"),
+        )
+    }
+
+    /// A table reference that resolves to nothing in scope.
+    pub(crate) fn unknown_table(name: &str) -> Self {
+        Self::new(
+            UNKNOWN_TABLE,
+            format!("Table `{name}` not found in schema nor subqueries"),
+        )
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
 
 #[derive(Debug, PartialEq)]
 pub struct SqlValidationError {
@@ -28,19 +103,22 @@ pub struct SqlValidationError {
 
 impl SqlValidationError {
     pub fn new(file_path: &std::path::Path, line_number: usize, description: String) -> Self {
-        let location = [
-            file_path.to_string_lossy().to_string(),
-            line_number.to_string(),
-        ]
-        .join(":");
-
         SqlValidationError {
-            location,
+            location: location(file_path, line_number),
             description,
         }
     }
 }
 
+/// `path:line`, as reported for findings in source files.
+pub(crate) fn location(file_path: &std::path::Path, line_number: usize) -> String {
+    [
+        file_path.to_string_lossy().to_string(),
+        line_number.to_string(),
+    ]
+    .join(":")
+}
+
 impl fmt::Display for SqlValidationError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
@@ -53,6 +131,73 @@ impl fmt::Display for SqlValidationError {
     }
 }
 
+/// A finding in a source file: a validation error or a lint, with the code
+/// of the rule that produced it and its configured severity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// `path:line`.
+    pub location: String,
+    pub path: std::path::PathBuf,
+    /// 1-based line of the query in `path`.
+    pub line: usize,
+    /// 1-based character column where the query's string literal starts,
+    /// and where it ends (exclusive).
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub severity: Severity,
+    pub code: &'static str,
+    pub description: String,
+    /// [`query_hash`] of the query the finding is in; unlike `line`, it
+    /// survives edits elsewhere in the file.
+    pub query_hash: u64,
+    /// Name of the target (e.g. a monorepo service) the file was checked
+    /// under, for runs over several. The library leaves it unset.
+    pub target: Option<String>,
+}
+
+/// Stable hash of a query's normalized text: the statements as sqlparser
+/// prints them, so whitespace and keyword case don't matter. FNV-1a, so
+/// the value is the same across builds and platforms and can be stored.
+pub fn query_hash(statements: &[Statement]) -> u64 {
+    let normalized = statements
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+        .join("; ");
+    fnv1a(&normalized)
+}
+
+/// [`query_hash`] for SQL that doesn't parse: the text with runs of
+/// whitespace collapsed, since there are no statements to print.
+pub fn text_hash(text: &str) -> u64 {
+    fnv1a(&text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self.severity {
+            Severity::Error => "error:".red(),
+            _ => "warning:".yellow(),
+        };
+        write!(
+            f,
+            "{}: {} {} [{}]",
+            self.location, label, self.description, self.code
+        )?;
+        match &self.target {
+            Some(target) => write!(f, " (target: {target})"),
+            None => Ok(()),
+        }
+    }
+}
+
 pub struct SqlQueryError {
     pub line: usize,
     pub description: String,
@@ -60,7 +205,7 @@ pub struct SqlQueryError {
 
 pub fn validate_queries_in_code(
     queries: &[finder::QueryInCode],
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
 ) -> Vec<SqlQueryError> {
     let mut errors: Vec<SqlQueryError> = Vec::new();
@@ -69,7 +214,7 @@ pub fn validate_queries_in_code(
         for query_error in query_errors {
             errors.push(SqlQueryError {
                 line: query.line,
-                description: query_error,
+                description: query_error.message,
             });
         }
     }
@@ -78,10 +223,10 @@ pub fn validate_queries_in_code(
 
 pub fn validate_statements_with_schema(
     query: &[Statement],
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
-) -> Vec<String> {
-    let mut errors: Vec<String> = Vec::new();
+) -> Vec<ValidationError> {
+    let mut errors: Vec<ValidationError> = Vec::new();
 
     for statement in query {
         match statement {
@@ -99,7 +244,11 @@ pub fn validate_statements_with_schema(
                 ..
             } => {
                 errors.extend(clauses::insert::validate_insert(
-                    table_name, columns, schema, dialect,
+                    table_name,
+                    columns,
+                    source.as_deref(),
+                    schema,
+                    dialect,
                 ));
                 if let Some(source_query) = source {
                     errors.extend(validate_query_with_schema(
@@ -116,7 +265,7 @@ pub fn validate_statements_with_schema(
                 selection,
                 ..
             } => {
-                let empty: Extras = HashMap::new();
+                let empty = Extras::new();
                 errors.extend(clauses::update::validate_update(
                     table,
                     assignments,
@@ -133,7 +282,7 @@ pub fn validate_statements_with_schema(
                 selection,
                 ..
             } => {
-                let empty: Extras = HashMap::new();
+                let empty = Extras::new();
                 errors.extend(clauses::delete::validate_delete(
                     from,
                     using.as_deref(),
@@ -150,7 +299,7 @@ pub fn validate_statements_with_schema(
                 clauses: merge_clauses,
                 ..
             } => {
-                let empty: Extras = HashMap::new();
+                let empty = Extras::new();
                 errors.extend(clauses::merge::validate_merge(
                     table,
                     source,
@@ -169,10 +318,10 @@ pub fn validate_statements_with_schema(
 
 pub fn validate_query_with_schema(
     query: &Query,
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
-) -> Vec<String> {
-    let empty: Extras = HashMap::new();
+) -> Vec<ValidationError> {
+    let empty = Extras::new();
     validate_query_with_scope(query, schema, dialect, &empty)
 }
 
@@ -181,12 +330,12 @@ pub fn validate_query_with_schema(
 /// nested subqueries (IN / EXISTS / scalar) and for CTE-to-CTE references.
 pub(crate) fn validate_query_with_scope(
     query: &Query,
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     parent_extras: &Extras,
-) -> Vec<String> {
+) -> Vec<ValidationError> {
     let mut extras: Extras = parent_extras.clone();
-    let mut errors: Vec<String> = vec![];
+    let mut errors: Vec<ValidationError> = vec![];
 
     validate_and_extract_subqueries(query, schema, dialect, &mut extras, &mut errors);
 
@@ -198,7 +347,7 @@ pub(crate) fn validate_query_with_scope(
         let select = boxed.as_ref();
         if !query.order_by.is_empty() {
             let order_exprs: Vec<&Expr> = query.order_by.iter().map(|ob| &ob.expr).collect();
-            errors.extend(clauses::select::validate_exprs_in_select_scope(
+            errors.extend(clauses::select::validate_order_by_exprs(
                 &order_exprs,
                 select,
                 schema,
@@ -217,10 +366,10 @@ pub(crate) fn validate_query_with_scope(
 /// branch so they don't leak between UNION arms.
 fn validate_set_expr(
     body: &SetExpr,
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &Extras,
-    errors: &mut Vec<String>,
+    errors: &mut Vec<ValidationError>,
 ) {
     match body {
         SetExpr::Select(boxed) => {
@@ -237,8 +386,9 @@ fn validate_set_expr(
                 count_projection(right.as_ref()),
             ) {
                 if l != r {
-                    errors.push(format!(
-                        "{op}: column count mismatch (left has {l}, right has {r})"
+                    errors.push(ValidationError::new(
+                        COLUMN_COUNT_MISMATCH,
+                        format!("{op}: column count mismatch (left has {l}, right has {r})"),
                     ));
                 }
             }
@@ -264,7 +414,11 @@ fn validate_set_expr(
             ..
         }) => {
             errors.extend(clauses::insert::validate_insert(
-                table_name, columns, schema, dialect,
+                table_name,
+                columns,
+                source.as_deref(),
+                schema,
+                dialect,
             ));
             if let Some(source_query) = source {
                 errors.extend(validate_query_with_scope(
@@ -331,7 +485,7 @@ fn count_projection(body: &SetExpr) -> Option<usize> {
 /// negative than a noisy false positive.
 fn project_columns(
     query: &Query,
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     parent_extras: &Extras,
 ) -> Vec<Ident> {
@@ -340,7 +494,7 @@ fn project_columns(
 
 fn project_columns_of_body(
     body: &SetExpr,
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &Extras,
 ) -> Vec<Ident> {
@@ -374,7 +528,7 @@ fn project_columns_of_body(
 
 fn project_select_columns(
     select: &Select,
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     parent_extras: &Extras,
 ) -> Vec<Ident> {
@@ -442,7 +596,7 @@ fn project_select_columns(
 /// and reused at ORDER BY time so outer references resolve.
 pub(crate) fn publish_derived(
     factor: &TableFactor,
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &mut Extras,
 ) {
@@ -465,7 +619,20 @@ pub(crate) fn publish_derived(
                 publish_derived(&j.relation, schema, dialect, extras);
             }
         }
-        _ => {}
+        _ => publish_table_function(factor, dialect, extras),
+    }
+}
+
+/// Stash the declared columns of a table-valued function, `UNNEST`, or
+/// `JSON_TABLE` under its alias. Calls that declare no columns stay out of
+/// `extras`, so references through them are never judged.
+fn publish_table_function(factor: &TableFactor, dialect: Dialect, extras: &mut Extras) {
+    let Some(tf) = table_function::table_function(factor) else {
+        return;
+    };
+    if let Some(columns) = tf.columns {
+        let cols = columns.iter().map(|c| fold_ident(c, dialect)).collect();
+        extras.insert(fold_ident(tf.name, dialect), cols);
     }
 }
 
@@ -473,10 +640,16 @@ pub(crate) fn publish_derived(
 /// qualified and bare names; CTE/derived references fall back to `extras`.
 fn relation_columns(
     factor: &TableFactor,
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &Extras,
 ) -> Vec<Ident> {
+    if let Some(tf) = table_function::table_function(factor) {
+        return extras
+            .get(&fold_ident(tf.name, dialect))
+            .map(|cols| cols.iter().map(|c| Ident::new(c.as_str())).collect())
+            .unwrap_or_default();
+    }
     match factor {
         TableFactor::Table { name, .. } => {
             let cols_from_schema = if name.0.len() > 1 {
@@ -519,7 +692,7 @@ fn relation_columns(
 
 fn relation_columns_if_match(
     factor: &TableFactor,
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &Extras,
     qualifier: &str,
@@ -553,63 +726,85 @@ fn relation_columns_if_match(
 }
 
 fn relation_qualifier_matches(factor: &TableFactor, dialect: Dialect, qualifier: &str) -> bool {
-    let folded = match factor {
-        TableFactor::Table { name, alias, .. } => {
-            if let Some(a) = alias {
-                fold_ident(&a.name, dialect)
-            } else {
-                let Some(last) = name.0.last() else {
-                    return false;
-                };
-                fold_ident(last, dialect)
-            }
-        }
-        TableFactor::Derived { alias, .. } => {
-            let Some(a) = alias else { return false };
-            fold_ident(&a.name, dialect)
-        }
-        _ => return false,
-    };
-    folded == qualifier
+    relation_qualifier(factor, dialect).is_some_and(|q| q == qualifier)
+}
+
+/// Folded name a FROM item is referenced by: its alias, else the table (or
+/// function) name. Derived tables without an alias have none.
+fn relation_qualifier(factor: &TableFactor, dialect: Dialect) -> Option<String> {
+    if let Some(tf) = table_function::table_function(factor) {
+        return Some(fold_ident(tf.name, dialect));
+    }
+    match factor {
+        TableFactor::Table { name, alias, .. } => match alias {
+            Some(a) => Some(fold_ident(&a.name, dialect)),
+            None => Some(fold_ident(name.0.last()?, dialect)),
+        },
+        TableFactor::Derived { alias, .. } => Some(fold_ident(&alias.as_ref()?.name, dialect)),
+        _ => None,
+    }
 }
 
 fn extract_derived_from_factors(
     select: &sqlparser::ast::Select,
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &mut Extras,
-    errors: &mut Vec<String>,
+    errors: &mut Vec<ValidationError>,
 ) {
+    // FROM items in order, so a LATERAL subquery can see the ones before it.
+    let mut preceding: Vec<&TableFactor> = Vec::new();
     for table in &select.from {
-        walk_factor_for_derived(&table.relation, schema, dialect, extras, errors);
-        for join in &table.joins {
-            walk_factor_for_derived(&join.relation, schema, dialect, extras, errors);
+        for factor in
+            std::iter::once(&table.relation).chain(table.joins.iter().map(|j| &j.relation))
+        {
+            walk_factor_for_derived(factor, &preceding, schema, dialect, extras, errors);
+            preceding.push(factor);
         }
     }
 }
 
 fn walk_factor_for_derived(
     factor: &TableFactor,
-    schema: &schema::TablesAndColumns,
+    preceding: &[&TableFactor],
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &mut Extras,
-    errors: &mut Vec<String>,
+    errors: &mut Vec<ValidationError>,
 ) {
     match factor {
         TableFactor::Derived {
-            subquery, alias, ..
+            lateral,
+            subquery,
+            alias,
         } => {
             let Some(alias) = alias.as_ref() else {
                 return;
             };
 
-            // Recursive validation: uses its own fresh extras, so inner CTEs
-            // don't leak out. Inner errors bubble up to the outer error list.
-            errors.extend(validate_query_with_schema(
-                subquery.as_ref(),
-                schema,
-                dialect,
-            ));
+            if *lateral {
+                // A LATERAL subquery sees the preceding FROM items: publish
+                // their columns under their qualifiers for the inner scope.
+                let mut lateral_extras = extras.clone();
+                for prev in preceding {
+                    publish_relation(prev, schema, dialect, &mut lateral_extras);
+                }
+                errors.extend(validate_query_with_scope(
+                    subquery.as_ref(),
+                    schema,
+                    dialect,
+                    &lateral_extras,
+                ));
+            } else {
+                // Recursive validation: uses its own fresh extras, so inner
+                // CTEs don't leak out. Inner errors bubble up to the outer
+                // error list.
+                errors.extend(validate_query_with_schema(
+                    subquery.as_ref(),
+                    schema,
+                    dialect,
+                ));
+            }
 
             let cols: HashSet<String> = project_columns(subquery.as_ref(), schema, dialect, extras)
                 .iter()
@@ -622,21 +817,68 @@ fn walk_factor_for_derived(
         TableFactor::NestedJoin {
             table_with_joins, ..
         } => {
-            walk_factor_for_derived(&table_with_joins.relation, schema, dialect, extras, errors);
+            walk_factor_for_derived(
+                &table_with_joins.relation,
+                preceding,
+                schema,
+                dialect,
+                extras,
+                errors,
+            );
             for join in &table_with_joins.joins {
-                walk_factor_for_derived(&join.relation, schema, dialect, extras, errors);
+                walk_factor_for_derived(&join.relation, preceding, schema, dialect, extras, errors);
             }
         }
-        _ => {}
+        _ => publish_table_function(factor, dialect, extras),
+    }
+}
+
+/// Publish a FROM item's columns under the name it is referenced by (alias
+/// or table name). Relations with unknown columns are left out.
+fn publish_relation(
+    factor: &TableFactor,
+    schema: &schema::Schema,
+    dialect: Dialect,
+    extras: &mut Extras,
+) {
+    if let TableFactor::NestedJoin {
+        table_with_joins, ..
+    } = factor
+    {
+        for f in std::iter::once(&table_with_joins.relation)
+            .chain(table_with_joins.joins.iter().map(|j| &j.relation))
+        {
+            publish_relation(f, schema, dialect, extras);
+        }
+        return;
+    }
+    let Some(qualifier) = relation_qualifier(factor, dialect) else {
+        return;
+    };
+    let cols = relation_columns(factor, schema, dialect, extras);
+    if !cols.is_empty() {
+        let cols = cols.iter().map(|c| fold_ident(c, dialect)).collect();
+        if let TableFactor::Table {
+            name,
+            alias: Some(_),
+            args: None,
+            ..
+        } = factor
+        {
+            if let Some(table) = name.0.last() {
+                extras.tables.insert(qualifier.clone(), table.value.clone());
+            }
+        }
+        extras.insert(qualifier, cols);
     }
 }
 
 fn validate_and_extract_subqueries(
     query: &Query,
-    schema: &schema::TablesAndColumns,
+    schema: &schema::Schema,
     dialect: Dialect,
     extras: &mut Extras,
-    errors: &mut Vec<String>,
+    errors: &mut Vec<ValidationError>,
 ) {
     let Some(with) = &query.with else {
         return;
@@ -650,10 +892,7 @@ fn validate_and_extract_subqueries(
             // schema (folded by current dialect).
             let key = crate::schema::sql::fold_ident(derived_from, dialect);
             if !schema.contains_key(&key) {
-                errors.push(format!(
-                    "Table `{}` not found in schema nor subqueries",
-                    derived_from.value
-                ));
+                errors.push(ValidationError::unknown_table(&derived_from.value));
                 continue;
             }
         }
@@ -0,0 +1,81 @@
+//! Relations produced by table-valued FROM items: set-returning function
+//! calls (`generate_series(1, 10) AS g`, `LATERAL f(x)`), `UNNEST(...)`, and
+//! MySQL's `JSON_TABLE(...)`. They are visible under their alias like any
+//! table; their columns are whatever the alias list (or `JSON_TABLE`'s
+//! `COLUMNS` clause) declares, and unknown otherwise.
+
+use sqlparser::ast::{Expr, Ident, TableAlias, TableFactor};
+
+use crate::validation::expr::arg_expr;
+
+/// A table-valued FROM item, as far as column resolution is concerned.
+pub(crate) struct TableFunction<'a> {
+    /// Name the relation is referred to by: the alias, or the function's
+    /// own name when none is given (`FROM generate_series(1, 3)`).
+    pub(crate) name: &'a Ident,
+    /// Declared output columns; `None` when the call doesn't declare them.
+    pub(crate) columns: Option<Vec<&'a Ident>>,
+    /// Argument expressions. They may reference preceding FROM items.
+    pub(crate) args: Vec<&'a Expr>,
+}
+
+/// Describe `factor` if it is a table-valued function, `UNNEST`, or
+/// `JSON_TABLE`. A `TableFactor::Table` with an argument list is a function
+/// call too — that's how sqlparser parses `FROM f(...)` without `LATERAL`.
+pub(crate) fn table_function(factor: &TableFactor) -> Option<TableFunction<'_>> {
+    match factor {
+        TableFactor::Table {
+            name,
+            alias,
+            args: Some(args),
+            ..
+        }
+        | TableFactor::Function {
+            name, alias, args, ..
+        } => Some(TableFunction {
+            name: alias.as_ref().map_or(name.0.last()?, |a| &a.name),
+            columns: alias_columns(alias.as_ref()),
+            args: args.iter().filter_map(arg_expr).collect(),
+        }),
+        TableFactor::TableFunction { expr, alias } => Some(TableFunction {
+            name: &alias.as_ref()?.name,
+            columns: alias_columns(alias.as_ref()),
+            args: vec![expr],
+        }),
+        // `UNNEST(arr) AS t(x) WITH OFFSET AS i` — an unaliased UNNEST can't
+        // be referenced, so it contributes no relation.
+        TableFactor::UNNEST {
+            alias,
+            array_exprs,
+            with_offset_alias,
+            ..
+        } => {
+            let alias = alias.as_ref()?;
+            let columns = alias_columns(Some(alias)).map(|mut cols| {
+                cols.extend(with_offset_alias.as_ref());
+                cols
+            });
+            Some(TableFunction {
+                name: &alias.name,
+                columns,
+                args: array_exprs.iter().collect(),
+            })
+        }
+        TableFactor::JsonTable {
+            json_expr,
+            columns,
+            alias,
+            ..
+        } => Some(TableFunction {
+            name: &alias.as_ref()?.name,
+            columns: Some(columns.iter().map(|c| &c.name).collect()),
+            args: vec![json_expr],
+        }),
+        _ => None,
+    }
+}
+
+fn alias_columns(alias: Option<&TableAlias>) -> Option<Vec<&Ident>> {
+    let alias = alias?;
+    (!alias.columns.is_empty()).then(|| alias.columns.iter().collect())
+}
@@ -0,0 +1,317 @@
+//! The directory walk: which files under a directory get checked. Besides
+//! the built-in list of build, VCS and cache directories, the walk honours
+//! ignore files the way git does — `.gitignore` / `.ignore` files in the
+//! directory and its parents, `.git/info/exclude` and the global excludes
+//! file — and `include` / `exclude` globs, and records what each of those
+//! skipped so a run can explain itself.
+
+use std::cell::OnceCell;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use globset::{Glob, GlobBuilder, GlobMatcher};
+use ignore::{DirEntry, WalkBuilder};
+
+use crate::finder;
+
+/// Directory names that are pruned from the file walker by default. These
+/// hold generated artefacts, caches, and vendored code — scanning them
+/// produces noise without value.
+const IGNORED_DIRS: &[&str] = &[
+    ".git",
+    "target",
+    "node_modules",
+    ".venv",
+    "venv",
+    "__pycache__",
+    ".pytest_cache",
+    ".mypy_cache",
+    ".ruff_cache",
+    ".tox",
+    "dist",
+    "build",
+    ".idea",
+    ".vscode",
+];
+
+/// What to leave out of a walk beyond the built-in directories. The
+/// default honours ignore files and has no globs.
+#[derive(Debug, Clone)]
+pub struct WalkOptions {
+    ignore_files: bool,
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+/// An `include` / `exclude` glob, as written and compiled.
+#[derive(Debug, Clone)]
+struct Pattern {
+    text: String,
+    /// Absolute directory the glob is relative to; `None` matches paths
+    /// as walked.
+    base: Option<PathBuf>,
+    matcher: GlobMatcher,
+}
+
+impl Pattern {
+    fn new(text: &str) -> Result<Self, String> {
+        Ok(Self {
+            text: text.to_string(),
+            base: None,
+            matcher: glob(text)?.compile_matcher(),
+        })
+    }
+
+    fn is_match(&self, relative: &Path, absolute: &OnceCell<PathBuf>, path: &Path) -> bool {
+        match &self.base {
+            None => self.matcher.is_match(relative),
+            Some(base) => absolute
+                .get_or_init(|| crate::config::absolute(path))
+                .strip_prefix(base)
+                .is_ok_and(|p| self.matcher.is_match(p)),
+        }
+    }
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            ignore_files: true,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+}
+
+impl WalkOptions {
+    /// Whether `.gitignore` / `.ignore` files are honoured (on by default).
+    pub fn ignore_files(&mut self, yes: bool) -> &mut Self {
+        self.ignore_files = yes;
+        self
+    }
+
+    /// Only walk files matching one of `patterns`. Globs match the path as
+    /// walked, without a leading `./`; `*` doesn't cross `/`.
+    pub fn include(&mut self, patterns: &[String]) -> Result<&mut Self, String> {
+        for pattern in patterns {
+            self.include.push(Pattern::new(pattern)?);
+        }
+        Ok(self)
+    }
+
+    /// Skip files matching any of `patterns` (same syntax as
+    /// [`include`](Self::include)); a trailing `/` means everything below.
+    pub fn exclude(&mut self, patterns: &[String]) -> Result<&mut Self, String> {
+        for pattern in patterns {
+            self.exclude.push(Pattern::new(pattern)?);
+        }
+        Ok(self)
+    }
+
+    /// Make the globs added so far relative to `dir` instead of to the
+    /// walked paths, as for the globs of a config file. Globs that already
+    /// have a directory keep it.
+    pub fn rebase(&mut self, dir: &Path) -> &mut Self {
+        let base = crate::config::absolute(dir);
+        for pattern in self.include.iter_mut().chain(&mut self.exclude) {
+            pattern.base.get_or_insert_with(|| base.clone());
+        }
+        self
+    }
+
+    /// Add the globs of `other` to these.
+    pub fn extend(&mut self, other: &WalkOptions) -> &mut Self {
+        self.include.extend(other.include.iter().cloned());
+        self.exclude.extend(other.exclude.iter().cloned());
+        self
+    }
+
+    /// Whether the `include` / `exclude` globs leave out `path`, for files
+    /// named explicitly rather than walked.
+    pub fn skip_reason(&self, path: &Path) -> Option<SkipReason> {
+        let relative = path.strip_prefix(".").unwrap_or(path);
+        let absolute = OnceCell::new();
+        if let Some(pattern) = self
+            .exclude
+            .iter()
+            .find(|p| p.is_match(relative, &absolute, path))
+        {
+            return Some(SkipReason::Excluded(pattern.text.clone()));
+        }
+        if !self.include.is_empty()
+            && !self
+                .include
+                .iter()
+                .any(|p| p.is_match(relative, &absolute, path))
+        {
+            return Some(SkipReason::NotIncluded);
+        }
+        None
+    }
+}
+
+fn glob(pattern: &str) -> Result<Glob, String> {
+    let expanded = match pattern.strip_suffix('/') {
+        Some(dir) => format!("{dir}/**"),
+        None => pattern.to_string(),
+    };
+    GlobBuilder::new(&expanded)
+        .literal_separator(true)
+        .build()
+        .map_err(|e| format!("invalid glob `{pattern}`: {e}"))
+}
+
+/// Why a path was left out of a walk.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SkipReason {
+    /// A built-in build, VCS or cache directory (`target/`, `.git/`, …).
+    DefaultDirectory,
+    /// Matched by a `.gitignore` or `.ignore` file.
+    IgnoreFile,
+    /// Matched by this `exclude` pattern.
+    Excluded(String),
+    /// Matched no `include` pattern.
+    NotIncluded,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DefaultDirectory => f.write_str("built-in ignored directory"),
+            Self::IgnoreFile => f.write_str(".gitignore / .ignore"),
+            Self::Excluded(pattern) => write!(f, "exclude `{pattern}`"),
+            Self::NotIncluded => f.write_str("not in include"),
+        }
+    }
+}
+
+/// A file or pruned directory left out of a walk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Skipped {
+    pub path: PathBuf,
+    pub reason: SkipReason,
+}
+
+/// The result of a walk: the files to check, and what was skipped.
+/// Only files the walk would otherwise have returned are listed as
+/// skipped; pruned directories are listed once, not file by file.
+#[derive(Debug, Default)]
+pub struct Walk {
+    pub files: Vec<PathBuf>,
+    pub skipped: Vec<Skipped>,
+}
+
+/// Supported source files under `dir`, filtered by `options`. Collected up
+/// front so rayon can parallelize cleanly over them.
+pub fn walk(dir: &Path, options: &WalkOptions) -> Walk {
+    walk_matching(dir, options, is_code_file)
+}
+
+/// Supported source files under `dir`, skipping build, VCS and cache
+/// directories (`target/`, `.git/`, `node_modules/`, …) and anything a
+/// `.gitignore` or `.ignore` file excludes.
+pub fn code_files(dir: &Path) -> Vec<PathBuf> {
+    walk(dir, &WalkOptions::default()).files
+}
+
+/// `.sql` files under `dir` (schema dumps, migrations), with the same
+/// pruning as [`code_files`]. The walk doesn't check these; `--watch`
+/// reloads the schema when one changes.
+pub fn sql_files(dir: &Path) -> Vec<PathBuf> {
+    walk_matching(dir, &WalkOptions::default(), |path| {
+        path.extension()
+            .is_some_and(|e| e == finder::SQL_FILE_EXTENSION)
+    })
+    .files
+}
+
+fn is_code_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| finder::SUPPORTED_CODE_FILE_EXTENSIONS.contains(&e))
+}
+
+fn walk_matching(dir: &Path, options: &WalkOptions, wanted: impl Fn(&Path) -> bool) -> Walk {
+    // `ignore` leaves out what ignore files exclude without saying so, so
+    // its walk only tells which paths survive them. The walk that produces
+    // the result reads no ignore files and prunes what the first one left
+    // out, recording it. Both prune the built-in directories, so neither
+    // descends into `.git/` or `node_modules/`.
+    let kept: Option<HashSet<PathBuf>> = options.ignore_files.then(|| {
+        builder(dir, true)
+            .filter_entry(|entry| !is_ignored_dir(entry))
+            .build()
+            .filter_map(Result::ok)
+            .map(DirEntry::into_path)
+            .collect()
+    });
+    // Everything skipped, in walk order: `(path, is_dir, reason)`.
+    let skipped = Arc::new(Mutex::new(Vec::new()));
+    let pruned = Arc::clone(&skipped);
+    let entries = builder(dir, false)
+        .filter_entry(move |entry| {
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            let reason = if is_ignored_dir(entry) {
+                SkipReason::DefaultDirectory
+            } else if kept
+                .as_ref()
+                .is_some_and(|kept| !kept.contains(entry.path()))
+            {
+                SkipReason::IgnoreFile
+            } else {
+                return true;
+            };
+            pruned
+                .lock()
+                .unwrap()
+                .push((entry.path().to_path_buf(), is_dir, reason));
+            false
+        })
+        .build();
+
+    let mut walk = Walk::default();
+    for entry in entries.filter_map(Result::ok) {
+        if !entry.file_type().is_some_and(|t| t.is_file()) || !wanted(entry.path()) {
+            continue;
+        }
+        let path = entry.into_path();
+        match options.skip_reason(&path) {
+            Some(reason) => skipped.lock().unwrap().push((path, false, reason)),
+            None => walk.files.push(path),
+        }
+    }
+    // Pruned directories are listed once; pruned files only if the walk
+    // would have returned them.
+    let skipped = std::mem::take(&mut *skipped.lock().unwrap());
+    walk.skipped = skipped
+        .into_iter()
+        .filter(|(path, is_dir, _)| *is_dir || wanted(path))
+        .map(|(path, _, reason)| Skipped { path, reason })
+        .collect();
+    walk
+}
+
+/// Whether `entry` is one of the built-in [`IGNORED_DIRS`].
+fn is_ignored_dir(entry: &DirEntry) -> bool {
+    entry.file_type().is_some_and(|t| t.is_dir())
+        && entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| IGNORED_DIRS.contains(&name))
+}
+
+/// A sorted walk of `dir`, hidden files included. With `ignore_files`,
+/// it honours `.gitignore` / `.ignore` files in `dir` and every directory
+/// above it, `.git/info/exclude` and the global git excludes file, in a
+/// git repository or not.
+fn builder(dir: &Path, ignore_files: bool) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(dir);
+    builder
+        .standard_filters(ignore_files)
+        .hidden(false)
+        .require_git(false)
+        .sort_by_file_name(|a, b| a.cmp(b));
+    builder
+}
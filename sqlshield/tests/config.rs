@@ -0,0 +1,96 @@
+//! `.sqlshield.toml` discovery: every config from a directory upward,
+//! layered so the nearest one wins for its subtree.
+
+use std::fs;
+use std::path::Path;
+
+use sqlshield::config::{self, CONFIG_FILE_NAME};
+use sqlshield::lint::Severity;
+use sqlshield::Dialect;
+
+fn write_config(dir: &Path, source: &str) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(dir.join(CONFIG_FILE_NAME), source).unwrap();
+}
+
+#[test]
+fn nested_configs_layer_over_the_ones_above_up_to_root() {
+    let outer = tempfile::tempdir().unwrap();
+    let project = outer.path().join("project");
+    let service = project.join("service");
+    write_config(
+        outer.path(),
+        "dialect = \"mysql\"\nfunctions = [\"outer_fn\"]\n",
+    );
+    write_config(
+        &project,
+        "root = true\ndialect = \"postgres\"\nschema = \"db/schema.sql\"\nfunctions = [\"project_fn\"]\n",
+    );
+    write_config(
+        &service,
+        "dialect = \"sqlite\"\n\n[rules]\nselect-star = \"off\"\n\n[[overrides]]\npaths = [\"tests/**\"]\nrules = { unknown-column = false }\n",
+    );
+    fs::create_dir_all(service.join("deep")).unwrap();
+
+    let files = config::find(&service.join("deep"));
+    assert_eq!(
+        files,
+        [
+            project.join(CONFIG_FILE_NAME),
+            service.join(CONFIG_FILE_NAME)
+        ]
+    );
+
+    let cfg = config::load_chain(&files).unwrap();
+    assert_eq!(cfg.dialect, Some(Dialect::Sqlite));
+    assert_eq!(cfg.schema, Some(project.join("db/schema.sql")));
+    assert_eq!(cfg.functions, ["project_fn"]);
+    assert_eq!(cfg.dir(), service);
+    assert_eq!(cfg.rules.severity("select-star"), Severity::Off);
+    // Override paths are relative to the config that declared them.
+    let scoped = cfg.rules.for_path(&service.join("tests/test_db.py"));
+    assert_eq!(scoped.severity("unknown-column"), Severity::Off);
+    let scoped = cfg.rules.for_path(&project.join("tests/test_db.py"));
+    assert_eq!(scoped.severity("unknown-column"), Severity::Error);
+
+    // The project on its own.
+    let cfg = config::discover(&project).unwrap().unwrap();
+    assert_eq!(cfg.dialect, Some(Dialect::Postgres));
+    assert_eq!(cfg.rules.severity("select-star"), Severity::Warning);
+}
+
+#[test]
+fn nested_settings_apply_to_the_targets_above_them() {
+    let root = tempfile::tempdir().unwrap();
+    write_config(
+        root.path(),
+        "[[targets]]\nname = \"api\"\ndirectory = \"services/api\"\nschema = \"services/api/schema.sql\"\ndialect = \"postgres\"\nexclude = [\"services/api/gen/\"]\n",
+    );
+    let legacy = root.path().join("services/api/legacy");
+    write_config(&legacy, "schema = \"old.sql\"\nexclude = [\"vendor/\"]\n");
+
+    let cfg = config::discover(&legacy).unwrap().unwrap();
+    let api = &cfg.targets[0];
+    assert_eq!(api.schema, Some(legacy.join("old.sql")));
+    assert_eq!(api.dialect, Some(Dialect::Postgres));
+    assert!(api.walk.skip_reason(&legacy.join("vendor/x.py")).is_some());
+    assert!(api
+        .walk
+        .skip_reason(&root.path().join("services/api/gen/x.py"))
+        .is_some());
+    assert!(api
+        .walk
+        .skip_reason(&root.path().join("services/api/vendor/x.py"))
+        .is_none());
+}
+
+#[test]
+fn malformed_nested_config_is_an_error() {
+    let root = tempfile::tempdir().unwrap();
+    write_config(root.path(), "dialect = \"postgres\"\n");
+    write_config(&root.path().join("sub"), "dialect = \"nope\"\n");
+
+    assert!(config::discover(root.path()).unwrap().is_some());
+    let err = config::discover(&root.path().join("sub")).unwrap_err();
+    assert!(err.contains("invalid"), "{err}");
+}
@@ -0,0 +1,117 @@
+//! Enum types: `CREATE TYPE … AS ENUM` / `ALTER TYPE` ingestion and label
+//! checks on comparisons, `INSERT … VALUES`, and `UPDATE … SET`.
+
+use sqlshield::{validate_query, validate_query_with_dialect, Dialect};
+
+const SCHEMA: &str = "
+    CREATE TYPE order_status AS ENUM ('pending', 'active', 'shipped');
+    CREATE TABLE orders (id INT, status order_status, note TEXT);
+";
+
+fn run(sql: &str) -> Vec<String> {
+    validate_query(sql, SCHEMA).expect("SQL/schema should parse")
+}
+
+#[test]
+fn valid_label_in_where_passes() {
+    let errs = run("SELECT id FROM orders WHERE status = 'active'");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn misspelled_label_in_where_is_flagged() {
+    let errs = run("SELECT id FROM orders WHERE status = 'actve'");
+    assert_eq!(errs.len(), 1, "got: {errs:?}");
+    assert!(errs[0].contains("`actve`") && errs[0].contains("`order_status`"));
+}
+
+#[test]
+fn literal_on_left_and_not_equal_are_checked() {
+    let errs = run("SELECT o.id FROM orders o WHERE 'shiped' <> o.status");
+    assert!(errs.iter().any(|e| e.contains("`shiped`")), "got: {errs:?}");
+}
+
+#[test]
+fn in_list_labels_are_checked() {
+    let errs = run("SELECT id FROM orders WHERE status IN ('pending', 'pendng')");
+    assert_eq!(errs.len(), 1, "got: {errs:?}");
+    assert!(errs[0].contains("`pendng`"));
+}
+
+#[test]
+fn non_enum_column_accepts_any_literal() {
+    let errs = run("SELECT id FROM orders WHERE note = 'actve'");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn cte_and_derived_tables_shadowing_an_enum_table_are_not_checked() {
+    let errs =
+        run("WITH orders AS (SELECT 'x' AS status) SELECT status FROM orders WHERE status = 'x'");
+    assert!(errs.is_empty(), "got: {errs:?}");
+    let errs = run("SELECT status FROM (SELECT 'x' AS status) AS orders WHERE status = 'x'");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn insert_values_with_column_list_are_checked() {
+    let errs = run("INSERT INTO orders (id, status) VALUES (1, 'pendng')");
+    assert!(errs.iter().any(|e| e.contains("`pendng`")), "got: {errs:?}");
+}
+
+#[test]
+fn insert_values_without_column_list_use_declared_order() {
+    let errs = run("INSERT INTO orders VALUES (1, 'pending', 'x'), (2, 'lost', 'y')");
+    assert_eq!(errs.len(), 1, "got: {errs:?}");
+    assert!(errs[0].contains("`lost`"));
+}
+
+#[test]
+fn update_assignment_is_checked() {
+    let errs = run("UPDATE orders SET status = 'done' WHERE id = 1");
+    assert!(errs.iter().any(|e| e.contains("`done`")), "got: {errs:?}");
+}
+
+#[test]
+fn alter_type_add_value_extends_labels() {
+    let schema = "
+        CREATE TYPE order_status AS ENUM ('pending', 'active');
+        ALTER TYPE order_status ADD VALUE IF NOT EXISTS 'shipped' AFTER 'active';
+        CREATE TABLE orders (id INT, status order_status);
+    ";
+    let errs = validate_query("SELECT id FROM orders WHERE status = 'shipped'", schema).unwrap();
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn alter_type_rename_value_replaces_label() {
+    let schema = "
+        CREATE TYPE order_status AS ENUM ('pending', 'actve');
+        ALTER TYPE order_status RENAME VALUE 'actve' TO 'active';
+        CREATE TABLE orders (id INT, status order_status);
+    ";
+    let errs = validate_query("SELECT id FROM orders WHERE status = 'actve'", schema).unwrap();
+    assert!(errs.iter().any(|e| e.contains("`actve`")), "got: {errs:?}");
+}
+
+#[test]
+fn mysql_inline_enum_column_is_checked() {
+    let schema = "CREATE TABLE users (id INT, role ENUM('admin', 'member'));";
+    let errs = validate_query_with_dialect(
+        "SELECT id FROM users WHERE role = 'admn'",
+        schema,
+        Dialect::MySql,
+    )
+    .unwrap();
+    assert!(errs.iter().any(|e| e.contains("`admn`")), "got: {errs:?}");
+}
+
+#[test]
+fn composite_types_do_not_break_schema_loading() {
+    let schema = "
+        CREATE TYPE address AS (street TEXT, city TEXT);
+        CREATE TABLE users (id INT);
+    ";
+    let errs = validate_query("SELECT id FROM users", schema).unwrap();
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
@@ -1,7 +1,8 @@
-//! Tests for `find_queries_in_code` — the language-agnostic extraction entry point.
+//! Tests for `find_queries_in_code` / `extract_from_code` — the
+//! language-agnostic extraction entry points.
 
-use sqlshield::finder::find_queries_in_code;
-use sqlshield::SqlShieldError;
+use sqlshield::finder::{extract_from_code, find_queries_in_code, UnparsedKind};
+use sqlshield::{usage, SqlShieldError};
 
 #[test]
 fn unsupported_extension_returns_typed_error() {
@@ -19,6 +20,7 @@ def load(user_id):
     assert_eq!(queries.len(), 1);
     // parsed statements should be non-empty
     assert!(!queries[0].statements.is_empty());
+    assert_eq!(queries[0].text, "SELECT name FROM users WHERE id = 1");
 }
 
 #[test]
@@ -60,3 +62,172 @@ fn line_numbers_are_one_based() {
     assert_eq!(queries.len(), 1);
     assert_eq!(queries[0].line, 3);
 }
+
+#[test]
+fn query_span_covers_the_string_literal_in_characters() {
+    let source = "# é\nlabel = \"é\"; q = \"\"\"SELECT id\n  FROM users\"\"\"\n";
+    let queries = find_queries_in_code(source.as_bytes(), "py").unwrap();
+    assert_eq!(queries.len(), 1);
+    let q = &queries[0];
+    assert_eq!((q.line, q.column), (2, 18));
+    assert_eq!((q.end_line, q.end_column), (3, 16));
+}
+
+#[test]
+fn sql_files_are_split_into_statements() {
+    let source =
+        "-- report queries\nSELECT id FROM users;\n\nSELECT nope FROM;\nSELECT name\n  FROM users";
+    let queries = find_queries_in_code(source.as_bytes(), "sql").unwrap();
+    // The unparseable middle statement is left out of the queries.
+    assert_eq!(queries.len(), 2);
+    assert_eq!(
+        (
+            queries[0].line,
+            queries[0].column,
+            queries[0].end_line,
+            queries[0].end_column
+        ),
+        (2, 1, 2, 22)
+    );
+    assert_eq!(
+        (
+            queries[1].line,
+            queries[1].column,
+            queries[1].end_line,
+            queries[1].end_column
+        ),
+        (5, 1, 6, 13)
+    );
+    assert_eq!(
+        queries[1].statements[0].to_string(),
+        "SELECT name FROM users"
+    );
+}
+
+#[test]
+fn python_db_api_placeholders_parse_without_a_warning() {
+    let source = br#"
+cursor.execute("UPDATE users SET name = %s WHERE id = 1", (name,))
+cursor.execute("SELECT id FROM users WHERE name = %(name)s AND bio LIKE 'a%%'", params)
+"#;
+    let dialect = sqlparser::dialect::GenericDialect {};
+    let extracted = extract_from_code(source, "py", &dialect).unwrap();
+    assert!(extracted.unparsed.is_empty(), "{:?}", extracted.unparsed);
+    let rendered: Vec<String> = extracted
+        .queries
+        .iter()
+        .map(|q| q.statements[0].to_string())
+        .collect();
+    assert_eq!(
+        rendered,
+        vec![
+            "UPDATE users SET name = 1 WHERE id = 1",
+            "SELECT id FROM users WHERE name = 1 AND bio LIKE 'a%'",
+        ]
+    );
+}
+
+#[test]
+fn sql_that_does_not_parse_is_reported_but_prose_is_not() {
+    let source = br#"
+greeting = "Update your profile: it is out of date."
+broken = "SELECT id FROM users WHERE id = AND 1"
+ok = "SELECT id FROM users"
+"#;
+    let dialect = sqlparser::dialect::GenericDialect {};
+    let extracted = extract_from_code(source, "py", &dialect).unwrap();
+    assert_eq!(extracted.queries.len(), 1);
+    assert_eq!(extracted.unparsed.len(), 1, "{:?}", extracted.unparsed);
+    let u = &extracted.unparsed[0];
+    assert_eq!(u.kind, UnparsedKind::Sql);
+    assert_eq!((u.line, u.column), (3, 10));
+    assert!(
+        u.message.starts_with("Query doesn't parse: "),
+        "{}",
+        u.message
+    );
+    assert_eq!(u.text, "SELECT id FROM users WHERE id = AND 1");
+}
+
+#[test]
+fn unparseable_sql_file_statements_are_reported() {
+    let source = "SELECT id FROM users;\nSELECT nope FROM;\n";
+    let dialect = sqlparser::dialect::GenericDialect {};
+    let extracted = extract_from_code(source.as_bytes(), "sql", &dialect).unwrap();
+    assert_eq!(extracted.queries.len(), 1);
+    assert_eq!(extracted.unparsed.len(), 1);
+    assert_eq!(extracted.unparsed[0].line, 2);
+}
+
+#[test]
+fn source_syntax_errors_are_reported() {
+    let source = b"def f(:\n    return \"SELECT id FROM users\"\n";
+    let dialect = sqlparser::dialect::GenericDialect {};
+    let extracted = extract_from_code(source, "py", &dialect).unwrap();
+    let source_errors: Vec<_> = extracted
+        .unparsed
+        .iter()
+        .filter(|u| u.kind == UnparsedKind::Source)
+        .collect();
+    assert_eq!(source_errors.len(), 1, "{:?}", extracted.unparsed);
+    assert_eq!(source_errors[0].line, 1);
+}
+
+#[test]
+fn queries_keep_their_text_and_what_they_reference() {
+    let source = b"INSERT INTO app.users (id, name) VALUES (1, 'a');\nUPDATE users u SET name = 'b' WHERE u.id = 2;\n";
+    let queries = find_queries_in_code(source, "sql").unwrap();
+    assert_eq!(
+        queries.iter().map(|q| q.text.as_str()).collect::<Vec<_>>(),
+        [
+            "INSERT INTO app.users (id, name) VALUES (1, 'a')",
+            "UPDATE users u SET name = 'b' WHERE u.id = 2"
+        ]
+    );
+    assert_eq!(
+        usage::relation_names(&queries[0].statements),
+        ["app.users".to_string()].into()
+    );
+    assert_eq!(
+        usage::column_references(&queries[0].statements),
+        ["id".to_string(), "name".to_string()].into()
+    );
+    assert_eq!(
+        usage::column_references(&queries[1].statements),
+        ["name".to_string(), "u.id".to_string()].into()
+    );
+}
+
+#[test]
+fn queries_know_the_function_they_are_in() {
+    let function = |source: &[u8], ext| {
+        find_queries_in_code(source, ext).unwrap()[0]
+            .function
+            .clone()
+    };
+    assert_eq!(
+        function(
+            b"impl Repo { fn load(&self) { q(\"SELECT id FROM users\"); } }",
+            "rs"
+        )
+        .as_deref(),
+        Some("load")
+    );
+    assert_eq!(
+        function(
+            b"const load = async () => db.query(`SELECT id FROM users`);",
+            "ts"
+        )
+        .as_deref(),
+        Some("load")
+    );
+    assert_eq!(
+        function(
+            b"package db\nfunc (r Repo) Load() { r.q(\"SELECT id FROM users\") }",
+            "go"
+        )
+        .as_deref(),
+        Some("Load")
+    );
+    assert_eq!(function(b"Q = \"SELECT id FROM users\"\n", "py"), None);
+}
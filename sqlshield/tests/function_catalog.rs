@@ -0,0 +1,298 @@
+//! Function calls: existence in the dialect's built-in catalog or the
+//! schema's `CREATE FUNCTION`s, and argument counts.
+
+use sqlshield::{
+    schema, validate_query, validate_query_against_schema, validate_query_with_dialect, Dialect,
+};
+
+const SCHEMA: &str = "CREATE TABLE events (id INT, a TEXT, b TEXT, ts TIMESTAMP);";
+
+fn run(sql: &str) -> Vec<String> {
+    validate_query(sql, SCHEMA).expect("SQL/schema should parse")
+}
+
+fn run_with(sql: &str, dialect: Dialect) -> Vec<String> {
+    validate_query_with_dialect(sql, SCHEMA, dialect).expect("SQL/schema should parse")
+}
+
+#[test]
+fn known_functions_pass() {
+    let errs = run("SELECT coalesce(a, b), upper(a), length(b) FROM events");
+    assert!(errs.is_empty(), "got: {errs:?}");
+    let errs = run("SELECT count(*), max(ts) FROM events");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn misspelled_function_is_flagged() {
+    let errs = run("SELECT coalese(a, b) FROM events");
+    assert_eq!(errs.len(), 1, "got: {errs:?}");
+    assert!(errs[0].contains("`coalese`") && errs[0].contains("not found"));
+}
+
+#[test]
+fn missing_argument_is_flagged() {
+    let errs = run_with("SELECT date_trunc(ts) FROM events", Dialect::Postgres);
+    assert_eq!(errs.len(), 1, "got: {errs:?}");
+    assert!(
+        errs[0].contains("`date_trunc`") && errs[0].contains("expects 2 to 3 arguments, got 1"),
+        "got: {errs:?}"
+    );
+}
+
+#[test]
+fn calls_nested_in_where_are_checked() {
+    let errs = run("SELECT id FROM events WHERE lenght(a) > 3");
+    assert!(errs.iter().any(|e| e.contains("`lenght`")), "got: {errs:?}");
+}
+
+#[test]
+fn catalogs_are_per_dialect() {
+    let sql = "SELECT ifnull(a, b) FROM events";
+    assert!(run_with(sql, Dialect::MySql).is_empty());
+    let errs = run_with(sql, Dialect::Postgres);
+    assert!(errs.iter().any(|e| e.contains("`ifnull`")), "got: {errs:?}");
+}
+
+#[test]
+fn incomplete_catalogs_only_check_arity() {
+    let errs = run_with("SELECT someUdf(a) FROM events", Dialect::ClickHouse);
+    assert!(errs.is_empty(), "got: {errs:?}");
+    let errs = run_with("SELECT today(a) FROM events", Dialect::ClickHouse);
+    assert!(errs.iter().any(|e| e.contains("`today`")), "got: {errs:?}");
+}
+
+/// Functions from the Postgres function index that application SQL calls;
+/// each must be known to both the Postgres and the generic catalog.
+const POSTGRES_INDEX_CALLS: &[&str] = &[
+    "inet_client_port()",
+    "inet_server_addr()",
+    "inet_server_port()",
+    "abbrev(a)",
+    "broadcast(a)",
+    "family(a)",
+    "hostmask(a)",
+    "masklen(a)",
+    "netmask(a)",
+    "network(a)",
+    "set_masklen(a, a)",
+    "inet_same_family(a, a)",
+    "inet_merge(a, a)",
+    "any_value(a)",
+    "bit_xor(a)",
+    "range_agg(a)",
+    "range_intersect_agg(a)",
+    "json_agg_strict(a)",
+    "jsonb_agg_strict(a)",
+    "json_object_agg_strict(a, a)",
+    "jsonb_object_agg_strict(a, a)",
+    "json_object_agg_unique(a, a)",
+    "jsonb_object_agg_unique(a, a)",
+    "xmlagg(a)",
+    "regr_count(a, a)",
+    "regr_avgx(a, a)",
+    "regr_avgy(a, a)",
+    "regr_r2(a, a)",
+    "regr_sxx(a, a)",
+    "regr_sxy(a, a)",
+    "regr_syy(a, a)",
+    "int4range(a, a)",
+    "int8range(a, a)",
+    "numrange(a, a)",
+    "tsrange(a, a)",
+    "tstzrange(a, a)",
+    "daterange(a, a)",
+    "isempty(a)",
+    "lower_inc(a)",
+    "upper_inc(a)",
+    "lower_inf(a)",
+    "upper_inf(a)",
+    "range_merge(a, a)",
+    "gcd(a, a)",
+    "lcm(a, a)",
+    "factorial(a)",
+    "pow(a, a)",
+    "scale(a)",
+    "min_scale(a)",
+    "trim_scale(a)",
+    "cot(a)",
+    "sinh(a)",
+    "cosh(a)",
+    "tanh(a)",
+    "asinh(a)",
+    "acosh(a)",
+    "atanh(a)",
+    "sind(a)",
+    "cosd(a)",
+    "tand(a)",
+    "asind(a)",
+    "acosd(a)",
+    "atand(a)",
+    "atan2d(a, a)",
+    "random_normal()",
+    "string_to_table(a, a)",
+    "parse_ident(a)",
+    "to_ascii(a)",
+    "to_bin(a)",
+    "to_oct(a)",
+    "unistr(a)",
+    "convert_from(a, a)",
+    "convert_to(a, a)",
+    "sha224(a)",
+    "sha384(a)",
+    "sha512(a)",
+    "get_byte(a, a)",
+    "set_byte(a, a, a)",
+    "get_bit(a, a)",
+    "set_bit(a, a, a)",
+    "bit_count(a)",
+    "date_add(a, a)",
+    "date_subtract(a, a)",
+    "array_sample(a, a)",
+    "array_shuffle(a)",
+    "trim_array(a, a)",
+    "json_populate_recordset(a, a)",
+    "jsonb_populate_recordset(a, a)",
+    "jsonb_path_match(a, a)",
+    "jsonb_path_query_array(a, a)",
+    "jsonb_set_lax(a, a, a)",
+    "json_scalar(a)",
+    "jsonb_to_tsvector(a, a)",
+    "json_to_tsvector(a, a)",
+    "array_to_tsvector(a)",
+    "tsvector_to_array(a)",
+    "ts_delete(a, a)",
+    "ts_filter(a, a)",
+    "strip(a)",
+    "numnode(a)",
+    "querytree(a)",
+    "pg_notify(a, a)",
+    "pg_listening_channels()",
+    "pg_notification_queue_usage()",
+    "pg_advisory_lock_shared(a)",
+    "pg_advisory_unlock_shared(a)",
+    "pg_advisory_unlock_all()",
+    "pg_try_advisory_lock_shared(a)",
+    "pg_advisory_xact_lock_shared(a)",
+    "pg_try_advisory_xact_lock_shared(a)",
+    "pg_current_xact_id()",
+    "pg_current_xact_id_if_assigned()",
+    "pg_xact_status(a)",
+    "txid_current_if_assigned()",
+    "pg_blocking_pids(a)",
+    "pg_cancel_backend(a)",
+    "pg_terminate_backend(a)",
+    "pg_reload_conf()",
+    "pg_is_in_recovery()",
+    "pg_postmaster_start_time()",
+    "pg_conf_load_time()",
+    "pg_current_wal_lsn()",
+    "pg_trigger_depth()",
+    "pg_my_temp_schema()",
+    "current_schemas(a)",
+    "to_regclass(a)",
+    "to_regtype(a)",
+    "to_regproc(a)",
+    "to_regprocedure(a)",
+    "to_regnamespace(a)",
+    "to_regrole(a)",
+    "pg_get_serial_sequence(a, a)",
+    "pg_get_viewdef(a)",
+    "pg_get_indexdef(a)",
+    "pg_get_constraintdef(a)",
+    "pg_get_functiondef(a)",
+    "pg_get_expr(a, a)",
+    "pg_get_userbyid(a)",
+    "format_type(a, a)",
+    "obj_description(a)",
+    "col_description(a, a)",
+    "shobj_description(a, a)",
+    "pg_table_is_visible(a)",
+    "pg_input_is_valid(a, a)",
+    "has_schema_privilege(a, a)",
+    "has_database_privilege(a, a)",
+    "has_column_privilege(a, a, a)",
+    "has_function_privilege(a, a)",
+    "has_sequence_privilege(a, a)",
+    "pg_has_role(a, a)",
+    "row_security_active(a)",
+    "pg_database_size(a)",
+    "pg_table_size(a)",
+    "pg_indexes_size(a)",
+    "pg_size_pretty(a)",
+    "pg_size_bytes(a)",
+    "pg_column_compression(a)",
+    "pg_relation_filenode(a)",
+    "pg_relation_filepath(a)",
+];
+
+#[test]
+fn postgres_function_index_entries_are_known() {
+    for call in POSTGRES_INDEX_CALLS {
+        let sql = format!("SELECT {call} FROM events");
+        let errs = run_with(&sql, Dialect::Postgres);
+        assert!(errs.is_empty(), "{call} (postgres): {errs:?}");
+        let errs = run(&sql);
+        assert!(errs.is_empty(), "{call} (generic): {errs:?}");
+    }
+}
+
+#[test]
+fn keyword_functions_without_parens_pass() {
+    let errs = run("SELECT id FROM events WHERE ts < CURRENT_TIMESTAMP");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn schema_functions_are_known_with_their_arity() {
+    let schema = "
+        CREATE TABLE events (id INT, a TEXT);
+        CREATE OR REPLACE FUNCTION slugify(input TEXT, sep TEXT DEFAULT '-')
+        RETURNS TEXT LANGUAGE sql IMMUTABLE AS $$
+            SELECT lower(replace(input, ' ', sep));
+        $$;
+    ";
+    let ok = validate_query("SELECT slugify(a), slugify(a, '_') FROM events", schema).unwrap();
+    assert!(ok.is_empty(), "got: {ok:?}");
+    let errs = validate_query("SELECT slugify() FROM events", schema).unwrap();
+    assert!(
+        errs.iter()
+            .any(|e| e.contains("`slugify` expects 1 to 2 arguments, got 0")),
+        "got: {errs:?}"
+    );
+}
+
+#[test]
+fn schema_functions_with_block_bodies_load() {
+    let schema = "
+        CREATE FUNCTION add_tax(amount DECIMAL(10, 2)) RETURNS DECIMAL(10, 2)
+        BEGIN
+            IF amount < 0 THEN RETURN 0; END IF;
+            RETURN amount * 1.2;
+        END;
+        CREATE TABLE orders (id INT, total DECIMAL(10, 2));
+    ";
+    let errs =
+        validate_query_with_dialect("SELECT add_tax(total) FROM orders", schema, Dialect::MySql)
+            .unwrap();
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn qualified_unknown_functions_are_not_flagged() {
+    let errs = run("SELECT ext.similarity(a, b) FROM events");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn allowed_extension_functions_accept_any_arguments() {
+    let mut loaded = schema::load_schema(SCHEMA.as_bytes(), "sql", Dialect::Postgres).unwrap();
+    loaded.allow_functions(&["ST_Distance"], Dialect::Postgres);
+    let errs = validate_query_against_schema(
+        "SELECT st_distance(a, b, true) FROM events",
+        &loaded,
+        Dialect::Postgres,
+    )
+    .unwrap();
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
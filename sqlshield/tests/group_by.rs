@@ -0,0 +1,142 @@
+//! GROUP BY / aggregate correctness: ungrouped columns in aggregated
+//! queries, aggregates in WHERE, nested aggregates, primary-key functional
+//! dependency, and MySQL's `ONLY_FULL_GROUP_BY`.
+
+use sqlshield::{schema, validate_query, validate_query_against_schema, Dialect};
+
+const SCHEMA: &str = "
+    CREATE TABLE users (id INT PRIMARY KEY, name TEXT, email TEXT);
+    CREATE TABLE orders (id INT, user_id INT, total INT, created_at TIMESTAMP);
+";
+
+fn run(sql: &str) -> Vec<String> {
+    validate_query(sql, SCHEMA).expect("SQL/schema should parse")
+}
+
+#[test]
+fn ungrouped_projection_column_is_flagged() {
+    let errs = run("SELECT user_id, total FROM orders GROUP BY user_id");
+    assert_eq!(errs.len(), 1, "got: {errs:?}");
+    assert!(
+        errs[0].contains("`total` in SELECT must appear in GROUP BY"),
+        "got: {errs:?}"
+    );
+}
+
+#[test]
+fn grouped_and_aggregated_columns_pass() {
+    let errs = run("SELECT user_id, sum(total), count(*) FROM orders GROUP BY user_id");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn aggregate_without_group_by_flags_bare_columns() {
+    let errs = run("SELECT user_id, count(*) FROM orders");
+    assert!(
+        errs.iter().any(|e| e.contains("`user_id`")),
+        "got: {errs:?}"
+    );
+}
+
+#[test]
+fn having_and_order_by_columns_are_checked() {
+    let errs =
+        run("SELECT user_id FROM orders GROUP BY user_id HAVING total > 10 ORDER BY created_at");
+    assert!(
+        errs.iter().any(|e| e.contains("`total` in HAVING")),
+        "got: {errs:?}"
+    );
+    assert!(
+        errs.iter().any(|e| e.contains("`created_at` in ORDER BY")),
+        "got: {errs:?}"
+    );
+}
+
+#[test]
+fn grouped_expressions_aliases_and_positions_match() {
+    let errs = run(
+        "SELECT date_trunc('day', created_at) AS day, count(*) FROM orders
+         GROUP BY date_trunc('day', created_at)",
+    );
+    assert!(errs.is_empty(), "got: {errs:?}");
+    let errs = run("SELECT user_id AS u, sum(total) AS s FROM orders GROUP BY u ORDER BY s");
+    assert!(errs.is_empty(), "got: {errs:?}");
+    let errs = run("SELECT user_id, sum(total) FROM orders GROUP BY 1");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn grouped_primary_key_covers_other_columns() {
+    let errs = run("SELECT u.id, u.name, count(o.id) FROM users u
+         JOIN orders o ON o.user_id = u.id GROUP BY u.id");
+    assert!(errs.is_empty(), "got: {errs:?}");
+    // `orders` declares no primary key, so nothing is implied for it.
+    let errs = run("SELECT u.id, o.total FROM users u
+         JOIN orders o ON o.user_id = u.id GROUP BY u.id");
+    assert!(
+        errs.iter().any(|e| e.contains("`o.total`")),
+        "got: {errs:?}"
+    );
+}
+
+#[test]
+fn aggregate_in_where_is_flagged() {
+    let errs = run("SELECT user_id FROM orders WHERE count(*) > 1");
+    assert!(
+        errs.iter()
+            .any(|e| e.contains("`count` is not allowed in WHERE")),
+        "got: {errs:?}"
+    );
+}
+
+#[test]
+fn nested_aggregate_is_flagged() {
+    let errs = run("SELECT sum(max(total)) FROM orders GROUP BY user_id");
+    assert!(
+        errs.iter()
+            .any(|e| e.contains("`max` cannot be nested inside aggregate `sum`")),
+        "got: {errs:?}"
+    );
+}
+
+#[test]
+fn window_over_aggregate_is_allowed() {
+    let errs = run("SELECT user_id, sum(count(*)) OVER () FROM orders GROUP BY user_id");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn aggregates_in_subqueries_use_their_own_scope() {
+    let errs = run(
+        "SELECT id FROM users WHERE id IN (SELECT user_id FROM orders GROUP BY user_id HAVING count(*) > 2)",
+    );
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn mysql_only_full_group_by_is_configurable() {
+    let sql = "SELECT user_id, total FROM orders GROUP BY user_id";
+    let mut loaded = schema::load_schema(SCHEMA.as_bytes(), "sql", Dialect::MySql).unwrap();
+    let errs = validate_query_against_schema(sql, &loaded, Dialect::MySql).unwrap();
+    assert_eq!(errs.len(), 1, "got: {errs:?}");
+
+    loaded.settings.only_full_group_by = false;
+    let errs = validate_query_against_schema(sql, &loaded, Dialect::MySql).unwrap();
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn primary_key_from_table_constraint_and_alter() {
+    let schema = "
+        CREATE TABLE a (id INT, x INT, PRIMARY KEY (id));
+        CREATE TABLE b (id INT, y INT);
+        ALTER TABLE b ADD CONSTRAINT b_pk PRIMARY KEY (id);
+    ";
+    for sql in [
+        "SELECT id, x FROM a GROUP BY id",
+        "SELECT id, y FROM b GROUP BY id",
+    ] {
+        let errs = validate_query(sql, schema).unwrap();
+        assert!(errs.is_empty(), "{sql}: {errs:?}");
+    }
+}
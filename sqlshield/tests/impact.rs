@@ -0,0 +1,117 @@
+//! Migration impact: the queries a schema change breaks, and why.
+
+use std::fs;
+
+use sqlshield::impact::{self, Breakage, Renames};
+use sqlshield::schema::{self, Schema};
+use sqlshield::Dialect;
+
+const DDL: &str = "
+    CREATE TYPE status AS ENUM ('active', 'banned', 'legacy');
+    CREATE TABLE users (id INT, name TEXT, nickname TEXT, state status);
+    CREATE TABLE sessions (id INT, user_id INT);
+";
+
+fn schemas(migration: &str) -> (Schema, Schema) {
+    let before = schema::load_schema(DDL.as_bytes(), "sql", Dialect::Postgres).unwrap();
+    let mut after = before.clone();
+    schema::apply_ddl(&mut after, migration.as_bytes(), Dialect::Postgres).unwrap();
+    (before, after)
+}
+
+fn breakages(code: &str, migration: &str) -> Vec<Breakage> {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("app.py");
+    fs::write(&file, code).unwrap();
+    let (before, after) = schemas(migration);
+    let mut renames = Renames::default();
+    renames
+        .record(migration.as_bytes(), Dialect::Postgres)
+        .unwrap();
+    impact::breakages(&[file], &before, &after, &renames, Dialect::Postgres)
+}
+
+#[test]
+fn only_queries_the_migration_breaks_are_reported() {
+    let code = "\
+def nick(uid):
+    return f\"SELECT nickname FROM users WHERE id = {uid}\"
+
+def names():
+    return \"SELECT id, name FROM users\"
+
+def sessions():
+    return \"SELECT id FROM sessions\"
+";
+    let found = breakages(
+        code,
+        "ALTER TABLE users DROP COLUMN nickname; DROP TABLE sessions;",
+    );
+    let found: Vec<(usize, Option<&str>, &[String])> = found
+        .iter()
+        .map(|b| (b.line, b.function.as_deref(), b.reasons.as_slice()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                2,
+                Some("nick"),
+                &["column users.nickname dropped".to_string()][..]
+            ),
+            (
+                8,
+                Some("sessions"),
+                &["table sessions dropped".to_string()][..]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn errors_the_query_already_has_are_not_the_migrations() {
+    let code = "Q = \"SELECT missing, nickname FROM users\"\n";
+    let found = breakages(code, "ALTER TABLE users DROP COLUMN nickname;");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].errors.len(), 1);
+    assert!(found[0].errors[0].message.contains("nickname"));
+    assert_eq!(found[0].errors[0].code, "unknown-column");
+
+    assert!(breakages(code, "ALTER TABLE users ADD COLUMN age INT;").is_empty());
+}
+
+#[test]
+fn renamed_tables_and_columns_are_reported_as_renames() {
+    let code = "\
+A = \"SELECT nickname FROM users\"
+B = \"SELECT user_id FROM sessions\"
+";
+    let found = breakages(
+        code,
+        "ALTER TABLE users RENAME COLUMN nickname TO nick;
+         ALTER TABLE users RENAME COLUMN nick TO handle;
+         ALTER TABLE sessions RENAME TO logins;",
+    );
+    let reasons: Vec<&[String]> = found.iter().map(|b| b.reasons.as_slice()).collect();
+    assert_eq!(
+        reasons,
+        vec![
+            &["column users.nickname renamed to handle".to_string()][..],
+            &["table sessions renamed to logins".to_string()][..],
+        ]
+    );
+}
+
+#[test]
+fn removed_enum_labels_are_a_reason() {
+    let code = "Q = \"SELECT id FROM users WHERE state = 'legacy'\"\n";
+    let found = breakages(
+        code,
+        "ALTER TYPE status RENAME VALUE 'legacy' TO 'retired';",
+    );
+    assert_eq!(found.len(), 1);
+    assert_eq!(
+        found[0].reasons,
+        vec!["column users.state lost enum label(s) 'legacy'"]
+    );
+}
@@ -0,0 +1,150 @@
+//! Foreign-key-aware join lint: ON / USING equalities that follow no declared
+//! foreign key between the joined tables, or compare incompatible types.
+
+use sqlshield::lint::{RuleConfig, Severity};
+use sqlshield::{lint_query, schema, validate_query, Dialect};
+
+const SCHEMA: &str = "
+    CREATE TABLE users (id INT PRIMARY KEY, email TEXT, manager_id INT REFERENCES users);
+    CREATE TABLE orders (
+        id INT PRIMARY KEY,
+        user_id INT,
+        created_at TIMESTAMP,
+        FOREIGN KEY (user_id) REFERENCES users (id)
+    );
+    CREATE TABLE audit (id INT, note TEXT);
+";
+
+fn lint(sql: &str, ddl: &str) -> Vec<String> {
+    let loaded = schema::load_schema(ddl.as_bytes(), "sql", Dialect::Generic).unwrap();
+    lint_query(sql, &loaded, Dialect::Generic, &RuleConfig::default())
+        .expect("SQL should parse")
+        .into_iter()
+        .filter(|l| l.code.starts_with("join-"))
+        .map(|l| l.message)
+        .collect()
+}
+
+fn run(sql: &str) -> Vec<String> {
+    lint(sql, SCHEMA)
+}
+
+#[test]
+fn join_following_foreign_key_passes() {
+    let errs = run("SELECT o.id FROM orders o JOIN users u ON o.user_id = u.id");
+    assert!(errs.is_empty(), "got: {errs:?}");
+    let errs = run("SELECT o.id FROM users u JOIN orders o ON u.id = o.user_id");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn join_on_wrong_columns_is_flagged() {
+    let errs = run("SELECT o.id FROM orders o JOIN users u ON o.id = u.id");
+    assert_eq!(
+        errs,
+        vec![
+            "JOIN condition `o.id = u.id` follows no foreign key between `orders` and `users`; \
+             expected `orders.user_id = users.id`"
+        ]
+    );
+}
+
+#[test]
+fn join_using_the_wrong_column_is_flagged() {
+    let errs = run("SELECT o.id FROM users u JOIN orders o USING (id)");
+    assert_eq!(
+        errs,
+        vec![
+            "JOIN condition `u.id = o.id` follows no foreign key between `orders` and `users`; \
+             expected `orders.user_id = users.id`"
+        ]
+    );
+    let ddl = "
+        CREATE TABLE accounts (account_id INT PRIMARY KEY);
+        CREATE TABLE logins (id INT, account_id INT REFERENCES accounts (account_id));
+    ";
+    let errs = lint(
+        "SELECT l.id FROM accounts a JOIN logins l USING (account_id)",
+        ddl,
+    );
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn extra_conditions_beside_a_key_pass() {
+    let errs =
+        run("SELECT o.id FROM orders o JOIN users u ON o.user_id = u.id AND o.id = u.manager_id");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn unrelated_tables_are_not_judged() {
+    let errs = run("SELECT a.id FROM audit a JOIN users u ON a.id = u.id");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn self_referencing_key_defaults_to_primary_key() {
+    let errs = run("SELECT e.id FROM users e JOIN users m ON e.manager_id = m.id");
+    assert!(errs.is_empty(), "got: {errs:?}");
+    // Comparing a column with itself is an identity join, not a key.
+    let errs = run("SELECT e.id FROM users e JOIN users m ON e.id = m.id");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn incompatible_column_types_are_flagged() {
+    let errs = run("SELECT a.id FROM audit a JOIN users u ON a.note = u.id");
+    assert_eq!(
+        errs,
+        vec!["JOIN condition `a.note = u.id` compares `audit.note` (TEXT) with `users.id` (INT)"]
+    );
+    let errs = run("SELECT a.id FROM audit a JOIN orders o ON a.id = o.created_at");
+    assert!(
+        errs.iter().any(|e| e.contains("(TIMESTAMP)")),
+        "got: {errs:?}"
+    );
+}
+
+#[test]
+fn foreign_keys_are_ingested_from_alter_table() {
+    let ddl = "
+        CREATE TABLE users (id INT PRIMARY KEY);
+        CREATE TABLE posts (id INT, author_id INT);
+        ALTER TABLE posts ADD CONSTRAINT posts_author_fk FOREIGN KEY (author_id) REFERENCES users (id);
+    ";
+    let loaded = schema::load_schema(ddl.as_bytes(), "sql", Dialect::Generic).unwrap();
+    assert_eq!(
+        loaded.foreign_key_pairs("posts", "users"),
+        vec![("author_id", "id")]
+    );
+    let errs = lint("SELECT p.id FROM posts p JOIN users u ON p.id = u.id", ddl);
+    assert_eq!(errs.len(), 1, "got: {errs:?}");
+}
+
+#[test]
+fn join_findings_are_warnings_not_validation_errors() {
+    let sql = "SELECT o.id FROM orders o JOIN users u ON o.id = u.id";
+    assert!(validate_query(sql, SCHEMA).unwrap().is_empty());
+    let loaded = schema::load_schema(SCHEMA.as_bytes(), "sql", Dialect::Generic).unwrap();
+    let lints = lint_query(sql, &loaded, Dialect::Generic, &RuleConfig::default()).unwrap();
+    assert_eq!(lints.len(), 1, "got: {lints:?}");
+    assert_eq!(lints[0].code, "join-without-foreign-key");
+    assert_eq!(lints[0].severity, Severity::Warning);
+}
+
+#[test]
+fn ctes_and_derived_tables_shadowing_a_table_are_not_judged() {
+    let errs =
+        run("WITH users AS (SELECT 1 AS id) SELECT o.id FROM orders o JOIN users u ON o.id = u.id");
+    assert!(errs.is_empty(), "got: {errs:?}");
+    let errs = run("SELECT o.id FROM orders o JOIN (SELECT 'x' AS id) users ON o.id = users.id");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn joins_inside_parenthesized_groups_are_checked() {
+    let errs =
+        run("SELECT a.id FROM audit a JOIN (orders o JOIN users u ON o.id = u.id) ON a.id = o.id");
+    assert_eq!(errs.len(), 1, "got: {errs:?}");
+}
@@ -0,0 +1,102 @@
+//! Table-valued FROM items: set-returning functions, `UNNEST`, `JSON_TABLE`,
+//! and `LATERAL` subqueries are relations with their declared alias columns.
+
+use sqlshield::{validate_query_with_dialect, Dialect};
+
+const SCHEMA: &str = "
+    CREATE TABLE users (id INT, name TEXT, tags TEXT);
+    CREATE TABLE orders (id INT, user_id INT, total INT);
+";
+
+fn run(sql: &str) -> Vec<String> {
+    validate_query_with_dialect(sql, SCHEMA, Dialect::Postgres).expect("SQL/schema should parse")
+}
+
+#[test]
+fn table_function_is_not_a_missing_table() {
+    let errs = run("SELECT g FROM generate_series(1, 10) g");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn undeclared_function_columns_are_not_judged() {
+    let errs = run("SELECT u.id, g FROM users u, generate_series(1, 3) g");
+    assert!(errs.is_empty(), "got: {errs:?}");
+    let errs = run("SELECT e.key, e.value FROM json_each('{}') e");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn unnest_alias_columns_are_resolved() {
+    let errs = run("SELECT t.x FROM unnest(ARRAY[1, 2]) AS t(x)");
+    assert!(errs.is_empty(), "got: {errs:?}");
+    let errs = run("SELECT t.y FROM unnest(ARRAY[1, 2]) AS t(x)");
+    assert_eq!(errs, vec!["Column `y` not found in table `t`"]);
+}
+
+#[test]
+fn typed_column_definition_lists_are_resolved() {
+    let errs = run("SELECT r.a, r.b FROM users u,
+         jsonb_to_recordset(u.tags::jsonb) AS r(a int, b varchar(10))");
+    assert!(errs.is_empty(), "got: {errs:?}");
+    let errs = run("SELECT r.c FROM jsonb_to_recordset('[]') AS r(a int, b double precision)");
+    assert_eq!(errs, vec!["Column `c` not found in table `r`"]);
+}
+
+#[test]
+fn parenthesized_lists_that_are_not_aliases_keep_parsing() {
+    let errs = run("SELECT count(*) OVER (PARTITION BY name ORDER BY id) FROM users");
+    assert!(errs.is_empty(), "got: {errs:?}");
+    let errs = run("SELECT id FROM users WHERE (id) IN (SELECT user_id FROM orders)");
+    assert!(errs.is_empty(), "got: {errs:?}");
+    let errs = run("SELECT CAST(abs(id) AS numeric(10, 2)) FROM users");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn declared_columns_expand_through_wildcards() {
+    let errs = run("SELECT d.x FROM (SELECT * FROM unnest(ARRAY[1]) AS t(x)) d");
+    assert!(errs.is_empty(), "got: {errs:?}");
+    let errs = run("SELECT d.y FROM (SELECT * FROM unnest(ARRAY[1]) AS t(x)) d");
+    assert!(errs.iter().any(|e| e.contains("`y`")), "got: {errs:?}");
+}
+
+#[test]
+fn function_arguments_see_preceding_from_items() {
+    let errs = run("SELECT u.id, s FROM users u CROSS JOIN LATERAL generate_series(1, u.id) s");
+    assert!(errs.is_empty(), "got: {errs:?}");
+    let errs = run("SELECT u.id, s FROM users u CROSS JOIN LATERAL generate_series(1, u.idd) s");
+    assert_eq!(errs, vec!["Column `idd` not found in table `users`"]);
+}
+
+#[test]
+fn lateral_subquery_sees_preceding_from_items() {
+    let errs = run("SELECT u.id, o.total FROM users u
+         CROSS JOIN LATERAL (SELECT total FROM orders WHERE orders.user_id = u.id LIMIT 1) o");
+    assert!(errs.is_empty(), "got: {errs:?}");
+    let errs = run("SELECT u.id FROM users u
+         CROSS JOIN LATERAL (SELECT total FROM orders WHERE orders.user_id = u.uid) o");
+    assert!(
+        errs.iter()
+            .any(|e| e.contains("`uid` not found in table `users`")),
+        "got: {errs:?}"
+    );
+    let errs = run("SELECT o.missing FROM users u
+         CROSS JOIN LATERAL (SELECT total FROM orders WHERE orders.user_id = u.id) o");
+    assert!(
+        errs.iter().any(|e| e.contains("`missing`")),
+        "got: {errs:?}"
+    );
+}
+
+#[test]
+fn mysql_json_table_columns_are_resolved() {
+    let sql = "SELECT jt.a FROM users u,
+         JSON_TABLE(u.tags, '$[*]' COLUMNS(a INT PATH '$.a')) AS jt";
+    let errs = validate_query_with_dialect(sql, SCHEMA, Dialect::MySql).unwrap();
+    assert!(errs.is_empty(), "got: {errs:?}");
+    let sql = "SELECT jt.b FROM users u,
+         JSON_TABLE(u.tags, '$[*]' COLUMNS(a INT PATH '$.a')) AS jt";
+    let errs = validate_query_with_dialect(sql, SCHEMA, Dialect::MySql).unwrap();
+    assert_eq!(errs, vec!["Column `b` not found in table `jt`"]);
+}
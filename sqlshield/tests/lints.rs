@@ -0,0 +1,108 @@
+//! Safety lints: codes, default severities, and per-rule configuration.
+
+use sqlshield::lint::{RuleConfig, Severity};
+use sqlshield::{lint_query, schema, Dialect};
+
+const SCHEMA: &str = "
+    CREATE TABLE users (id INT PRIMARY KEY, email TEXT NOT NULL, team_id INT);
+    CREATE TABLE teams (id INT PRIMARY KEY, name TEXT);
+";
+
+fn codes_with(sql: &str, rules: &RuleConfig) -> Vec<&'static str> {
+    let loaded = schema::load_schema(SCHEMA.as_bytes(), "sql", Dialect::Generic).unwrap();
+    lint_query(sql, &loaded, Dialect::Generic, rules)
+        .expect("SQL should parse")
+        .into_iter()
+        .map(|l| l.code)
+        .collect()
+}
+
+fn codes(sql: &str) -> Vec<&'static str> {
+    codes_with(sql, &RuleConfig::default())
+}
+
+#[test]
+fn update_and_delete_without_where() {
+    assert_eq!(
+        codes("UPDATE users SET email = 'x'"),
+        vec!["update-without-where"]
+    );
+    assert_eq!(codes("DELETE FROM users"), vec!["delete-without-where"]);
+    assert!(codes("UPDATE users SET email = 'x' WHERE id = 1").is_empty());
+    assert!(codes("DELETE FROM users WHERE id = 1").is_empty());
+}
+
+#[test]
+fn comma_join_without_predicate() {
+    assert_eq!(
+        codes("SELECT u.id FROM users u, teams t"),
+        vec!["implicit-cross-join"]
+    );
+    assert!(codes("SELECT u.id FROM users u, teams t WHERE u.team_id = t.id").is_empty());
+    // Unqualified columns are attributed through the schema.
+    assert!(codes("SELECT email FROM users, teams WHERE team_id = name").is_empty());
+    // An explicit CROSS JOIN says it on purpose.
+    assert!(codes("SELECT u.id FROM users u CROSS JOIN teams t").is_empty());
+}
+
+#[test]
+fn select_star_only_in_the_result() {
+    assert_eq!(codes("SELECT * FROM users"), vec!["select-star"]);
+    assert_eq!(codes("SELECT u.* FROM users u"), vec!["select-star"]);
+    assert!(codes(
+        "SELECT id FROM users WHERE EXISTS (SELECT * FROM teams WHERE teams.id = users.team_id)"
+    )
+    .is_empty());
+}
+
+#[test]
+fn not_in_over_nullable_column() {
+    assert_eq!(
+        codes("SELECT id FROM teams WHERE id NOT IN (SELECT team_id FROM users)"),
+        vec!["not-in-nullable"]
+    );
+    assert!(codes("SELECT id FROM users WHERE id NOT IN (SELECT id FROM teams)").is_empty());
+    assert!(codes(
+        "SELECT id FROM teams WHERE id NOT IN (SELECT team_id FROM users WHERE team_id IS NOT NULL)"
+    )
+    .is_empty());
+}
+
+#[test]
+fn comparison_with_null() {
+    let loaded = schema::load_schema(SCHEMA.as_bytes(), "sql", Dialect::Generic).unwrap();
+    let lints = lint_query(
+        "SELECT id FROM users WHERE team_id = NULL",
+        &loaded,
+        Dialect::Generic,
+        &RuleConfig::default(),
+    )
+    .unwrap();
+    assert_eq!(lints.len(), 1, "got: {lints:?}");
+    assert_eq!(lints[0].code, "null-comparison");
+    assert_eq!(lints[0].severity, Severity::Warning);
+    assert!(lints[0].message.contains("use `IS NULL`"));
+}
+
+#[test]
+fn limit_without_order_by() {
+    assert_eq!(
+        codes("SELECT id FROM users LIMIT 10"),
+        vec!["limit-without-order-by"]
+    );
+    assert!(codes("SELECT id FROM users ORDER BY id LIMIT 10").is_empty());
+    assert!(codes(
+        "SELECT id FROM teams WHERE EXISTS (SELECT 1 FROM users WHERE users.team_id = teams.id LIMIT 1)"
+    )
+    .is_empty());
+}
+
+#[test]
+fn rules_can_be_disabled_or_escalated() {
+    let mut rules = RuleConfig::default();
+    rules.set("select-star", Severity::Off).unwrap();
+    rules.set("delete-without-where", Severity::Error).unwrap();
+    assert!(codes_with("SELECT * FROM users", &rules).is_empty());
+    assert_eq!(rules.severity("delete-without-where"), Severity::Error);
+    assert!(rules.set("no-such-rule", Severity::Off).is_err());
+}
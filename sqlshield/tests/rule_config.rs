@@ -0,0 +1,147 @@
+//! Rule codes for validation errors, per-rule severities and path-scoped
+//! overrides.
+
+use std::fs;
+use std::path::Path;
+
+use sqlparser::dialect::GenericDialect;
+use sqlshield::lint::{RuleConfig, Severity};
+use sqlshield::validation::validate_statements_with_schema;
+use sqlshield::{check_files_with_schema, check_paths_with_schema, schema, Dialect};
+
+#[test]
+fn validation_errors_have_rule_codes() {
+    let cases = [
+        ("SELECT id FROM ghosts", "unknown-table"),
+        ("SELECT nickname FROM users", "unknown-column"),
+        (
+            "SELECT id FROM users u JOIN teams t ON u.team_id = t.id",
+            "ambiguous-column",
+        ),
+        ("SELECT frobnicate(id) FROM users", "unknown-function"),
+        ("SELECT lower(name, email) FROM users", "function-arity"),
+        (
+            "SELECT name FROM users GROUP BY team_id",
+            "ungrouped-column",
+        ),
+        (
+            "SELECT id FROM users WHERE count(*) > 1",
+            "misplaced-aggregate",
+        ),
+        (
+            "SELECT id, name FROM users UNION SELECT id FROM teams",
+            "column-count-mismatch",
+        ),
+        ("SELECT rank() OVER w FROM users", "unknown-window"),
+        ("INSERT INTO users (nickname) VALUES (1)", "unknown-column"),
+    ];
+    let ddl = "
+        CREATE TABLE users (id INT, name TEXT, email TEXT, team_id INT);
+        CREATE TABLE teams (id INT, name TEXT);
+    ";
+    let loaded = schema::load_schema(ddl.as_bytes(), "sql", Dialect::Generic).unwrap();
+    for (sql, code) in cases {
+        let statements = sqlshield::parse::parse_sql(&GenericDialect {}, sql).unwrap();
+        let errors = validate_statements_with_schema(&statements, &loaded, Dialect::Generic);
+        assert!(!errors.is_empty(), "{sql}: expected an error");
+        assert_eq!(errors[0].code, code, "{sql}: {errors:?}");
+    }
+}
+
+#[test]
+fn overrides_apply_to_matching_paths_only() {
+    let mut scoped = RuleConfig::default();
+    scoped.set("unknown-column", Severity::Warning).unwrap();
+    scoped.enable("select-star", false).unwrap();
+    let mut rules = RuleConfig::default();
+    rules.set("select-star", Severity::Error).unwrap();
+    rules
+        .add_override(&["tests/**".to_string()], scoped)
+        .unwrap();
+
+    let in_tests = rules.for_path(Path::new("./tests/unit/test_db.py"));
+    assert_eq!(in_tests.severity("unknown-column"), Severity::Warning);
+    assert_eq!(in_tests.severity("select-star"), Severity::Off);
+
+    let in_src = rules.for_path(Path::new("src/tests.py"));
+    assert_eq!(in_src.severity("unknown-column"), Severity::Error);
+    assert_eq!(in_src.severity("select-star"), Severity::Error);
+
+    assert!(rules
+        .add_override(&["[".to_string()], RuleConfig::default())
+        .is_err());
+}
+
+#[test]
+fn checked_files_use_configured_severities() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("legacy")).unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        "q = \"SELECT nickname FROM users\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("legacy/old.py"),
+        "q = \"SELECT nickname FROM users\"\n",
+    )
+    .unwrap();
+    let loaded = schema::load_schema(
+        "CREATE TABLE users (id INT);".as_bytes(),
+        "sql",
+        Dialect::Generic,
+    )
+    .unwrap();
+
+    let mut scoped = RuleConfig::default();
+    scoped.enable("unknown-column", false).unwrap();
+    let mut rules = RuleConfig::default();
+    rules
+        .add_override(&["**/legacy/**".to_string()], scoped)
+        .unwrap();
+
+    let diagnostics = check_files_with_schema(dir.path(), &loaded, Dialect::Generic, &rules);
+    assert_eq!(diagnostics.len(), 1, "got: {diagnostics:?}");
+    assert!(diagnostics[0].location.contains("app.py"));
+    assert_eq!(diagnostics[0].code, "unknown-column");
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+}
+
+#[test]
+fn files_that_cannot_be_checked_are_reported_under_their_own_rules() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = dir.path().join("app.py");
+    fs::write(&app, "q = \"SELECT id FROM users WHERE id = AND 1\"\n").unwrap();
+    let missing = dir.path().join("missing.py");
+    let loaded = schema::load_schema(
+        "CREATE TABLE users (id INT);".as_bytes(),
+        "sql",
+        Dialect::Generic,
+    )
+    .unwrap();
+
+    let files = [app.clone(), missing.clone()];
+    let diagnostics =
+        check_paths_with_schema(&files, &loaded, Dialect::Generic, &RuleConfig::default());
+    let codes: Vec<_> = diagnostics
+        .iter()
+        .map(|d| (d.path.clone(), d.code))
+        .collect();
+    assert_eq!(
+        codes,
+        [
+            (app.clone(), "unparseable-sql"),
+            (missing.clone(), "unreadable-file")
+        ]
+    );
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert_eq!(diagnostics[1].severity, Severity::Error);
+
+    let mut rules = RuleConfig::default();
+    rules.set("unparseable-sql", Severity::Off).unwrap();
+    rules.set("unreadable-file", Severity::Warning).unwrap();
+    let diagnostics = check_paths_with_schema(&files, &loaded, Dialect::Generic, &rules);
+    assert_eq!(diagnostics.len(), 1, "got: {diagnostics:?}");
+    assert_eq!(diagnostics[0].code, "unreadable-file");
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+}
@@ -5,8 +5,8 @@ use sqlshield::{Dialect, SqlShieldError};
 
 #[test]
 fn unsupported_schema_type_returns_typed_error() {
-    let err = load_schema(b"", "json", Dialect::default()).unwrap_err();
-    assert!(matches!(err, SqlShieldError::UnsupportedSchemaType(s) if s == "json"));
+    let err = load_schema(b"", "xml", Dialect::default()).unwrap_err();
+    assert!(matches!(err, SqlShieldError::UnsupportedSchemaType(s) if s == "xml"));
 }
 
 #[test]
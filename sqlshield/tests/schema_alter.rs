@@ -1,8 +1,8 @@
-//! Schema ingestion: ALTER TABLE ADD/DROP/RENAME COLUMN.
+//! Schema ingestion: ALTER TABLE ADD/DROP/RENAME COLUMN and RENAME TO.
 //! Real-world schemas combine the original CREATE TABLE with later
 //! migrations; ignoring ALTER means under-reporting columns.
 
-use sqlshield::validate_query;
+use sqlshield::{schema, validate_query, Dialect};
 
 #[test]
 fn alter_add_column_makes_column_visible() {
@@ -50,6 +50,25 @@ fn alter_rename_column_renames() {
     );
 }
 
+#[test]
+fn alter_rename_table_moves_the_table() {
+    let schema = "
+        CREATE TABLE users (id INT, name TEXT);
+        CREATE TABLE posts (id INT, author_id INT REFERENCES users (id));
+        ALTER TABLE users RENAME TO accounts;
+    ";
+    let errs = validate_query("SELECT id, name FROM accounts", schema).unwrap();
+    assert!(errs.is_empty(), "got: {errs:?}");
+    let errs = validate_query("SELECT id FROM users", schema).unwrap();
+    assert!(!errs.is_empty(), "RENAME TO should drop the old name");
+
+    let loaded = schema::load_schema(schema.as_bytes(), "sql", Dialect::Generic).unwrap();
+    assert_eq!(
+        loaded.foreign_key_pairs("posts", "accounts"),
+        vec![("author_id", "id")]
+    );
+}
+
 #[test]
 fn multiple_alters_compose() {
     let schema = "
@@ -0,0 +1,138 @@
+//! What a schema change touches: applying a migration on top of a schema,
+//! diffing two schemas by table, and finding the files whose queries use
+//! the affected tables. Backs `--changed-since`.
+
+use std::fs;
+
+use sqlshield::schema::{apply_ddl, load_schema};
+use sqlshield::usage::TableUsageIndex;
+use sqlshield::{validate_query, Dialect};
+
+#[test]
+fn drop_table_removes_table() {
+    let schema = "
+        CREATE TABLE users (id INT);
+        CREATE TABLE legacy (id INT);
+        DROP TABLE legacy;
+    ";
+    let errs = validate_query("SELECT id FROM legacy", schema).unwrap();
+    assert!(
+        errs.iter().any(|e| e.contains("legacy")),
+        "DROP TABLE should make `legacy` unknown; got: {errs:?}"
+    );
+}
+
+#[test]
+fn changed_tables_lists_tables_whose_columns_differ() {
+    let dialect = Dialect::Generic;
+    let schema = load_schema(
+        b"CREATE TABLE users (id INT); CREATE TABLE orders (id INT); CREATE TABLE gone (id INT);",
+        "sql",
+        dialect,
+    )
+    .unwrap();
+
+    let mut migrated = schema.clone();
+    apply_ddl(
+        &mut migrated,
+        b"ALTER TABLE users ADD COLUMN email TEXT; DROP TABLE gone; CREATE TABLE fresh (id INT);",
+        dialect,
+    )
+    .unwrap();
+
+    let changed: Vec<String> = schema.changed_tables(&migrated).into_iter().collect();
+    assert_eq!(changed, ["fresh", "gone", "users"]);
+    assert!(schema.changed_tables(&schema.clone()).is_empty());
+}
+
+#[test]
+fn changed_tables_follows_enum_labels_and_functions_are_diffed() {
+    let dialect = Dialect::Postgres;
+    let schema = load_schema(
+        b"CREATE TYPE mood AS ENUM ('happy');
+          CREATE TABLE people (id INT, feeling mood);
+          CREATE TABLE orders (id INT);
+          CREATE FUNCTION slugify(t TEXT) RETURNS TEXT AS 'select t' LANGUAGE sql;
+          CREATE FUNCTION keep(t TEXT) RETURNS TEXT AS 'select t' LANGUAGE sql;",
+        "sql",
+        dialect,
+    )
+    .unwrap();
+
+    let mut migrated = schema.clone();
+    apply_ddl(&mut migrated, b"ALTER TYPE mood ADD VALUE 'sad';", dialect).unwrap();
+    let changed: Vec<String> = schema.changed_tables(&migrated).into_iter().collect();
+    assert_eq!(changed, ["people"]);
+    assert!(schema.changed_functions(&migrated).is_empty());
+
+    let mut migrated = schema.clone();
+    apply_ddl(
+        &mut migrated,
+        b"DROP FUNCTION slugify(TEXT);
+          CREATE FUNCTION fresh() RETURNS INT AS 'select 1' LANGUAGE sql;",
+        dialect,
+    )
+    .unwrap();
+    assert!(!migrated.functions.contains_key("slugify"));
+    let changed: Vec<String> = schema.changed_functions(&migrated).into_iter().collect();
+    assert_eq!(changed, ["fresh", "slugify"]);
+    assert!(schema.changed_tables(&migrated).is_empty());
+}
+
+#[test]
+fn usage_index_maps_tables_to_files() {
+    let root = tempfile::tempdir().unwrap();
+    let users = root.path().join("users.py");
+    let orders = root.path().join("orders.py");
+    let report = root.path().join("report.sql");
+    fs::write(&users, b"q = \"SELECT id FROM users\"\n").unwrap();
+    fs::write(
+        &orders,
+        b"q = \"SELECT o.id FROM orders o JOIN Users u ON u.id = o.user_id\"\n",
+    )
+    .unwrap();
+    fs::write(&report, b"SELECT count(*) FROM public.orders;\n").unwrap();
+
+    let files = [users.clone(), orders.clone(), report.clone()];
+    let index = TableUsageIndex::build(&files, Dialect::Postgres);
+
+    let using_users = index.files_using(["users"]);
+    assert_eq!(using_users.len(), 2, "{using_users:?}");
+    assert!(using_users.contains(users.as_path()));
+    assert!(using_users.contains(orders.as_path()));
+
+    let using_qualified = index.files_using(["public.orders"]);
+    assert_eq!(
+        using_qualified.into_iter().collect::<Vec<_>>(),
+        [report.as_path()]
+    );
+    assert!(index.files_using(["nothing"]).is_empty());
+}
+
+#[test]
+fn usage_index_maps_functions_to_files() {
+    let root = tempfile::tempdir().unwrap();
+    let slugs = root.path().join("slugs.py");
+    let series = root.path().join("series.sql");
+    fs::write(&slugs, b"q = \"SELECT slugify(name) FROM users\"\n").unwrap();
+    fs::write(&series, b"SELECT n FROM make_series(3) AS s(n);\n").unwrap();
+
+    let files = [slugs.clone(), series.clone()];
+    let index = TableUsageIndex::build(&files, Dialect::Postgres);
+
+    assert_eq!(
+        index
+            .files_calling(["slugify"])
+            .into_iter()
+            .collect::<Vec<_>>(),
+        [slugs.as_path()]
+    );
+    assert_eq!(
+        index
+            .files_calling(["make_series"])
+            .into_iter()
+            .collect::<Vec<_>>(),
+        [series.as_path()]
+    );
+    assert!(index.files_calling(["users"]).is_empty());
+}
@@ -0,0 +1,118 @@
+//! Schema drift: what two schemas disagree on.
+
+use sqlshield::schema::diff::{self, canonical_type, Drift, Side};
+use sqlshield::schema::{self, ForeignKey, Schema, TablesAndColumns};
+use sqlshield::Dialect;
+
+fn load(ddl: &str) -> Schema {
+    schema::load_schema(ddl.as_bytes(), "sql", Dialect::Postgres).unwrap()
+}
+
+#[test]
+fn identical_schemas_have_no_drift() {
+    let ddl = "CREATE TABLE users (id SERIAL PRIMARY KEY, name VARCHAR(40) NOT NULL);";
+    assert_eq!(diff::diff(&load(ddl), &load(ddl)), vec![]);
+}
+
+#[test]
+fn tables_columns_types_nullability_and_keys_are_compared() {
+    let file = load(
+        "CREATE TABLE users (id INT PRIMARY KEY, nickname TEXT, age INT);
+         CREATE TABLE orders (id INT PRIMARY KEY, user_id INT REFERENCES users);
+         CREATE TABLE legacy (id INT);",
+    );
+    let database = load(
+        "CREATE TABLE users (id integer PRIMARY KEY, email TEXT, age BIGINT NOT NULL);
+         CREATE TABLE orders (id INT, user_id INT);
+         CREATE TABLE audit (id INT);",
+    );
+    let user_fk = ForeignKey {
+        columns: vec!["user_id".into()],
+        referenced_table: "users".into(),
+        referenced_columns: vec!["id".into()],
+    };
+    assert_eq!(
+        diff::diff(&file, &database),
+        vec![
+            Drift::Table {
+                table: "audit".into(),
+                only_in: Side::Right,
+            },
+            Drift::Table {
+                table: "legacy".into(),
+                only_in: Side::Left,
+            },
+            Drift::Nullability {
+                table: "orders".into(),
+                column: "id".into(),
+                left_nullable: false,
+            },
+            Drift::PrimaryKey {
+                table: "orders".into(),
+                left: vec!["id".into()],
+                right: vec![],
+            },
+            Drift::ForeignKey {
+                table: "orders".into(),
+                foreign_key: user_fk,
+                only_in: Side::Left,
+            },
+            Drift::Column {
+                table: "users".into(),
+                column: "email".into(),
+                only_in: Side::Right,
+            },
+            Drift::Column {
+                table: "users".into(),
+                column: "nickname".into(),
+                only_in: Side::Left,
+            },
+            Drift::Type {
+                table: "users".into(),
+                column: "age".into(),
+                left: "INT".into(),
+                right: "BIGINT".into(),
+            },
+            Drift::Nullability {
+                table: "users".into(),
+                column: "age".into(),
+                left_nullable: true,
+            },
+        ]
+    );
+    assert_eq!(
+        diff::diff(&file, &database)[7].describe("schema file", "database"),
+        "column `users.age` is INT in the schema file but BIGINT in the database"
+    );
+}
+
+#[test]
+fn untyped_schemas_only_compare_names() {
+    let file = load("CREATE TABLE users (id INT PRIMARY KEY, name TEXT NOT NULL);");
+    let mut tables = TablesAndColumns::new();
+    tables.insert(
+        "users".into(),
+        ["id".to_string(), "name".to_string()].into(),
+    );
+    tables.insert(
+        "public.users".into(),
+        ["id".to_string(), "name".to_string()].into(),
+    );
+    assert_eq!(diff::diff(&file, &Schema::from(tables)), vec![]);
+}
+
+#[test]
+fn type_aliases_are_the_same_type() {
+    assert_eq!(canonical_type("INT4"), canonical_type("integer"));
+    assert_eq!(canonical_type("serial"), "integer");
+    assert_eq!(canonical_type("VARCHAR(255)"), "character varying(255)");
+    assert_eq!(
+        canonical_type("NUMERIC(10, 2)"),
+        canonical_type("numeric(10,2)")
+    );
+    assert_eq!(
+        canonical_type("TIMESTAMPTZ"),
+        canonical_type("timestamp with time zone")
+    );
+    assert_ne!(canonical_type("INT"), canonical_type("BIGINT"));
+}
@@ -0,0 +1,142 @@
+//! Dumping a loaded schema as DDL or JSON, and loading the dump back.
+
+use sqlshield::schema::{self, load_schema, Schema};
+use sqlshield::{Dialect, SqlShieldError};
+
+const DDL: &str = r#"
+    CREATE TYPE status AS ENUM ('active', 'it''s banned');
+    CREATE FUNCTION slugify(t TEXT, sep TEXT DEFAULT '-') RETURNS TEXT AS $$ SELECT t $$;
+    CREATE FUNCTION concat_all(VARIADIC parts TEXT[]) RETURNS TEXT AS $$ SELECT 1 $$;
+    CREATE TABLE orgs (id INT PRIMARY KEY, name VARCHAR(80) NOT NULL UNIQUE, slug TEXT, UNIQUE (id, slug));
+    CREATE TABLE app."Users" (
+        id BIGINT NOT NULL,
+        org_id INT REFERENCES orgs (id),
+        "Email" TEXT,
+        state status,
+        kind ENUM('a', 'b'),
+        PRIMARY KEY (id)
+    );
+    CREATE VIEW active_users AS SELECT id, "Email" FROM app."Users";
+"#;
+
+fn load(source: &str, schema_type: &str) -> Schema {
+    load_schema(source.as_bytes(), schema_type, Dialect::Postgres).unwrap()
+}
+
+#[test]
+fn sql_dump_loads_back_into_the_same_schema() {
+    let loaded = load(DDL, "sql");
+    let dumped = schema::dump_sql(&loaded);
+    assert_eq!(load(&dumped, "sql"), loaded, "dump:\n{dumped}");
+    assert_eq!(schema::dump_sql(&load(&dumped, "sql")), dumped);
+}
+
+#[test]
+fn json_dump_loads_back_into_the_same_schema() {
+    let loaded = load(DDL, "sql");
+    let dumped = schema::dump_json(&loaded);
+    assert_eq!(load(&dumped, "json"), loaded, "dump:\n{dumped}");
+    assert_eq!(schema::dump_json(&load(&dumped, "json")), dumped);
+}
+
+#[test]
+fn sql_dump_is_canonical_ddl() {
+    let loaded = load(
+        "CREATE TABLE b (id INT PRIMARY KEY, a_id INT REFERENCES a (id));
+         CREATE TABLE a (id INT NOT NULL);",
+        "sql",
+    );
+    assert_eq!(
+        schema::dump_sql(&loaded),
+        "CREATE TABLE a (
+    id INT NOT NULL
+);
+
+CREATE TABLE b (
+    id INT NOT NULL,
+    a_id INT,
+    PRIMARY KEY (id),
+    FOREIGN KEY (a_id) REFERENCES a (id)
+);
+"
+    );
+}
+
+#[test]
+fn sql_dump_declares_functions_with_their_types_and_keeps_unique_keys() {
+    let loaded = load(
+        "CREATE FUNCTION slugify(IN t TEXT, sep VARCHAR(4) DEFAULT '-') RETURNS TEXT AS $$ SELECT t $$;
+         CREATE FUNCTION concat_all(VARIADIC TEXT[]) RETURNS TEXT AS $$ SELECT 1 $$;
+         CREATE TABLE a (id INT, email TEXT UNIQUE, CONSTRAINT a_pair UNIQUE (id, email));",
+        "sql",
+    );
+    assert_eq!(
+        schema::dump_sql(&loaded),
+        "CREATE FUNCTION concat_all(VARIADIC TEXT[]) RETURNS TEXT;
+CREATE FUNCTION slugify(TEXT, VARCHAR(4) DEFAULT NULL) RETURNS TEXT;
+
+CREATE TABLE a (
+    id INT,
+    email TEXT,
+    UNIQUE (email),
+    UNIQUE (id, email)
+);
+"
+    );
+
+    // Without declared parameter types there's nothing to declare.
+    let loaded = load(
+        r#"{ "sqlshield_schema": 1, "functions": [{ "name": "f", "min_args": 1, "max_args": 2 }] }"#,
+        "json",
+    );
+    assert_eq!(
+        schema::dump_sql(&loaded),
+        "-- FUNCTION f: 1 to 2 arguments, parameter types unknown\n"
+    );
+}
+
+#[test]
+fn json_schema_is_versioned() {
+    let loaded = load(
+        r#"{
+            "sqlshield_schema": 1,
+            "tables": [
+                {
+                    "name": "public.users",
+                    "columns": [
+                        { "name": "ID", "type": "INT", "nullable": false },
+                        { "name": "state", "type": "status" }
+                    ],
+                    "primary_key": ["ID"]
+                },
+                { "name": "active_users", "columns": [{ "name": "id" }] }
+            ],
+            "enums": [{ "name": "status", "labels": ["active"] }]
+        }"#,
+        "json",
+    );
+    assert!(loaded["users"].contains("ID"));
+    assert!(loaded.contains_key("public.users"));
+    assert_eq!(loaded.primary_keys["users"], vec!["ID"]);
+    assert_eq!(
+        loaded.enum_labels("users", "state").unwrap().labels,
+        ["active"]
+    );
+    assert!(loaded.column("users", "state").unwrap().nullable);
+    assert!(!loaded.columns.contains_key("active_users"));
+
+    let err = load_schema(
+        br#"{ "sqlshield_schema": 2, "tables": [] }"#,
+        "json",
+        Dialect::Postgres,
+    )
+    .unwrap_err();
+    assert!(matches!(err, SqlShieldError::InvalidSchema(_)));
+    assert!(err.to_string().contains("version 2"));
+
+    // Without the key it isn't sqlshield's format, nor a BigQuery schema.
+    let err = load_schema(br#"{ "tables": [] }"#, "json", Dialect::Postgres).unwrap_err();
+    assert!(matches!(err, SqlShieldError::InvalidSchema(_)));
+    let err = load_schema(br#"{ "tables": "#, "json", Dialect::Postgres).unwrap_err();
+    assert!(matches!(err, SqlShieldError::SchemaJson(_)));
+}
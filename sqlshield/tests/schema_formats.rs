@@ -0,0 +1,260 @@
+//! Loading schemas from YAML, dbt `schema.yml` files and BigQuery table
+//! schemas.
+
+use std::fs;
+use std::path::Path;
+
+use sqlshield::schema::{self, load_file_schema, load_schema, ForeignKey, Schema};
+use sqlshield::{validate_query_against_schema, Dialect, SqlShieldError};
+
+fn load(source: &str, schema_type: &str) -> Schema {
+    load_schema(source.as_bytes(), schema_type, Dialect::Postgres).unwrap()
+}
+
+fn errors(query: &str, schema: &Schema, dialect: Dialect) -> Vec<String> {
+    validate_query_against_schema(query, schema, dialect).unwrap()
+}
+
+#[test]
+fn native_yaml_loads_like_the_json_format() {
+    let yaml = r#"
+sqlshield_schema: 1
+tables:
+  - name: public.users
+    columns:
+      - { name: id, type: INT, nullable: false }
+      - { name: state, type: status }
+    primary_key: [id]
+  - name: active_users
+    columns: [{ name: id }]
+enums:
+  - { name: status, labels: [active, banned] }
+"#;
+    let loaded = load(yaml, "yaml");
+    assert_eq!(load(&schema::dump_json(&loaded), "json"), loaded);
+    assert_eq!(load(yaml, "yml"), loaded);
+    assert_eq!(loaded.primary_keys["users"], ["id"]);
+    assert!(loaded.columns.contains_key("public.users"));
+    assert!(!loaded.columns.contains_key("active_users"));
+    assert_eq!(loaded.enums["status"], ["active", "banned"]);
+}
+
+#[test]
+fn yaml_that_is_neither_format_is_rejected() {
+    let err = load_schema(b"tables: []\n", "yaml", Dialect::Postgres).unwrap_err();
+    assert!(matches!(err, SqlShieldError::InvalidSchema(_)), "{err}");
+    let err = load_schema(b"tables: [\n", "yaml", Dialect::Postgres).unwrap_err();
+    assert!(matches!(err, SqlShieldError::SchemaYaml(_)), "{err}");
+}
+
+const DBT: &str = r#"
+version: 2
+
+sources:
+  - name: raw
+    schema: landing
+    tables:
+      - name: orgs
+        columns:
+          - name: id
+            data_type: integer
+            tests: [unique, not_null]
+          - name: name
+            data_type: text
+      - name: events
+        identifier: raw_events
+        columns:
+          - name: payload
+
+models:
+  - name: stg_users
+    config:
+      alias: users
+      schema: analytics
+    constraints:
+      - type: primary_key
+        columns: [ID]
+    columns:
+      - name: ID
+        data_type: bigint
+        data_tests:
+          - not_null:
+              config: { severity: warn }
+      - name: org_id
+        data_type: integer
+        constraints:
+          - type: foreign_key
+            to: source('raw', 'orgs')
+            to_columns: [id]
+      - name: Nickname
+        data_type: varchar(40)
+        quote: true
+  - name: user_counts
+    columns:
+      - name: total
+"#;
+
+#[test]
+fn dbt_sources_and_models_load_as_tables() {
+    let loaded = load(DBT, "yml");
+
+    assert_eq!(loaded.tables["landing.orgs"], loaded.tables["orgs"]);
+    assert!(loaded.tables.contains_key("landing.raw_events"));
+    assert!(!loaded.tables.contains_key("events"));
+    assert!(loaded.tables.contains_key("analytics.users"));
+    assert!(!loaded.tables.contains_key("stg_users"));
+
+    let id = loaded.column("orgs", "id").unwrap();
+    assert_eq!((id.data_type.as_str(), id.nullable), ("integer", false));
+    assert!(loaded.column("orgs", "name").unwrap().nullable);
+    assert!(!loaded.column("users", "id").unwrap().nullable);
+    assert_eq!(
+        loaded.column("users", "Nickname").unwrap().data_type,
+        "varchar(40)"
+    );
+    assert_eq!(loaded.primary_keys["users"], ["id"]);
+    assert_eq!(
+        loaded.foreign_keys["users"],
+        [ForeignKey {
+            columns: vec!["org_id".into()],
+            referenced_table: "orgs".into(),
+            referenced_columns: vec!["id".into()],
+        }]
+    );
+    // Without a data_type on every column only the names count.
+    assert!(!loaded.columns.contains_key("user_counts"));
+    assert!(loaded.tables["user_counts"].contains("total"));
+
+    assert!(errors(
+        r#"SELECT u.id, u."Nickname", o.name FROM analytics.users u JOIN landing.orgs o ON o.id = u.org_id"#,
+        &loaded,
+        Dialect::Postgres,
+    )
+    .is_empty());
+    assert!(!errors("SELECT nickname FROM users", &loaded, Dialect::Postgres).is_empty());
+}
+
+const BIGQUERY_FIELDS: &str = r#"[
+  { "name": "id", "type": "INTEGER", "mode": "REQUIRED" },
+  { "name": "Email", "type": "STRING" },
+  {
+    "name": "address",
+    "type": "RECORD",
+    "fields": [
+      { "name": "city", "type": "STRING" },
+      { "name": "geo", "type": "RECORD", "fields": [{ "name": "lat", "type": "FLOAT64" }] }
+    ]
+  },
+  { "name": "tags", "type": "STRING", "mode": "REPEATED" },
+  { "name": "orders", "type": "RECORD", "mode": "REPEATED", "fields": [{ "name": "sku", "type": "STRING" }] }
+]"#;
+
+#[test]
+fn bigquery_field_list_is_named_after_its_file() {
+    let loaded = load_file_schema(
+        BIGQUERY_FIELDS.as_bytes(),
+        "json",
+        Dialect::BigQuery,
+        Path::new("schemas/users.json"),
+    )
+    .unwrap();
+
+    let types: Vec<(&str, &str, bool)> = loaded.columns["users"]
+        .iter()
+        .map(|c| (c.name.as_str(), c.data_type.as_str(), c.nullable))
+        .collect();
+    assert_eq!(
+        types,
+        [
+            ("id", "INTEGER", false),
+            ("email", "STRING", true),
+            (
+                "address",
+                "STRUCT<city STRING, geo STRUCT<lat FLOAT64>>",
+                true
+            ),
+            ("tags", "ARRAY<STRING>", true),
+            ("orders", "ARRAY<STRUCT<sku STRING>>", true),
+        ]
+    );
+    assert!(errors(
+        "SELECT id, email, address.city, tags FROM users",
+        &loaded,
+        Dialect::BigQuery
+    )
+    .is_empty());
+    assert!(!errors("SELECT city FROM users", &loaded, Dialect::BigQuery).is_empty());
+
+    // RECORD fields are checked, nested ones too.
+    assert!(errors(
+        "SELECT u.address.city, address.geo.lat FROM users u WHERE u.address.geo.lat > 0",
+        &loaded,
+        Dialect::BigQuery
+    )
+    .is_empty());
+    assert_eq!(
+        errors("SELECT address.zip FROM users", &loaded, Dialect::BigQuery),
+        ["Field `zip` not found in struct `address`"]
+    );
+    assert_eq!(
+        errors(
+            "SELECT u.address.geo.lng FROM users u",
+            &loaded,
+            Dialect::BigQuery
+        ),
+        ["Field `lng` not found in struct `address.geo`"]
+    );
+
+    let err = load_schema(BIGQUERY_FIELDS.as_bytes(), "json", Dialect::BigQuery).unwrap_err();
+    assert!(matches!(err, SqlShieldError::InvalidSchema(_)), "{err}");
+}
+
+#[test]
+fn bigquery_table_resources_load_under_their_dataset() {
+    let resources = r#"[
+      {
+        "tableReference": { "projectId": "acme", "datasetId": "shop", "tableId": "orders" },
+        "schema": { "fields": [
+          { "name": "id", "type": "INT64", "mode": "REQUIRED" },
+          { "name": "customer_id", "type": "INT64" }
+        ] },
+        "tableConstraints": {
+          "primaryKey": { "columns": ["id"] },
+          "foreignKeys": [{
+            "referencedTable": { "projectId": "acme", "datasetId": "shop", "tableId": "customers" },
+            "columnReferences": [{ "referencingColumn": "customer_id", "referencedColumn": "id" }]
+          }]
+        }
+      },
+      {
+        "tableReference": { "datasetId": "shop", "tableId": "customers" },
+        "schema": { "fields": [{ "name": "id", "type": "INT64" }] }
+      }
+    ]"#;
+    let loaded = load_schema(resources.as_bytes(), "json", Dialect::BigQuery).unwrap();
+
+    for key in ["orders", "shop.orders", "acme.shop.orders"] {
+        assert!(loaded.columns.contains_key(key), "{key}");
+    }
+    assert!(loaded.tables.contains_key("shop.customers"));
+    assert!(!loaded.tables.contains_key("acme.shop.customers"));
+    assert_eq!(loaded.primary_keys["orders"], ["id"]);
+    assert_eq!(
+        loaded.foreign_keys["orders"][0].referenced_table,
+        "customers"
+    );
+
+    let list: serde_json::Value = serde_json::from_str(resources).unwrap();
+    let single = list[0].to_string();
+    let loaded = load_schema(single.as_bytes(), "json", Dialect::BigQuery).unwrap();
+    assert!(loaded.tables.contains_key("shop.orders"));
+}
+
+#[test]
+fn schema_files_name_bigquery_field_lists_by_their_stem() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("events.json");
+    fs::write(&path, BIGQUERY_FIELDS).unwrap();
+    let loaded = schema::load_schema_from_file(&path, Dialect::BigQuery).unwrap();
+    assert!(loaded.columns.contains_key("events"));
+}
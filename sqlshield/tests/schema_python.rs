@@ -0,0 +1,283 @@
+//! Schemas derived from SQLAlchemy and Django model definitions.
+
+use std::fs;
+use std::path::Path;
+
+use sqlshield::schema::{load_file_schema, load_schema, ForeignKey, Schema};
+use sqlshield::{validate_query_against_schema, Dialect};
+
+fn columns(schema: &Schema, table: &str) -> Vec<(String, String, bool)> {
+    schema.columns[table]
+        .iter()
+        .map(|c| (c.name.clone(), c.data_type.clone(), c.nullable))
+        .collect()
+}
+
+fn column(name: &str, data_type: &str, nullable: bool) -> (String, String, bool) {
+    (name.to_string(), data_type.to_string(), nullable)
+}
+
+const SQLALCHEMY: &str = r#"
+import enum
+from typing import Optional
+
+import sqlalchemy as sa
+from sqlalchemy import Column, ForeignKey, Integer, String, Table
+from sqlalchemy.orm import DeclarativeBase, Mapped, mapped_column, relationship
+
+
+class Base(DeclarativeBase):
+    pass
+
+
+class Status(enum.Enum):
+    active = 1
+    banned = 2
+
+
+class TimestampMixin:
+    created_at = Column(sa.DateTime(timezone=True), nullable=False)
+
+
+class Org(Base):
+    __tablename__ = "orgs"
+    __table_args__ = {"schema": "app"}
+
+    id = Column(Integer, primary_key=True)
+    name = Column("display_name", String(80), nullable=False)
+
+
+class User(TimestampMixin, Base):
+    __tablename__ = "users"
+
+    id: Mapped[int] = mapped_column(primary_key=True)
+    org_id: Mapped[int] = mapped_column(ForeignKey("orgs.id"))
+    email: Mapped[str] = mapped_column(String(255), unique=True)
+    nickname: Mapped[Optional[str]]
+    bio: Mapped[str | None] = mapped_column(sa.Text)
+    status: Mapped[Status]
+    score = Column(sa.Numeric(10, 2))
+    manager_id = Column(ForeignKey("users.id"))
+
+    org: Mapped["Org"] = relationship(back_populates="users")
+
+
+audit_log = Table(
+    "audit_log",
+    Base.metadata,
+    Column("id", Integer, primary_key=True),
+    Column("user_id", ForeignKey("users.id"), nullable=False),
+    Column("payload", sa.JSON),
+    schema="ops",
+)
+"#;
+
+#[test]
+fn sqlalchemy_declarative_classes_and_tables_load() {
+    let loaded = load_schema(SQLALCHEMY.as_bytes(), "py", Dialect::Postgres).unwrap();
+
+    assert_eq!(
+        columns(&loaded, "app.orgs"),
+        [
+            column("id", "INTEGER", false),
+            column("display_name", "VARCHAR(80)", false),
+        ]
+    );
+    assert_eq!(loaded.tables["orgs"], loaded.tables["app.orgs"]);
+    assert_eq!(
+        columns(&loaded, "users"),
+        [
+            column("created_at", "TIMESTAMP WITH TIME ZONE", false),
+            column("id", "INTEGER", false),
+            column("org_id", "INTEGER", false),
+            column("email", "VARCHAR(255)", false),
+            column("nickname", "VARCHAR", true),
+            column("bio", "TEXT", true),
+            column("status", "status", false),
+            column("score", "NUMERIC(10, 2)", true),
+            column("manager_id", "INTEGER", true),
+        ]
+    );
+    assert_eq!(
+        loaded.column("users", "status").unwrap().inline_enum,
+        Some(vec!["active".to_string(), "banned".to_string()])
+    );
+    assert_eq!(loaded.primary_keys["users"], ["id"]);
+    assert_eq!(
+        loaded.foreign_keys["users"][0],
+        ForeignKey {
+            columns: vec!["org_id".into()],
+            referenced_table: "orgs".into(),
+            referenced_columns: vec!["id".into()],
+        }
+    );
+    assert!(!loaded.tables.contains_key("base"));
+    assert!(!loaded.tables.contains_key("timestampmixin"));
+
+    assert_eq!(
+        columns(&loaded, "ops.audit_log"),
+        [
+            column("id", "INTEGER", false),
+            column("user_id", "INTEGER", false),
+            column("payload", "JSON", true),
+        ]
+    );
+}
+
+#[test]
+fn sqlalchemy_subclasses_and_flask_models_share_or_name_tables() {
+    let source = r#"
+from flask_sqlalchemy import SQLAlchemy
+
+db = SQLAlchemy()
+
+
+class UserAccount(db.Model):
+    id = db.Column(db.Integer, primary_key=True)
+    kind = db.Column(db.String(20))
+
+
+class Admin(UserAccount):
+    level = db.Column(db.SmallInteger)
+
+
+class Widget(CustomBase):
+    __tablename__ = "widgets"
+    id = db.Column(MyCustomType, primary_key=True)
+"#;
+    let loaded = load_schema(source.as_bytes(), "py", Dialect::Postgres).unwrap();
+    assert_eq!(
+        columns(&loaded, "user_account"),
+        [
+            column("id", "INTEGER", false),
+            column("kind", "VARCHAR(20)", true),
+            column("level", "SMALLINT", true),
+        ]
+    );
+    assert!(!loaded.tables.contains_key("admin"));
+    // An unknown column type leaves the table with names only.
+    assert!(loaded.tables["widgets"].contains("id"));
+    assert!(!loaded.columns.contains_key("widgets"));
+}
+
+const DJANGO: &str = r#"
+from django.db import models
+
+
+class TimeStamped(models.Model):
+    created = models.DateTimeField(auto_now_add=True)
+
+    class Meta:
+        abstract = True
+
+
+class Org(models.Model):
+    code = models.CharField(max_length=8, primary_key=True)
+
+    class Meta:
+        db_table = "orgs"
+
+
+class Tag(models.Model):
+    label = models.SlugField()
+
+
+class Post(TimeStamped):
+    title = models.CharField(max_length=200)
+    body = models.TextField(blank=True, null=True)
+    price = models.DecimalField(max_digits=8, decimal_places=2)
+    org = models.ForeignKey(Org, on_delete=models.CASCADE)
+    author = models.ForeignKey("auth.User", null=True, on_delete=models.SET_NULL)
+    parent = models.ForeignKey("self", null=True, on_delete=models.CASCADE, db_column="parent")
+    tags = models.ManyToManyField(Tag)
+
+    objects = PostManager()
+
+
+class Featured(Post):
+    rank = models.PositiveSmallIntegerField()
+
+
+class PostProxy(Post):
+    class Meta:
+        proxy = True
+"#;
+
+#[test]
+fn django_models_load_with_their_default_table_names() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("blog").join("models.py");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, DJANGO).unwrap();
+    let loaded = sqlshield::schema::load_schema_from_file(&path, Dialect::Postgres).unwrap();
+
+    assert_eq!(
+        columns(&loaded, "orgs"),
+        [column("code", "varchar(8)", false)]
+    );
+    assert_eq!(loaded.primary_keys["orgs"], ["code"]);
+    assert_eq!(
+        columns(&loaded, "blog_post"),
+        [
+            column("id", "bigint", false),
+            column("created", "timestamp with time zone", false),
+            column("title", "varchar(200)", false),
+            column("body", "text", true),
+            column("price", "numeric(8, 2)", false),
+            column("org_id", "varchar(8)", false),
+            column("author_id", "bigint", true),
+            column("parent", "bigint", true),
+        ]
+    );
+    assert_eq!(loaded.primary_keys["blog_post"], ["id"]);
+    let references: Vec<&str> = loaded.foreign_keys["blog_post"]
+        .iter()
+        .map(|fk| fk.referenced_table.as_str())
+        .collect();
+    assert_eq!(references, ["orgs", "auth_user", "blog_post"]);
+
+    assert_eq!(
+        columns(&loaded, "blog_post_tags"),
+        [
+            column("id", "bigint", false),
+            column("post_id", "bigint", false),
+            column("tag_id", "bigint", false),
+        ]
+    );
+    assert_eq!(
+        columns(&loaded, "blog_featured"),
+        [
+            column("post_ptr_id", "bigint", false),
+            column("rank", "smallint", false),
+        ]
+    );
+    assert_eq!(loaded.primary_keys["blog_featured"], ["post_ptr_id"]);
+    assert!(!loaded.tables.contains_key("blog_timestamped"));
+    assert!(!loaded.tables.contains_key("blog_postproxy"));
+
+    // Without a path there is no app label to prefix.
+    let loaded = load_schema(DJANGO.as_bytes(), "py", Dialect::Postgres).unwrap();
+    assert!(loaded.tables.contains_key("post"));
+    let in_package = load_file_schema(
+        DJANGO.as_bytes(),
+        "py",
+        Dialect::Postgres,
+        Path::new("shop/models/post.py"),
+    )
+    .unwrap();
+    assert!(in_package.tables.contains_key("shop_post"));
+}
+
+#[test]
+fn raw_queries_validate_against_python_models() {
+    let loaded = load_schema(SQLALCHEMY.as_bytes(), "py", Dialect::Postgres).unwrap();
+    let errors = |query: &str| validate_query_against_schema(query, &loaded, Dialect::Postgres);
+
+    assert!(errors(
+        "SELECT u.email, o.display_name FROM users u JOIN app.orgs o ON o.id = u.org_id"
+    )
+    .unwrap()
+    .is_empty());
+    assert!(!errors("SELECT u.name FROM users u").unwrap().is_empty());
+    assert!(!errors("SELECT 1 FROM org").unwrap().is_empty());
+}
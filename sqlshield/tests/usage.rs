@@ -0,0 +1,144 @@
+//! Table and column usage: which schema columns each query reads or
+//! writes, where, and what no query uses.
+
+use std::fs;
+
+use sqlshield::schema;
+use sqlshield::usage::{self, Access, Reference, Unused};
+use sqlshield::Dialect;
+
+const DDL: &str = "
+    CREATE TABLE users (id INT, name TEXT, email TEXT, legacy_flag INT);
+    CREATE TABLE orders (id INT, user_id INT, total INT);
+    CREATE TABLE audit_log (id INT, entry TEXT);
+";
+
+fn refs(sql: &str) -> Vec<(String, Option<String>, Access)> {
+    let schema = schema::load_schema(DDL.as_bytes(), "sql", Dialect::Generic).unwrap();
+    let dialect = Dialect::Generic.as_sqlparser();
+    let statements = sqlparser::parser::Parser::parse_sql(dialect.as_ref(), sql).unwrap();
+    usage::references(&statements, &schema, Dialect::Generic)
+        .into_iter()
+        .map(
+            |Reference {
+                 table,
+                 column,
+                 access,
+             }| (table, column, access),
+        )
+        .collect()
+}
+
+fn r(table: &str, column: Option<&str>) -> (String, Option<String>, Access) {
+    (table.to_string(), column.map(str::to_string), Access::Read)
+}
+
+fn w(table: &str, column: Option<&str>) -> (String, Option<String>, Access) {
+    (table.to_string(), column.map(str::to_string), Access::Write)
+}
+
+#[test]
+fn columns_bind_to_the_table_they_resolve_to() {
+    assert_eq!(
+        refs("SELECT u.name, total FROM users u JOIN orders o ON o.user_id = u.id"),
+        [
+            r("orders", None),
+            r("orders", Some("total")),
+            r("orders", Some("user_id")),
+            r("users", None),
+            r("users", Some("id")),
+            r("users", Some("name")),
+        ]
+    );
+    // Ambiguous `id` binds to nothing; a CTE shadows the table it's named
+    // after, and its body is read like any query.
+    assert_eq!(
+        refs("WITH users AS (SELECT user_id AS id FROM orders) SELECT id FROM users"),
+        [r("orders", None), r("orders", Some("user_id"))]
+    );
+    assert_eq!(
+        refs("SELECT id FROM users, orders"),
+        [r("orders", None), r("users", None)]
+    );
+}
+
+#[test]
+fn correlated_subqueries_see_the_outer_tables() {
+    assert_eq!(
+        refs("SELECT id FROM users WHERE EXISTS (SELECT 1 FROM orders WHERE user_id = users.id AND email <> '')"),
+        [
+            r("orders", None),
+            r("orders", Some("user_id")),
+            r("users", None),
+            r("users", Some("email")),
+            r("users", Some("id")),
+        ]
+    );
+}
+
+#[test]
+fn dml_targets_and_set_columns_are_writes() {
+    assert_eq!(
+        refs("INSERT INTO users (id, name) SELECT user_id, 'x' FROM orders"),
+        [
+            r("orders", None),
+            r("orders", Some("user_id")),
+            w("users", None),
+            w("users", Some("id")),
+            w("users", Some("name")),
+        ]
+    );
+    assert_eq!(
+        refs("UPDATE users SET email = name WHERE id = 1"),
+        [
+            w("users", None),
+            w("users", Some("email")),
+            r("users", Some("id")),
+            r("users", Some("name")),
+        ]
+    );
+    assert_eq!(
+        refs("DELETE FROM orders WHERE total < 0"),
+        [w("orders", None), r("orders", Some("total"))]
+    );
+    assert_eq!(
+        refs("INSERT INTO audit_log VALUES (1, 'x')"),
+        [
+            w("audit_log", None),
+            w("audit_log", Some("entry")),
+            w("audit_log", Some("id")),
+        ]
+    );
+}
+
+#[test]
+fn uses_carry_their_place_and_unused_schema_is_listed() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = dir.path().join("app.py");
+    fs::write(
+        &app,
+        "def load(uid):\n    return db.one(f\"SELECT * FROM users WHERE id = {uid}\")\n\nTOTALS = \"SELECT user_id, total FROM orders\"\n",
+    )
+    .unwrap();
+    let schema = schema::load_schema(DDL.as_bytes(), "sql", Dialect::Generic).unwrap();
+
+    let uses = usage::uses(&[app.clone()], &schema, Dialect::Generic);
+    let email = uses
+        .iter()
+        .find(|u| u.reference.column.as_deref() == Some("email"))
+        .unwrap();
+    assert_eq!((email.line, email.function.as_deref()), (2, Some("load")));
+    let total = uses
+        .iter()
+        .find(|u| u.reference.column.as_deref() == Some("total"))
+        .unwrap();
+    assert_eq!((total.line, total.function.as_deref()), (4, None));
+
+    assert_eq!(
+        usage::unused(&schema, &uses),
+        Unused {
+            tables: vec!["audit_log".to_string()],
+            columns: vec![("orders".to_string(), "id".to_string())],
+        }
+    );
+}
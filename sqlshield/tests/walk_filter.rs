@@ -5,6 +5,7 @@
 use std::fs;
 
 use sqlshield::validate_files;
+use sqlshield::walk::{walk, SkipReason, WalkOptions};
 
 fn write_py(dir: &std::path::Path, query: &str) {
     fs::write(dir.join("file.py"), format!("q = \"{query}\"\n").as_bytes()).unwrap();
@@ -83,3 +84,127 @@ fn does_not_skip_normal_directories() {
         "src/ should be walked; got: {errs:?}"
     );
 }
+
+/// `root` with `name` (relative, `/`-separated) created as an empty
+/// Python file, parents included.
+fn touch(root: &std::path::Path, name: &str) {
+    let path = root.join(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, b"").unwrap();
+}
+
+fn names(root: &std::path::Path, paths: &[std::path::PathBuf]) -> Vec<String> {
+    paths
+        .iter()
+        .map(|p| {
+            p.strip_prefix(root)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect()
+}
+
+#[test]
+fn respects_gitignore_and_ignore_files() {
+    let root = tempfile::tempdir().unwrap();
+    fs::write(root.path().join(".gitignore"), "gen/\n*_pb2.py\n").unwrap();
+    touch(root.path(), "app/models.py");
+    touch(root.path(), "app/models_pb2.py");
+    touch(root.path(), "gen/client.py");
+    touch(root.path(), "third_party/vendored.py");
+    touch(root.path(), "third_party/keep.py");
+    fs::write(root.path().join("third_party/.ignore"), "*.py\n!keep.py\n").unwrap();
+
+    let walked = walk(root.path(), &WalkOptions::default());
+    assert_eq!(
+        names(root.path(), &walked.files),
+        ["app/models.py", "third_party/keep.py"]
+    );
+    let skipped: Vec<(String, SkipReason)> = walked
+        .skipped
+        .iter()
+        .map(|s| {
+            (
+                names(root.path(), &[s.path.clone()]).remove(0),
+                s.reason.clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        skipped,
+        [
+            ("app/models_pb2.py".to_string(), SkipReason::IgnoreFile),
+            ("gen".to_string(), SkipReason::IgnoreFile),
+            (
+                "third_party/vendored.py".to_string(),
+                SkipReason::IgnoreFile
+            ),
+        ]
+    );
+
+    let mut options = WalkOptions::default();
+    options.ignore_files(false);
+    assert_eq!(walk(root.path(), &options).files.len(), 5);
+}
+
+#[test]
+fn walking_a_subdirectory_honours_the_repository_ignore_files() {
+    let root = tempfile::tempdir().unwrap();
+    fs::create_dir_all(root.path().join(".git/info")).unwrap();
+    fs::write(root.path().join(".git/info/exclude"), "scratch.py\n").unwrap();
+    fs::write(root.path().join(".gitignore"), "gen/\n").unwrap();
+    touch(root.path(), "services/api/app.py");
+    touch(root.path(), "services/api/scratch.py");
+    touch(root.path(), "services/api/gen/client.py");
+    fs::write(root.path().join("services/.ignore"), "legacy.py\n").unwrap();
+    touch(root.path(), "services/api/legacy.py");
+
+    let api = root.path().join("services/api");
+    let walked = walk(&api, &WalkOptions::default());
+    assert_eq!(names(&api, &walked.files), ["app.py"]);
+    let skipped: Vec<String> = walked
+        .skipped
+        .iter()
+        .filter(|s| s.reason == SkipReason::IgnoreFile)
+        .map(|s| names(&api, &[s.path.clone()]).remove(0))
+        .collect();
+    assert_eq!(skipped, ["gen", "legacy.py", "scratch.py"]);
+}
+
+#[test]
+fn include_and_exclude_globs_filter_files() {
+    let root = tempfile::tempdir().unwrap();
+    touch(root.path(), "src/app.py");
+    touch(root.path(), "src/gen/models.py");
+    touch(root.path(), "tests/fixtures/strings.py");
+    touch(root.path(), "tests/test_app.py");
+    touch(root.path(), "scripts/once.py");
+
+    let prefix = format!("{}/", root.path().to_string_lossy().replace('\\', "/"));
+    let mut options = WalkOptions::default();
+    options
+        .include(&[format!("{prefix}src/**"), format!("{prefix}tests/**")])
+        .unwrap()
+        .exclude(&[
+            format!("{prefix}src/gen/"),
+            format!("{prefix}tests/fixtures/**"),
+        ])
+        .unwrap();
+    let walked = walk(root.path(), &options);
+    assert_eq!(
+        names(root.path(), &walked.files),
+        ["src/app.py", "tests/test_app.py"]
+    );
+    let reasons: Vec<&SkipReason> = walked.skipped.iter().map(|s| &s.reason).collect();
+    assert_eq!(
+        reasons,
+        [
+            &SkipReason::NotIncluded,
+            &SkipReason::Excluded(format!("{prefix}src/gen/")),
+            &SkipReason::Excluded(format!("{prefix}tests/fixtures/**")),
+        ]
+    );
+
+    assert!(WalkOptions::default().exclude(&["[".to_string()]).is_err());
+}
@@ -89,7 +89,7 @@ fn where_across_join_unknown_column() {
 
 #[test]
 fn having_with_valid_column() {
-    let sql = "SELECT user_id FROM receipt GROUP BY user_id HAVING total > 0";
+    let sql = "SELECT user_id FROM receipt GROUP BY user_id HAVING sum(total) > 0";
     assert!(run(sql).is_empty());
 }
 
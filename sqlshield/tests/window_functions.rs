@@ -0,0 +1,79 @@
+//! Window functions: column references inside `OVER (PARTITION BY … ORDER
+//! BY …)`, named `WINDOW` definitions, and references to undefined windows.
+
+use sqlshield::{validate_query, validate_query_with_dialect, Dialect};
+
+const SCHEMA: &str = "
+    CREATE TABLE sales (id INT, region TEXT, amount INT, sold_at TIMESTAMP);
+";
+
+fn run(sql: &str) -> Vec<String> {
+    validate_query(sql, SCHEMA).expect("SQL/schema should parse")
+}
+
+#[test]
+fn valid_inline_window_passes() {
+    let errs = run("SELECT id, sum(amount) OVER (PARTITION BY region ORDER BY sold_at) FROM sales");
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn partition_by_typo_is_flagged() {
+    let errs = run("SELECT id, rank() OVER (PARTITION BY regoin ORDER BY amount) FROM sales");
+    assert_eq!(errs.len(), 1, "got: {errs:?}");
+    assert!(errs[0].contains("`regoin`"), "got: {errs:?}");
+}
+
+#[test]
+fn window_order_by_typo_is_flagged() {
+    let errs = run("SELECT s.id, row_number() OVER (ORDER BY s.sold) FROM sales s");
+    assert!(errs.iter().any(|e| e.contains("`sold`")), "got: {errs:?}");
+}
+
+#[test]
+fn named_window_definition_is_resolved() {
+    let errs = run(
+        "SELECT id, sum(amount) OVER w FROM sales WINDOW w AS (PARTITION BY region ORDER BY sold_on)",
+    );
+    assert_eq!(errs.len(), 1, "got: {errs:?}");
+    assert!(errs[0].contains("`sold_on`"), "got: {errs:?}");
+}
+
+#[test]
+fn undefined_window_name_is_flagged() {
+    let errs = run("SELECT id, sum(amount) OVER w2 FROM sales WINDOW w AS (PARTITION BY region)");
+    assert_eq!(errs.len(), 1, "got: {errs:?}");
+    assert!(
+        errs[0].contains("Window `w2` is not defined"),
+        "got: {errs:?}"
+    );
+}
+
+#[test]
+fn undefined_window_in_order_by_is_flagged() {
+    let errs = run("SELECT id FROM sales ORDER BY rank() OVER missing");
+    assert!(
+        errs.iter().any(|e| e.contains("Window `missing`")),
+        "got: {errs:?}"
+    );
+}
+
+#[test]
+fn windows_in_subqueries_use_their_own_definitions() {
+    let errs = run(
+        "SELECT id FROM (SELECT id, rank() OVER w AS r FROM sales WINDOW w AS (ORDER BY amount)) ranked
+         WHERE r = 1",
+    );
+    assert!(errs.is_empty(), "got: {errs:?}");
+}
+
+#[test]
+fn qualify_is_resolved() {
+    let errs = validate_query_with_dialect(
+        "SELECT id FROM sales QUALIFY row_number() OVER (PARTITION BY regin ORDER BY amount) = 1",
+        SCHEMA,
+        Dialect::Snowflake,
+    )
+    .unwrap();
+    assert!(errs.iter().any(|e| e.contains("`regin`")), "got: {errs:?}");
+}